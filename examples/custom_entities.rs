@@ -2,16 +2,13 @@
 //! and later use to decode text and attribute values.
 //!
 //! NB: this example is deliberately kept simple:
-//! * it assumes that the XML file is UTF-8 encoded (custom_entities must only contain UTF-8 data)
 //! * it only handles internal entities;
-//! * the regex in this example is simple but brittle;
 //! * it does not support the use of entities in entity declaration.
 
 use std::collections::HashMap;
 
 use quick_xml::events::Event;
 use quick_xml::reader::Reader;
-use regex::bytes::Regex;
 
 const DATA: &str = r#"
 
@@ -28,16 +25,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     reader.trim_text(true);
 
     let mut custom_entities: HashMap<String, String> = HashMap::new();
-    let entity_re = Regex::new(r#"<!ENTITY\s+([^ \t\r\n]+)\s+"([^"]*)"\s*>"#)?;
 
     loop {
         match reader.read_event() {
             Ok(Event::DocType(ref e)) => {
-                for cap in entity_re.captures_iter(e) {
-                    custom_entities.insert(
-                        reader.decoder().decode(&cap[1])?.into_owned(),
-                        reader.decoder().decode(&cap[2])?.into_owned(),
-                    );
+                for entity in e.parse()?.entities {
+                    custom_entities.insert(entity.name, entity.value);
                 }
             }
             Ok(Event::Start(ref e)) => {
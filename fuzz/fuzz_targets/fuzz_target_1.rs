@@ -40,16 +40,29 @@ where
                     }
                 }
             }
-            Ok(Event::Text(ref e))
-            | Ok(Event::Comment(ref e))
-            | Ok(Event::PI(ref e))
-            | Ok(Event::DocType(ref e)) => {
+            Ok(Event::Text(ref e)) | Ok(Event::Comment(ref e)) => {
                 debug_format!(e);
                 if let Err(err) = e.unescape() {
                     debug_format!(err);
                     break;
                 }
             }
+            Ok(Event::PI(ref e)) => {
+                debug_format!(e);
+                let _ = black_box(e.target());
+                let _ = black_box(e.content());
+                if let Err(err) = e.decode() {
+                    debug_format!(err);
+                    break;
+                }
+            }
+            Ok(Event::DocType(ref e)) => {
+                debug_format!(e);
+                if let Err(err) = e.parse() {
+                    debug_format!(err);
+                    break;
+                }
+            }
             Ok(Event::CData(e)) => {
                 if let Err(err) = e.escape() {
                     let _displayed = black_box(format!("{}", err));
@@ -0,0 +1,236 @@
+//! Contains a type that deserializes values of the `xs:duration` XML Schema type
+
+use crate::errors::serialize::DeError;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// A value of the [`xs:duration`] type, split into components.
+///
+/// The lexical representation is `PnYnMnDTnHnMnS`, optionally preceded by the
+/// `-` sign, where any component can be omitted, but at least one component
+/// should be present. The `T` separator should be omitted if all time components
+/// are omitted. Only seconds can have a fractional part, which is stored with
+/// nanosecond precision; excess digits are truncated.
+///
+/// Components are not normalized, so `PT90M` is deserialized as 90 minutes,
+/// not as 1 hour and 30 minutes.
+///
+/// Values which cannot be parsed are reported as [`DeError::Custom`] with
+/// a message that contains the malformed value.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// # use serde::Deserialize;
+/// use quick_xml::de::{from_str, XsDuration};
+/// use std::time::Duration;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Task {
+///     #[serde(rename = "@timeout")]
+///     timeout: XsDuration,
+/// }
+///
+/// let task: Task = from_str(r#"<task timeout="PT1M30.5S"/>"#).unwrap();
+///
+/// assert_eq!(task.timeout.minutes, 1);
+/// assert_eq!(task.timeout.seconds, 30);
+/// assert_eq!(task.timeout.nanoseconds, 500_000_000);
+/// assert_eq!(task.timeout.to_std(), Some(Duration::from_millis(90_500)));
+/// ```
+///
+/// [`xs:duration`]: https://www.w3.org/TR/xmlschema11-2/#duration
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct XsDuration {
+    /// `true` if the duration is preceded by the `-` sign
+    pub negative: bool,
+    /// Number of years (the `Y` component)
+    pub years: u64,
+    /// Number of months (the `M` component before `T`)
+    pub months: u64,
+    /// Number of days (the `D` component)
+    pub days: u64,
+    /// Number of hours (the `H` component)
+    pub hours: u64,
+    /// Number of minutes (the `M` component after `T`)
+    pub minutes: u64,
+    /// Whole number of seconds (the `S` component)
+    pub seconds: u64,
+    /// Fractional part of the `S` component in nanoseconds
+    pub nanoseconds: u32,
+}
+
+impl XsDuration {
+    /// Converts this duration to [`Duration`]. Days are considered to be
+    /// 24 hours long.
+    ///
+    /// Returns `None` if the duration is negative, contains years or months,
+    /// which have no fixed length, or overflows.
+    pub fn to_std(&self) -> Option<Duration> {
+        if self.negative || self.years != 0 || self.months != 0 {
+            return None;
+        }
+        let seconds = self
+            .days
+            .checked_mul(24)?
+            .checked_add(self.hours)?
+            .checked_mul(60)?
+            .checked_add(self.minutes)?
+            .checked_mul(60)?
+            .checked_add(self.seconds)?;
+        Some(Duration::new(seconds, self.nanoseconds))
+    }
+}
+
+impl FromStr for XsDuration {
+    type Err = DeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).ok_or_else(|| DeError::Custom(format!("Invalid xs:duration value '{}'", s)))
+    }
+}
+
+impl<'de> Deserialize<'de> for XsDuration {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct XsDurationVisitor;
+
+        impl<'de> Visitor<'de> for XsDurationVisitor {
+            type Value = XsDuration;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an `xs:duration` value, for example, `P1Y2M3DT4H5M6S`")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(XsDurationVisitor)
+    }
+}
+
+/// Parses the lexical representation of `xs:duration`. Returns `None` if
+/// `s` is malformed
+fn parse(s: &str) -> Option<XsDuration> {
+    let (negative, rest) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let rest = rest.strip_prefix('P')?;
+    let (mut date, time) = match rest.find('T') {
+        Some(i) => (&rest[..i], Some(&rest[i + 1..])),
+        None => (rest, None),
+    };
+
+    let mut duration = XsDuration {
+        negative,
+        ..XsDuration::default()
+    };
+    let mut found = false;
+    for (designator, component) in [
+        ('Y', &mut duration.years),
+        ('M', &mut duration.months),
+        ('D', &mut duration.days),
+    ] {
+        if let Some(value) = integer(&mut date, designator)? {
+            *component = value;
+            found = true;
+        }
+    }
+    if !date.is_empty() {
+        return None;
+    }
+
+    if let Some(mut time) = time {
+        let mut found_time = false;
+        for (designator, component) in [('H', &mut duration.hours), ('M', &mut duration.minutes)] {
+            if let Some(value) = integer(&mut time, designator)? {
+                *component = value;
+                found_time = true;
+            }
+        }
+        if let Some((seconds, nanoseconds)) = seconds(&mut time)? {
+            duration.seconds = seconds;
+            duration.nanoseconds = nanoseconds;
+            found_time = true;
+        }
+        // `T` must be followed by at least one time component
+        if !time.is_empty() || !found_time {
+            return None;
+        }
+        found = true;
+    }
+
+    if found {
+        Some(duration)
+    } else {
+        None
+    }
+}
+
+/// Returns the length of the run of ASCII digits at the start of `input`
+fn digits(input: &str) -> usize {
+    input.bytes().take_while(u8::is_ascii_digit).count()
+}
+
+/// Consumes an integer followed by the `designator` from the start of `input`.
+///
+/// Returns `Some(None)` if `input` does not start with such a component and
+/// `None` if the component is malformed.
+fn integer(input: &mut &str, designator: char) -> Option<Option<u64>> {
+    let len = digits(input);
+    if !input[len..].starts_with(designator) {
+        return Some(None);
+    }
+    if len == 0 {
+        return None;
+    }
+    let value = input[..len].parse().ok()?;
+    *input = &input[len + 1..];
+    Some(Some(value))
+}
+
+/// Consumes the seconds component with an optional fractional part from the
+/// start of `input`.
+///
+/// Returns `Some(None)` if `input` does not start with such a component and
+/// `None` if the component is malformed.
+fn seconds(input: &mut &str) -> Option<Option<(u64, u32)>> {
+    let len = digits(input);
+    let (fraction, rest) = match input[len..].strip_prefix('.') {
+        Some(rest) => {
+            let fraction = digits(rest);
+            (Some(&rest[..fraction]), &rest[fraction..])
+        }
+        None => (None, &input[len..]),
+    };
+    let rest = match rest.strip_prefix('S') {
+        Some(rest) => rest,
+        None => return Some(None),
+    };
+    if len == 0 {
+        return None;
+    }
+    let seconds = input[..len].parse().ok()?;
+    let nanoseconds = match fraction {
+        Some("") => return None,
+        Some(fraction) => fraction
+            .bytes()
+            .chain(std::iter::repeat(b'0'))
+            .take(9)
+            .fold(0, |n, digit| n * 10 + u32::from(digit - b'0')),
+        None => 0,
+    };
+    *input = rest;
+    Some(Some((seconds, nanoseconds)))
+}
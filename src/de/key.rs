@@ -1,4 +1,4 @@
-use crate::de::str2bool;
+use crate::de::{str2bool, BoolFormat, NameRewriter};
 use crate::encoding::Decoder;
 use crate::errors::serialize::DeError;
 use crate::name::QName;
@@ -27,6 +27,14 @@ fn decode_name<'n>(name: QName<'n>, decoder: Decoder) -> Result<Cow<'n, str>, De
     Ok(decoder.decode(local.into_inner())?)
 }
 
+/// Decodes raw bytes of the qualified name using the deserializer encoding and
+/// passes result to the `rewriter`.
+#[inline]
+fn rewrite_name(name: &[u8], decoder: Decoder, rewriter: &NameRewriter) -> Result<String, DeError> {
+    let name = decoder.decode(name)?;
+    Ok(rewriter(&name).into_owned())
+}
+
 /// A deserializer for xml names of elements and attributes.
 ///
 /// Used for deserializing values from:
@@ -38,10 +46,12 @@ fn decode_name<'n>(name: QName<'n>, decoder: Decoder) -> Result<Cow<'n, str>, De
 /// - if it is an [`attribute`] name, put `@` in front of the identifier
 /// - if it is a namespace binding (`xmlns` or `xmlns:xxx`) put the decoded name
 ///   to the identifier
+/// - if it is a name with the reserved `xml` prefix (`xml:lang`, `xml:space`, etc.)
+///   put the decoded name to the identifier
 /// - put the decoded [`local_name()`] of a name to the identifier
 ///
-/// The final identifier looks like `[@]local_name`, or `@xmlns`, or `@xmlns:binding`
-/// (where `[]` means optional element).
+/// The final identifier looks like `[@]local_name`, or `@xmlns`, or `@xmlns:binding`,
+/// or `@xml:name` (where `[]` means optional element).
 ///
 /// The deserializer also supports deserializing names as other primitive types:
 /// - numbers
@@ -74,23 +84,81 @@ pub struct QNameDeserializer<'i, 'd> {
 }
 
 impl<'i, 'd> QNameDeserializer<'i, 'd> {
-    /// Creates deserializer from name of an attribute
-    pub fn from_attr(name: QName<'d>, decoder: Decoder) -> Result<Self, DeError> {
+    /// Creates deserializer from name of an attribute. If `rewriter` is provided,
+    /// it is applied to the qualified name before any other processing. The
+    /// name is prefixed with `prefix`. `fields` are the names of the fields of
+    /// the struct that is deserialized, or empty for maps
+    pub fn from_attr(
+        name: QName<'d>,
+        decoder: Decoder,
+        rewriter: Option<&NameRewriter>,
+        prefix: &str,
+        fields: &[&str],
+    ) -> Result<Self, DeError> {
+        Ok(Self {
+            name: CowRef::Owned(format!(
+                "{}{}",
+                prefix,
+                Self::attr_name(name, decoder, rewriter, prefix, fields)?
+            )),
+        })
+    }
+
+    /// Returns the name of an attribute as it will be matched with field names,
+    /// but without the attribute prefix
+    pub fn attr_name(
+        name: QName,
+        decoder: Decoder,
+        rewriter: Option<&NameRewriter>,
+        prefix: &str,
+        fields: &[&str],
+    ) -> Result<String, DeError> {
+        let rewritten;
+        let (name, decoder) = match rewriter {
+            Some(rewriter) => {
+                rewritten = rewrite_name(name.into_inner(), decoder, rewriter)?;
+                (QName(rewritten.as_bytes()), Decoder::utf8())
+            }
+            None => (name, decoder),
+        };
+
         // https://github.com/tafia/quick-xml/issues/537
-        // Namespace bindings (xmlns:xxx) map to `@xmlns:xxx` instead of `@xxx`
+        // Namespace bindings (xmlns:xxx) map to `@xmlns:xxx` instead of `@xxx`.
+        // The `xml` prefix is always bound to the XML namespace, even without
+        // explicit declaration, so `xml:xxx` maps to `@xml:xxx` if the struct
+        // has such field, and to `@xxx` otherwise
+        let is_reserved = name.prefix().map_or(false, |p| p.as_ref() == b"xml");
         let field = if name.as_namespace_binding().is_some() {
             decoder.decode(name.into_inner())?
+        } else if is_reserved {
+            let qualified = decoder.decode(name.as_ref())?;
+            if fields
+                .iter()
+                .any(|f| f.strip_prefix(prefix) == Some(&qualified))
+            {
+                qualified
+            } else {
+                decode_name(name, decoder)?
+            }
         } else {
             decode_name(name, decoder)?
         };
 
-        Ok(Self {
-            name: CowRef::Owned(format!("@{field}")),
-        })
+        Ok(field.into_owned())
     }
 
-    /// Creates deserializer from name of an element
-    pub fn from_elem(name: CowRef<'i, 'd, [u8]>, decoder: Decoder) -> Result<Self, DeError> {
+    /// Creates deserializer from name of an element. If `rewriter` is provided,
+    /// it is applied to the qualified name before any other processing
+    pub fn from_elem(
+        name: CowRef<'i, 'd, [u8]>,
+        decoder: Decoder,
+        rewriter: Option<&NameRewriter>,
+    ) -> Result<Self, DeError> {
+        if let Some(rewriter) = rewriter {
+            let rewritten = rewrite_name(&name, decoder, rewriter)?;
+            return Self::from_elem(CowRef::Owned(rewritten.into_bytes()), Decoder::utf8(), None);
+        }
+
         let local = match name {
             CowRef::Input(borrowed) => match decode_name(QName(borrowed), decoder)? {
                 Cow::Borrowed(borrowed) => CowRef::Input(borrowed),
@@ -135,7 +203,7 @@ impl<'de, 'd> Deserializer<'de> for QNameDeserializer<'de, 'd> {
     where
         V: Visitor<'de>,
     {
-        str2bool(self.name.as_ref(), visitor)
+        str2bool(self.name.as_ref(), &BoolFormat::default(), visitor)
     }
 
     deserialize_num!(deserialize_i8, visit_i8);
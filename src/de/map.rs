@@ -4,14 +4,17 @@ use crate::{
     de::key::QNameDeserializer,
     de::resolver::EntityResolver,
     de::simple_type::SimpleTypeDeserializer,
-    de::{str2bool, DeEvent, Deserializer, XmlRead, TEXT_KEY, VALUE_KEY},
+    de::{
+        path_segment, BoolFormat, BytesFormat, DeEvent, Deserializer, DuplicateAttributePolicy,
+        NameRewriter, Text, TextCapture, XmlRead, VALUE_KEY,
+    },
     encoding::Decoder,
     errors::serialize::DeError,
-    events::attributes::IterState,
+    events::attributes::{Attr, IterState},
     events::BytesStart,
     name::QName,
 };
-use serde::de::value::BorrowedStrDeserializer;
+use serde::de::value::{BorrowedStrDeserializer, StringDeserializer};
 use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
 use serde::serde_if_integer128;
 use std::borrow::Cow;
@@ -34,6 +37,14 @@ enum ValueSource {
     /// Next value should be deserialized from an attribute value; value is located
     /// at specified span.
     Attribute(Range<usize>),
+    /// Next value should be deserialized from an attribute without a value,
+    /// which is allowed by the [`Deserializer::minimized_bool_attrs`] setting.
+    /// Such attributes are deserialized as if they have the `"true"` value.
+    FlagAttribute,
+    /// Next value should be deserialized from all attributes that are not mapped
+    /// to other fields. That attributes are collected in [`MapAccess::attributes`].
+    /// The corresponding key is always the [`Deserializer::attribute_prefix`].
+    Attributes,
     /// Value should be deserialized from the text content of the XML node, which
     /// represented or by an ordinary text node, or by a CDATA node:
     ///
@@ -190,6 +201,26 @@ where
     /// <tag>value for VALUE_KEY field<tag>
     /// ```
     has_value_field: bool,
+    /// Text, captured according to the [`Deserializer::text_capture`] setting.
+    /// Will be reported as a [`Deserializer::text_field_name`] field when closing
+    /// tag is reached
+    captured_text: Option<Text<'de>>,
+    /// `true` if at least one child element was seen
+    child_seen: bool,
+    /// If `true`, then the deserialized struct has a field with a special name:
+    /// [`Deserializer::attribute_prefix`]. That field should be deserialized from all attributes
+    /// that are not mapped to other fields
+    has_attributes_field: bool,
+    /// Qualified names and spans of values of attributes that are not mapped
+    /// to other fields, in the document order
+    attributes: Vec<(String, Range<usize>)>,
+    /// `true` if the [`Deserializer::attribute_prefix`] field was already reported
+    attributes_reported: bool,
+    /// Name of the last reported key, if [`Deserializer::track_path`] is enabled.
+    /// Used to report the path to the value that failed to deserialize
+    key: Option<String>,
+    /// Position in the input of the `start` tag
+    start_position: usize,
 }
 
 impl<'de, 'a, R, E> MapAccess<'de, 'a, R, E>
@@ -203,15 +234,130 @@ where
         start: BytesStart<'de>,
         fields: &'static [&'static str],
     ) -> Result<Self, DeError> {
+        let mut iter = IterState::new(start.name().as_ref().len(), de.minimized_bool_attrs);
+        iter.check_duplicates(de.duplicate_attributes == DuplicateAttributePolicy::Error);
+        // The `start` event was just returned by `next()`
+        let start_position = de.position;
+        let has_attributes_field = fields.contains(&de.attribute_prefix);
         Ok(MapAccess {
             de,
-            iter: IterState::new(start.name().as_ref().len(), false),
+            iter,
             start,
             source: ValueSource::Unknown,
             fields,
             has_value_field: fields.contains(&VALUE_KEY),
+            captured_text: None,
+            child_seen: false,
+            has_attributes_field,
+            attributes: Vec::new(),
+            attributes_reported: false,
+            key: None,
+            start_position,
         })
     }
+
+    /// Returns the next attribute that should be reported as a separate
+    /// field. If the struct has a field named as the
+    /// [`Deserializer::attribute_prefix`], attributes which are not mapped
+    /// to other fields are collected into [`Self::attributes`] instead.
+    fn next_attr(&mut self) -> Result<Option<Attr<Range<usize>>>, DeError> {
+        let decoder = self.de.reader.decoder();
+        while let Some(a) = self.iter.next(&self.start.buf).transpose()? {
+            let (key, value) = a.clone().into();
+            if self.is_overridden(&key)? {
+                continue;
+            }
+            if self.has_attributes_field || self.denies_unknown() {
+                let name = QName(&self.start.buf[key.clone()]);
+                let prefix = self.de.attribute_prefix;
+                let field = QNameDeserializer::attr_name(
+                    name,
+                    decoder,
+                    self.de.name_rewriter.as_ref(),
+                    prefix,
+                    self.fields,
+                )?;
+                if !self
+                    .fields
+                    .iter()
+                    .any(|f| f.strip_prefix(prefix) == Some(&field))
+                {
+                    let is_binding = name.as_namespace_binding().is_some();
+                    let name = decoder.decode(name.into_inner())?.into_owned();
+                    if self.has_attributes_field {
+                        self.attributes.push((name, value.unwrap_or_default()));
+                        continue;
+                    }
+                    if !is_binding && self.de.variant_attribute != Some(&*name) {
+                        return Err(DeError::UnknownField {
+                            name: format!("{}{}", prefix, name),
+                            // `buf` does not include the opening `<`
+                            position: self.start_position + 1 + key.start,
+                        });
+                    }
+                }
+            }
+            return Ok(Some(a));
+        }
+        Ok(None)
+    }
+
+    /// Returns `true` if elements and attributes which are not mapped to any
+    /// field should be reported as errors. Fields are unknown only for structs
+    fn denies_unknown(&self) -> bool {
+        self.de.deny_unknown && !self.fields.is_empty()
+    }
+
+    /// Returns `true` if the attribute with the name at the `key` span should
+    /// be ignored, because another attribute with the same name wins according
+    /// to the [`Deserializer::duplicate_attributes`] setting
+    fn is_overridden(&self, key: &Range<usize>) -> Result<bool, DeError> {
+        let buf = &self.start.buf;
+        let (mut iter, end) = match self.de.duplicate_attributes {
+            // Duplicates are reported by the iterator itself
+            DuplicateAttributePolicy::Error => return Ok(false),
+            // Search the same name before the current attribute
+            DuplicateAttributePolicy::First => {
+                let mut iter = IterState::new(
+                    self.start.name().as_ref().len(),
+                    self.de.minimized_bool_attrs,
+                );
+                iter.check_duplicates(false);
+                (iter, key.start)
+            }
+            // Search the same name after the current attribute
+            DuplicateAttributePolicy::Last => (self.iter.clone(), buf.len()),
+        };
+        while let Some(a) = iter.next(buf).transpose()? {
+            let (other, _) = a.into();
+            if other.start >= end {
+                break;
+            }
+            if buf[other] == buf[key.clone()] {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Remembers the name of the reported special key if errors should contain a path
+    fn track_key(&mut self, name: &str) {
+        if self.de.track_path {
+            self.key = Some(name.to_owned());
+        }
+    }
+
+    /// Stores `text` to be reported later according to the `capture` mode
+    fn capture_text(&mut self, text: Text<'de>, capture: TextCapture) {
+        self.captured_text = match (capture, self.captured_text.take()) {
+            (TextCapture::Leading, captured) if self.child_seen => captured,
+            (TextCapture::Trailing, _) | (_, None) => Some(text),
+            (_, Some(captured)) => Some(Text {
+                cdata: captured.cdata && text.cdata,
+                text: Cow::Owned(captured.text.into_owned() + &text.text),
+            }),
+        };
+    }
 }
 
 impl<'de, 'a, R, E> de::MapAccess<'de> for MapAccess<'de, 'a, R, E>
@@ -228,17 +374,59 @@ where
         debug_assert_eq!(self.source, ValueSource::Unknown);
 
         // FIXME: There error positions counted from the start of tag name - need global position
+        let attr = self.next_attr()?;
         let slice = &self.start.buf;
         let decoder = self.de.reader.decoder();
+        let rewriter = self.de.name_rewriter.clone();
+        let track_path = self.de.track_path;
+        let denies_unknown = self.denies_unknown();
 
-        if let Some(a) = self.iter.next(slice).transpose()? {
+        if let Some(a) = attr {
             // try getting map from attributes (key= "value")
             let (key, value) = a.into();
-            self.source = ValueSource::Attribute(value.unwrap_or_default());
+            self.source = match value {
+                Some(value) => ValueSource::Attribute(value),
+                None => ValueSource::FlagAttribute,
+            };
+            if track_path {
+                self.key = Some(format!(
+                    "{}{}",
+                    self.de.attribute_prefix,
+                    path_segment(decoder, &slice[key.clone()])
+                ));
+            }
 
-            let de = QNameDeserializer::from_attr(QName(&slice[key]), decoder)?;
+            let de = QNameDeserializer::from_attr(
+                QName(&slice[key]),
+                decoder,
+                rewriter.as_ref(),
+                self.de.attribute_prefix,
+                self.fields,
+            )?;
+            seed.deserialize(de).map(Some)
+        } else if self.has_attributes_field && !self.attributes_reported {
+            // all remaining attributes are collected, report them as one map
+            self.attributes_reported = true;
+            self.source = ValueSource::Attributes;
+
+            let de = BorrowedStrDeserializer::<DeError>::new(self.de.attribute_prefix);
             seed.deserialize(de).map(Some)
         } else {
+            // Text mixed with child elements is captured and reported when
+            // the closing tag is reached
+            let capture = self.de.text_capture;
+            if let Some(capture) = capture {
+                while !self.has_value_field && matches!(self.de.peek()?, DeEvent::Text(_)) {
+                    if let DeEvent::Text(text) = self.de.next()? {
+                        self.capture_text(text, capture);
+                    }
+                }
+            }
+            // Whitespaces between child elements are kept only for the fields
+            // that consume text content
+            if !self.has_value_field && !self.fields.contains(&self.de.text_key) {
+                self.de.skip_whitespaces()?;
+            }
             // try getting from events (<key>value</key>)
             match self.de.peek()? {
                 // We shouldn't have both `$value` and `$text` fields in the same
@@ -246,6 +434,7 @@ where
                 // text content to `$value`
                 DeEvent::Text(_) if self.has_value_field => {
                     self.source = ValueSource::Content;
+                    self.track_key(VALUE_KEY);
                     // Deserialize `key` from special attribute name which means
                     // that value should be taken from the text content of the
                     // XML node
@@ -253,11 +442,13 @@ where
                     seed.deserialize(de).map(Some)
                 }
                 DeEvent::Text(_) => {
+                    let text_key = self.de.text_key;
                     self.source = ValueSource::Text;
+                    self.track_key(text_key);
                     // Deserialize `key` from special attribute name which means
                     // that value should be taken from the text content of the
                     // XML node
-                    let de = BorrowedStrDeserializer::<DeError>::new(TEXT_KEY);
+                    let de = BorrowedStrDeserializer::<DeError>::new(text_key);
                     seed.deserialize(de).map(Some)
                 }
                 // Used to deserialize collections of enums, like:
@@ -276,16 +467,47 @@ where
                 // }
                 // TODO: This should be handled by #[serde(flatten)]
                 // See https://github.com/serde-rs/serde/issues/1905
-                DeEvent::Start(e) if self.has_value_field && not_in(self.fields, e, decoder)? => {
+                DeEvent::Start(e)
+                    if self.has_value_field
+                        && not_in(self.fields, e, decoder, rewriter.as_ref())? =>
+                {
                     self.source = ValueSource::Content;
+                    self.track_key(VALUE_KEY);
 
                     let de = BorrowedStrDeserializer::<DeError>::new(VALUE_KEY);
                     seed.deserialize(de).map(Some)
                 }
+                DeEvent::Start(e)
+                    if denies_unknown && not_in(self.fields, e, decoder, rewriter.as_ref())? =>
+                {
+                    let name = decoder.decode(e.name().as_ref())?.into_owned();
+                    Err(DeError::UnknownField {
+                        name,
+                        position: self.de.peek_position(),
+                    })
+                }
                 DeEvent::Start(e) => {
                     self.source = ValueSource::Nested;
+                    if track_path {
+                        self.key = Some(path_segment(decoder, e.name().as_ref()));
+                    }
+                    // Text before the child element is not trailing text
+                    self.child_seen = true;
+                    if capture == Some(TextCapture::Trailing) {
+                        self.captured_text = None;
+                    }
 
-                    let de = QNameDeserializer::from_elem(e.raw_name(), decoder)?;
+                    let de =
+                        QNameDeserializer::from_elem(e.raw_name(), decoder, rewriter.as_ref())?;
+                    seed.deserialize(de).map(Some)
+                }
+                // Report captured text before the closing tag
+                DeEvent::End(_) if self.captured_text.is_some() => {
+                    let text_key = self.de.text_key;
+                    self.source = ValueSource::Text;
+                    self.track_key(text_key);
+
+                    let de = BorrowedStrDeserializer::<DeError>::new(text_key);
                     seed.deserialize(de).map(Some)
                 }
                 // Stop iteration after reaching a closing tag
@@ -303,13 +525,52 @@ where
         &mut self,
         seed: K,
     ) -> Result<K::Value, Self::Error> {
+        let key = self.key.take();
+        let result = self.next_value_impl(seed);
+        match key {
+            Some(key) => result.map_err(|e| e.in_path(key)),
+            None => result,
+        }
+    }
+}
+
+impl<'de, 'a, R, E> MapAccess<'de, 'a, R, E>
+where
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    /// Deserializes the value of the last reported key
+    fn next_value_impl<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<K::Value, DeError> {
         match std::mem::replace(&mut self.source, ValueSource::Unknown) {
             ValueSource::Attribute(value) => seed.deserialize(SimpleTypeDeserializer::from_part(
                 &self.start.buf,
                 value,
                 true,
                 self.de.reader.decoder(),
+                self.de.bool_format,
+                self.de.bytes_format,
             )),
+            ValueSource::FlagAttribute => {
+                // Attribute without value means `true`, so use the representation
+                // of `true` that the configured format accepts
+                let format = self.de.bool_format;
+                seed.deserialize(SimpleTypeDeserializer::from_text_content(
+                    Text {
+                        text: Cow::Borrowed(format.truthy.first().copied().unwrap_or("true")),
+                        cdata: false,
+                    },
+                    format,
+                    None,
+                ))
+            }
+            ValueSource::Attributes => seed.deserialize(AttributesDeserializer {
+                buf: &self.start.buf,
+                attributes: std::mem::take(&mut self.attributes).into_iter(),
+                value: None,
+                decoder: self.de.reader.decoder(),
+                bool_format: self.de.bool_format,
+                bytes_format: self.de.bytes_format,
+            }),
             // This arm processes the following XML shape:
             // <any-tag>
             //   text value
@@ -318,10 +579,23 @@ where
             // is implicit and equals to the `TEXT_KEY` constant, and the value
             // is a `Text` event (the value deserializer will see that event)
             // This case are checked by "xml_schema_lists::element" tests in tests/serde-de.rs
-            ValueSource::Text => match self.de.next()? {
-                DeEvent::Text(e) => seed.deserialize(SimpleTypeDeserializer::from_text_content(e)),
-                // SAFETY: We set `Text` only when we seen `Text`
-                _ => unreachable!(),
+            ValueSource::Text => match self.captured_text.take() {
+                Some(e) => seed.deserialize(SimpleTypeDeserializer::from_text_content(
+                    e,
+                    self.de.bool_format,
+                    self.de.bytes_format,
+                )),
+                None => match self.de.next()? {
+                    DeEvent::Text(e) => {
+                        seed.deserialize(SimpleTypeDeserializer::from_text_content(
+                            e,
+                            self.de.bool_format,
+                            self.de.bytes_format,
+                        ))
+                    }
+                    // SAFETY: We set `Text` only when we seen `Text`
+                    _ => unreachable!(),
+                },
             },
             // This arm processes the following XML shape:
             // <any-tag>
@@ -352,6 +626,79 @@ where
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// A deserializer of attributes, that are not mapped to other fields of a struct,
+/// into a map. Keys are qualified names of attributes, values are deserialized
+/// as attribute values.
+struct AttributesDeserializer<'de, 'a> {
+    /// Buffer of the element which attributes are deserialized
+    buf: &'a Cow<'de, [u8]>,
+    /// Names and spans of values of the remaining attributes
+    attributes: std::vec::IntoIter<(String, Range<usize>)>,
+    /// Span of the value of the last returned key
+    value: Option<Range<usize>>,
+    decoder: Decoder,
+    /// Strings recognized as boolean values
+    bool_format: BoolFormat,
+    /// Representation of binary data
+    bytes_format: Option<BytesFormat>,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for AttributesDeserializer<'de, 'a> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(self)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de, 'a> de::MapAccess<'de> for AttributesDeserializer<'de, 'a> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        match self.attributes.next() {
+            Some((name, value)) => {
+                self.value = Some(value);
+                seed.deserialize(StringDeserializer::<DeError>::new(name))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(SimpleTypeDeserializer::from_part(
+                self.buf,
+                value,
+                true,
+                self.decoder,
+                self.bool_format,
+                self.bytes_format,
+            )),
+            None => Err(DeError::KeyNotRead),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.attributes.len())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
 macro_rules! forward {
     (
         $deserialize:ident
@@ -472,6 +819,46 @@ where
     fn read_string(&mut self) -> Result<Cow<'de, str>, DeError> {
         self.map.de.read_string_impl(self.allow_start)
     }
+
+    /// Returns a `bool` represented by [`Self::read_string`] according to the
+    /// [`Deserializer::bool_format`] setting
+    #[inline]
+    fn read_bool<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value, DeError> {
+        self.map.de.read_bool_impl(self.allow_start, visitor)
+    }
+
+    /// Returns bytes represented by [`Self::read_string`] according to the
+    /// [`Deserializer::bytes_format`] setting
+    #[inline]
+    fn read_bytes<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value, DeError> {
+        self.map.de.read_bytes_impl(self.allow_start, visitor)
+    }
+
+    /// Creates an accessor to the sequence of items that is the value of the
+    /// current field
+    fn into_seq_access(self) -> Result<MapValueSeqAccess<'de, 'a, 'm, R, E>, DeError> {
+        let filter = if self.allow_start {
+            match self.map.de.peek()? {
+                // Clone is cheap if event borrows from the input
+                DeEvent::Start(e) => TagFilter::Include(e.clone()),
+                // SAFETY: we use that deserializer with `allow_start == true`
+                // only from the `MapAccess::next_value_seed` and only when we
+                // peeked `Start` event
+                _ => unreachable!(),
+            }
+        } else {
+            TagFilter::Exclude(self.map.fields)
+        };
+        Ok(MapValueSeqAccess {
+            #[cfg(feature = "overlapped-lists")]
+            checkpoint: self.map.de.skip_checkpoint(),
+
+            map: self.map,
+            filter,
+            index: 0,
+            exhausted: false,
+        })
+    }
 }
 
 impl<'de, 'a, 'm, R, E> de::Deserializer<'de> for MapValueDeserializer<'de, 'a, 'm, R, E>
@@ -496,9 +883,57 @@ where
         variants: &'static [&'static str]
     ));
 
-    forward!(deserialize_any);
     forward!(deserialize_ignored_any);
 
+    /// If [`Deserializer::text_elements_as_strings`] is set, elements without
+    /// attributes that contain only text are deserialized as strings, so such
+    /// elements, unknown to the struct, can be captured by a `#[serde(flatten)]`
+    /// map of strings. Other elements are deserialized as maps
+    ///
+    /// [`Deserializer::text_elements_as_strings`]: crate::de::Deserializer::text_elements_as_strings
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        let de = &mut *self.map.de;
+        if !self.allow_start || !de.text_elements_as_strings {
+            return de.deserialize_any(visitor);
+        }
+        let start = match de.peek()? {
+            DeEvent::Start(e) if e.attributes().next().is_none() => match de.next()? {
+                DeEvent::Start(e) => e,
+                // SAFETY: We peeked `Start` above
+                _ => unreachable!(),
+            },
+            _ => return de.deserialize_any(visitor),
+        };
+        let text = match de.peek()? {
+            DeEvent::Text(_) => match de.next()? {
+                DeEvent::Text(e) => Some(e),
+                // SAFETY: We peeked `Text` above
+                _ => unreachable!(),
+            },
+            _ => None,
+        };
+        if text.is_some() && matches!(de.peek()?, DeEvent::End(_)) {
+            de.next()?;
+            return match text.map(|t| t.text) {
+                Some(Cow::Borrowed(s)) => visitor.visit_borrowed_str(s),
+                Some(Cow::Owned(s)) => visitor.visit_string(s),
+                // SAFETY: We checked that text is present above
+                None => unreachable!(),
+            };
+        }
+        // The element has child elements, so deserialize it as a map.
+        // Already read text is reported as a `$text` value
+        let name = start.name().as_ref().to_vec();
+        let mut map = MapAccess::new(de, start, &[])?;
+        map.captured_text = text;
+        let value = visitor.visit_map(map)?;
+        de.read_to_end(QName(&name))?;
+        Ok(value)
+    }
+
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, DeError>
     where
         V: Visitor<'de>,
@@ -520,25 +955,33 @@ where
     where
         V: Visitor<'de>,
     {
-        let filter = if self.allow_start {
-            match self.map.de.peek()? {
-                // Clone is cheap if event borrows from the input
-                DeEvent::Start(e) => TagFilter::Include(e.clone()),
-                // SAFETY: we use that deserializer with `allow_start == true`
-                // only from the `MapAccess::next_value_seed` and only when we
-                // peeked `Start` event
-                _ => unreachable!(),
+        visitor.visit_seq(self.into_seq_access()?)
+    }
+
+    /// Representation of tuples and fixed-size arrays the same as
+    /// [sequences](#method.deserialize_seq), but the number of repeated
+    /// elements should be exactly `len`. If fewer or more elements with the
+    /// same name are found, the same invalid length error is returned.
+    ///
+    /// The number of items is not checked for `$value` and `$text` fields.
+    fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        if !self.allow_start {
+            return self.deserialize_seq(visitor);
+        }
+        let mut seq = self.into_seq_access()?;
+        let value = match visitor.visit_seq(&mut seq) {
+            // The visitor asked for an element after the last one, so it failed
+            // because there are too few elements
+            Err(_) if seq.exhausted && seq.index < len => {
+                return Err(invalid_length(seq.index, len));
             }
-        } else {
-            TagFilter::Exclude(self.map.fields)
+            result => result?,
         };
-        visitor.visit_seq(MapValueSeqAccess {
-            #[cfg(feature = "overlapped-lists")]
-            checkpoint: self.map.de.skip_checkpoint(),
-
-            map: self.map,
-            filter,
-        })
+        seq.end(len)?;
+        Ok(value)
     }
 
     #[inline]
@@ -557,8 +1000,13 @@ fn not_in(
     fields: &'static [&'static str],
     start: &BytesStart,
     decoder: Decoder,
+    rewriter: Option<&NameRewriter>,
 ) -> Result<bool, DeError> {
     let tag = decoder.decode(start.name().into_inner())?;
+    let tag = match rewriter {
+        Some(rewriter) => Cow::Owned(rewriter(&tag).into_owned()),
+        None => tag,
+    };
 
     Ok(fields.iter().all(|&field| field != tag.as_ref()))
 }
@@ -605,10 +1053,15 @@ enum TagFilter<'de> {
 }
 
 impl<'de> TagFilter<'de> {
-    fn is_suitable(&self, start: &BytesStart, decoder: Decoder) -> Result<bool, DeError> {
+    fn is_suitable(
+        &self,
+        start: &BytesStart,
+        decoder: Decoder,
+        rewriter: Option<&NameRewriter>,
+    ) -> Result<bool, DeError> {
         match self {
             Self::Include(n) => Ok(n.name() == start.name()),
-            Self::Exclude(fields) => not_in(fields, start, decoder),
+            Self::Exclude(fields) => not_in(fields, start, decoder, rewriter),
         }
     }
 }
@@ -654,6 +1107,12 @@ where
     ///
     /// [`overlapped-lists`]: ../../index.html#overlapped-lists
     filter: TagFilter<'de>,
+    /// Index of the next item, reported in the path of errors
+    index: usize,
+    /// `true` if [`Self::next_element_seed`] has reported the end of the sequence
+    ///
+    /// [`Self::next_element_seed`]: SeqAccess::next_element_seed
+    exhausted: bool,
 
     /// Checkpoint after which all skipped events should be returned. All events,
     /// that was skipped before creating this checkpoint, will still stay buffered
@@ -673,6 +1132,45 @@ where
     }
 }
 
+impl<'de, 'a, 'm, R, E> MapValueSeqAccess<'de, 'a, 'm, R, E>
+where
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    /// Checks that the sequence does not contain more than `len` elements.
+    /// Remaining elements that pass the [`Self::filter`] are consumed and
+    /// counted to report the actual length of the sequence.
+    fn end(&mut self, len: usize) -> Result<(), DeError> {
+        let decoder = self.map.de.reader.decoder();
+        let rewriter = self.map.de.name_rewriter.clone();
+        let mut count = self.index;
+        loop {
+            match self.map.de.peek()? {
+                DeEvent::Start(e) if self.filter.is_suitable(e, decoder, rewriter.as_ref())? => {
+                    if let DeEvent::Start(e) = self.map.de.next()? {
+                        self.map.de.read_to_end(e.name())?;
+                    }
+                    count += 1;
+                }
+                // If we see a tag that we not interested, skip it
+                #[cfg(feature = "overlapped-lists")]
+                DeEvent::Start(_) => self.map.de.skip()?,
+                _ => break,
+            }
+        }
+        if count > len {
+            return Err(invalid_length(count, len));
+        }
+        Ok(())
+    }
+}
+
+/// Returns an error for a fixed-size sequence of `len` repeated elements
+/// which actually has `count` elements
+fn invalid_length(count: usize, len: usize) -> DeError {
+    de::Error::invalid_length(count, &format!("an array of length {}", len).as_str())
+}
+
 impl<'de, 'a, 'm, R, E> SeqAccess<'de> for MapValueSeqAccess<'de, 'a, 'm, R, E>
 where
     R: XmlRead<'de>,
@@ -685,17 +1183,24 @@ where
         T: DeserializeSeed<'de>,
     {
         let decoder = self.map.de.reader.decoder();
-        loop {
+        let rewriter = self.map.de.name_rewriter.clone();
+        let result = loop {
+            // Whitespaces between the list elements are not items of the list
+            if let TagFilter::Include(_) = self.filter {
+                self.map.de.skip_whitespaces()?;
+            }
             break match self.map.de.peek()? {
                 // If we see a tag that we not interested, skip it
                 #[cfg(feature = "overlapped-lists")]
-                DeEvent::Start(e) if !self.filter.is_suitable(e, decoder)? => {
+                DeEvent::Start(e) if !self.filter.is_suitable(e, decoder, rewriter.as_ref())? => {
                     self.map.de.skip()?;
                     continue;
                 }
                 // Stop iteration when list elements ends
                 #[cfg(not(feature = "overlapped-lists"))]
-                DeEvent::Start(e) if !self.filter.is_suitable(e, decoder)? => Ok(None),
+                DeEvent::Start(e) if !self.filter.is_suitable(e, decoder, rewriter.as_ref())? => {
+                    Ok(None)
+                }
 
                 // Stop iteration after reaching a closing tag
                 DeEvent::End(e) if e.name() == self.map.start.name() => Ok(None),
@@ -706,11 +1211,21 @@ where
                 DeEvent::Eof => Err(DeError::UnexpectedEof),
 
                 // Start(tag), Text
-                _ => seed
-                    .deserialize(SeqItemDeserializer { map: self.map })
-                    .map(Some),
+                _ => {
+                    let index = self.index;
+                    self.index += 1;
+                    let result = seed.deserialize(SeqItemDeserializer { map: self.map });
+                    match result {
+                        Err(e) if self.map.de.track_path => Err(e.in_path(format!("[{}]", index))),
+                        result => result.map(Some),
+                    }
+                }
             };
+        };
+        if let Ok(None) = result {
+            self.exhausted = true;
         }
+        result
     }
 }
 
@@ -741,6 +1256,20 @@ where
     fn read_string(&mut self) -> Result<Cow<'de, str>, DeError> {
         self.map.de.read_string_impl(true)
     }
+
+    /// Returns a `bool` represented by [`Self::read_string`] according to the
+    /// [`Deserializer::bool_format`] setting
+    #[inline]
+    fn read_bool<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value, DeError> {
+        self.map.de.read_bool_impl(true, visitor)
+    }
+
+    /// Returns bytes represented by [`Self::read_string`] according to the
+    /// [`Deserializer::bytes_format`] setting
+    #[inline]
+    fn read_bytes<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value, DeError> {
+        self.map.de.read_bytes_impl(true, visitor)
+    }
 }
 
 impl<'de, 'a, 'm, R, E> de::Deserializer<'de> for SeqItemDeserializer<'de, 'a, 'm, R, E>
@@ -775,6 +1304,14 @@ where
         deserialize_option!(self.map.de, self, visitor)
     }
 
+    /// Representation of tuples the same as [sequences](#method.deserialize_seq).
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
     /// This method deserializes a sequence inside of element that itself is a
     /// sequence element:
     ///
@@ -792,17 +1329,23 @@ where
         V: Visitor<'de>,
     {
         match self.map.de.next()? {
-            DeEvent::Text(e) => {
-                SimpleTypeDeserializer::from_text_content(e).deserialize_seq(visitor)
-            }
+            DeEvent::Text(e) => SimpleTypeDeserializer::from_text_content(
+                e,
+                self.map.de.bool_format,
+                self.map.de.bytes_format,
+            )
+            .deserialize_seq(visitor),
             // This is a sequence element. We cannot treat it as another flatten
             // sequence if type will require `deserialize_seq` We instead forward
             // it to `xs:simpleType` implementation
             DeEvent::Start(e) => {
                 let value = match self.map.de.next()? {
-                    DeEvent::Text(e) => {
-                        SimpleTypeDeserializer::from_text_content(e).deserialize_seq(visitor)
-                    }
+                    DeEvent::Text(e) => SimpleTypeDeserializer::from_text_content(
+                        e,
+                        self.map.de.bool_format,
+                        self.map.de.bytes_format,
+                    )
+                    .deserialize_seq(visitor),
                     e => Err(DeError::Unsupported(
                         format!("unsupported event {:?}", e).into(),
                     )),
@@ -829,13 +1372,13 @@ where
 fn test_not_in() {
     let tag = BytesStart::new("tag");
 
-    assert_eq!(not_in(&[], &tag, Decoder::utf8()).unwrap(), true);
+    assert_eq!(not_in(&[], &tag, Decoder::utf8(), None).unwrap(), true);
     assert_eq!(
-        not_in(&["no", "such", "tags"], &tag, Decoder::utf8()).unwrap(),
+        not_in(&["no", "such", "tags"], &tag, Decoder::utf8(), None).unwrap(),
         true
     );
     assert_eq!(
-        not_in(&["some", "tag", "included"], &tag, Decoder::utf8()).unwrap(),
+        not_in(&["some", "tag", "included"], &tag, Decoder::utf8(), None).unwrap(),
         false
     );
 }
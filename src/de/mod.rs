@@ -30,6 +30,7 @@
 //!   - [`<element>` lists](#element-lists)
 //!   - [Overlapped (Out-of-Order) Elements](#overlapped-out-of-order-elements)
 //!   - [Enum::Unit Variants As a Text](#enumunit-variants-as-a-text)
+//!   - [Collecting Unknown Attributes](#collecting-unknown-attributes)
 //!   - [Internally Tagged Enums](#internally-tagged-enums)
 //!
 //!
@@ -250,7 +251,11 @@
 //!
 //! NOTE: XML allows you to have an attribute and an element with the same name
 //! inside the one element. quick-xml deals with that by prepending a `@` prefix
-//! to the name of attributes.
+//! to the name of attributes. An attribute is matched only with a field named
+//! with the `@` prefix and an element only with a field named without it, so
+//! there is no precedence between them: when both are present, each value goes
+//! to its own field, and when only one is present, the other field is missing.
+//! Attributes are always visited before child elements.
 //! </div>
 //! </td>
 //! </tr>
@@ -1777,6 +1782,74 @@
 //! If you still want to keep your struct untouched, you can instead use the
 //! helper module [`text_content`].
 //!
+//! Only unit variants can be selected by a text content (or an attribute value),
+//! because a text has no place for the data of the variant. An attempt to select
+//! a newtype, tuple or struct variant returns a [`DeError::Unsupported`] error
+//! with the name of the enum. The serializer refuses to write such variants as
+//! a text for the same reason:
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! # use serde::Deserialize;
+//! # use quick_xml::de::from_str;
+//! # use quick_xml::DeError;
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! #[serde(rename_all = "lowercase")]
+//! enum Status {
+//!     Active,
+//!     Other(String),
+//! }
+//!
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct Field {
+//!     #[serde(rename = "$text")]
+//!     content: Status,
+//! }
+//!
+//! let field: Field = from_str("<status>active</status>").unwrap();
+//! assert_eq!(field, Field { content: Status::Active });
+//!
+//! match from_str::<Field>("<status>other</status>") {
+//!     Err(DeError::Unsupported(msg)) => assert_eq!(
+//!         msg,
+//!         "cannot deserialize enum newtype variant of `Status` from an attribute or \
+//!          text content value, only unit variants can be selected by text",
+//!     ),
+//!     x => panic!("{:?}", x),
+//! }
+//! ```
+//!
+//!
+//! Collecting Unknown Attributes
+//! -----------------------------
+//! When the set of attributes is not known in advance, use a map-typed field
+//! with a special name `@`. All attributes, which are not mapped to other fields
+//! of the struct, will be deserialized into that map in the document order.
+//! Keys of the map are qualified names of the attributes (without the `@` prefix)
+//! and values are unescaped attribute values:
+//!
+//! ```
+//! # use pretty_assertions::assert_eq;
+//! # use serde::Deserialize;
+//! # use std::collections::BTreeMap;
+//! #[derive(Debug, PartialEq, Deserialize)]
+//! struct AnyName {
+//!     #[serde(rename = "@id")]
+//!     id: u32,
+//!     #[serde(rename = "@")]
+//!     other: BTreeMap<String, String>,
+//! }
+//!
+//! let any: AnyName = quick_xml::de::from_str(
+//!     r#"<any id="1" name="&lt;name&gt;" xml:lang="en"/>"#
+//! ).unwrap();
+//!
+//! assert_eq!(any.id, 1);
+//! assert_eq!(any.other, BTreeMap::from([
+//!     ("name".to_string(), "<name>".to_string()),
+//!     ("xml:lang".to_string(), "en".to_string()),
+//! ]));
+//! ```
+//!
 //!
 //! Internally Tagged Enums
 //! -----------------------
@@ -1795,6 +1868,7 @@
 //! [`deserialize_with`]: https://serde.rs/field-attrs.html#deserialize_with
 //! [#497]: https://github.com/tafia/quick-xml/issues/497
 //! [`text_content`]: crate::serde_helpers::text_content
+//! [`DeError::Unsupported`]: crate::DeError::Unsupported
 //! [Tagged enums]: https://serde.rs/enum-representations.html#internally-tagged
 //! [serde#1183]: https://github.com/serde-rs/serde/issues/1183
 //! [serde#1495]: https://github.com/serde-rs/serde/issues/1495
@@ -1813,7 +1887,7 @@ macro_rules! deserialize_type {
         {
             // No need to unescape because valid integer representations cannot be escaped
             let text = self.read_string()?;
-            visitor.$visit(text.parse()?)
+            visitor.$visit($crate::de::trim_xml_spaces(&text).parse()?)
         }
     };
 }
@@ -1844,17 +1918,17 @@ macro_rules! deserialize_primitives {
         where
             V: Visitor<'de>,
         {
-            let text = self.read_string()?;
-
-            str2bool(&text, visitor)
+            self.read_bool(visitor)
         }
 
-        /// Character represented as [strings](#method.deserialize_str).
-        fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, DeError>
+        /// Character represented as [strings](#method.deserialize_str) with
+        /// exactly one character.
+        fn deserialize_char<V>($($mut)? self, visitor: V) -> Result<V::Value, DeError>
         where
             V: Visitor<'de>,
         {
-            self.deserialize_str(visitor)
+            let text = self.read_string()?;
+            $crate::de::str2char(&text, visitor)
         }
 
         fn deserialize_str<V>($($mut)? self, visitor: V) -> Result<V::Value, DeError>
@@ -1876,12 +1950,14 @@ macro_rules! deserialize_primitives {
             self.deserialize_str(visitor)
         }
 
-        /// Returns [`DeError::Unsupported`]
-        fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value, DeError>
+        /// Decodes content according to the [`BytesFormat`], if it is set.
+        /// Otherwise returns content of CDATA sections as is, borrowing data
+        /// from input, if possible, and for text content returns [`DeError::Unsupported`]
+        fn deserialize_bytes<V>($($mut)? self, visitor: V) -> Result<V::Value, DeError>
         where
             V: Visitor<'de>,
         {
-            Err(DeError::Unsupported("binary data content is not supported by XML format".into()))
+            self.read_bytes(visitor)
         }
 
         /// Forwards deserialization to the [`deserialize_bytes`](#method.deserialize_bytes).
@@ -1916,14 +1992,6 @@ macro_rules! deserialize_primitives {
             self.deserialize_tuple(1, visitor)
         }
 
-        /// Representation of tuples the same as [sequences](#method.deserialize_seq).
-        fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, DeError>
-        where
-            V: Visitor<'de>,
-        {
-            self.deserialize_seq(visitor)
-        }
-
         /// Representation of named tuples the same as [unnamed tuples](#method.deserialize_tuple).
         fn deserialize_tuple_struct<V>(
             self,
@@ -1949,44 +2017,116 @@ macro_rules! deserialize_primitives {
 
 macro_rules! deserialize_option {
     ($de:expr, $deserializer:ident, $visitor:ident) => {
-        match $de.peek()? {
-            DeEvent::Text(t) if t.is_empty() => $visitor.visit_none(),
-            DeEvent::Eof => $visitor.visit_none(),
-            _ => $visitor.visit_some($deserializer),
+        if ($de.whitespace_as_none && $de.skip_empty_element()?) || $de.skip_nil_element()? {
+            $visitor.visit_none()
+        } else {
+            match $de.peek()? {
+                DeEvent::Text(t) if t.is_empty() => $visitor.visit_none(),
+                DeEvent::Eof => $visitor.visit_none(),
+                _ => $visitor.visit_some($deserializer),
+            }
         }
     };
 }
 
+#[cfg(feature = "xs-duration")]
+mod duration;
 mod key;
 mod map;
+mod raw;
 mod resolver;
 mod simple_type;
 mod var;
 
 pub use crate::errors::serialize::DeError;
-pub use resolver::{EntityResolver, NoEntityResolver};
+#[cfg(feature = "xs-duration")]
+pub use duration::XsDuration;
+pub use raw::{RawXml, WithRaw};
+pub(crate) use raw::{RAW_XML, WITH_RAW};
+pub use resolver::{EntityResolver, NoEntityResolver, UnknownEntityPolicy};
 
 use crate::{
+    de::raw::RawRecorder,
     encoding::Decoder,
     errors::Error,
-    events::{BytesCData, BytesEnd, BytesStart, BytesText, Event},
-    name::QName,
-    reader::Reader,
+    escape::EscapeError,
+    escapei::unescape_custom,
+    events::{BytesCData, BytesDocType, BytesEnd, BytesStart, BytesText, Event},
+    name::{NamespaceResolver, QName, ResolveResult},
+    reader::{is_whitespace, Reader, Span},
 };
 use serde::de::{self, Deserialize, DeserializeOwned, DeserializeSeed, SeqAccess, Visitor};
 use std::borrow::Cow;
-#[cfg(feature = "overlapped-lists")]
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::io::BufRead;
 use std::mem::replace;
-#[cfg(feature = "overlapped-lists")]
 use std::num::NonZeroUsize;
 use std::ops::Deref;
+use std::sync::Arc;
 
 /// Data represented by a text node or a CDATA node. XML markup is not expected
 pub(crate) const TEXT_KEY: &str = "$text";
 /// Data represented by any XML markup inside
 pub(crate) const VALUE_KEY: &str = "$value";
+/// All attributes of an element, that are not mapped to other fields. This is
+/// also the default prefix of fields that are mapped to attributes
+pub(crate) const ATTRIBUTES_KEY: &str = "@";
+
+/// A function that rewrites qualified names of elements and attributes before
+/// they will be matched with field names. See [`Deserializer::with_name_rewriter`]
+pub(crate) type NameRewriter = Arc<dyn Fn(&str) -> Cow<str> + Send + Sync>;
+
+/// A function that preprocesses text content of an element before it will be
+/// deserialized as a scalar value. See [`Deserializer::with_scalar_hook`]
+pub(crate) type ScalarHook = Arc<dyn Fn(&str) -> Cow<str> + Send + Sync>;
+
+/// The namespace of elements which are replaced by the [`IncludeResolver`]
+const XINCLUDE_NAMESPACE: &[u8] = b"http://www.w3.org/2001/XInclude";
+/// The namespace of the `nil` attribute. See [`Deserializer::xsi_nil`]
+const XSI_NAMESPACE: &[u8] = b"http://www.w3.org/2001/XMLSchema-instance";
+
+/// A function that returns events which replace an `<xi:include href="..."/>`
+/// element. See [`Deserializer::with_include_resolver`]
+pub(crate) type IncludeResolver =
+    Box<dyn Fn(&str) -> Result<Vec<Event<'static>>, DeError> + Send + Sync>;
+
+/// Defines which text nodes of an element are captured by a `$text` field of
+/// a struct which also has fields for child elements. See [`Deserializer::text_capture`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextCapture {
+    /// All text nodes which are direct children of an element are concatenated,
+    /// including text between child elements: `<root>pre<a/>post</root>` gives
+    /// `prepost`
+    All,
+    /// Only text before the first child element is captured: `<root>pre<a/>post</root>`
+    /// gives `pre`
+    Leading,
+    /// Only text after the last child element is captured: `<root>pre<a/>post</root>`
+    /// gives `post`
+    Trailing,
+}
+
+/// Defines how the deserializer handles several attributes with the same
+/// qualified name on one element. See [`Deserializer::duplicate_attributes`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DuplicateAttributePolicy {
+    /// Return an [`AttrError::Duplicated`] error wrapped into [`DeError::InvalidXml`].
+    /// This is the default, because XML forbids duplicated attributes
+    ///
+    /// [`AttrError::Duplicated`]: crate::events::attributes::AttrError::Duplicated
+    Error,
+    /// Use the value of the first attribute and ignore the others
+    First,
+    /// Use the value of the last attribute and ignore the others
+    Last,
+}
+
+impl Default for DuplicateAttributePolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Error
+    }
+}
 
 /// Decoded and concatenated content of consequent [`Text`] and [`CData`]
 /// events. _Consequent_ means that events should follow each other or be
@@ -1999,6 +2139,8 @@ pub(crate) const VALUE_KEY: &str = "$value";
 #[derive(Debug, PartialEq, Eq)]
 pub struct Text<'a> {
     text: Cow<'a, str>,
+    /// `true` if text was assembled only from [`CData`](Event::CData) events
+    cdata: bool,
 }
 
 impl<'a> Deref for Text<'a> {
@@ -2015,6 +2157,7 @@ impl<'a> From<&'a str> for Text<'a> {
     fn from(text: &'a str) -> Self {
         Self {
             text: Cow::Borrowed(text),
+            cdata: false,
         }
     }
 }
@@ -2065,7 +2208,7 @@ pub enum PayloadEvent<'a> {
     /// Unescaped character data stored in `<![CDATA[...]]>`.
     CData(BytesCData<'a>),
     /// Document type definition data (DTD) stored in `<!DOCTYPE ...>`.
-    DocType(BytesText<'a>),
+    DocType(BytesDocType<'a>),
     /// End of XML document.
     Eof,
 }
@@ -2096,12 +2239,51 @@ struct XmlReader<'i, R: XmlRead<'i>, E: EntityResolver = NoEntityResolver> {
     /// trailing spaces is not. Before the event will be returned, trimming of
     /// the spaces could be necessary
     lookahead: Result<PayloadEvent<'i>, DeError>,
+    /// Position in the input of the `lookahead` event
+    lookahead_start: usize,
+    /// Position in the input of the last event returned by `next_impl()`.
+    /// Events of the resolved includes have the position of the `include` element
+    start: usize,
 
     /// Used to resolve unknown entities that would otherwise cause the parser
     /// to return an [`EscapeError::UnrecognizedSymbol`] error.
     ///
     /// [`EscapeError::UnrecognizedSymbol`]: crate::escape::EscapeError::UnrecognizedSymbol
     entity_resolver: E,
+    /// Defines what to do with entities that was not resolved by `entity_resolver`
+    unknown_entity: UnknownEntityPolicy,
+    /// If `false`, trailing whitespaces are not removed from text events
+    trim_text: bool,
+
+    /// Recorders of XML of elements which is deserialized into [`WithRaw`].
+    /// Each returned event is passed to all unfinished recorders
+    recorders: Vec<RawRecorder>,
+
+    /// Maximum allowed nesting of elements
+    max_depth: Option<NonZeroUsize>,
+    /// Number of the currently opened elements
+    depth: usize,
+    /// Maximum allowed number of returned start and text events
+    max_events: Option<NonZeroUsize>,
+    /// Number of already returned start and text events
+    events: usize,
+    /// Number of already returned start events of top-level elements
+    top_level: usize,
+    /// Maximum allowed length of a text node in bytes
+    max_text_len: Option<NonZeroUsize>,
+    /// Maximum allowed total length of replacement text of resolved entities
+    max_entity_expansion: Option<NonZeroUsize>,
+    /// Total length of replacement text of already resolved entities
+    expanded: usize,
+
+    /// Resolves `href`s of `include` elements to events which replace them
+    include_resolver: Option<IncludeResolver>,
+    /// Namespace bindings in scope, used to recognize `include` elements.
+    /// Tracked only when `include_resolver` is set
+    namespaces: NamespaceResolver,
+    /// Events of the resolved includes which should be returned before
+    /// the next event of `reader`
+    included: VecDeque<PayloadEvent<'static>>,
 }
 
 impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
@@ -2109,18 +2291,115 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
         // Lookahead by one event immediately, so we do not need to check in the
         // loop if we need lookahead or not
         let lookahead = reader.next();
+        let lookahead_start = reader.event_start();
 
         Self {
             reader,
             lookahead,
+            lookahead_start,
+            start: 0,
             entity_resolver,
+            unknown_entity: UnknownEntityPolicy::Error,
+            trim_text: true,
+            recorders: Vec::new(),
+            max_depth: None,
+            depth: 0,
+            max_events: None,
+            events: 0,
+            top_level: 0,
+            max_text_len: None,
+            max_entity_expansion: None,
+            expanded: 0,
+            include_resolver: None,
+            namespaces: NamespaceResolver::default(),
+            included: VecDeque::new(),
+        }
+    }
+
+    /// Sets the include resolver. If the already pre-read event is an `include`
+    /// element, it is replaced immediately
+    fn set_include_resolver(&mut self, resolver: IncludeResolver) {
+        self.include_resolver = Some(resolver);
+        self.lookahead = match replace(&mut self.lookahead, Ok(PayloadEvent::Eof)) {
+            Ok(event) => match self.include(&event) {
+                Ok(true) => self.read(),
+                Ok(false) => Ok(event),
+                Err(err) => Err(err),
+            },
+            err => err,
+        };
+        self.lookahead_start = self.reader.event_start();
+    }
+
+    /// Reads the next event either from the resolved includes or from the
+    /// underlying reader. `include` elements are replaced by the resolved events
+    fn read(&mut self) -> Result<PayloadEvent<'i>, DeError> {
+        loop {
+            if let Some(event) = self.included.pop_front() {
+                match &event {
+                    PayloadEvent::Start(e) => self.namespaces.push(e)?,
+                    PayloadEvent::End(_) => self.namespaces.pop(),
+                    _ => {}
+                }
+                return Ok(event);
+            }
+            let event = self.reader.next()?;
+            if !self.include(&event)? {
+                return Ok(event);
+            }
+        }
+    }
+
+    /// If `event` is a start of an `include` element and the include resolver
+    /// is set, skips that element and queues the events returned by the resolver.
+    /// Returns `true` if the event was replaced
+    fn include(&mut self, event: &PayloadEvent) -> Result<bool, DeError> {
+        let resolver = match &self.include_resolver {
+            Some(resolver) => resolver,
+            None => return Ok(false),
+        };
+        let start = match event {
+            PayloadEvent::Start(e) => e,
+            PayloadEvent::End(_) => {
+                self.namespaces.pop();
+                return Ok(false);
+            }
+            _ => return Ok(false),
+        };
+        self.namespaces.push(start)?;
+        if !is_include(start, &self.namespaces) {
+            return Ok(false);
         }
+        // The element is replaced, so its scope is not needed anymore
+        self.namespaces.pop();
+
+        let decoder = self.reader.decoder();
+        let href = include_href(start, decoder)?;
+        resolve_include(
+            resolver,
+            &href,
+            &mut Vec::new(),
+            &mut self.namespaces.clone(),
+            decoder,
+            &mut self.included,
+        )?;
+        self.reader.read_to_end(start.name())?;
+        Ok(true)
     }
 
     /// Read next event and put it in lookahead, return the current lookahead
     #[inline(always)]
     fn next_impl(&mut self) -> Result<PayloadEvent<'i>, DeError> {
-        replace(&mut self.lookahead, self.reader.next())
+        let next = self.read();
+        self.start = replace(&mut self.lookahead_start, self.reader.event_start());
+        replace(&mut self.lookahead, next)
+    }
+
+    /// Returns `true` if the next event, that will be returned by `next()`,
+    /// is an end tag
+    #[inline]
+    fn is_end_next(&self) -> bool {
+        matches!(self.lookahead, Ok(PayloadEvent::End(_)))
     }
 
     #[inline(always)]
@@ -2132,17 +2411,38 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
         )
     }
 
+    /// Removes trailing whitespaces from the text if text trimming is enabled.
+    /// Returns `true` if the text should be skipped: if it is empty after trimming
+    /// or, if trimming is disabled, consists only of whitespaces outside of the
+    /// root element
+    #[inline]
+    fn skip_text(&self, e: &mut BytesText) -> bool {
+        if self.trim_text {
+            self.need_trim_end() && e.inplace_trim_end()
+        } else {
+            self.depth == 0 && e.iter().all(|&b| is_whitespace(b))
+        }
+    }
+
     /// Read all consequent [`Text`] and [`CData`] events until non-text event
     /// occurs. Content of all events would be appended to `result` and returned
-    /// as [`DeEvent::Text`].
+    /// as [`DeEvent::Text`]. `cdata` should be `true` if `result` was read from
+    /// the [`CData`] event.
     ///
     /// [`Text`]: PayloadEvent::Text
     /// [`CData`]: PayloadEvent::CData
-    fn drain_text(&mut self, mut result: Cow<'i, str>) -> Result<DeEvent<'i>, DeError> {
+    fn drain_text(
+        &mut self,
+        mut result: Cow<'i, str>,
+        mut cdata: bool,
+    ) -> Result<DeEvent<'i>, DeError> {
+        self.check_text_len(result.len())?;
         loop {
             match self.lookahead {
                 Ok(PayloadEvent::Text(_) | PayloadEvent::CData(_)) => {
+                    cdata &= matches!(self.lookahead, Ok(PayloadEvent::CData(_)));
                     let text = self.next_text()?;
+                    self.check_text_len(result.len() + text.len())?;
 
                     let mut s = result.into_owned();
                     s += &text;
@@ -2151,7 +2451,20 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
                 _ => break,
             }
         }
-        Ok(DeEvent::Text(Text { text: result }))
+        Ok(DeEvent::Text(Text {
+            text: result,
+            cdata,
+        }))
+    }
+
+    /// Returns [`DeError::TextLimitExceeded`] if text of length `len` is longer
+    /// than allowed
+    #[inline]
+    fn check_text_len(&self, len: usize) -> Result<(), DeError> {
+        match self.max_text_len {
+            Some(max) if len > max.get() => Err(DeError::TextLimitExceeded(max)),
+            _ => Ok(()),
+        }
     }
 
     /// Read one text event, panics if current event is not a text event
@@ -2167,10 +2480,10 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
     fn next_text(&mut self) -> Result<Cow<'i, str>, DeError> {
         match self.next_impl()? {
             PayloadEvent::Text(mut e) => {
-                if self.need_trim_end() {
+                if self.trim_text && self.need_trim_end() {
                     e.inplace_trim_end();
                 }
-                Ok(e.unescape_with(|entity| self.entity_resolver.resolve(entity))?)
+                self.unescape(e)
             }
             PayloadEvent::CData(e) => Ok(e.decode()?),
 
@@ -2179,19 +2492,115 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
         }
     }
 
+    /// Decodes and unescapes text, resolving entities using `entity_resolver`.
+    /// Unknown entities are handled according to the `unknown_entity` policy
+    fn unescape(&mut self, e: BytesText<'i>) -> Result<Cow<'i, str>, DeError> {
+        let text = e.decode()?;
+        let expanded = &mut self.expanded;
+        let unescaped = unescape_custom(&text, |entity, range, out| {
+            match self.entity_resolver.try_resolve(entity) {
+                Ok(Some(value)) => {
+                    *expanded += value.len();
+                    match self.max_entity_expansion {
+                        Some(max) if *expanded > max.get() => {
+                            return Err(DeError::ExpansionLimitExceeded(max))
+                        }
+                        _ => out.push_str(&value),
+                    }
+                }
+                Ok(None) => match &self.unknown_entity {
+                    UnknownEntityPolicy::Error => {
+                        let error = EscapeError::UnrecognizedSymbol(range, entity.to_string());
+                        return Err(DeError::from(error));
+                    }
+                    UnknownEntityPolicy::Keep => {
+                        out.push('&');
+                        out.push_str(entity);
+                        out.push(';');
+                    }
+                    UnknownEntityPolicy::Replace(replacement) => out.push_str(replacement),
+                },
+                Err(err) => {
+                    return Err(DeError::Custom(format!(
+                        "cannot resolve entity `{}`: {}",
+                        entity, err
+                    )))
+                }
+            }
+            Ok(())
+        })?;
+        match unescaped {
+            // Because result is borrowed, no replacements was done and we can use original string
+            Cow::Borrowed(_) => Ok(text),
+            Cow::Owned(s) => Ok(Cow::Owned(s)),
+        }
+    }
+
     /// Return an input-borrowing event.
     fn next(&mut self) -> Result<DeEvent<'i>, DeError> {
+        let event = self.next_event()?;
+        self.check_limits(&event)?;
+        for recorder in self.recorders.iter_mut().filter(|r| !r.is_finished()) {
+            recorder.record(&event, self.start..self.lookahead_start);
+        }
+        Ok(event)
+    }
+
+    /// Returns an error for the unexpected end of input. If the input is not
+    /// empty, but neither element nor text was read from it, because it contains
+    /// only whitespaces, comments, processing instructions or declarations,
+    /// [`DeError::NoRootElement`] is returned, otherwise [`DeError::UnexpectedEof`]
+    fn eof_error(&self) -> DeError {
+        if self.events == 0 && self.start > 0 {
+            DeError::NoRootElement
+        } else {
+            DeError::UnexpectedEof
+        }
+    }
+
+    /// Counts the returned `event` and checks that neither nesting nor number
+    /// of start and text events exceeds the configured limits
+    fn check_limits(&mut self, event: &DeEvent) -> Result<(), DeError> {
+        match event {
+            DeEvent::Start(_) => {
+                if self.depth == 0 {
+                    self.top_level += 1;
+                }
+                self.depth += 1;
+                if let Some(max) = self.max_depth {
+                    if self.depth > max.get() {
+                        return Err(DeError::DepthLimitExceeded(max));
+                    }
+                }
+            }
+            DeEvent::Text(_) => {}
+            DeEvent::End(_) => {
+                self.depth = self.depth.saturating_sub(1);
+                return Ok(());
+            }
+            DeEvent::Eof => return Ok(()),
+        }
+        self.events += 1;
+        match self.max_events {
+            Some(max) if self.events > max.get() => Err(DeError::EventLimitExceeded(max)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Return an input-borrowing event without passing it to the recorders.
+    fn next_event(&mut self) -> Result<DeEvent<'i>, DeError> {
         loop {
             return match self.next_impl()? {
                 PayloadEvent::Start(e) => Ok(DeEvent::Start(e)),
                 PayloadEvent::End(e) => Ok(DeEvent::End(e)),
                 PayloadEvent::Text(mut e) => {
-                    if self.need_trim_end() && e.inplace_trim_end() {
+                    if self.skip_text(&mut e) {
                         continue;
                     }
-                    self.drain_text(e.unescape_with(|entity| self.entity_resolver.resolve(entity))?)
+                    let text = self.unescape(e)?;
+                    self.drain_text(text, false)
                 }
-                PayloadEvent::CData(e) => self.drain_text(e.decode()?),
+                PayloadEvent::CData(e) => self.drain_text(e.decode()?, true),
                 PayloadEvent::DocType(e) => {
                     self.entity_resolver
                         .capture(e)
@@ -2203,9 +2612,28 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
         }
     }
 
+    /// Skips events up to and including the end tag with the specified name.
+    /// Returns the position in the input of that end tag
     #[inline]
-    fn read_to_end(&mut self, name: QName) -> Result<(), DeError> {
-        match self.lookahead {
+    fn read_to_end(&mut self, name: QName) -> Result<usize, DeError> {
+        // Recorders should see all events, so read them one by one. Included
+        // events are not known to the reader, so they also should be read one by one
+        if self.include_resolver.is_some() || self.recorders.iter().any(|r| !r.is_finished()) {
+            let mut depth = 0;
+            loop {
+                match self.next()? {
+                    DeEvent::Start(_) => depth += 1,
+                    DeEvent::End(_) if depth == 0 => break,
+                    DeEvent::End(_) => depth -= 1,
+                    DeEvent::Text(_) => {}
+                    DeEvent::Eof => return Err(DeError::UnexpectedEof),
+                }
+            }
+            return Ok(self.start);
+        }
+        // The end event of the skipped element is consumed below without `next()`
+        self.depth = self.depth.saturating_sub(1);
+        let end = match self.lookahead {
             // We pre-read event with the same name that is required to be skipped.
             // First call of `read_to_end` will end out pre-read event, the second
             // will consume other events
@@ -2215,28 +2643,34 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
 
                 // In case of error `next` returns `Eof`
                 self.lookahead = self.reader.next();
+                self.lookahead_start = self.reader.event_start();
                 result1?;
-                result2?;
+                result2?.end
             }
             // We pre-read event with the same name that is required to be skipped.
             // Because this is end event, we already consume the whole tree, so
             // nothing to do, just update lookahead
             Ok(PayloadEvent::End(ref e)) if e.name() == name => {
+                let end = self.lookahead_start;
                 self.lookahead = self.reader.next();
+                self.lookahead_start = self.reader.event_start();
+                end
             }
             Ok(_) => {
                 let result = self.reader.read_to_end(name);
 
                 // In case of error `next` returns `Eof`
                 self.lookahead = self.reader.next();
-                result?;
+                self.lookahead_start = self.reader.event_start();
+                result?.end
             }
             // Read next lookahead event, unpack error from the current lookahead
             Err(_) => {
                 self.next_impl()?;
+                self.start
             }
-        }
-        Ok(())
+        };
+        Ok(end)
     }
 
     #[inline]
@@ -2245,6 +2679,103 @@ impl<'i, R: XmlRead<'i>, E: EntityResolver> XmlReader<'i, R, E> {
     }
 }
 
+/// Returns `true` if `e` is a start of an `<xi:include href="..."/>` element,
+/// i.e. an `include` element in the [`XINCLUDE_NAMESPACE`]. `namespaces` should
+/// already contain bindings declared by `e`
+#[inline]
+fn is_include(e: &BytesStart, namespaces: &NamespaceResolver) -> bool {
+    match namespaces.resolve(e.name(), true) {
+        (ResolveResult::Bound(ns), local) => {
+            ns.into_inner() == XINCLUDE_NAMESPACE && local.as_ref() == b"include"
+        }
+        _ => false,
+    }
+}
+
+/// Returns the unescaped `href` attribute of an `include` element
+fn include_href(e: &BytesStart, decoder: Decoder) -> Result<String, DeError> {
+    match e.try_get_attribute("href")? {
+        Some(href) => {
+            let value = decoder.decode(&href.value)?;
+            Ok(crate::escape::unescape(&value)?.into_owned())
+        }
+        None => Err(DeError::Custom(
+            "`include` element without `href` attribute".to_string(),
+        )),
+    }
+}
+
+/// Calls `resolver` for the `href` and appends returned events to `output`.
+/// Nested `include` elements are resolved recursively, `stack` contains `href`s
+/// of all includes that are currently resolved and is used to detect cycles.
+/// `namespaces` contains bindings in scope of the replaced element
+fn resolve_include(
+    resolver: &IncludeResolver,
+    href: &str,
+    stack: &mut Vec<String>,
+    namespaces: &mut NamespaceResolver,
+    decoder: Decoder,
+    output: &mut VecDeque<PayloadEvent<'static>>,
+) -> Result<(), DeError> {
+    if stack.iter().any(|h| h == href) {
+        return Err(DeError::Custom(format!("cyclic include of `{}`", href)));
+    }
+    stack.push(href.to_string());
+
+    let mut events = resolver(href)?.into_iter();
+    while let Some(event) = events.next() {
+        match event {
+            Event::Start(e) => {
+                namespaces.push(&e)?;
+                if !is_include(&e, namespaces) {
+                    output.push_back(PayloadEvent::Start(e));
+                    continue;
+                }
+                namespaces.pop();
+
+                let href = include_href(&e, decoder)?;
+                // Skip content of the `include` element
+                let mut depth = 0;
+                loop {
+                    match events.next() {
+                        Some(Event::Start(_)) => depth += 1,
+                        Some(Event::End(_)) if depth == 0 => break,
+                        Some(Event::End(_)) => depth -= 1,
+                        Some(_) => {}
+                        None => return Err(DeError::UnexpectedEof),
+                    }
+                }
+                resolve_include(resolver, &href, stack, namespaces, decoder, output)?;
+            }
+            Event::Empty(e) => {
+                namespaces.push(&e)?;
+                let include = is_include(&e, namespaces);
+                namespaces.pop();
+
+                if include {
+                    let href = include_href(&e, decoder)?;
+                    resolve_include(resolver, &href, stack, namespaces, decoder, output)?;
+                } else {
+                    let end = e.to_end().into_owned();
+                    output.push_back(PayloadEvent::Start(e));
+                    output.push_back(PayloadEvent::End(end));
+                }
+            }
+            Event::End(e) => {
+                namespaces.pop();
+                output.push_back(PayloadEvent::End(e));
+            }
+            Event::Text(e) => output.push_back(PayloadEvent::Text(e)),
+            Event::CData(e) => output.push_back(PayloadEvent::CData(e)),
+            Event::DocType(e) => output.push_back(PayloadEvent::DocType(e)),
+            Event::Decl(_) | Event::PI(_) | Event::Comment(_) | Event::Eof => {}
+        }
+    }
+
+    stack.pop();
+    Ok(())
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// Deserialize an instance of type `T` from a string of XML text.
@@ -2268,24 +2799,290 @@ where
     T::deserialize(&mut de)
 }
 
-// TODO: According to the https://www.w3.org/TR/xmlschema11-2/#boolean,
-// valid boolean representations are only "true", "false", "1", and "0"
-fn str2bool<'de, V>(value: &str, visitor: V) -> Result<V::Value, DeError>
+/// Deserialize an instance of type `T` from bytes of XML text.
+///
+/// If the `encoding` feature is enabled, the encoding of the input is detected
+/// from the byte order mark or the XML declaration, and the input is decoded
+/// from that encoding. Otherwise the input should be in UTF-8, and the declared
+/// encoding is ignored.
+///
+/// Because decoded data cannot be borrowed from the input, `T` should own all
+/// its data. If you have `&str` input, use [`from_str`] instead.
+///
+/// # Examples
+///
+/// With the `encoding` feature enabled:
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// # use serde::Deserialize;
+/// use quick_xml::de::from_slice;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Price {
+///     #[serde(rename = "$text")]
+///     value: String,
+/// }
+///
+/// # #[cfg(feature = "encoding")]
+/// # {
+/// // 0x80 is the euro sign in windows-1252
+/// let xml = b"<?xml version='1.0' encoding='windows-1252'?><price>42 \x80</price>";
+/// let price: Price = from_slice(xml).unwrap();
+///
+/// assert_eq!(price.value, "42 \u{20AC}");
+/// # }
+/// ```
+pub fn from_slice<T>(bytes: &[u8]) -> Result<T, DeError>
+where
+    T: DeserializeOwned,
+{
+    from_reader(bytes)
+}
+
+/// Deserialize an instance of type `T` from a string of XML text and check
+/// that nothing except whitespaces, comments and processing instructions
+/// follows it.
+///
+/// Returns [`DeError::TrailingData`] if another element or a text follows
+/// the deserialized value.
+pub fn from_str_strict<'de, T>(s: &'de str) -> Result<T, DeError>
 where
-    V: de::Visitor<'de>,
+    T: Deserialize<'de>,
 {
-    match value {
-        "true" | "1" | "True" | "TRUE" | "t" | "Yes" | "YES" | "yes" | "y" => {
-            visitor.visit_bool(true)
+    let mut de = Deserializer::from_str(s);
+    let value = T::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+/// Deserialize from a reader and check that nothing except whitespaces,
+/// comments and processing instructions follows the deserialized value.
+/// See [`from_reader`] and [`from_str_strict`] for details.
+pub fn from_reader_strict<R, T>(reader: R) -> Result<T, DeError>
+where
+    R: BufRead,
+    T: DeserializeOwned,
+{
+    let mut de = Deserializer::from_reader(reader);
+    let value = T::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+/// Defines which strings are recognized as boolean values when deserializing
+/// a `bool` from text, CDATA or attribute content. See [`Deserializer::bool_format`].
+///
+/// Strings are compared exactly, without any case folding or trimming.
+/// Any other string gives a [`DeError::InvalidBoolean`] error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoolFormat {
+    /// Strings that are deserialized as `true`
+    pub truthy: &'static [&'static str],
+    /// Strings that are deserialized as `false`
+    pub falsy: &'static [&'static str],
+}
+
+impl BoolFormat {
+    /// Only representations allowed by the [XML Schema]: `true` / `1` and
+    /// `false` / `0`.
+    ///
+    /// [XML Schema]: https://www.w3.org/TR/xmlschema11-2/#boolean
+    pub const XSD: Self = Self {
+        truthy: &["true", "1"],
+        falsy: &["false", "0"],
+    };
+
+    /// Returns `Some(true)` if `value` is one of [`Self::truthy`] strings,
+    /// `Some(false)` if it is one of [`Self::falsy`] strings and `None` otherwise
+    fn parse(&self, value: &[u8]) -> Option<bool> {
+        if self.truthy.iter().any(|s| s.as_bytes() == value) {
+            Some(true)
+        } else if self.falsy.iter().any(|s| s.as_bytes() == value) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for BoolFormat {
+    /// In addition to the representations allowed by the [XML Schema] handles
+    /// following:
+    ///
+    /// |`bool` |XML content
+    /// |-------|-------------------------------------------------------------
+    /// |`true` |`"True"`,  `"TRUE"`,  `"t"`, `"Yes"`, `"YES"`, `"yes"`, `"y"`
+    /// |`false`|`"False"`, `"FALSE"`, `"f"`, `"No"`,  `"NO"`,  `"no"`,  `"n"`
+    ///
+    /// [XML Schema]: https://www.w3.org/TR/xmlschema11-2/#boolean
+    fn default() -> Self {
+        Self {
+            truthy: &["true", "1", "True", "TRUE", "t", "Yes", "YES", "yes", "y"],
+            falsy: &["false", "0", "False", "FALSE", "f", "No", "NO", "no", "n"],
+        }
+    }
+}
+
+/// Defines how binary data is represented in text, CDATA or attribute content.
+/// Used by the [`Deserializer::bytes_format`] and the [`Serializer::bytes_format`]
+/// settings for types that deserialize and serialize themselves as bytes, for
+/// example [`serde_bytes::ByteBuf`].
+///
+/// Note, that plain `Vec<u8>` and `[u8; N]` are serialized by serde as sequences
+/// of numbers, not as bytes, so this format is not applied to them. Use a wrapper
+/// such as [`ByteBuf`] or a field with the `#[serde(with = "serde_bytes")]`
+/// attribute to get binary data in the selected format.
+///
+/// [`ByteBuf`]: crate::utils::ByteBuf
+/// [`Serializer::bytes_format`]: crate::se::Serializer::bytes_format
+/// [`serde_bytes::ByteBuf`]: https://docs.rs/serde_bytes/latest/serde_bytes/struct.ByteBuf.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BytesFormat {
+    /// The [`xs:base64Binary`] representation: standard base64 alphabet with
+    /// padding. Whitespace is ignored when decoding
+    ///
+    /// [`xs:base64Binary`]: https://www.w3.org/TR/xmlschema11-2/#base64Binary
+    Base64,
+    /// The [`xs:hexBinary`] representation: two hexadecimal digits per byte.
+    /// Lowercase digits are written, both cases are accepted when decoding.
+    /// Whitespace around the digits is ignored when decoding
+    ///
+    /// [`xs:hexBinary`]: https://www.w3.org/TR/xmlschema11-2/#hexBinary
+    Hex,
+}
+
+impl BytesFormat {
+    const BASE64: &'static [u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    const HEX: &'static [u8; 16] = b"0123456789abcdef";
+
+    /// Returns a textual representation of `bytes` in this format
+    pub(crate) fn encode(&self, bytes: &[u8]) -> String {
+        let mut result = String::new();
+        match self {
+            Self::Base64 => {
+                for chunk in bytes.chunks(3) {
+                    let b = [
+                        chunk[0],
+                        chunk.get(1).copied().unwrap_or(0),
+                        chunk.get(2).copied().unwrap_or(0),
+                    ];
+                    let n = (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize;
+                    for i in 0..4 {
+                        if i <= chunk.len() {
+                            result.push(Self::BASE64[n >> (18 - 6 * i) & 0x3F] as char);
+                        } else {
+                            result.push('=');
+                        }
+                    }
+                }
+            }
+            Self::Hex => {
+                for b in bytes {
+                    result.push(Self::HEX[(b >> 4) as usize] as char);
+                    result.push(Self::HEX[(b & 0xF) as usize] as char);
+                }
+            }
         }
-        "false" | "0" | "False" | "FALSE" | "f" | "No" | "NO" | "no" | "n" => {
-            visitor.visit_bool(false)
+        result
+    }
+
+    /// Restores bytes from their textual representation in this format
+    pub(crate) fn decode(&self, text: &str) -> Result<Vec<u8>, DeError> {
+        let invalid = || DeError::Custom(format!("invalid {:?} binary data `{}`", self, text));
+        match self {
+            Self::Base64 => {
+                let digits: Vec<u8> = text.bytes().filter(|b| !is_whitespace(*b)).collect();
+                if digits.len() % 4 != 0 {
+                    return Err(invalid());
+                }
+                let padding = digits.iter().rev().take_while(|&&b| b == b'=').count();
+                if padding > 2 {
+                    return Err(invalid());
+                }
+                let mut result = Vec::with_capacity(digits.len() / 4 * 3);
+                for (i, chunk) in digits.chunks(4).enumerate() {
+                    let last = (i + 1) * 4 == digits.len();
+                    let used = if last { 4 - padding } else { 4 };
+                    let mut n = 0;
+                    for (j, &b) in chunk.iter().enumerate() {
+                        let value = if j < used {
+                            Self::BASE64
+                                .iter()
+                                .position(|&c| c == b)
+                                .ok_or_else(invalid)?
+                        } else {
+                            0
+                        };
+                        n = n << 6 | value;
+                    }
+                    let decoded = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+                    result.extend_from_slice(&decoded[..used - 1]);
+                }
+                Ok(result)
+            }
+            Self::Hex => {
+                let digit = |b: u8| (b as char).to_digit(16).ok_or_else(invalid);
+                let text = trim_xml_spaces(text).as_bytes();
+                if text.len() % 2 != 0 {
+                    return Err(invalid());
+                }
+                text.chunks(2)
+                    .map(|pair| Ok((digit(pair[0])? << 4 | digit(pair[1])?) as u8))
+                    .collect()
+            }
         }
-        _ => Err(DeError::InvalidBoolean(value.into())),
     }
 }
 
-fn deserialize_bool<'de, V>(value: &[u8], decoder: Decoder, visitor: V) -> Result<V::Value, DeError>
+/// Returns the name of an element or attribute as a segment of the path,
+/// reported by [`DeError::WithPath`]
+fn path_segment(decoder: Decoder, name: &[u8]) -> String {
+    match decoder.decode(name) {
+        Ok(name) => name.into_owned(),
+        Err(_) => String::from_utf8_lossy(name).into_owned(),
+    }
+}
+
+/// Removes XML whitespace characters from both ends of `text`. Whitespaces
+/// around numbers are not significant
+#[inline]
+pub(crate) fn trim_xml_spaces(text: &str) -> &str {
+    text.trim_matches(|c: char| c.is_ascii() && is_whitespace(c as u8))
+}
+
+/// Deserializes a `char` from `text`, which should contain exactly one character
+pub(crate) fn str2char<'de, V>(text: &str, visitor: V) -> Result<V::Value, DeError>
+where
+    V: de::Visitor<'de>,
+{
+    let mut chars = text.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => visitor.visit_char(ch),
+        _ => Err(DeError::Custom(format!(
+            "expected single character, found {}",
+            text.chars().count()
+        ))),
+    }
+}
+
+fn str2bool<'de, V>(value: &str, format: &BoolFormat, visitor: V) -> Result<V::Value, DeError>
+where
+    V: de::Visitor<'de>,
+{
+    match format.parse(value.as_bytes()) {
+        Some(value) => visitor.visit_bool(value),
+        None => Err(DeError::InvalidBoolean(value.into())),
+    }
+}
+
+fn deserialize_bool<'de, V>(
+    value: &[u8],
+    decoder: Decoder,
+    format: &BoolFormat,
+    visitor: V,
+) -> Result<V::Value, DeError>
 where
     V: Visitor<'de>,
 {
@@ -2293,86 +3090,1007 @@ where
     {
         let value = decoder.decode(value)?;
         // No need to unescape because valid boolean representations cannot be escaped
-        str2bool(value.as_ref(), visitor)
+        str2bool(value.as_ref(), format, visitor)
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    {
+        // No need to unescape because valid boolean representations cannot be escaped
+        match format.parse(value) {
+            Some(value) => visitor.visit_bool(value),
+            None => Err(DeError::InvalidBoolean(decoder.decode(value)?.into())),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A structure that deserializes XML into Rust values.
+pub struct Deserializer<'de, R, E: EntityResolver = NoEntityResolver>
+where
+    R: XmlRead<'de>,
+{
+    /// An XML reader that streams events into this deserializer
+    reader: XmlReader<'de, R, E>,
+
+    /// When deserializing sequences sometimes we have to skip unwanted events.
+    /// That events should be stored and then replayed. This is a replay buffer,
+    /// that streams events while not empty. When it exhausted, events will
+    /// requested from [`Self::reader`].
+    #[cfg(feature = "overlapped-lists")]
+    read: VecDeque<DeEvent<'de>>,
+    /// When deserializing sequences sometimes we have to skip events, because XML
+    /// is tolerant to elements order and even if in the XSD order is strictly
+    /// specified (using `xs:sequence`) most of XML parsers allows order violations.
+    /// That means, that elements, forming a sequence, could be overlapped with
+    /// other elements, do not related to that sequence.
+    ///
+    /// In order to support this, deserializer will scan events and skip unwanted
+    /// events, store them here. After call [`Self::start_replay()`] all events
+    /// moved from this to [`Self::read`].
+    #[cfg(feature = "overlapped-lists")]
+    write: VecDeque<DeEvent<'de>>,
+    /// Positions in the input of the events in [`Self::read`]
+    #[cfg(feature = "overlapped-lists")]
+    read_positions: VecDeque<usize>,
+    /// Positions in the input of the events in [`Self::write`]
+    #[cfg(feature = "overlapped-lists")]
+    write_positions: VecDeque<usize>,
+    /// Maximum number of events that can be skipped when processing sequences
+    /// that occur out-of-order. This field is used to prevent potential
+    /// denial-of-service (DoS) attacks which could cause infinite memory
+    /// consumption when parsing a very large amount of XML into a sequence field.
+    #[cfg(feature = "overlapped-lists")]
+    limit: Option<NonZeroUsize>,
+
+    #[cfg(not(feature = "overlapped-lists"))]
+    peek: Option<DeEvent<'de>>,
+    /// Position in the input of the event, returned by the last call to
+    /// [`Self::next()`]
+    position: usize,
+
+    /// A function that rewrites names of elements and attributes before matching
+    name_rewriter: Option<NameRewriter>,
+    /// Functions that preprocess text content of elements with the specified
+    /// local names before it will be deserialized as a scalar value
+    scalar_hooks: HashMap<String, ScalarHook>,
+    /// Which text nodes are captured by a `$text` field. If `None`, each text
+    /// node is reported separately
+    text_capture: Option<TextCapture>,
+    /// How to handle several attributes with the same name on one element
+    duplicate_attributes: DuplicateAttributePolicy,
+    /// If `true`, attributes without values are allowed and deserialized as
+    /// if they have the `"true"` value
+    minimized_bool_attrs: bool,
+    /// If `true`, elements with whitespace-only content are deserialized as
+    /// `None` into `Option` fields
+    whitespace_as_none: bool,
+    /// If `true`, elements without attributes that contain only text are
+    /// deserialized as strings by `deserialize_any`
+    text_elements_as_strings: bool,
+    /// If `true`, errors are wrapped into [`DeError::WithPath`]
+    track_path: bool,
+    /// Number of structs that are currently deserialized. Used to add the name
+    /// of the root element to the error path
+    struct_depth: usize,
+    /// Strings recognized as boolean values
+    bool_format: BoolFormat,
+    /// Representation of binary data. If `None`, only CDATA content can be
+    /// deserialized as bytes
+    bytes_format: Option<BytesFormat>,
+    /// Name of an attribute which contains the name of an enum variant. If
+    /// `None` or if an element has no such attribute, the element name is used
+    variant_attribute: Option<&'static str>,
+    /// Namespace bindings in scope of the events returned by [`Self::next()`].
+    /// Tracked only if recognition of the `xsi:nil` attribute is enabled, which
+    /// marks elements that should be deserialized as `None`
+    namespaces: Option<NamespaceResolver>,
+    /// If `true`, elements and attributes that are not mapped to any field of
+    /// a struct are reported as errors
+    deny_unknown: bool,
+    /// Name of a field that receives text content of an element
+    text_key: &'static str,
+    /// Prefix of names of fields that are mapped to attributes
+    attribute_prefix: &'static str,
+}
+
+impl<'de, R, E> Deserializer<'de, R, E>
+where
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    /// Create an XML deserializer from one of the possible quick_xml input sources.
+    ///
+    /// Typically it is more convenient to use one of these methods instead:
+    ///
+    ///  - [`Deserializer::from_str`]
+    ///  - [`Deserializer::from_reader`]
+    fn new(reader: R, entity_resolver: E) -> Self {
+        Self {
+            reader: XmlReader::new(reader, entity_resolver),
+
+            #[cfg(feature = "overlapped-lists")]
+            read: VecDeque::new(),
+            #[cfg(feature = "overlapped-lists")]
+            write: VecDeque::new(),
+            #[cfg(feature = "overlapped-lists")]
+            read_positions: VecDeque::new(),
+            #[cfg(feature = "overlapped-lists")]
+            write_positions: VecDeque::new(),
+            #[cfg(feature = "overlapped-lists")]
+            limit: None,
+
+            #[cfg(not(feature = "overlapped-lists"))]
+            peek: None,
+            position: 0,
+
+            name_rewriter: None,
+            scalar_hooks: HashMap::new(),
+            text_capture: None,
+            duplicate_attributes: DuplicateAttributePolicy::Error,
+            minimized_bool_attrs: false,
+            whitespace_as_none: false,
+            text_elements_as_strings: false,
+            track_path: false,
+            struct_depth: 0,
+            bool_format: BoolFormat::default(),
+            bytes_format: None,
+            variant_attribute: None,
+            namespaces: None,
+            deny_unknown: false,
+            text_key: TEXT_KEY,
+            attribute_prefix: ATTRIBUTES_KEY,
+        }
+    }
+
+    /// Set a function that will be applied to the qualified name of each element
+    /// and attribute before it will be matched with the field names or enum
+    /// variant names. Use it to perform arbitrary normalization of names.
+    ///
+    /// The result of the rewriter is processed as usual: the local name is used
+    /// for matching and the `@` prefix is added to the attribute names.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     float: f64,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<root><vendor-float>4.2</vendor-float></root>");
+    /// de.with_name_rewriter(|name| name.trim_start_matches("vendor-").to_owned().into());
+    ///
+    /// assert_eq!(Root::deserialize(&mut de).unwrap(), Root { float: 4.2 });
+    /// ```
+    pub fn with_name_rewriter<F>(&mut self, rewriter: F) -> &mut Self
+    where
+        F: Fn(&str) -> Cow<str> + Send + Sync + 'static,
+    {
+        self.name_rewriter = Some(Arc::new(rewriter));
+        self
+    }
+
+    /// Set a function that will be applied to the text content of elements with
+    /// the specified local name before that content will be deserialized as
+    /// a scalar value, such as a string, number or boolean. Use it to normalize
+    /// values before they will be parsed by `Deserialize` implementations.
+    ///
+    /// Only one function can be registered for each name; registering another
+    /// function for the same name replaces the previous one.
+    ///
+    /// The function is applied only when the whole element is deserialized as
+    /// a scalar value. It is not applied to:
+    /// - attribute values, even if the attribute has the same name;
+    /// - the content of `$text` and `$value` fields of a struct deserialized
+    ///   from the element with that name. Register the hook for the element
+    ///   with the text instead or preprocess the text in the `Deserialize`
+    ///   implementation of the field type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     date: String,
+    ///     count: u32,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<root><date>2023-01-02Z</date><count>1_000</count></root>");
+    /// de.with_scalar_hook("date", |text| text.trim_end_matches('Z').into());
+    /// de.with_scalar_hook("count", |text| text.replace('_', "").into());
+    ///
+    /// assert_eq!(
+    ///     Root::deserialize(&mut de).unwrap(),
+    ///     Root { date: "2023-01-02".into(), count: 1000 },
+    /// );
+    /// ```
+    pub fn with_scalar_hook<F>(&mut self, element: &str, hook: F) -> &mut Self
+    where
+        F: Fn(&str) -> Cow<str> + Send + Sync + 'static,
+    {
+        self.scalar_hooks.insert(element.to_owned(), Arc::new(hook));
+        self
+    }
+
+    /// Set a function that will be called for each `<xi:include href="..."/>` element
+    /// with the value of its `href` attribute. The element is replaced by the
+    /// returned events. Includes inside the returned events are resolved
+    /// recursively; cyclic includes are reported as a [`DeError::Custom`] error.
+    ///
+    /// Only `include` elements in the `http://www.w3.org/2001/XInclude` namespace
+    /// are replaced, the prefix bound to that namespace does not matter. Other
+    /// elements named `include` are deserialized as usual.
+    ///
+    /// Empty elements in the returned events are expanded. Comments, processing
+    /// instructions and XML declarations are ignored.
+    ///
+    /// The resolver should be set before deserialization starts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     name: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str(r#"
+    ///     <root xmlns:xi="http://www.w3.org/2001/XInclude">
+    ///         <xi:include href="name.xml"/>
+    ///         <value>42</value>
+    ///     </root>
+    /// "#);
+    /// de.with_include_resolver(|href| {
+    ///     assert_eq!(href, "name.xml");
+    ///     Ok(vec![
+    ///         Event::Start(BytesStart::new("name")),
+    ///         Event::Text(BytesText::new("answer")),
+    ///         Event::End(BytesEnd::new("name")),
+    ///     ])
+    /// });
+    ///
+    /// assert_eq!(
+    ///     Root::deserialize(&mut de).unwrap(),
+    ///     Root { name: "answer".into(), value: 42 },
+    /// );
+    /// ```
+    pub fn with_include_resolver<F>(&mut self, resolver: F) -> &mut Self
+    where
+        F: Fn(&str) -> Result<Vec<Event<'static>>, DeError> + Send + Sync + 'static,
+    {
+        self.reader.set_include_resolver(Box::new(resolver));
+        self
+    }
+
+    /// Set what to do when an entity cannot be resolved neither as a predefined
+    /// entity nor by the [`EntityResolver`]. By default an [`EscapeError::UnrecognizedSymbol`]
+    /// error is returned.
+    ///
+    /// The policy applies only to the text content of elements, attribute values
+    /// are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::{Deserializer, UnknownEntityPolicy};
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct X {
+    ///     #[serde(rename = "$text")]
+    ///     text: String,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<x>a &weird; b</x>");
+    /// de.on_unknown_entity(UnknownEntityPolicy::Keep);
+    ///
+    /// assert_eq!(X::deserialize(&mut de).unwrap().text, "a &weird; b");
+    /// ```
+    pub fn on_unknown_entity(&mut self, policy: UnknownEntityPolicy) -> &mut Self {
+        self.reader.unknown_entity = policy;
+        self
+    }
+
+    /// Set which text nodes are captured by a `$text` field of a struct when
+    /// text is mixed with child elements.
+    ///
+    /// By default each text node is reported as a separate `$text` value, so
+    /// deserialization of a struct from an element with several text nodes
+    /// fails with a "duplicate field `$text`" error.
+    ///
+    /// Has no effect on structs with a `$value` field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::{Deserializer, TextCapture};
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     #[serde(rename = "$text")]
+    ///     text: String,
+    ///     a: (),
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<root>pre<a/>post</root>");
+    /// de.text_capture(TextCapture::All);
+    ///
+    /// assert_eq!(Root::deserialize(&mut de).unwrap().text, "prepost");
+    /// ```
+    pub fn text_capture(&mut self, capture: TextCapture) -> &mut Self {
+        self.text_capture = Some(capture);
+        self
+    }
+
+    /// Set how several attributes with the same qualified name on one element
+    /// are handled. XML forbids such attributes, so by default an error is
+    /// returned. Use this method to accept such malformed documents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::{Deserializer, DuplicateAttributePolicy};
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     #[serde(rename = "@a")]
+    ///     a: u32,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str(r#"<root a="1" a="2"/>"#);
+    /// assert!(Root::deserialize(&mut de).is_err());
+    ///
+    /// let mut de = Deserializer::from_str(r#"<root a="1" a="2"/>"#);
+    /// de.duplicate_attributes(DuplicateAttributePolicy::Last);
+    /// assert_eq!(Root::deserialize(&mut de).unwrap(), Root { a: 2 });
+    /// ```
+    pub fn duplicate_attributes(&mut self, policy: DuplicateAttributePolicy) -> &mut Self {
+        self.duplicate_attributes = policy;
+        self
+    }
+
+    /// Set strings that are recognized as boolean values when deserializing
+    /// a `bool` from text, CDATA or attribute content.
+    ///
+    /// By default the [`BoolFormat::default()`] set is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::{BoolFormat, Deserializer};
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     #[serde(rename = "@enabled")]
+    ///     enabled: bool,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str(r#"<root enabled="on"/>"#);
+    /// de.bool_format(BoolFormat {
+    ///     truthy: &["on"],
+    ///     falsy: &["off"],
+    /// });
+    ///
+    /// assert_eq!(Root::deserialize(&mut de).unwrap(), Root { enabled: true });
+    /// ```
+    pub fn bool_format(&mut self, format: BoolFormat) -> &mut Self {
+        self.bool_format = format;
+        self
+    }
+
+    /// Set how binary data is represented in text, CDATA or attribute content.
+    /// Affects types that request bytes from the deserializer, for example
+    /// [`serde_bytes::ByteBuf`].
+    ///
+    /// By default binary data is not decoded: content of CDATA sections is
+    /// returned as is and text content and attribute values cannot be
+    /// deserialized as bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::{BytesFormat, Deserializer};
+    /// # use quick_xml::utils::ByteBuf;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     data: ByteBuf,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<root><data>AQID</data></root>");
+    /// de.bytes_format(BytesFormat::Base64);
+    /// assert_eq!(Root::deserialize(&mut de).unwrap(), Root { data: ByteBuf(vec![1, 2, 3]) });
+    /// ```
+    ///
+    /// [`serde_bytes::ByteBuf`]: https://docs.rs/serde_bytes/latest/serde_bytes/struct.ByteBuf.html
+    pub fn bytes_format(&mut self, format: BytesFormat) -> &mut Self {
+        self.bytes_format = Some(format);
+        self
+    }
+
+    /// Enable or disable reading of attributes without values (`<input disabled/>`),
+    /// which are not allowed by the XML specification, but used in HTML. Such
+    /// attributes are deserialized as if they have the `"true"` value. Unquoted
+    /// attribute values are allowed too. Defaults to `false`.
+    ///
+    /// Use [`Serializer::minimized_bool_attrs`] to write boolean attributes
+    /// in that form. Because `false` attributes are not written at all, mark
+    /// such fields with `#[serde(default)]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Input {
+    ///     #[serde(rename = "@disabled", default)]
+    ///     disabled: bool,
+    ///     #[serde(rename = "@checked", default)]
+    ///     checked: bool,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<input disabled/>");
+    /// de.minimized_bool_attrs(true);
+    ///
+    /// assert_eq!(
+    ///     Input::deserialize(&mut de).unwrap(),
+    ///     Input {
+    ///         disabled: true,
+    ///         checked: false,
+    ///     }
+    /// );
+    /// ```
+    ///
+    /// [`Serializer::minimized_bool_attrs`]: crate::se::Serializer::minimized_bool_attrs
+    pub fn minimized_bool_attrs(&mut self, enable: bool) -> &mut Self {
+        self.minimized_bool_attrs = enable;
+        self
+    }
+
+    /// Enable or disable deserialization of elements with whitespace-only
+    /// content, such as `<opt>\n  </opt>`, into `None` when an `Option` is
+    /// expected. The same is applied to empty elements (`<opt/>`). Defaults to
+    /// `false`, in which case such elements are deserialized as `Some` with
+    /// the value deserialized from an empty string.
+    ///
+    /// Leading and trailing spaces are not considered significant and are
+    /// always trimmed from the text, so whitespace-only text is not passed
+    /// to non-`Option` types at all: for example, a [`String`] gets an empty
+    /// string. Text with other characters and CDATA sections, even those
+    /// containing only spaces, are kept intact. Attribute values are not
+    /// affected by this option.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     string: Option<String>,
+    ///     number: Option<i32>,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str(r#"
+    ///     <root>
+    ///         <string>
+    ///         </string>
+    ///         <number>
+    ///         </number>
+    ///     </root>
+    /// "#);
+    /// de.whitespace_as_none(true);
+    ///
+    /// assert_eq!(
+    ///     Root::deserialize(&mut de).unwrap(),
+    ///     Root {
+    ///         string: None,
+    ///         number: None,
+    ///     }
+    /// );
+    /// ```
+    pub fn whitespace_as_none(&mut self, enable: bool) -> &mut Self {
+        self.whitespace_as_none = enable;
+        self
+    }
+
+    /// If set, elements without attributes that contain only text are
+    /// deserialized as strings when the type does not tell what it expects
+    /// (that is, by [`deserialize_any`]). This allows to capture unknown
+    /// elements by a `#[serde(flatten)]` map of strings. By default such
+    /// elements are deserialized as maps with a `$text` key.
+    ///
+    /// Because the same text element can not be deserialized as a struct with
+    /// a `$text` field anymore, this option breaks flattened structs and
+    /// untagged enums with such fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// use std::collections::HashMap;
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Root {
+    ///     known: u32,
+    ///     #[serde(flatten)]
+    ///     extra: HashMap<String, String>,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<e><known>1</known><extra>2</extra></e>");
+    /// de.text_elements_as_strings(true);
+    ///
+    /// assert_eq!(
+    ///     Root::deserialize(&mut de).unwrap(),
+    ///     Root {
+    ///         known: 1,
+    ///         extra: HashMap::from([("extra".to_string(), "2".to_string())]),
+    ///     }
+    /// );
+    /// ```
+    ///
+    /// [`deserialize_any`]: serde::Deserializer::deserialize_any
+    pub fn text_elements_as_strings(&mut self, enable: bool) -> &mut Self {
+        self.text_elements_as_strings = enable;
+        self
+    }
+
+    /// Enables or disables recognition of the [`xsi:nil`] attribute. If enabled,
+    /// elements with that attribute set to `true` (or `1`) are deserialized as
+    /// `None` when an `Option` is expected, regardless of their content.
+    /// Default is `false`, which means that the attribute is treated as any
+    /// other attribute.
+    ///
+    /// The attribute is recognized by its namespace, which should be
+    /// `http://www.w3.org/2001/XMLSchema-instance`, so any prefix bound to that
+    /// namespace can be used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Person {
+    ///     name: String,
+    ///     email: Option<String>,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str(r#"
+    ///     <person xmlns:x="http://www.w3.org/2001/XMLSchema-instance">
+    ///         <name>Alice</name>
+    ///         <email x:nil="true"/>
+    ///     </person>
+    /// "#);
+    /// de.xsi_nil(true);
+    ///
+    /// assert_eq!(
+    ///     Person::deserialize(&mut de).unwrap(),
+    ///     Person {
+    ///         name: "Alice".to_string(),
+    ///         email: None,
+    ///     }
+    /// );
+    /// ```
+    ///
+    /// [`xsi:nil`]: https://www.w3.org/TR/xmlschema-1/#xsi_nil
+    pub fn xsi_nil(&mut self, enable: bool) -> &mut Self {
+        self.namespaces = if enable {
+            Some(NamespaceResolver::default())
+        } else {
+            None
+        };
+        self
+    }
+
+    /// Enables or disables reporting of elements and attributes that are not
+    /// mapped to any field of a struct. Default is `false`, which means that
+    /// such elements and attributes are skipped.
+    ///
+    /// When enabled, they are reported as [`DeError::UnknownField`], as if
+    /// `#[serde(deny_unknown_fields)]` would be applied to each struct. Attribute
+    /// names are reported with the `@` prefix. Namespace declarations and the
+    /// [`variant_attribute`] are never reported.
+    ///
+    /// Unknown elements are not reported for structs with a `$value` field, which
+    /// receives them, and unknown attributes are not reported for structs with
+    /// a `@` field, which receives them. Maps and structs with flattened fields
+    /// are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use quick_xml::DeError;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Person {
+    ///     name: String,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<person><name>Alice</name><age>42</age></person>");
+    /// de.deny_unknown(true);
+    ///
+    /// match Person::deserialize(&mut de) {
+    ///     Err(DeError::UnknownField { name, position }) => {
+    ///         assert_eq!(name, "age");
+    ///         assert_eq!(position, 26);
+    ///     }
+    ///     x => panic!("Expected `Err(UnknownField {{ .. }})`, but got `{:?}`", x),
+    /// }
+    /// ```
+    ///
+    /// [`variant_attribute`]: Self::variant_attribute
+    pub fn deny_unknown(&mut self, deny: bool) -> &mut Self {
+        self.deny_unknown = deny;
+        self
+    }
+
+    /// Sets the name of a field which receives text content of an element.
+    /// Default is `$text`. Change it if the default name collides with names
+    /// used by your types.
+    ///
+    /// Enum variants with that name are also used to deserialize text content.
+    /// Use [`Serializer::text_field_name`] to write such fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Note {
+    ///     #[serde(rename = "#text")]
+    ///     content: String,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<note>content</note>");
+    /// de.text_field_name("#text");
+    ///
+    /// let note = Note::deserialize(&mut de).unwrap();
+    /// assert_eq!(note.content, "content");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty.
+    ///
+    /// [`Serializer::text_field_name`]: crate::se::Serializer::text_field_name
+    pub fn text_field_name(&mut self, name: &'static str) -> &mut Self {
+        assert!(!name.is_empty(), "text field name must not be empty");
+        self.text_key = name;
+        self
+    }
+
+    /// Sets the prefix of names of fields which are mapped to attributes.
+    /// Default is `@`. A field which name is equal to the prefix itself receives
+    /// all attributes that are not mapped to other fields.
+    ///
+    /// Use [`Serializer::attribute_prefix`] to write such fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Item {
+    ///     #[serde(rename = "attr_id")]
+    ///     id: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str(r#"<item id="1"><name>first</name></item>"#);
+    /// de.attribute_prefix("attr_");
+    ///
+    /// let item = Item::deserialize(&mut de).unwrap();
+    /// assert_eq!(item, Item { id: 1, name: "first".into() });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `prefix` is empty, because every field would be considered
+    /// as an attribute in that case.
+    ///
+    /// [`Serializer::attribute_prefix`]: crate::se::Serializer::attribute_prefix
+    pub fn attribute_prefix(&mut self, prefix: &'static str) -> &mut Self {
+        assert!(!prefix.is_empty(), "attribute prefix must not be empty");
+        self.attribute_prefix = prefix;
+        self
+    }
+
+    /// Enables or disables removing of leading and trailing whitespaces from
+    /// text. Default is `true`.
+    ///
+    /// When disabled, text is passed to the visitors as is, which is required
+    /// to keep spaces between words in mixed content, such as
+    /// `<p>Hello <b>world</b>!</p>`. Text that consists only of whitespaces,
+    /// such as the space in `<b>Hello</b> <i>world</i>`, is also kept when it
+    /// is consumed by a `$value` or `$text` field. Such text between other child
+    /// elements, for example, the indentation of a pretty-printed document, and
+    /// outside of the root element is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// enum Inline {
+    ///     #[serde(rename = "$text")]
+    ///     Text(String),
+    ///     #[serde(rename = "b")]
+    ///     Bold(String),
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Paragraph {
+    ///     #[serde(rename = "$value")]
+    ///     content: Vec<Inline>,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str("<p>Hello <b>world</b>!</p>");
+    /// de.trim_text(false);
+    ///
+    /// assert_eq!(
+    ///     Paragraph::deserialize(&mut de).unwrap(),
+    ///     Paragraph {
+    ///         content: vec![
+    ///             Inline::Text("Hello ".into()),
+    ///             Inline::Bold("world".into()),
+    ///             Inline::Text("!".into()),
+    ///         ],
+    ///     }
+    /// );
+    /// ```
+    pub fn trim_text(&mut self, trim: bool) -> &mut Self {
+        self.reader.trim_text = trim;
+        self.reader.reader.trim_text(trim);
+        self
+    }
+
+    /// If set, errors are reported as [`DeError::WithPath`] with the names of
+    /// elements and attributes that lead from the root element to the place
+    /// where the error occurred. Default is `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, Deserialize)]
+    /// struct Root {
+    ///     item: Vec<Item>,
+    /// }
+    /// #[derive(Debug, Deserialize)]
+    /// struct Item {
+    ///     price: u32,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str(r#"
+    ///     <root>
+    ///         <item><price>1</price></item>
+    ///         <item><price>x</price></item>
+    ///     </root>
+    /// "#);
+    /// de.track_path(true);
+    ///
+    /// assert_eq!(
+    ///     Root::deserialize(&mut de).unwrap_err().to_string(),
+    ///     "at root > item[1] > price: invalid digit found in string",
+    /// );
+    /// ```
+    pub fn track_path(&mut self, enable: bool) -> &mut Self {
+        self.track_path = enable;
+        self
+    }
+
+    /// Set the name of an attribute from which the name of an enum variant is
+    /// read. The variant content is deserialized from the same element, so
+    /// fields of struct variants can be stored in attributes and children of
+    /// that element. Elements without that attribute select variant by their
+    /// names, as by default.
+    ///
+    /// Use [`Serializer::variant_attribute`] to write enums in that form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// enum Shape {
+    ///     Circle {
+    ///         #[serde(rename = "@radius")]
+    ///         radius: f64,
+    ///     },
+    ///     Point,
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize)]
+    /// struct Drawing {
+    ///     shape: Vec<Shape>,
+    /// }
+    ///
+    /// let mut de = Deserializer::from_str(
+    ///     r#"<drawing><shape type="Circle" radius="1.5"/><shape type="Point"/></drawing>"#,
+    /// );
+    /// de.variant_attribute("type");
+    ///
+    /// assert_eq!(
+    ///     Drawing::deserialize(&mut de).unwrap(),
+    ///     Drawing {
+    ///         shape: vec![Shape::Circle { radius: 1.5 }, Shape::Point],
+    ///     }
+    /// );
+    /// ```
+    ///
+    /// [`Serializer::variant_attribute`]: crate::se::Serializer::variant_attribute
+    pub fn variant_attribute(&mut self, name: &'static str) -> &mut Self {
+        self.variant_attribute = Some(name);
+        self
+    }
+
+    /// Set the maximum nesting depth of elements. If an element is nested deeper,
+    /// [`DeError::DepthLimitExceeded`] is returned.
+    ///
+    /// Deserialization of nested elements is recursive, so this method can be
+    /// used to prevent a stack overflow when parsing data from untrusted sources.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use quick_xml::de::Deserializer;
+    /// # use quick_xml::DeError;
+    /// # use serde::Deserialize;
+    /// # use std::collections::HashMap;
+    /// # use std::num::NonZeroUsize;
+    /// let mut de = Deserializer::from_str("<a><b><c>text</c></b></a>");
+    /// de.max_depth(NonZeroUsize::new(2));
+    ///
+    /// assert!(matches!(
+    ///     HashMap::<String, HashMap<String, String>>::deserialize(&mut de),
+    ///     Err(DeError::DepthLimitExceeded(_)),
+    /// ));
+    /// ```
+    pub fn max_depth(&mut self, limit: Option<NonZeroUsize>) -> &mut Self {
+        self.reader.max_depth = limit;
+        self
     }
 
-    #[cfg(not(feature = "encoding"))]
-    {
-        // No need to unescape because valid boolean representations cannot be escaped
-        match value {
-            b"true" | b"1" | b"True" | b"TRUE" | b"t" | b"Yes" | b"YES" | b"yes" | b"y" => {
-                visitor.visit_bool(true)
-            }
-            b"false" | b"0" | b"False" | b"FALSE" | b"f" | b"No" | b"NO" | b"no" | b"n" => {
-                visitor.visit_bool(false)
-            }
-            e => Err(DeError::InvalidBoolean(decoder.decode(e)?.into())),
-        }
+    /// Set the maximum number of events that deserializer can read from the
+    /// input. Each start tag and text node (all consequent text and CDATA nodes
+    /// are counted as one) is an event. If the input contains more events,
+    /// [`DeError::EventLimitExceeded`] is returned.
+    pub fn max_events(&mut self, limit: Option<NonZeroUsize>) -> &mut Self {
+        self.reader.max_events = limit;
+        self
     }
-}
-
-////////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A structure that deserializes XML into Rust values.
-pub struct Deserializer<'de, R, E: EntityResolver = NoEntityResolver>
-where
-    R: XmlRead<'de>,
-{
-    /// An XML reader that streams events into this deserializer
-    reader: XmlReader<'de, R, E>,
+    /// Set the maximum length in bytes of a text content. Consequent text and
+    /// CDATA nodes are merged and counted as one text, length is counted after
+    /// unescaping. If the input contains a longer text, [`DeError::TextLimitExceeded`]
+    /// is returned.
+    ///
+    /// The limit is checked after a text node was read, so when reading from
+    /// a [`BufRead`] the buffer of the reader can still grow up to the length of
+    /// one text node. Use [`Read::take`] to limit the size of the whole input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use quick_xml::de::Deserializer;
+    /// # use quick_xml::DeError;
+    /// # use serde::Deserialize;
+    /// # use std::num::NonZeroUsize;
+    /// let mut de = Deserializer::from_str("<text>long text<![CDATA[ and CDATA]]></text>");
+    /// de.max_text_len(NonZeroUsize::new(16));
+    ///
+    /// assert!(matches!(
+    ///     String::deserialize(&mut de),
+    ///     Err(DeError::TextLimitExceeded(_)),
+    /// ));
+    /// ```
+    ///
+    /// [`Read::take`]: std::io::Read::take
+    pub fn max_text_len(&mut self, limit: Option<NonZeroUsize>) -> &mut Self {
+        self.reader.max_text_len = limit;
+        self
+    }
 
-    /// When deserializing sequences sometimes we have to skip unwanted events.
-    /// That events should be stored and then replayed. This is a replay buffer,
-    /// that streams events while not empty. When it exhausted, events will
-    /// requested from [`Self::reader`].
-    #[cfg(feature = "overlapped-lists")]
-    read: VecDeque<DeEvent<'de>>,
-    /// When deserializing sequences sometimes we have to skip events, because XML
-    /// is tolerant to elements order and even if in the XSD order is strictly
-    /// specified (using `xs:sequence`) most of XML parsers allows order violations.
-    /// That means, that elements, forming a sequence, could be overlapped with
-    /// other elements, do not related to that sequence.
+    /// Set the maximum total length in bytes of replacement text of all entities
+    /// resolved by the [`EntityResolver`] in the document. If entities are
+    /// expanded to a longer text, [`DeError::ExpansionLimitExceeded`] is returned.
     ///
-    /// In order to support this, deserializer will scan events and skip unwanted
-    /// events, store them here. After call [`Self::start_replay()`] all events
-    /// moved from this to [`Self::read`].
-    #[cfg(feature = "overlapped-lists")]
-    write: VecDeque<DeEvent<'de>>,
-    /// Maximum number of events that can be skipped when processing sequences
-    /// that occur out-of-order. This field is used to prevent potential
-    /// denial-of-service (DoS) attacks which could cause infinite memory
-    /// consumption when parsing a very large amount of XML into a sequence field.
-    #[cfg(feature = "overlapped-lists")]
-    limit: Option<NonZeroUsize>,
+    /// Use this limit to protect from the ["billion laughs"] attack when your
+    /// resolver expands entities defined in the DTD.
+    ///
+    /// ["billion laughs"]: https://en.wikipedia.org/wiki/Billion_laughs_attack
+    pub fn max_entity_expansion(&mut self, limit: Option<NonZeroUsize>) -> &mut Self {
+        self.reader.max_entity_expansion = limit;
+        self
+    }
 
-    #[cfg(not(feature = "overlapped-lists"))]
-    peek: Option<DeEvent<'de>>,
-}
+    /// Sets all limits to the values used by [`Deserializer::untrusted`]
+    fn untrusted_limits(&mut self) -> &mut Self {
+        self.max_depth(NonZeroUsize::new(128))
+            .max_events(NonZeroUsize::new(1_000_000))
+            .max_text_len(NonZeroUsize::new(1 << 20))
+            .max_entity_expansion(NonZeroUsize::new(1 << 16));
+        #[cfg(feature = "overlapped-lists")]
+        self.event_buffer_size(NonZeroUsize::new(1024));
+        self
+    }
 
-impl<'de, R, E> Deserializer<'de, R, E>
-where
-    R: XmlRead<'de>,
-    E: EntityResolver,
-{
-    /// Create an XML deserializer from one of the possible quick_xml input sources.
+    /// Returns the number of top-level elements read from the input so far.
     ///
-    /// Typically it is more convenient to use one of these methods instead:
+    /// Use this method after deserialization of a top-level sequence (several
+    /// concatenated XML documents) to check how many elements were consumed.
     ///
-    ///  - [`Deserializer::from_str`]
-    ///  - [`Deserializer::from_reader`]
-    fn new(reader: R, entity_resolver: E) -> Self {
-        Self {
-            reader: XmlReader::new(reader, entity_resolver),
-
-            #[cfg(feature = "overlapped-lists")]
-            read: VecDeque::new(),
-            #[cfg(feature = "overlapped-lists")]
-            write: VecDeque::new(),
-            #[cfg(feature = "overlapped-lists")]
-            limit: None,
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::de::Deserializer;
+    /// # use serde::Deserialize;
+    /// let mut de = Deserializer::from_str("<item>1</item><item>2</item>");
+    ///
+    /// assert_eq!(Vec::<u32>::deserialize(&mut de).unwrap(), vec![1, 2]);
+    /// assert_eq!(de.top_level_elements(), 2);
+    /// ```
+    pub fn top_level_elements(&self) -> usize {
+        self.reader.top_level
+    }
 
-            #[cfg(not(feature = "overlapped-lists"))]
-            peek: None,
+    /// Checks that the input contains nothing except whitespaces, comments and
+    /// processing instructions after the already deserialized value. Call this
+    /// method after deserialization to reject documents with trailing data.
+    ///
+    /// Returns [`DeError::TrailingData`] if any element or text follows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use quick_xml::de::Deserializer;
+    /// # use quick_xml::DeError;
+    /// # use serde::Deserialize;
+    /// let mut de = Deserializer::from_str("<root/><!-- comment --><root/>");
+    ///
+    /// assert_eq!(<()>::deserialize(&mut de).unwrap(), ());
+    /// assert!(matches!(de.end(), Err(DeError::TrailingData)));
+    /// ```
+    pub fn end(&mut self) -> Result<(), DeError> {
+        match self.next()? {
+            DeEvent::Eof => Ok(()),
+            _ => Err(DeError::TrailingData),
         }
     }
 
@@ -2445,6 +4163,7 @@ where
     fn peek(&mut self) -> Result<&DeEvent<'de>, DeError> {
         if self.read.is_empty() {
             self.read.push_front(self.reader.next()?);
+            self.read_positions.push_front(self.reader.start);
         }
         if let Some(event) = self.read.front() {
             return Ok(event);
@@ -2470,16 +4189,126 @@ where
     }
 
     fn next(&mut self) -> Result<DeEvent<'de>, DeError> {
+        let event = self.next_event()?;
+        match (&mut self.namespaces, &event) {
+            (Some(namespaces), DeEvent::Start(e)) => namespaces.push(e)?,
+            (Some(namespaces), DeEvent::End(_)) => namespaces.pop(),
+            _ => {}
+        }
+        Ok(event)
+    }
+
+    fn next_event(&mut self) -> Result<DeEvent<'de>, DeError> {
         // Replay skipped or peeked events
         #[cfg(feature = "overlapped-lists")]
         if let Some(event) = self.read.pop_front() {
+            self.position = self.read_positions.pop_front().unwrap_or_default();
             return Ok(event);
         }
         #[cfg(not(feature = "overlapped-lists"))]
         if let Some(e) = self.peek.take() {
+            self.position = self.reader.start;
             return Ok(e);
         }
-        self.reader.next()
+        let event = self.reader.next();
+        self.position = self.reader.start;
+        event
+    }
+
+    /// Closes the namespace scope of an element which end event was consumed
+    /// without [`Self::next()`]
+    #[inline]
+    fn end_namespace_scope(&mut self) {
+        if let Some(namespaces) = &mut self.namespaces {
+            namespaces.pop();
+        }
+    }
+
+    /// Returns the position in the input of the event, returned by the last
+    /// call to [`Self::peek()`]
+    fn peek_position(&self) -> usize {
+        #[cfg(feature = "overlapped-lists")]
+        if let Some(position) = self.read_positions.front() {
+            return *position;
+        }
+        self.reader.start
+    }
+
+    /// Consumes text events that consist only of whitespaces. Such events are
+    /// only returned when [text trimming] is disabled, and should be skipped
+    /// between elements unless some field consumes the text content.
+    ///
+    /// [text trimming]: Self::trim_text
+    fn skip_whitespaces(&mut self) -> Result<(), DeError> {
+        if self.reader.trim_text {
+            return Ok(());
+        }
+        while let DeEvent::Text(t) = self.peek()? {
+            if t.cdata || !t.bytes().all(is_whitespace) {
+                break;
+            }
+            self.next()?;
+        }
+        Ok(())
+    }
+
+    /// If the next event is a start of an element without attributes and any
+    /// content, consumes that element and returns `true`. Whitespace-only text
+    /// is already skipped by the reader, so such elements are considered as
+    /// empty too
+    fn skip_empty_element(&mut self) -> Result<bool, DeError> {
+        match self.peek()? {
+            DeEvent::Start(e) if e.attributes().next().is_none() => {}
+            _ => return Ok(false),
+        }
+        // The start event is in the peek buffer, so check the event after it
+        #[cfg(feature = "overlapped-lists")]
+        let is_empty = match self.read.get(1) {
+            Some(event) => matches!(event, DeEvent::End(_)),
+            None => self.reader.is_end_next(),
+        };
+        #[cfg(not(feature = "overlapped-lists"))]
+        let is_empty = self.reader.is_end_next();
+
+        if is_empty {
+            self.next()?;
+            self.next()?;
+        }
+        Ok(is_empty)
+    }
+
+    /// Consumes the next element if it has the [`xsi:nil`](Self::xsi_nil) attribute
+    /// set to `true` or `1`. Returns `true` if the element was consumed.
+    fn skip_nil_element(&mut self) -> Result<bool, DeError> {
+        if self.namespaces.is_none() {
+            return Ok(false);
+        }
+        let start = match self.peek()? {
+            DeEvent::Start(e) => e.clone(),
+            _ => return Ok(false),
+        };
+        let is_nil = match &mut self.namespaces {
+            Some(namespaces) => {
+                // The attribute can be bound to the namespace by the element itself
+                namespaces.push(&start)?;
+                let is_nil = start.attributes().with_checks(false).flatten().any(|a| {
+                    matches!(
+                        namespaces.resolve(a.key, false),
+                        (ResolveResult::Bound(ns), local)
+                            if ns.into_inner() == XSI_NAMESPACE && local.as_ref() == b"nil"
+                    ) && matches!(&*a.value, b"true" | b"1")
+                });
+                namespaces.pop();
+                is_nil
+            }
+            None => false,
+        };
+        if is_nil {
+            if let DeEvent::Start(e) = self.next()? {
+                self.read_to_end(e.name())?;
+            }
+        }
+        Ok(is_nil)
     }
 
     /// Returns the mark after which all events, skipped by [`Self::skip()`] call,
@@ -2540,6 +4369,7 @@ where
             }
         }
         self.write.push_back(event);
+        self.write_positions.push_back(self.position);
         Ok(())
     }
 
@@ -2558,10 +4388,15 @@ where
         if checkpoint == 0 {
             self.write.append(&mut self.read);
             std::mem::swap(&mut self.read, &mut self.write);
+            self.write_positions.append(&mut self.read_positions);
+            std::mem::swap(&mut self.read_positions, &mut self.write_positions);
         } else {
             let mut read = self.write.split_off(checkpoint);
             read.append(&mut self.read);
             self.read = read;
+            let mut read_positions = self.write_positions.split_off(checkpoint);
+            read_positions.append(&mut self.read_positions);
+            self.read_positions = read_positions;
         }
     }
 
@@ -2570,6 +4405,16 @@ where
         self.read_string_impl(true)
     }
 
+    #[inline]
+    fn read_bool<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value, DeError> {
+        self.read_bool_impl(true, visitor)
+    }
+
+    #[inline]
+    fn read_bytes<V: Visitor<'de>>(&mut self, visitor: V) -> Result<V::Value, DeError> {
+        self.read_bytes_impl(true, visitor)
+    }
+
     /// Consumes consequent [`Text`] and [`CData`] (both a referred below as a _text_)
     /// events, merge them into one string. If there are no such events, returns
     /// an empty string.
@@ -2604,33 +4449,115 @@ where
     /// [`Text`]: Event::Text
     /// [`CData`]: Event::CData
     fn read_string_impl(&mut self, allow_start: bool) -> Result<Cow<'de, str>, DeError> {
+        Ok(self.read_text_impl(allow_start)?.text)
+    }
+
+    /// Reads a string as [`Self::read_string_impl`] does and converts it to
+    /// a `bool` according to the [`Self::bool_format`] setting
+    fn read_bool_impl<V: Visitor<'de>>(
+        &mut self,
+        allow_start: bool,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let text = self.read_string_impl(allow_start)?;
+        str2bool(&text, &self.bool_format, visitor)
+    }
+
+    /// Reads a text as [`Self::read_text_impl`] does and converts it to bytes
+    /// according to the [`Self::bytes_format`] setting. Without that setting
+    /// only content of CDATA sections can be returned as bytes
+    fn read_bytes_impl<V: Visitor<'de>>(
+        &mut self,
+        allow_start: bool,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let text = self.read_text_impl(allow_start)?;
+        if let Some(format) = self.bytes_format {
+            return visitor.visit_byte_buf(format.decode(&text.text)?);
+        }
+        if !text.cdata && !text.is_empty() {
+            return Err(DeError::Unsupported(
+                "binary data content is not supported by XML format".into(),
+            ));
+        }
+        match text.text {
+            Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes.as_bytes()),
+            Cow::Owned(bytes) => visitor.visit_byte_buf(bytes.into_bytes()),
+        }
+    }
+
+    /// The same as [`Self::read_string_impl`], but also returns whether the
+    /// text was assembled only from CDATA sections
+    fn read_text_impl(&mut self, allow_start: bool) -> Result<Text<'de>, DeError> {
         match self.next()? {
-            DeEvent::Text(e) => Ok(e.text),
+            DeEvent::Text(e) => Ok(e),
             // allow one nested level
-            DeEvent::Start(e) if allow_start => match self.next()? {
-                DeEvent::Text(t) => {
-                    self.read_to_end(e.name())?;
-                    Ok(t.text)
-                }
-                DeEvent::Start(s) => Err(DeError::UnexpectedStart(s.name().as_ref().to_owned())),
-                // We can get End event in case of `<tag></tag>` or `<tag/>` input
-                // Return empty text in that case
-                DeEvent::End(end) if end.name() == e.name() => Ok("".into()),
-                DeEvent::End(end) => Err(DeError::UnexpectedEnd(end.name().as_ref().to_owned())),
-                DeEvent::Eof => Err(DeError::UnexpectedEof),
-            },
+            DeEvent::Start(e) if allow_start => {
+                let text = match self.next()? {
+                    DeEvent::Text(t) => {
+                        self.read_to_end(e.name())?;
+                        t
+                    }
+                    DeEvent::Start(s) => {
+                        return Err(DeError::UnexpectedStart(s.name().as_ref().to_owned()))
+                    }
+                    // We can get End event in case of `<tag></tag>` or `<tag/>` input
+                    // Return empty text in that case
+                    DeEvent::End(end) if end.name() == e.name() => "".into(),
+                    DeEvent::End(end) => {
+                        return Err(DeError::UnexpectedEnd(end.name().as_ref().to_owned()))
+                    }
+                    DeEvent::Eof => return Err(DeError::UnexpectedEof),
+                };
+                Ok(self.apply_scalar_hook(&e, text))
+            }
             DeEvent::Start(e) => Err(DeError::UnexpectedStart(e.name().as_ref().to_owned())),
             DeEvent::End(e) => Err(DeError::UnexpectedEnd(e.name().as_ref().to_owned())),
-            DeEvent::Eof => Err(DeError::UnexpectedEof),
+            DeEvent::Eof => Err(self.reader.eof_error()),
+        }
+    }
+
+    /// Applies a function registered by [`Self::with_scalar_hook`] for the
+    /// element `start` to the `text` content of that element
+    fn apply_scalar_hook(&self, start: &BytesStart, text: Text<'de>) -> Text<'de> {
+        if self.scalar_hooks.is_empty() {
+            return text;
+        }
+        let decoder = self.reader.decoder();
+        let hook = match decoder.decode(start.local_name().into_inner()) {
+            Ok(name) => match self.scalar_hooks.get(name.as_ref()) {
+                Some(hook) => hook,
+                None => return text,
+            },
+            Err(_) => return text,
+        };
+        let content = match text.text {
+            Cow::Borrowed(s) => hook(s),
+            Cow::Owned(s) => Cow::Owned(hook(&s).into_owned()),
+        };
+        Text {
+            text: content,
+            cdata: text.cdata,
         }
     }
 
     /// Drops all events until event with [name](BytesEnd::name()) `name` won't be
     /// dropped. This method should be called after [`Self::next()`]
-    #[cfg(feature = "overlapped-lists")]
     fn read_to_end(&mut self, name: QName) -> Result<(), DeError> {
+        // Dropped events do not change namespace bindings in scope, so disable
+        // tracking and just close the scope of the element
+        let namespaces = self.namespaces.take();
+        let result = self.drop_to_end(name);
+        self.namespaces = namespaces;
+        self.end_namespace_scope();
+        result
+    }
+
+    #[cfg(feature = "overlapped-lists")]
+    fn drop_to_end(&mut self, name: QName) -> Result<(), DeError> {
         let mut depth = 0;
         loop {
+            self.read_positions.pop_front();
             match self.read.pop_front() {
                 Some(DeEvent::Start(e)) if e.name() == name => {
                     depth += 1;
@@ -2672,14 +4599,17 @@ where
         Ok(())
     }
     #[cfg(not(feature = "overlapped-lists"))]
-    fn read_to_end(&mut self, name: QName) -> Result<(), DeError> {
+    fn drop_to_end(&mut self, name: QName) -> Result<(), DeError> {
         // First one might be in self.peek
         match self.next()? {
-            DeEvent::Start(e) => self.reader.read_to_end(e.name())?,
+            DeEvent::Start(e) => {
+                self.reader.read_to_end(e.name())?;
+            }
             DeEvent::End(e) if e.name() == name => return Ok(()),
             _ => (),
         }
-        self.reader.read_to_end(name)
+        self.reader.read_to_end(name)?;
+        Ok(())
     }
 }
 
@@ -2691,6 +4621,53 @@ impl<'de> Deserializer<'de, SliceReader<'de>> {
     pub fn from_str(source: &'de str) -> Self {
         Self::from_str_with_resolver(source, NoEntityResolver)
     }
+
+    /// Create new deserializer that will borrow data from the specified string
+    /// and is configured for parsing data from untrusted sources. All limits
+    /// are set to the conservative values:
+    ///
+    /// - [`max_depth`] is 128;
+    /// - [`max_events`] is 1 000 000;
+    /// - [`max_text_len`] is 1 MiB;
+    /// - [`max_entity_expansion`] is 64 KiB;
+    /// - [`event_buffer_size`] is 1024, if the `overlapped-lists` feature is enabled.
+    ///
+    /// As [`Self::from_str`], the deserializer does not resolve custom entities,
+    /// so entities defined in the DTD cannot be expanded. You can change any of
+    /// the limits after creation.
+    ///
+    /// Use [`Deserializer::untrusted_reader`] to read untrusted data from a [`BufRead`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use quick_xml::de::Deserializer;
+    /// # use quick_xml::DeError;
+    /// # use serde::Deserialize;
+    /// #[derive(Debug, Deserialize)]
+    /// struct Node {
+    ///     node: Option<Box<Node>>,
+    /// }
+    ///
+    /// let xml = format!("{}{}", "<node>".repeat(1000), "</node>".repeat(1000));
+    /// let mut de = Deserializer::untrusted(&xml);
+    ///
+    /// assert!(matches!(
+    ///     Node::deserialize(&mut de),
+    ///     Err(DeError::DepthLimitExceeded(_)),
+    /// ));
+    /// ```
+    ///
+    /// [`max_depth`]: Self::max_depth
+    /// [`max_events`]: Self::max_events
+    /// [`max_text_len`]: Self::max_text_len
+    /// [`max_entity_expansion`]: Self::max_entity_expansion
+    /// [`event_buffer_size`]: Self::event_buffer_size
+    pub fn untrusted(source: &'de str) -> Self {
+        let mut de = Self::from_str(source);
+        de.untrusted_limits();
+        de
+    }
 }
 
 impl<'de, E> Deserializer<'de, SliceReader<'de>, E>
@@ -2706,7 +4683,9 @@ where
         Self::new(
             SliceReader {
                 reader,
+                input: source.as_bytes(),
                 start_trimmer: StartTrimmer::default(),
+                event_start: 0,
             },
             entity_resolver,
         )
@@ -2728,6 +4707,20 @@ where
     pub fn from_reader(reader: R) -> Self {
         Self::with_resolver(reader, NoEntityResolver)
     }
+
+    /// Create new deserializer that will copy data from the specified reader
+    /// into internal buffer and is configured for parsing data from untrusted
+    /// sources. The limits are the same as set by [`Deserializer::untrusted`].
+    ///
+    /// Text nodes are read into the buffer completely before their length is
+    /// checked, so consider also to limit the size of the input with [`Read::take`].
+    ///
+    /// [`Read::take`]: std::io::Read::take
+    pub fn untrusted_reader(reader: R) -> Self {
+        let mut de = Self::from_reader(reader);
+        de.untrusted_limits();
+        de
+    }
 }
 
 impl<'de, R, E> Deserializer<'de, IoReader<R>, E>
@@ -2750,6 +4743,7 @@ where
                 reader,
                 start_trimmer: StartTrimmer::default(),
                 buf: Vec::new(),
+                event_start: 0,
             },
             entity_resolver,
         )
@@ -2767,24 +4761,38 @@ where
 
     fn deserialize_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, DeError>
     where
         V: Visitor<'de>,
     {
+        if name == WITH_RAW {
+            return self.deserialize_with_raw(visitor);
+        }
+        if name == RAW_XML {
+            return self.deserialize_raw_xml(visitor);
+        }
         match self.next()? {
             DeEvent::Start(e) => {
                 let name = e.name().as_ref().to_vec();
-                let map = map::MapAccess::new(self, e, fields)?;
-                let value = visitor.visit_map(map)?;
+                self.struct_depth += 1;
+                let value =
+                    map::MapAccess::new(self, e, fields).and_then(|map| visitor.visit_map(map));
+                self.struct_depth -= 1;
+                let value = match value {
+                    Err(e) if self.track_path && self.struct_depth == 0 => {
+                        return Err(e.in_path(path_segment(self.reader.decoder(), &name)));
+                    }
+                    value => value?,
+                };
                 self.read_to_end(QName(&name))?;
                 Ok(value)
             }
             DeEvent::End(e) => Err(DeError::UnexpectedEnd(e.name().as_ref().to_owned())),
             DeEvent::Text(_) => Err(DeError::ExpectedStart),
-            DeEvent::Eof => Err(DeError::UnexpectedEof),
+            DeEvent::Eof => Err(self.reader.eof_error()),
         }
     }
 
@@ -2816,7 +4824,7 @@ where
             }
             DeEvent::Text(_) => visitor.visit_unit(),
             DeEvent::End(e) => Err(DeError::UnexpectedEnd(e.name().as_ref().to_owned())),
-            DeEvent::Eof => Err(DeError::UnexpectedEof),
+            DeEvent::Eof => Err(self.reader.eof_error()),
         }
     }
 
@@ -2839,6 +4847,14 @@ where
         visitor.visit_seq(self)
     }
 
+    /// Representation of tuples the same as [sequences](#method.deserialize_seq).
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, DeError>
     where
         V: Visitor<'de>,
@@ -2871,7 +4887,7 @@ where
         match self.next()? {
             DeEvent::Start(e) => self.read_to_end(e.name())?,
             DeEvent::End(e) => return Err(DeError::UnexpectedEnd(e.name().as_ref().to_owned())),
-            DeEvent::Eof => return Err(DeError::UnexpectedEof),
+            DeEvent::Eof => return Err(self.reader.eof_error()),
             _ => (),
         }
         visitor.visit_unit()
@@ -2926,6 +4942,9 @@ struct StartTrimmer {
     /// except [`Event::Text`] and [`Event::CData`], so [`Event::Text`] events
     /// read right after them does not trimmed.
     trim_start: bool,
+    /// If `false`, leading whitespaces are not removed from text events and
+    /// text events that contain only whitespaces are not skipped
+    trim: bool,
 }
 
 impl StartTrimmer {
@@ -2943,7 +4962,7 @@ impl StartTrimmer {
             Event::CData(e) => (PayloadEvent::CData(e), false),
             Event::Text(mut e) => {
                 // If event is empty after trimming, skip it
-                if self.trim_start && e.inplace_trim_start() {
+                if self.trim_start && self.trim && e.inplace_trim_start() {
                     return None;
                 }
                 (PayloadEvent::Text(e), false)
@@ -2959,7 +4978,10 @@ impl StartTrimmer {
 impl Default for StartTrimmer {
     #[inline]
     fn default() -> Self {
-        Self { trim_start: true }
+        Self {
+            trim_start: true,
+            trim: true,
+        }
     }
 }
 
@@ -2976,11 +4998,33 @@ pub trait XmlRead<'i> {
     fn next(&mut self) -> Result<PayloadEvent<'i>, DeError>;
 
     /// Skips until end element is found. Unlike `next()` it will not allocate
-    /// when it cannot satisfy the lifetime.
-    fn read_to_end(&mut self, name: QName) -> Result<(), DeError>;
+    /// when it cannot satisfy the lifetime. Returns the span from the current
+    /// position to the start of the end element.
+    fn read_to_end(&mut self, name: QName) -> Result<Span, DeError>;
+
+    /// Returns the part of the input in the specified span or `None` if the
+    /// input is not kept by the reader.
+    ///
+    /// The default implementation always returns `None`.
+    fn input(&self, _span: Span) -> Option<&'i [u8]> {
+        None
+    }
 
     /// A copy of the reader's decoder used to decode strings.
     fn decoder(&self) -> Decoder;
+
+    /// Enables or disables removing of leading whitespaces from text events.
+    ///
+    /// The default implementation does nothing.
+    fn trim_text(&mut self, _trim: bool) {}
+
+    /// Returns the position in the input of the first byte of the event,
+    /// returned by the last call to `next()`.
+    ///
+    /// The default implementation always returns `0`.
+    fn event_start(&self) -> usize {
+        0
+    }
 }
 
 /// XML input source that reads from a std::io input stream.
@@ -2991,6 +5035,8 @@ pub struct IoReader<R: BufRead> {
     reader: Reader<R>,
     start_trimmer: StartTrimmer,
     buf: Vec<u8>,
+    /// Position of the last returned event
+    event_start: usize,
 }
 
 impl<'i, R: BufRead> XmlRead<'i> for IoReader<R> {
@@ -2998,24 +5044,37 @@ impl<'i, R: BufRead> XmlRead<'i> for IoReader<R> {
         loop {
             self.buf.clear();
 
-            let event = self.reader.read_event_into(&mut self.buf)?;
+            let (event, span) = self.reader.read_event_into_with_span(&mut self.buf)?;
             if let Some(event) = self.start_trimmer.trim(event) {
+                self.event_start = span.start;
                 return Ok(event.into_owned());
             }
         }
     }
 
-    fn read_to_end(&mut self, name: QName) -> Result<(), DeError> {
+    fn read_to_end(&mut self, name: QName) -> Result<Span, DeError> {
         match self.reader.read_to_end_into(name, &mut self.buf) {
             Err(Error::UnexpectedEof(_)) => Err(DeError::UnexpectedEof),
             Err(e) => Err(e.into()),
-            Ok(_) => Ok(()),
+            Ok(span) => Ok(span),
         }
     }
 
+    fn input(&self, _span: Span) -> Option<&'i [u8]> {
+        None
+    }
+
     fn decoder(&self) -> Decoder {
         self.reader.decoder()
     }
+
+    fn trim_text(&mut self, trim: bool) {
+        self.start_trimmer.trim = trim;
+    }
+
+    fn event_start(&self) -> usize {
+        self.event_start
+    }
 }
 
 /// XML input source that reads from a slice of bytes and can borrow from it.
@@ -3024,30 +5083,49 @@ impl<'i, R: BufRead> XmlRead<'i> for IoReader<R> {
 /// [`Deserializer::from_str`].
 pub struct SliceReader<'de> {
     reader: Reader<&'de [u8]>,
+    /// The whole input, used to return parts of it as is
+    input: &'de [u8],
     start_trimmer: StartTrimmer,
+    /// Position of the last returned event
+    event_start: usize,
 }
 
 impl<'de> XmlRead<'de> for SliceReader<'de> {
     fn next(&mut self) -> Result<PayloadEvent<'de>, DeError> {
         loop {
-            let event = self.reader.read_event()?;
+            let (event, span) = self.reader.read_event_with_span()?;
             if let Some(event) = self.start_trimmer.trim(event) {
+                self.event_start = span.start;
                 return Ok(event);
             }
         }
     }
 
-    fn read_to_end(&mut self, name: QName) -> Result<(), DeError> {
+    fn read_to_end(&mut self, name: QName) -> Result<Span, DeError> {
         match self.reader.read_to_end(name) {
             Err(Error::UnexpectedEof(_)) => Err(DeError::UnexpectedEof),
             Err(e) => Err(e.into()),
-            Ok(_) => Ok(()),
+            Ok(span) => Ok(span),
         }
     }
 
+    fn input(&self, span: Span) -> Option<&'de [u8]> {
+        // Positions are counted after the stripped BOM
+        let bom = self.reader.bom().map_or(0, |bom| bom.as_bytes().len());
+        self.input.get(span.start + bom..span.end + bom)
+    }
+
     fn decoder(&self) -> Decoder {
         self.reader.decoder()
     }
+
+    fn trim_text(&mut self, trim: bool) {
+        self.start_trimmer.trim = trim;
+    }
+
+    fn event_start(&self) -> usize {
+        self.event_start
+    }
 }
 
 #[cfg(test)]
@@ -3060,6 +5138,14 @@ mod tests {
         Deserializer::from_str(source)
     }
 
+    /// Creates a text which is read only from CDATA sections
+    fn cdata_text(text: &str) -> Text<'_> {
+        Text {
+            text: text.into(),
+            cdata: true,
+        }
+    }
+
     #[cfg(feature = "overlapped-lists")]
     mod skip {
         use super::*;
@@ -3565,7 +5651,7 @@ mod tests {
                 de.next().unwrap(),
                 Start(BytesStart::from_content(r#"tag a="2""#, 3))
             );
-            assert_eq!(de.next().unwrap(), Text("cdata content".into()));
+            assert_eq!(de.next().unwrap(), Text(cdata_text("cdata content")));
             assert_eq!(de.next().unwrap(), End(BytesEnd::new("tag")));
 
             assert_eq!(de.next().unwrap(), Start(BytesStart::new("self-closed")));
@@ -3594,7 +5680,7 @@ mod tests {
             let mut de = make_de("<tag><![CDATA[]]><tag></tag>");
 
             assert_eq!(de.next().unwrap(), Start(BytesStart::new("tag")));
-            assert_eq!(de.peek().unwrap(), &Text("".into()));
+            assert_eq!(de.peek().unwrap(), &Text(cdata_text("")));
 
             match de.read_to_end(QName(b"tag")) {
                 Err(DeError::UnexpectedEof) => (),
@@ -3602,6 +5688,36 @@ mod tests {
             }
             assert_eq!(de.next().unwrap(), Eof);
         }
+
+        /// Skipped content should not be accumulated in the buffer of the reader
+        #[test]
+        fn ignored_any_from_reader() {
+            let mut xml = String::from("<root><skipped>");
+            for i in 0..10_000 {
+                xml += &format!(
+                    r#"<item n="{}">text<![CDATA[cdata]]><nested><item/></nested></item>"#,
+                    i
+                );
+            }
+            xml += "</skipped><value>42</value></root>";
+
+            let mut de = Deserializer::from_reader(xml.as_bytes());
+            assert_eq!(de.next().unwrap(), Start(BytesStart::new("root")));
+
+            serde::de::IgnoredAny::deserialize(&mut de).unwrap();
+            let capacity = de.reader.reader.buf.capacity();
+            assert!(
+                capacity < 64,
+                "buffer should hold only one event, but its capacity is {}",
+                capacity
+            );
+
+            assert_eq!(de.next().unwrap(), Start(BytesStart::new("value")));
+            assert_eq!(de.next().unwrap(), Text("42".into()));
+            assert_eq!(de.next().unwrap(), End(BytesEnd::new("value")));
+            assert_eq!(de.next().unwrap(), End(BytesEnd::new("root")));
+            assert_eq!(de.next().unwrap(), Eof);
+        }
     }
 
     #[test]
@@ -3616,10 +5732,13 @@ mod tests {
             reader: Reader::from_reader(s.as_bytes()),
             start_trimmer: StartTrimmer::default(),
             buf: Vec::new(),
+            event_start: 0,
         };
         let mut reader2 = SliceReader {
             reader: Reader::from_str(s),
+            input: s.as_bytes(),
             start_trimmer: StartTrimmer::default(),
+            event_start: 0,
         };
 
         loop {
@@ -3645,7 +5764,9 @@ mod tests {
 
         let mut reader = SliceReader {
             reader: Reader::from_str(s),
+            input: s.as_bytes(),
             start_trimmer: StartTrimmer::default(),
+            event_start: 0,
         };
 
         reader.reader.expand_empty_elements(true);
@@ -3728,7 +5849,7 @@ mod tests {
         #[test]
         fn cdata() {
             let mut de = make_de("<![CDATA[cdata]]>");
-            assert_eq!(de.next().unwrap(), DeEvent::Text("cdata".into()));
+            assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text("cdata")));
         }
 
         #[test]
@@ -3763,7 +5884,10 @@ mod tests {
                     <![CDATA[>cdata]]>\
                 ",
             );
-            assert_eq!(de.next().unwrap(), DeEvent::Text("cdata]]>cdata".into()));
+            assert_eq!(
+                de.next().unwrap(),
+                DeEvent::Text(cdata_text("cdata]]>cdata"))
+            );
         }
 
         mod comment_between {
@@ -3791,7 +5915,10 @@ mod tests {
                         <![CDATA[>cdata]]>\
                     ",
                 );
-                assert_eq!(de.next().unwrap(), DeEvent::Text("cdata]]>cdata".into()));
+                assert_eq!(
+                    de.next().unwrap(),
+                    DeEvent::Text(cdata_text("cdata]]>cdata"))
+                );
             }
 
             #[test]
@@ -3851,7 +5978,10 @@ mod tests {
                         <![CDATA[]>cdata]]>\
                     ",
                 );
-                assert_eq!(de.next().unwrap(), DeEvent::Text("cdata]]>cdata".into()));
+                assert_eq!(
+                    de.next().unwrap(),
+                    DeEvent::Text(cdata_text("cdata]]>cdata"))
+                );
             }
         }
 
@@ -3880,7 +6010,10 @@ mod tests {
                         <![CDATA[>cdata]]>\
                     ",
                 );
-                assert_eq!(de.next().unwrap(), DeEvent::Text("cdata]]>cdata".into()));
+                assert_eq!(
+                    de.next().unwrap(),
+                    DeEvent::Text(cdata_text("cdata]]>cdata"))
+                );
             }
 
             #[test]
@@ -3940,7 +6073,10 @@ mod tests {
                         <![CDATA[]>cdata]]>\
                     ",
                 );
-                assert_eq!(de.next().unwrap(), DeEvent::Text("cdata]]>cdata".into()));
+                assert_eq!(
+                    de.next().unwrap(),
+                    DeEvent::Text(cdata_text("cdata]]>cdata"))
+                );
             }
         }
     }
@@ -3995,7 +6131,7 @@ mod tests {
                     let mut de = make_de("<tag1><tag2><![CDATA[ cdata ]]>");
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag1")));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag2")));
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
 
@@ -4053,7 +6189,7 @@ mod tests {
                     let mut de = make_de("<tag></tag><![CDATA[ cdata ]]>");
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
                     assert_eq!(de.next().unwrap(), DeEvent::End(BytesEnd::new("tag")));
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
 
@@ -4123,7 +6259,7 @@ mod tests {
                 fn start() {
                     let mut de = make_de("<tag><![CDATA[ cdata ]]><tag2>");
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag2")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
@@ -4132,7 +6268,7 @@ mod tests {
                 fn end() {
                     let mut de = make_de("<tag><![CDATA[ cdata ]]></tag>");
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::End(BytesEnd::new("tag")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
@@ -4150,7 +6286,10 @@ mod tests {
                 fn cdata() {
                     let mut de = make_de("<tag><![CDATA[ cdata ]]><![CDATA[ cdata2 ]]>");
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata  cdata2 ".into()));
+                    assert_eq!(
+                        de.next().unwrap(),
+                        DeEvent::Text(cdata_text(" cdata  cdata2 "))
+                    );
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
 
@@ -4158,7 +6297,7 @@ mod tests {
                 fn eof() {
                     let mut de = make_de("<tag><![CDATA[ cdata ]]>");
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
@@ -4225,7 +6364,7 @@ mod tests {
                     // Text is trimmed from both sides
                     assert_eq!(de.next().unwrap(), DeEvent::Text("text".into()));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
 
@@ -4330,7 +6469,7 @@ mod tests {
                 #[test]
                 fn start() {
                     let mut de = make_de("<![CDATA[ cdata ]]><tag1><tag2>");
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag1")));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag2")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
@@ -4340,7 +6479,7 @@ mod tests {
                 #[test]
                 fn end() {
                     let mut de = make_de("<![CDATA[ cdata ]]><tag></tag>");
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
                     assert_eq!(de.next().unwrap(), DeEvent::End(BytesEnd::new("tag")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
@@ -4349,7 +6488,7 @@ mod tests {
                 #[test]
                 fn text() {
                     let mut de = make_de("<![CDATA[ cdata ]]><tag> text ");
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
                     // Text is trimmed from both sides
                     assert_eq!(de.next().unwrap(), DeEvent::Text("text".into()));
@@ -4359,16 +6498,16 @@ mod tests {
                 #[test]
                 fn cdata() {
                     let mut de = make_de("<![CDATA[ cdata ]]><tag><![CDATA[ cdata2 ]]>");
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata2 ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata2 ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
 
                 #[test]
                 fn eof() {
                     let mut de = make_de("<![CDATA[ cdata ]]><tag>");
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                    assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
@@ -4379,7 +6518,7 @@ mod tests {
             #[test]
             fn end() {
                 let mut de = make_de("<![CDATA[ cdata ]]></tag>");
-                assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata ".into()));
+                assert_eq!(de.next().unwrap(), DeEvent::Text(cdata_text(" cdata ")));
                 match de.next() {
                     Err(DeError::InvalidXml(Error::EndEventMismatch { expected, found })) => {
                         assert_eq!(expected, "");
@@ -4447,7 +6586,10 @@ mod tests {
                 #[test]
                 fn start() {
                     let mut de = make_de("<![CDATA[ cdata ]]><![CDATA[ cdata2 ]]><tag>");
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata  cdata2 ".into()));
+                    assert_eq!(
+                        de.next().unwrap(),
+                        DeEvent::Text(cdata_text(" cdata  cdata2 "))
+                    );
                     assert_eq!(de.next().unwrap(), DeEvent::Start(BytesStart::new("tag")));
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
@@ -4455,7 +6597,10 @@ mod tests {
                 #[test]
                 fn end() {
                     let mut de = make_de("<![CDATA[ cdata ]]><![CDATA[ cdata2 ]]></tag>");
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata  cdata2 ".into()));
+                    assert_eq!(
+                        de.next().unwrap(),
+                        DeEvent::Text(cdata_text(" cdata  cdata2 "))
+                    );
                     match de.next() {
                         Err(DeError::InvalidXml(Error::EndEventMismatch { expected, found })) => {
                             assert_eq!(expected, "");
@@ -4483,7 +6628,7 @@ mod tests {
                         make_de("<![CDATA[ cdata ]]><![CDATA[ cdata2 ]]><![CDATA[ cdata3 ]]>");
                     assert_eq!(
                         de.next().unwrap(),
-                        DeEvent::Text(" cdata  cdata2  cdata3 ".into())
+                        DeEvent::Text(cdata_text(" cdata  cdata2  cdata3 "))
                     );
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
@@ -4491,7 +6636,10 @@ mod tests {
                 #[test]
                 fn eof() {
                     let mut de = make_de("<![CDATA[ cdata ]]><![CDATA[ cdata2 ]]>");
-                    assert_eq!(de.next().unwrap(), DeEvent::Text(" cdata  cdata2 ".into()));
+                    assert_eq!(
+                        de.next().unwrap(),
+                        DeEvent::Text(cdata_text(" cdata  cdata2 "))
+                    );
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                     assert_eq!(de.next().unwrap(), DeEvent::Eof);
                 }
@@ -0,0 +1,427 @@
+//! Contains a wrapper that captures XML of an element together with its
+//! deserialized value and a type that captures the markup inside an element
+
+use crate::{
+    de::resolver::EntityResolver,
+    de::{DeEvent, Deserializer, XmlRead},
+    errors::serialize::DeError,
+};
+use serde::de::value::StringDeserializer;
+use serde::de::{self, Deserialize, DeserializeSeed, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::Range;
+
+/// The name of the struct that is used to signal to the [`Deserializer`] that
+/// it should capture XML of the current element
+pub(crate) const WITH_RAW: &str = "$quick-xml::de::WithRaw";
+/// The name of the struct that is used to signal to the [`Deserializer`] that
+/// it should return the markup inside of the current element as is
+pub(crate) const RAW_XML: &str = "$quick-xml::de::RawXml";
+
+/// A wrapper which deserializes a `T` as usual, but in addition stores the XML
+/// of the element from which `T` was deserialized.
+///
+/// The XML is the part of the input from the `<` of the start tag to the `>`
+/// of the end tag exactly as it is written, including comments, processing
+/// instructions, CDATA sections and whitespaces. Because of that it can be
+/// captured only from the input that is kept in memory, that is when the
+/// [`Deserializer`] was created using [`Deserializer::from_str`] (or
+/// [`from_str`]). Other deserializers return [`DeError::Unsupported`].
+///
+/// This wrapper works only with the deserializer from this crate.
+///
+/// When serialized with the [serializer] from this crate, the wrapper is
+/// transparent: by default only [`Self::value`] is serialized, so any changes
+/// made to it are reflected in the output. If [`Serializer::write_raw_xml`]
+/// is enabled, the stored [`Self::raw`] XML is written verbatim instead.
+/// Other serializers see a struct with two fields: `value` and `raw`.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// # use serde::Deserialize;
+/// use quick_xml::de::{from_str, WithRaw};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Item {
+///     #[serde(rename = "@id")]
+///     id: usize,
+///     name: String,
+/// }
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Root {
+///     item: WithRaw<Item>,
+/// }
+///
+/// let root: Root = from_str(r#"
+///     <root>
+///         <item id="1"><!-- only --><name>first &amp; only</name></item>
+///     </root>
+/// "#).unwrap();
+///
+/// assert_eq!(root.item.value, Item { id: 1, name: "first & only".into() });
+/// assert_eq!(root.item.raw, r#"<item id="1"><!-- only --><name>first &amp; only</name></item>"#);
+/// ```
+///
+/// [`from_str`]: crate::de::from_str
+/// [serializer]: crate::se::Serializer
+/// [`Serializer::write_raw_xml`]: crate::se::Serializer::write_raw_xml
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WithRaw<T> {
+    /// Deserialized value
+    pub value: T,
+    /// XML of the element from which [`Self::value`] was deserialized
+    pub raw: String,
+}
+
+impl<'de, T> Deserialize<'de> for WithRaw<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct WithRawVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for WithRawVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = WithRaw<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an XML element")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let value = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let raw = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                Ok(WithRaw { value, raw })
+            }
+        }
+
+        deserializer.deserialize_struct(WITH_RAW, &["value", "raw"], WithRawVisitor(PhantomData))
+    }
+}
+
+impl<T> Serialize for WithRaw<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_struct(WITH_RAW, 2)?;
+        s.serialize_field("value", &self.value)?;
+        s.serialize_field("raw", &self.raw)?;
+        s.end()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The markup inside of an element, captured verbatim: child elements,
+/// comments, CDATA sections and entity references are neither parsed nor
+/// unescaped.
+///
+/// Unlike [`WithRaw`], which captures XML of the whole element together with
+/// its parsed value, this type returns only the part of the input between the
+/// start and the end tags of the element exactly as it is written. Because of
+/// that it can be deserialized only from the input that is kept in memory,
+/// that is when the [`Deserializer`] was created using [`Deserializer::from_str`]
+/// (or [`from_str`]). Other deserializers return [`DeError::Unsupported`].
+///
+/// This type works only with the deserializer from this crate.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// # use serde::Deserialize;
+/// use quick_xml::de::{from_str, RawXml};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Article {
+///     title: String,
+///     body: RawXml,
+/// }
+///
+/// let article: Article = from_str(r#"
+///     <article>
+///         <title>Fish &amp; chips</title>
+///         <body><p>Fish &amp; <b>chips</b></p></body>
+///     </article>
+/// "#).unwrap();
+///
+/// assert_eq!(article.title, "Fish & chips");
+/// assert_eq!(article.body, RawXml("<p>Fish &amp; <b>chips</b></p>".into()));
+/// ```
+///
+/// [`from_str`]: crate::de::from_str
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RawXml(pub String);
+
+impl<'de> Deserialize<'de> for RawXml {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct RawXmlVisitor;
+
+        impl<'de> Visitor<'de> for RawXmlVisitor {
+            type Value = RawXml;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an XML element")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(RawXml(value.to_owned()))
+            }
+
+            fn visit_string<E>(self, value: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(RawXml(value))
+            }
+        }
+
+        deserializer.deserialize_struct(RAW_XML, &[], RawXmlVisitor)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Tracks the span in the input of one element (including all its descendants)
+/// which events are passed to [`Self::record`].
+#[derive(Debug, Default)]
+pub(crate) struct RawRecorder {
+    /// Position of the first recorded event
+    start: Option<usize>,
+    /// Position of the end of the recorded XML. If `end_tag` is `true`, this is
+    /// the position of the `<` of the end tag, which should be included
+    end: usize,
+    /// `true` if the last recorded event was an end tag
+    end_tag: bool,
+    /// Count of currently opened elements
+    depth: usize,
+    /// `true` when the whole element was recorded
+    finished: bool,
+}
+
+impl RawRecorder {
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Extends the recorded span with the event. `span` starts at the position
+    /// of the event and ends at the position of the next event
+    pub fn record(&mut self, event: &DeEvent, span: Range<usize>) {
+        if self.start.is_none() {
+            self.start = Some(span.start);
+        }
+        self.end_tag = false;
+        match event {
+            DeEvent::Start(_) => self.depth += 1,
+            DeEvent::End(_) => {
+                self.depth = self.depth.saturating_sub(1);
+                self.end = span.start;
+                self.end_tag = true;
+            }
+            DeEvent::Text(_) => self.end = span.end,
+            DeEvent::Eof => {
+                self.depth = 0;
+                self.end = span.start;
+            }
+        }
+        self.finished = self.depth == 0;
+    }
+}
+
+impl<'de, R, E> Deserializer<'de, R, E>
+where
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    /// Starts recording XML of the peeked event and, if it is a [`DeEvent::Start`],
+    /// of all events up to and including the corresponding [`DeEvent::End`].
+    fn start_recording(&mut self) -> Result<(), DeError> {
+        let mut recorder = RawRecorder::default();
+        self.peek()?;
+        let start = self.peek_position();
+        // The next event after the peeked one is either the skipped event or
+        // the lookahead event of the reader
+        #[cfg(feature = "overlapped-lists")]
+        let next = self.read_positions.get(1).copied();
+        #[cfg(not(feature = "overlapped-lists"))]
+        let next = None;
+        let next = next.unwrap_or(self.reader.lookahead_start);
+        recorder.record(self.peek()?, start..next);
+
+        // Events, skipped earlier, already was read from the reader, so feed
+        // them to the recorder here
+        #[cfg(feature = "overlapped-lists")]
+        for (i, event) in self.read.iter().enumerate().skip(1) {
+            if recorder.is_finished() {
+                break;
+            }
+            let start = self.read_positions[i];
+            let next = self.read_positions.get(i + 1).copied();
+            recorder.record(event, start..next.unwrap_or(self.reader.lookahead_start));
+        }
+
+        self.reader.recorders.push(recorder);
+        Ok(())
+    }
+
+    /// Returns XML, recorded since the last call of [`Self::start_recording`]
+    fn finish_recording(&mut self) -> Result<String, DeError> {
+        let recorder = match self.reader.recorders.pop() {
+            Some(recorder) => recorder,
+            None => return Ok(String::new()),
+        };
+        let start = recorder.start.unwrap_or(recorder.end);
+        let mut end = recorder.end;
+        let unsupported = || {
+            DeError::Unsupported(
+                "raw XML can be captured only when deserializing from a string".into(),
+            )
+        };
+        if recorder.end_tag {
+            // The reader is always ahead of the returned events, so the whole
+            // end tag was already read
+            let tag = self
+                .reader
+                .reader
+                .input(end..self.reader.lookahead_start)
+                .ok_or_else(unsupported)?;
+            // An end tag generated for an empty element `<tag/>` has no markup
+            if tag.starts_with(b"</") {
+                end += tag
+                    .iter()
+                    .position(|&b| b == b'>')
+                    .map_or(tag.len(), |i| i + 1);
+            }
+        }
+        let raw = self
+            .reader
+            .reader
+            .input(start..end)
+            .ok_or_else(unsupported)?;
+        Ok(self.reader.decoder().decode(raw)?.into_owned())
+    }
+
+    /// Deserializes [`RawXml`] from the content of the next element
+    pub(crate) fn deserialize_raw_xml<V>(&mut self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        let e = match self.next()? {
+            DeEvent::Start(e) => e,
+            DeEvent::End(e) => return Err(DeError::UnexpectedEnd(e.name().as_ref().to_owned())),
+            DeEvent::Text(_) => return Err(DeError::ExpectedStart),
+            DeEvent::Eof => return Err(self.reader.eof_error()),
+        };
+        // Events of the element, skipped when looking for overlapped list items,
+        // are buffered and their positions cannot be tracked
+        #[cfg(feature = "overlapped-lists")]
+        if !self.read.is_empty() {
+            return Err(DeError::Unsupported(
+                "raw XML of an element after an overlapped list".into(),
+            ));
+        }
+        // Start of the content after the `<` and the content of the start tag
+        let start = self.position + 1 + e.len();
+        let end = self.reader.read_to_end(e.name())?;
+        self.end_namespace_scope();
+        let raw = match self.reader.reader.input(start..end) {
+            // An empty element `<tag/>` which was expanded to `<tag></tag>`
+            Some(raw) if raw.starts_with(b"/") => &raw[..0],
+            Some(raw) => raw.strip_prefix(b">").unwrap_or(raw),
+            None => {
+                return Err(DeError::Unsupported(
+                    "raw XML can be captured only when deserializing from a string".into(),
+                ))
+            }
+        };
+        match self.reader.decoder().decode(raw)? {
+            Cow::Borrowed(raw) => visitor.visit_borrowed_str(raw),
+            Cow::Owned(raw) => visitor.visit_string(raw),
+        }
+    }
+
+    /// Deserializes [`WithRaw`] from the next element
+    pub(crate) fn deserialize_with_raw<V>(&mut self, visitor: V) -> Result<V::Value, DeError>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_seq(WithRawAccess {
+            de: self,
+            value_read: false,
+            raw: None,
+        })
+    }
+}
+
+/// Gives access to the deserialized value and its XML representation
+struct WithRawAccess<'de, 'a, R, E>
+where
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    de: &'a mut Deserializer<'de, R, E>,
+    /// `true` if the first element (the value) was already deserialized
+    value_read: bool,
+    /// XML of deserialized value. `None` until value will be deserialized and
+    /// after the second element (the XML) will be deserialized
+    raw: Option<String>,
+}
+
+impl<'de, 'a, R, E> SeqAccess<'de> for WithRawAccess<'de, 'a, R, E>
+where
+    R: XmlRead<'de>,
+    E: EntityResolver,
+{
+    type Error = DeError;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, DeError>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        if !self.value_read {
+            self.value_read = true;
+            self.de.start_recording()?;
+            let value = seed.deserialize(&mut *self.de);
+            // Always finish recording to keep recorders balanced
+            let raw = self.de.finish_recording();
+            let value = value?;
+            self.raw = Some(raw?);
+            return Ok(Some(value));
+        }
+        match self.raw.take() {
+            Some(raw) => seed
+                .deserialize(StringDeserializer::<DeError>::new(raw))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -1,9 +1,10 @@
 //! Entity resolver module
 
+use std::borrow::Cow;
 use std::convert::Infallible;
 use std::error::Error;
 
-use crate::events::BytesText;
+use crate::events::BytesDocType;
 
 /// Used to resolve unknown entities while parsing
 ///
@@ -12,37 +13,22 @@ use crate::events::BytesText;
 /// ```
 /// # use serde::Deserialize;
 /// # use pretty_assertions::assert_eq;
-/// use regex::bytes::Regex;
 /// use std::collections::BTreeMap;
-/// use std::string::FromUtf8Error;
 /// use quick_xml::de::{Deserializer, EntityResolver};
-/// use quick_xml::events::BytesText;
+/// use quick_xml::events::BytesDocType;
+/// use quick_xml::Error;
 ///
+/// #[derive(Default)]
 /// struct DocTypeEntityResolver {
-///     re: Regex,
 ///     map: BTreeMap<String, String>,
 /// }
 ///
-/// impl Default for DocTypeEntityResolver {
-///     fn default() -> Self {
-///         Self {
-///             // We do not focus on true parsing in this example
-///             // You should use special libraries to parse DTD
-///             re: Regex::new(r#"<!ENTITY\s+([^ \t\r\n]+)\s+"([^"]*)"\s*>"#).unwrap(),
-///             map: BTreeMap::new(),
-///         }
-///     }
-/// }
-///
 /// impl EntityResolver for DocTypeEntityResolver {
-///     type Error = FromUtf8Error;
+///     type Error = Error;
 ///
-///     fn capture(&mut self, doctype: BytesText) -> Result<(), Self::Error> {
-///         for cap in self.re.captures_iter(&doctype) {
-///             self.map.insert(
-///                 String::from_utf8(cap[1].to_vec())?,
-///                 String::from_utf8(cap[2].to_vec())?,
-///             );
+///     fn capture(&mut self, doctype: BytesDocType) -> Result<(), Self::Error> {
+///         for entity in doctype.parse()?.entities {
+///             self.map.insert(entity.name, entity.value);
 ///         }
 ///         Ok(())
 ///     }
@@ -75,7 +61,7 @@ pub trait EntityResolver {
     /// Can be called multiple times, for each parsed `<!DOCTYPE >` declaration.
     ///
     /// [`Event::DocType`]: crate::events::Event::DocType
-    fn capture(&mut self, doctype: BytesText) -> Result<(), Self::Error>;
+    fn capture(&mut self, doctype: BytesDocType) -> Result<(), Self::Error>;
 
     /// Called when an entity needs to be resolved.
     ///
@@ -85,6 +71,61 @@ pub trait EntityResolver {
     ///
     /// [`EscapeError::UnrecognizedSymbol`]: crate::escape::EscapeError::UnrecognizedSymbol
     fn resolve(&self, entity: &str) -> Option<&str>;
+
+    /// Called when an entity needs to be resolved. Unlike [`resolve`], allows
+    /// to return a value computed on the fly and to fail resolution.
+    ///
+    /// `Ok(None)` is returned if a suitable value can not be found and is
+    /// treated the same way as `None` from [`resolve`]. An error is reported
+    /// by a deserializer as [`DeError::Custom`].
+    ///
+    /// The default implementation delegates to [`resolve`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use serde::Deserialize;
+    /// # use pretty_assertions::assert_eq;
+    /// use std::borrow::Cow;
+    /// use std::convert::Infallible;
+    /// use quick_xml::de::{Deserializer, EntityResolver};
+    /// use quick_xml::events::BytesDocType;
+    ///
+    /// /// Resolves `&upper-<text>;` entities to an uppercased `<text>`
+    /// struct UpperResolver;
+    ///
+    /// impl EntityResolver for UpperResolver {
+    ///     type Error = Infallible;
+    ///
+    ///     fn capture(&mut self, _doctype: BytesDocType) -> Result<(), Self::Error> {
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn resolve(&self, _entity: &str) -> Option<&str> {
+    ///         None
+    ///     }
+    ///
+    ///     fn try_resolve(&self, entity: &str) -> Result<Option<Cow<'_, str>>, Self::Error> {
+    ///         Ok(entity
+    ///             .strip_prefix("upper-")
+    ///             .map(|text| Cow::Owned(text.to_uppercase())))
+    ///     }
+    /// }
+    ///
+    /// let mut de = Deserializer::with_resolver(
+    ///     "<root>&upper-hello; world</root>".as_bytes(),
+    ///     UpperResolver,
+    /// );
+    /// let data = String::deserialize(&mut de).unwrap();
+    ///
+    /// assert_eq!(data, "HELLO world");
+    /// ```
+    ///
+    /// [`resolve`]: Self::resolve
+    /// [`DeError::Custom`]: crate::DeError::Custom
+    fn try_resolve(&self, entity: &str) -> Result<Option<Cow<'_, str>>, Self::Error> {
+        Ok(self.resolve(entity).map(Cow::Borrowed))
+    }
 }
 
 /// An `EntityResolver` that does nothing and always returns `None`.
@@ -94,7 +135,7 @@ pub struct NoEntityResolver;
 impl EntityResolver for NoEntityResolver {
     type Error = Infallible;
 
-    fn capture(&mut self, _doctype: BytesText) -> Result<(), Self::Error> {
+    fn capture(&mut self, _doctype: BytesDocType) -> Result<(), Self::Error> {
         Ok(())
     }
 
@@ -102,3 +143,43 @@ impl EntityResolver for NoEntityResolver {
         None
     }
 }
+
+/// Defines what deserializer should do when it meets an entity that is neither
+/// predefined nor resolved by an [`EntityResolver`].
+///
+/// # Example
+///
+/// ```
+/// # use serde::Deserialize;
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::de::{Deserializer, UnknownEntityPolicy};
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct X {
+///     #[serde(rename = "$text")]
+///     text: String,
+/// }
+///
+/// let mut de = Deserializer::from_str("<x>a &weird; b</x>");
+/// de.on_unknown_entity(UnknownEntityPolicy::Replace("?".into()));
+///
+/// assert_eq!(X::deserialize(&mut de).unwrap().text, "a ? b");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnknownEntityPolicy {
+    /// Return an [`EscapeError::UnrecognizedSymbol`] error. This is the default
+    ///
+    /// [`EscapeError::UnrecognizedSymbol`]: crate::escape::EscapeError::UnrecognizedSymbol
+    Error,
+    /// Leave the entity reference (`&unknown;`) in the text as is
+    Keep,
+    /// Replace the entity reference with the specified string
+    Replace(String),
+}
+
+impl Default for UnknownEntityPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self::Error
+    }
+}
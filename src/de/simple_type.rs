@@ -3,7 +3,9 @@
 //! [simple types]: https://www.w3schools.com/xml/el_simpletype.asp
 //! [as defined]: https://www.w3.org/TR/xmlschema11-1/#Simple_Type_Definition
 
-use crate::de::{deserialize_bool, str2bool, Text};
+use crate::de::{
+    deserialize_bool, str2bool, str2char, trim_xml_spaces, BoolFormat, BytesFormat, Text,
+};
 use crate::encoding::Decoder;
 use crate::errors::serialize::DeError;
 use crate::escape::unescape;
@@ -20,7 +22,7 @@ macro_rules! deserialize_num {
         where
             V: Visitor<'de>,
         {
-            visitor.$visit(self.content.as_str().parse()?)
+            visitor.$visit(trim_xml_spaces(self.content.as_str()).parse()?)
         }
     };
     ($method:ident => $visit:ident) => {
@@ -29,7 +31,7 @@ macro_rules! deserialize_num {
             V: Visitor<'de>,
         {
             let string = self.decode()?;
-            visitor.$visit(string.as_str().parse()?)
+            visitor.$visit(trim_xml_spaces(string.as_str()).parse()?)
         }
     };
 }
@@ -150,6 +152,8 @@ struct AtomicDeserializer<'de, 'a> {
     content: Content<'de, 'a>,
     /// If `true`, `content` in an escaped form and should be unescaped before use
     escaped: bool,
+    /// Strings recognized as boolean values
+    bool_format: BoolFormat,
 }
 
 impl<'de, 'a> Deserializer<'de> for AtomicDeserializer<'de, 'a> {
@@ -163,19 +167,12 @@ impl<'de, 'a> Deserializer<'de> for AtomicDeserializer<'de, 'a> {
         self.deserialize_str(visitor)
     }
 
-    /// According to the <https://www.w3.org/TR/xmlschema11-2/#boolean>,
-    /// valid boolean representations are only `"true"`, `"false"`, `"1"`,
-    /// and `"0"`. But this method also handles following:
-    ///
-    /// |`bool` |XML content
-    /// |-------|-------------------------------------------------------------
-    /// |`true` |`"True"`,  `"TRUE"`,  `"t"`, `"Yes"`, `"YES"`, `"yes"`, `"y"`
-    /// |`false`|`"False"`, `"FALSE"`, `"f"`, `"No"`,  `"NO"`,  `"no"`,  `"n"`
+    /// Recognized representations are defined by the [`BoolFormat`]
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        str2bool(self.content.as_str(), visitor)
+        str2bool(self.content.as_str(), &self.bool_format, visitor)
     }
 
     deserialize_num!(deserialize_i8, visit_i8);
@@ -196,12 +193,16 @@ impl<'de, 'a> Deserializer<'de> for AtomicDeserializer<'de, 'a> {
     deserialize_num!(deserialize_f32, visit_f32);
     deserialize_num!(deserialize_f64, visit_f64);
 
-    /// Forwards deserialization to the [`Self::deserialize_str`]
+    /// Unescapes content if required and checks that it contains exactly one character
     fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_str(visitor)
+        if self.escaped {
+            str2char(&unescape(self.content.as_str())?, visitor)
+        } else {
+            str2char(self.content.as_str(), visitor)
+        }
     }
 
     /// Supply to the visitor borrowed string, string slice, or owned string
@@ -383,6 +384,8 @@ struct ListIter<'de, 'a> {
     content: Option<Content<'de, 'a>>,
     /// If `true`, `content` in escaped form and should be unescaped before use
     escaped: bool,
+    /// Strings recognized as boolean values
+    bool_format: BoolFormat,
 }
 impl<'de, 'a> SeqAccess<'de> for ListIter<'de, 'a> {
     type Error = DeError;
@@ -404,6 +407,7 @@ impl<'de, 'a> SeqAccess<'de> for ListIter<'de, 'a> {
                     None => seed.deserialize(AtomicDeserializer {
                         content,
                         escaped: self.escaped,
+                        bool_format: self.bool_format,
                     }),
                     // `content` started with a space, skip them all
                     Some(0) => {
@@ -433,6 +437,7 @@ impl<'de, 'a> SeqAccess<'de> for ListIter<'de, 'a> {
                             seed.deserialize(AtomicDeserializer {
                                 content: Content::Input(item),
                                 escaped: self.escaped,
+                                bool_format: self.bool_format,
                             })
                         }
                         Content::Slice(s) => {
@@ -442,6 +447,7 @@ impl<'de, 'a> SeqAccess<'de> for ListIter<'de, 'a> {
                             seed.deserialize(AtomicDeserializer {
                                 content: Content::Slice(item),
                                 escaped: self.escaped,
+                                bool_format: self.bool_format,
                             })
                         }
                         // Skip additional bytes if we own data for next iteration, but deserialize from
@@ -451,6 +457,7 @@ impl<'de, 'a> SeqAccess<'de> for ListIter<'de, 'a> {
                             let result = seed.deserialize(AtomicDeserializer {
                                 content: Content::Slice(item),
                                 escaped: self.escaped,
+                                bool_format: self.bool_format,
                             });
 
                             self.content = Some(Content::Owned(s, skip + end));
@@ -488,19 +495,36 @@ pub struct SimpleTypeDeserializer<'de, 'a> {
     content: CowRef<'de, 'a, [u8]>,
     /// If `true`, `content` in escaped form and should be unescaped before use
     escaped: bool,
+    /// If `true`, `content` was read only from CDATA sections and can be
+    /// deserialized as raw bytes
+    cdata: bool,
     /// Decoder used to deserialize string data, numeric and boolean data.
     /// Not used for deserializing raw byte buffers
     decoder: Decoder,
+    /// Strings recognized as boolean values
+    bool_format: BoolFormat,
+    /// Representation of binary data. If `None`, only CDATA content can be
+    /// deserialized as bytes
+    bytes_format: Option<BytesFormat>,
 }
 
 impl<'de, 'a> SimpleTypeDeserializer<'de, 'a> {
     /// Creates a deserializer from a value, that possible borrowed from input
-    pub fn from_text_content(value: Text<'de>) -> Self {
+    pub fn from_text_content(
+        value: Text<'de>,
+        bool_format: BoolFormat,
+        bytes_format: Option<BytesFormat>,
+    ) -> Self {
         let content = match value.text {
             Cow::Borrowed(slice) => CowRef::Input(slice.as_bytes()),
             Cow::Owned(content) => CowRef::Owned(content.into_bytes()),
         };
-        Self::new(content, false, Decoder::utf8())
+        Self {
+            cdata: value.cdata,
+            bool_format,
+            bytes_format,
+            ..Self::new(content, false, Decoder::utf8())
+        }
     }
 
     /// Creates a deserializer from a part of value at specified range
@@ -510,12 +534,18 @@ impl<'de, 'a> SimpleTypeDeserializer<'de, 'a> {
         range: Range<usize>,
         escaped: bool,
         decoder: Decoder,
+        bool_format: BoolFormat,
+        bytes_format: Option<BytesFormat>,
     ) -> Self {
         let content = match value {
             Cow::Borrowed(slice) => CowRef::Input(&slice[range]),
             Cow::Owned(slice) => CowRef::Slice(&slice[range]),
         };
-        Self::new(content, escaped, decoder)
+        Self {
+            bool_format,
+            bytes_format,
+            ..Self::new(content, escaped, decoder)
+        }
     }
 
     /// Constructor for tests
@@ -524,7 +554,10 @@ impl<'de, 'a> SimpleTypeDeserializer<'de, 'a> {
         Self {
             content,
             escaped,
+            cdata: false,
             decoder,
+            bool_format: BoolFormat::default(),
+            bytes_format: None,
         }
     }
 
@@ -564,7 +597,7 @@ impl<'de, 'a> Deserializer<'de> for SimpleTypeDeserializer<'de, 'a> {
     where
         V: Visitor<'de>,
     {
-        deserialize_bool(&self.content, self.decoder, visitor)
+        deserialize_bool(&self.content, self.decoder, &self.bool_format, visitor)
     }
 
     deserialize_num!(deserialize_i8  => visit_i8);
@@ -585,12 +618,18 @@ impl<'de, 'a> Deserializer<'de> for SimpleTypeDeserializer<'de, 'a> {
     deserialize_num!(deserialize_f32 => visit_f32);
     deserialize_num!(deserialize_f64 => visit_f64);
 
-    /// Forwards deserialization to the [`Self::deserialize_str`]
+    /// Decodes and unescapes content if required and checks that it contains
+    /// exactly one character
     fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        self.deserialize_str(visitor)
+        let content = self.decode()?;
+        if self.escaped {
+            str2char(&unescape(content.as_str())?, visitor)
+        } else {
+            str2char(content.as_str(), visitor)
+        }
     }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -616,14 +655,32 @@ impl<'de, 'a> Deserializer<'de> for SimpleTypeDeserializer<'de, 'a> {
         self.deserialize_str(visitor)
     }
 
-    /// Returns [`DeError::Unsupported`]
-    fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    /// Decodes content according to the [`BytesFormat`], if it is set.
+    /// Otherwise returns content of CDATA sections as is and for text content
+    /// and attribute values returns [`DeError::Unsupported`]
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        Err(DeError::Unsupported(
-            "binary data content is not supported by XML format".into(),
-        ))
+        if let Some(format) = self.bytes_format {
+            let content = self.decode()?;
+            let bytes = if self.escaped {
+                format.decode(&unescape(content.as_str())?)?
+            } else {
+                format.decode(content.as_str())?
+            };
+            return visitor.visit_byte_buf(bytes);
+        }
+        if !self.cdata {
+            return Err(DeError::Unsupported(
+                "binary data content is not supported by XML format".into(),
+            ));
+        }
+        match self.content {
+            CowRef::Input(content) => visitor.visit_borrowed_bytes(content),
+            CowRef::Slice(content) => visitor.visit_bytes(content),
+            CowRef::Owned(content) => visitor.visit_byte_buf(content),
+        }
     }
 
     /// Forwards deserialization to the [`Self::deserialize_bytes`]
@@ -682,6 +739,7 @@ impl<'de, 'a> Deserializer<'de> for SimpleTypeDeserializer<'de, 'a> {
         visitor.visit_seq(ListIter {
             content: Some(self.decode()?),
             escaped: self.escaped,
+            bool_format: self.bool_format,
         })
     }
 
@@ -710,16 +768,18 @@ impl<'de, 'a> Deserializer<'de> for SimpleTypeDeserializer<'de, 'a> {
     unsupported!(deserialize_struct(&'static str, &'static [&'static str])
                  => "structures are not supported for XSD `simpleType`s");
 
+    /// Only unit variants can be selected by a text content or an attribute value,
+    /// because there is no place for data of other variants
     fn deserialize_enum<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        visitor.visit_enum(self)
+        visitor.visit_enum(SimpleTypeEnumAccess { de: self, name })
     }
 
     /// Forwards deserialization to the [`Self::deserialize_str`]
@@ -738,7 +798,15 @@ impl<'de, 'a> Deserializer<'de> for SimpleTypeDeserializer<'de, 'a> {
     }
 }
 
-impl<'de, 'a> EnumAccess<'de> for SimpleTypeDeserializer<'de, 'a> {
+/// Enum access of a [`SimpleTypeDeserializer`] that remembers the name of
+/// the deserialized enum to report it in errors
+pub struct SimpleTypeEnumAccess<'de, 'a> {
+    de: SimpleTypeDeserializer<'de, 'a>,
+    /// The name of the deserialized enum
+    name: &'static str,
+}
+
+impl<'de, 'a> EnumAccess<'de> for SimpleTypeEnumAccess<'de, 'a> {
     type Error = DeError;
     type Variant = SimpleTypeUnitOnly;
 
@@ -746,8 +814,8 @@ impl<'de, 'a> EnumAccess<'de> for SimpleTypeDeserializer<'de, 'a> {
     where
         V: DeserializeSeed<'de>,
     {
-        let name = seed.deserialize(self)?;
-        Ok((name, SimpleTypeUnitOnly))
+        let variant = seed.deserialize(self.de)?;
+        Ok((variant, SimpleTypeUnitOnly { name: self.name }))
     }
 }
 
@@ -756,7 +824,25 @@ impl<'de, 'a> EnumAccess<'de> for SimpleTypeDeserializer<'de, 'a> {
 /// Deserializer of variant data, that supports only unit variants.
 /// Attempt to deserialize newtype, tuple or struct variant will return a
 /// [`DeError::Unsupported`] error.
-pub struct SimpleTypeUnitOnly;
+pub struct SimpleTypeUnitOnly {
+    /// The name of the deserialized enum
+    name: &'static str,
+}
+
+impl SimpleTypeUnitOnly {
+    /// Returns an error for an attempt to deserialize a variant of the `kind`
+    /// (`newtype`, `tuple` or `struct`)
+    fn unsupported(&self, kind: &str) -> DeError {
+        DeError::Unsupported(
+            format!(
+                "cannot deserialize enum {} variant of `{}` from an attribute or text content value, only unit variants can be selected by text",
+                kind, self.name
+            )
+            .into(),
+        )
+    }
+}
+
 impl<'de> VariantAccess<'de> for SimpleTypeUnitOnly {
     type Error = DeError;
 
@@ -769,18 +855,14 @@ impl<'de> VariantAccess<'de> for SimpleTypeUnitOnly {
     where
         T: DeserializeSeed<'de>,
     {
-        Err(DeError::Unsupported(
-            "enum newtype variants are not supported for XSD `simpleType`s".into(),
-        ))
+        Err(self.unsupported("newtype"))
     }
 
     fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, DeError>
     where
         V: Visitor<'de>,
     {
-        Err(DeError::Unsupported(
-            "enum tuple variants are not supported for XSD `simpleType`s".into(),
-        ))
+        Err(self.unsupported("tuple"))
     }
 
     fn struct_variant<V>(
@@ -791,9 +873,7 @@ impl<'de> VariantAccess<'de> for SimpleTypeUnitOnly {
     where
         V: Visitor<'de>,
     {
-        Err(DeError::Unsupported(
-            "enum struct variants are not supported for XSD `simpleType`s".into(),
-        ))
+        Err(self.unsupported("struct"))
     }
 }
 
@@ -803,7 +883,7 @@ impl<'de> VariantAccess<'de> for SimpleTypeUnitOnly {
 mod tests {
     use super::*;
     use crate::se::simple_type::{QuoteTarget, SimpleTypeSerializer};
-    use crate::se::{Indent, QuoteLevel};
+    use crate::se::{CDataPolicy, FloatFormat, Indent, QuoteLevel};
     use crate::utils::{ByteBuf, Bytes};
     use serde::de::IgnoredAny;
     use serde::{Deserialize, Serialize};
@@ -840,6 +920,9 @@ mod tests {
                         writer: String::new(),
                         target: QuoteTarget::Text,
                         level: QuoteLevel::Full,
+                        cdata: CDataPolicy::Never,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
                         indent: Indent::None,
                     })
                     .unwrap(),
@@ -923,6 +1006,7 @@ mod tests {
                     let de = AtomicDeserializer {
                         content: Content::Input($input),
                         escaped: true,
+                        bool_format: BoolFormat::default(),
                     };
                     let data: $type = Deserialize::deserialize(de).unwrap();
 
@@ -940,6 +1024,7 @@ mod tests {
                     let de = AtomicDeserializer {
                         content: Content::Input($input),
                         escaped: true,
+                        bool_format: BoolFormat::default(),
                     };
                     let data: $type = Deserialize::deserialize(de).unwrap();
 
@@ -951,6 +1036,8 @@ mod tests {
                             writer: String::new(),
                             target: QuoteTarget::Text,
                             level: QuoteLevel::Full,
+                            float_format: FloatFormat::Shortest,
+                            bytes_format: None,
                         })
                         .unwrap(),
                         $input
@@ -968,6 +1055,7 @@ mod tests {
                     let de = AtomicDeserializer {
                         content: Content::Input($input),
                         escaped: true,
+                        bool_format: BoolFormat::default(),
                     };
                     let err = <$type as Deserialize>::deserialize(de).unwrap_err();
 
@@ -1064,6 +1152,7 @@ mod tests {
             let de = AtomicDeserializer {
                 content: Content::Owned("string slice".into(), 7),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
             assert_eq!(de.content.as_str(), "slice");
 
@@ -1078,6 +1167,7 @@ mod tests {
             let de = AtomicDeserializer {
                 content: Content::Slice("string slice"),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
             assert_eq!(de.content.as_str(), "string slice");
 
@@ -1096,6 +1186,7 @@ mod tests {
             let mut seq = ListIter {
                 content: Some(Content::Input("")),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
 
             assert_eq!(seq.next_element::<&str>().unwrap(), None);
@@ -1107,6 +1198,7 @@ mod tests {
             let mut seq = ListIter {
                 content: Some(Content::Input("  ")),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
 
             assert_eq!(seq.next_element::<&str>().unwrap(), None);
@@ -1118,6 +1210,7 @@ mod tests {
             let mut seq = ListIter {
                 content: Some(Content::Input("abc")),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
 
             assert_eq!(seq.next_element::<&str>().unwrap(), Some("abc"));
@@ -1130,6 +1223,7 @@ mod tests {
             let mut seq = ListIter {
                 content: Some(Content::Input("abc def")),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
 
             assert_eq!(seq.next_element::<&str>().unwrap(), Some("abc"));
@@ -1143,6 +1237,7 @@ mod tests {
             let mut seq = ListIter {
                 content: Some(Content::Input("  def")),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
 
             assert_eq!(seq.next_element::<&str>().unwrap(), Some("def"));
@@ -1155,6 +1250,7 @@ mod tests {
             let mut seq = ListIter {
                 content: Some(Content::Input("abc  ")),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
 
             assert_eq!(seq.next_element::<&str>().unwrap(), Some("abc"));
@@ -1167,6 +1263,7 @@ mod tests {
             let mut seq = ListIter {
                 content: Some(Content::Input("string 1.23 42 true false h Unit")),
                 escaped: true,
+                bool_format: BoolFormat::default(),
             };
 
             assert_eq!(seq.next_element::<&str>().unwrap(), Some("string"));
@@ -1238,11 +1335,11 @@ mod tests {
 
         simple!(utf8, enum_unit: Enum = "Unit" => Enum::Unit);
         err!(utf8, enum_newtype: Enum = "Newtype"
-             => Unsupported("enum newtype variants are not supported for XSD `simpleType`s"));
+             => Unsupported("cannot deserialize enum newtype variant of `Enum` from an attribute or text content value, only unit variants can be selected by text"));
         err!(utf8, enum_tuple: Enum = "Tuple"
-             => Unsupported("enum tuple variants are not supported for XSD `simpleType`s"));
+             => Unsupported("cannot deserialize enum tuple variant of `Enum` from an attribute or text content value, only unit variants can be selected by text"));
         err!(utf8, enum_struct: Enum = "Struct"
-             => Unsupported("enum struct variants are not supported for XSD `simpleType`s"));
+             => Unsupported("cannot deserialize enum struct variant of `Enum` from an attribute or text content value, only unit variants can be selected by text"));
         err!(utf8, enum_other: Enum = "any data"
              => Custom("unknown variant `any data`, expected one of `Unit`, `Newtype`, `Tuple`, `Struct`"));
 
@@ -1321,11 +1418,11 @@ mod tests {
 
         utf16!(enum_unit: Enum = "Unit" => Enum::Unit);
         unsupported!(enum_newtype: Enum = "Newtype"
-                     => "enum newtype variants are not supported for XSD `simpleType`s");
+                     => "cannot deserialize enum newtype variant of `Enum` from an attribute or text content value, only unit variants can be selected by text");
         unsupported!(enum_tuple: Enum = "Tuple"
-                     => "enum tuple variants are not supported for XSD `simpleType`s");
+                     => "cannot deserialize enum tuple variant of `Enum` from an attribute or text content value, only unit variants can be selected by text");
         unsupported!(enum_struct: Enum = "Struct"
-                     => "enum struct variants are not supported for XSD `simpleType`s");
+                     => "cannot deserialize enum struct variant of `Enum` from an attribute or text content value, only unit variants can be selected by text");
         err!(utf16, enum_other: Enum = to_utf16("any data")
              => Custom("unknown variant `any data`, expected one of `Unit`, `Newtype`, `Tuple`, `Struct`"));
 
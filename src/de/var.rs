@@ -2,10 +2,11 @@ use crate::{
     de::key::QNameDeserializer,
     de::resolver::EntityResolver,
     de::simple_type::SimpleTypeDeserializer,
-    de::{DeEvent, Deserializer, XmlRead, TEXT_KEY},
+    de::{DeEvent, Deserializer, XmlRead},
     errors::serialize::DeError,
+    escape::unescape,
 };
-use serde::de::value::BorrowedStrDeserializer;
+use serde::de::value::{BorrowedStrDeserializer, StringDeserializer};
 use serde::de::{self, DeserializeSeed, Deserializer as _, Visitor};
 
 /// An enum access
@@ -40,17 +41,34 @@ where
         V: DeserializeSeed<'de>,
     {
         let decoder = self.de.reader.decoder();
+        let rewriter = self.de.name_rewriter.clone();
+        let variant_attribute = self.de.variant_attribute;
+        let text_key = self.de.text_key;
         let (name, is_text) = match self.de.peek()? {
-            DeEvent::Start(e) => (
-                seed.deserialize(QNameDeserializer::from_elem(e.raw_name(), decoder)?)?,
-                false,
-            ),
+            DeEvent::Start(e) => {
+                let attribute = match variant_attribute {
+                    Some(attribute) => e.try_get_attribute(attribute)?,
+                    None => None,
+                };
+                let name = match attribute {
+                    Some(a) => {
+                        let value = unescape(&decoder.decode(&a.value)?)?.into_owned();
+                        seed.deserialize(StringDeserializer::<DeError>::new(value))?
+                    }
+                    None => seed.deserialize(QNameDeserializer::from_elem(
+                        e.raw_name(),
+                        decoder,
+                        rewriter.as_ref(),
+                    )?)?,
+                };
+                (name, false)
+            }
             DeEvent::Text(_) => (
-                seed.deserialize(BorrowedStrDeserializer::<DeError>::new(TEXT_KEY))?,
+                seed.deserialize(BorrowedStrDeserializer::<DeError>::new(text_key))?,
                 true,
             ),
             DeEvent::End(e) => return Err(DeError::UnexpectedEnd(e.name().into_inner().to_vec())),
-            DeEvent::Eof => return Err(DeError::UnexpectedEof),
+            DeEvent::Eof => return Err(self.de.reader.eof_error()),
         };
         Ok((
             name,
@@ -98,7 +116,11 @@ where
     {
         if self.is_text {
             match self.de.next()? {
-                DeEvent::Text(e) => seed.deserialize(SimpleTypeDeserializer::from_text_content(e)),
+                DeEvent::Text(e) => seed.deserialize(SimpleTypeDeserializer::from_text_content(
+                    e,
+                    self.de.bool_format,
+                    self.de.bytes_format,
+                )),
                 // SAFETY: the other events are filtered in `variant_seed()`
                 _ => unreachable!("Only `Text` events are possible here"),
             }
@@ -113,9 +135,12 @@ where
     {
         if self.is_text {
             match self.de.next()? {
-                DeEvent::Text(e) => {
-                    SimpleTypeDeserializer::from_text_content(e).deserialize_tuple(len, visitor)
-                }
+                DeEvent::Text(e) => SimpleTypeDeserializer::from_text_content(
+                    e,
+                    self.de.bool_format,
+                    self.de.bytes_format,
+                )
+                .deserialize_tuple(len, visitor),
                 // SAFETY: the other events are filtered in `variant_seed()`
                 _ => unreachable!("Only `Text` events are possible here"),
             }
@@ -134,8 +159,12 @@ where
     {
         if self.is_text {
             match self.de.next()? {
-                DeEvent::Text(e) => SimpleTypeDeserializer::from_text_content(e)
-                    .deserialize_struct("", fields, visitor),
+                DeEvent::Text(e) => SimpleTypeDeserializer::from_text_content(
+                    e,
+                    self.de.bool_format,
+                    self.de.bytes_format,
+                )
+                .deserialize_struct("", fields, visitor),
                 // SAFETY: the other events are filtered in `variant_seed()`
                 _ => unreachable!("Only `Text` events are possible here"),
             }
@@ -14,13 +14,67 @@ use crate::Result;
 pub(crate) const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
 /// Unicode "byte order mark" (\u{FEFF}) encoded as UTF-16 with little-endian byte order.
 /// See <https://unicode.org/faq/utf_bom.html#bom1>
-#[cfg(feature = "encoding")]
 pub(crate) const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
 /// Unicode "byte order mark" (\u{FEFF}) encoded as UTF-16 with big-endian byte order.
 /// See <https://unicode.org/faq/utf_bom.html#bom1>
-#[cfg(feature = "encoding")]
 pub(crate) const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
 
+/// Unicode "byte order mark" (BOM) found at the start of an input.
+///
+/// BOMs are recognized regardless of the [`encoding`] feature, but the input
+/// in UTF-16 can be decoded only when that feature is enabled.
+///
+/// [`encoding`]: ../index.html#encoding
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Bom {
+    /// UTF-8 BOM (`EF BB BF`)
+    Utf8,
+    /// UTF-16 little-endian BOM (`FF FE`)
+    Utf16Le,
+    /// UTF-16 big-endian BOM (`FE FF`)
+    Utf16Be,
+}
+
+impl Bom {
+    /// Recognizes a BOM at the start of `bytes`
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            _ if bytes.starts_with(UTF8_BOM) => Some(Self::Utf8),
+            _ if bytes.starts_with(UTF16_LE_BOM) => Some(Self::Utf16Le),
+            _ if bytes.starts_with(UTF16_BE_BOM) => Some(Self::Utf16Be),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes of this BOM
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            Self::Utf8 => UTF8_BOM,
+            Self::Utf16Le => UTF16_LE_BOM,
+            Self::Utf16Be => UTF16_BE_BOM,
+        }
+    }
+
+    /// Returns the name of the encoding indicated by this BOM
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Utf8 => "UTF-8",
+            Self::Utf16Le => "UTF-16LE",
+            Self::Utf16Be => "UTF-16BE",
+        }
+    }
+
+    /// Returns the encoding indicated by this BOM
+    #[cfg(feature = "encoding")]
+    pub fn encoding(&self) -> &'static Encoding {
+        match self {
+            Self::Utf8 => UTF_8,
+            Self::Utf16Le => UTF_16LE,
+            Self::Utf16Be => UTF_16BE,
+        }
+    }
+}
+
 /// Decoder of byte slices into strings.
 ///
 /// If feature [`encoding`] is enabled, this encoding taken from the `"encoding"`
@@ -21,6 +21,12 @@ pub enum Error {
     ///
     /// [`encoding`]: index.html#encoding
     NonDecodable(Option<Utf8Error>),
+    /// The input is assumed to be UTF-8 by [`Reader::assume_utf8`], but starts
+    /// with a BOM of other encoding or declares other encoding in the XML
+    /// declaration. Contains the name of that encoding
+    ///
+    /// [`Reader::assume_utf8`]: crate::reader::Reader::assume_utf8
+    UnexpectedEncoding(&'static str),
     /// Unexpected End of File
     UnexpectedEof(String),
     /// End event mismatch
@@ -65,8 +71,70 @@ pub enum Error {
         /// Namespace to which prefix tried to be bound
         namespace: Vec<u8>,
     },
+    /// The document is not [well-formed](https://www.w3.org/TR/xml11/#dt-wellformed)
+    IllFormed(IllFormedError),
+    /// A [restricted character] was found in a text or inside a tag. Returned
+    /// only when [`Reader::check_restricted_chars`] is enabled.
+    ///
+    /// [restricted character]: https://www.w3.org/TR/xml11/#NT-RestrictedChar
+    /// [`Reader::check_restricted_chars`]: crate::reader::Reader::check_restricted_chars
+    IllegalCharacter {
+        /// The illegal byte
+        byte: u8,
+        /// Position of the byte in the input
+        position: usize,
+    },
+    /// An element contains two attributes with the same [expanded name], for
+    /// example, `<e p:a="1" q:a="2"/>` where prefixes `p` and `q` are bound to
+    /// the same namespace. Returned only when
+    /// [`NsReader::check_duplicate_attributes`] is enabled.
+    ///
+    /// [expanded name]: https://www.w3.org/TR/xml-names11/#dt-expname
+    /// [`NsReader::check_duplicate_attributes`]: crate::reader::NsReader::check_duplicate_attributes
+    DuplicateAttribute {
+        /// The qualified name of the duplicated attribute as written in the input
+        name: String,
+        /// Position of the duplicated attribute name in the input
+        position: usize,
+    },
+}
+
+/// Describes a violation of well-formedness constraints found by the reader
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IllFormedError {
+    /// An entity or character reference (`&...;`) was found in the name of an
+    /// element or an attribute. Contains the content of the tag (without `<`
+    /// and `>`) in which reference was found
+    EntityInName(String),
+    /// Content of the document type declaration `<!DOCTYPE ...>` cannot be
+    /// parsed by [`BytesDocType::parse`]. Contains the description of the problem
+    ///
+    /// [`BytesDocType::parse`]: crate::events::BytesDocType::parse
+    InvalidDocType(String),
+    /// A `<` character in the text which does not start a markup. Such
+    /// characters should be escaped as `&lt;`. Reported only in the
+    /// [error recovery] mode
+    ///
+    /// [error recovery]: crate::reader::Reader::error_recovery
+    UnescapedLt,
 }
 
+impl fmt::Display for IllFormedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::EntityInName(tag) => write!(
+                f,
+                "entity references are not allowed in element and attribute names, but found in `<{}>`",
+                tag
+            ),
+            Self::InvalidDocType(reason) => write!(f, "invalid DOCTYPE declaration: {}", reason),
+            Self::UnescapedLt => write!(f, "`<` that does not start a markup should be escaped"),
+        }
+    }
+}
+
+impl std::error::Error for IllFormedError {}
+
 impl From<IoError> for Error {
     /// Creates a new `Error::Io` from the given error
     #[inline]
@@ -115,6 +183,9 @@ impl fmt::Display for Error {
             Error::Io(e) => write!(f, "I/O error: {}", e),
             Error::NonDecodable(None) => write!(f, "Malformed input, decoding impossible"),
             Error::NonDecodable(Some(e)) => write!(f, "Malformed UTF-8 input: {}", e),
+            Error::UnexpectedEncoding(e) => {
+                write!(f, "Input is assumed to be UTF-8, but {} encoding was found", e)
+            }
             Error::UnexpectedEof(e) => write!(f, "Unexpected EOF during reading {}", e),
             Error::EndEventMismatch { expected, found } => {
                 write!(f, "Expecting </{}> found </{}>", expected, found)
@@ -146,6 +217,17 @@ impl fmt::Display for Error {
                 write_byte_string(f, namespace)?;
                 f.write_str("'")
             }
+            Error::IllFormed(e) => write!(f, "ill-formed document: {}", e),
+            Error::IllegalCharacter { byte, position } => write!(
+                f,
+                "illegal character 0x{:02X} at position {}",
+                byte, position
+            ),
+            Error::DuplicateAttribute { name, position } => write!(
+                f,
+                "duplicate attribute `{}` at position {}",
+                name, position
+            ),
         }
     }
 }
@@ -157,6 +239,7 @@ impl std::error::Error for Error {
             Error::NonDecodable(Some(e)) => Some(e),
             Error::InvalidAttr(e) => Some(e),
             Error::EscapeError(e) => Some(e),
+            Error::IllFormed(e) => Some(e),
             _ => None,
         }
     }
@@ -169,9 +252,7 @@ pub mod serialize {
     use super::*;
     use crate::utils::write_byte_string;
     use std::borrow::Cow;
-    #[cfg(feature = "overlapped-lists")]
-    use std::num::NonZeroUsize;
-    use std::num::{ParseFloatError, ParseIntError};
+    use std::num::{NonZeroUsize, ParseFloatError, ParseIntError};
 
     /// (De)serialization error
     #[derive(Clone, Debug)]
@@ -236,6 +317,84 @@ pub mod serialize {
         /// exceeded. The limit was provided as an argument
         #[cfg(feature = "overlapped-lists")]
         TooManyEvents(NonZeroUsize),
+        /// The input contains an element or a text after the deserialized value.
+        /// Returned only by [`Deserializer::end`] and functions that use it.
+        ///
+        /// [`Deserializer::end`]: crate::de::Deserializer::end
+        TrailingData,
+        /// The input is not empty, but contains only whitespaces, comments,
+        /// processing instructions or declarations and no root element.
+        /// An empty input is reported as [`Self::UnexpectedEof`]
+        NoRootElement,
+        /// Elements are nested deeper than allowed. The limit was provided as
+        /// an argument of [`Deserializer::max_depth`]
+        ///
+        /// [`Deserializer::max_depth`]: crate::de::Deserializer::max_depth
+        DepthLimitExceeded(NonZeroUsize),
+        /// The input contains more events than allowed. The limit was provided
+        /// as an argument of [`Deserializer::max_events`]
+        ///
+        /// [`Deserializer::max_events`]: crate::de::Deserializer::max_events
+        EventLimitExceeded(NonZeroUsize),
+        /// The input contains a text longer than allowed. The limit was provided
+        /// as an argument of [`Deserializer::max_text_len`]
+        ///
+        /// [`Deserializer::max_text_len`]: crate::de::Deserializer::max_text_len
+        TextLimitExceeded(NonZeroUsize),
+        /// Entities are expanded to a text longer than allowed. The limit was
+        /// provided as an argument of [`Deserializer::max_entity_expansion`]
+        ///
+        /// [`Deserializer::max_entity_expansion`]: crate::de::Deserializer::max_entity_expansion
+        ExpansionLimitExceeded(NonZeroUsize),
+        /// An error occurred while deserializing a value at the specified path.
+        /// The path consists of names of elements and attributes (the latter
+        /// with the `@` prefix), starting from the root element. Items of
+        /// sequences are marked with their index, for example `item[3]`.
+        ///
+        /// Returned only if [`Deserializer::track_path`] is enabled.
+        ///
+        /// [`Deserializer::track_path`]: crate::de::Deserializer::track_path
+        WithPath(Vec<String>, Box<DeError>),
+        /// Serialization was not completed: start tags of the listed elements
+        /// were written, but the elements were not closed, because `end()` was
+        /// not called for the corresponding `SerializeStruct`, `SerializeMap`
+        /// or `SerializeStructVariant`. Names are listed from the outermost
+        /// element.
+        ///
+        /// Returned only by [`Finalizer::finalize`].
+        ///
+        /// [`Finalizer::finalize`]: crate::se::Finalizer::finalize
+        NotFinalized(Vec<String>),
+        /// An element or an attribute is not mapped to any field of a struct.
+        /// Names of attributes have the `@` prefix. `position` is the position
+        /// of the element start tag or of the attribute name in the input.
+        ///
+        /// Returned only if [`Deserializer::deny_unknown`] is enabled.
+        ///
+        /// [`Deserializer::deny_unknown`]: crate::de::Deserializer::deny_unknown
+        UnknownField {
+            /// Qualified name of the element or the attribute
+            name: String,
+            /// Position of the element or the attribute in the input
+            position: usize,
+        },
+    }
+
+    impl DeError {
+        /// Prepends `segment` to the path of this error. An index segment (`[N]`)
+        /// at the start of the path is merged with the new segment
+        pub(crate) fn in_path(self, segment: String) -> Self {
+            match self {
+                DeError::WithPath(mut path, e) => {
+                    match path.first_mut() {
+                        Some(first) if first.starts_with('[') => first.insert_str(0, &segment),
+                        _ => path.insert(0, segment),
+                    }
+                    DeError::WithPath(path, e)
+                }
+                e => DeError::WithPath(vec![segment], Box::new(e)),
+            }
+        }
     }
 
     impl fmt::Display for DeError {
@@ -262,6 +421,29 @@ pub mod serialize {
                 DeError::Unsupported(s) => write!(f, "Unsupported operation: {}", s),
                 #[cfg(feature = "overlapped-lists")]
                 DeError::TooManyEvents(s) => write!(f, "Deserializer buffers {} events, limit exceeded", s),
+                DeError::TrailingData => write!(f, "Unexpected data after the deserialized value"),
+                DeError::NoRootElement => write!(f, "Document does not contain a root element"),
+                DeError::DepthLimitExceeded(s) => {
+                    write!(f, "Elements are nested deeper than {} levels", s)
+                }
+                DeError::EventLimitExceeded(s) => {
+                    write!(f, "Document contains more than {} events", s)
+                }
+                DeError::TextLimitExceeded(s) => {
+                    write!(f, "Document contains a text longer than {} bytes", s)
+                }
+                DeError::ExpansionLimitExceeded(s) => {
+                    write!(f, "Entities are expanded to more than {} bytes", s)
+                }
+                DeError::WithPath(path, e) => write!(f, "at {}: {}", path.join(" > "), e),
+                DeError::NotFinalized(open) => write!(
+                    f,
+                    "Serialization was not finalized, elements were not closed: {}",
+                    open.join(" > ")
+                ),
+                DeError::UnknownField { name, position } => {
+                    write!(f, "Unknown field `{}` at position {}", name, position)
+                }
             }
         }
     }
@@ -272,6 +454,7 @@ pub mod serialize {
                 DeError::InvalidXml(e) => Some(e),
                 DeError::InvalidInt(e) => Some(e),
                 DeError::InvalidFloat(e) => Some(e),
+                DeError::WithPath(_, e) => Some(e.as_ref()),
                 _ => None,
             }
         }
@@ -1,7 +1,8 @@
 //! Manage xml character escapes
 
-use memchr::memchr2_iter;
+use memchr::{memchr, memchr2_iter, Memchr2};
 use std::borrow::Cow;
+use std::iter::FusedIterator;
 use std::ops::Range;
 
 #[cfg(test)]
@@ -75,6 +76,35 @@ pub fn escape(raw: &str) -> Cow<str> {
     _escape(raw, |ch| matches!(ch, b'<' | b'>' | b'&' | b'\'' | b'\"'))
 }
 
+/// Escapes an `&str` in the same way as [`escape`] does, but appends the result
+/// to `out` instead of allocating a new string.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::escape::escape_into;
+///
+/// let mut out = String::new();
+/// escape_into("<tag>", &mut out);
+/// escape_into(" & ", &mut out);
+/// assert_eq!(out, "&lt;tag&gt; &amp; ");
+/// ```
+pub fn escape_into(raw: &str, out: &mut String) {
+    let bytes = raw.as_bytes();
+    let mut pos = 0;
+    while let Some(i) = bytes[pos..]
+        .iter()
+        .position(|&b| matches!(b, b'<' | b'>' | b'&' | b'\'' | b'\"'))
+    {
+        let new_pos = pos + i;
+        out.push_str(&raw[pos..new_pos]);
+        out.push_str(escaped_char(bytes[new_pos]));
+        pos = new_pos + 1;
+    }
+    out.push_str(&raw[pos..]);
+}
+
 /// Escapes an `&str` and replaces xml special characters (`<`, `>`, `&`)
 /// with their corresponding xml escaped value.
 ///
@@ -92,6 +122,22 @@ pub fn partial_escape(raw: &str) -> Cow<str> {
     _escape(raw, |ch| matches!(ch, b'<' | b'>' | b'&'))
 }
 
+/// Escapes an `&str` and replaces only the xml special characters that must be
+/// escaped (`<`, `&`) with their corresponding xml escaped value.
+///
+/// Should only be used for escaping text content. Attribute values additionally
+/// require escaping of the quote character used to delimit the value.
+///
+/// This function performs following replacements:
+///
+/// | Character | Replacement
+/// |-----------|------------
+/// | `<`       | `&lt;`
+/// | `&`       | `&amp;`
+pub fn minimal_escape(raw: &str) -> Cow<'_, str> {
+    _escape(raw, |ch| matches!(ch, b'<' | b'&'))
+}
+
 /// Escapes an `&str` and replaces a subset of xml special characters (`<`, `>`,
 /// `&`, `'`, `"`) with their corresponding xml escaped value.
 pub(crate) fn _escape<F: Fn(u8) -> bool>(raw: &str, escape_chars: F) -> Cow<str> {
@@ -106,24 +152,7 @@ pub(crate) fn _escape<F: Fn(u8) -> bool>(raw: &str, escape_chars: F) -> Cow<str>
         let escaped = escaped.as_mut().expect("initialized");
         let new_pos = pos + i;
         escaped.extend_from_slice(&bytes[pos..new_pos]);
-        match bytes[new_pos] {
-            b'<' => escaped.extend_from_slice(b"&lt;"),
-            b'>' => escaped.extend_from_slice(b"&gt;"),
-            b'\'' => escaped.extend_from_slice(b"&apos;"),
-            b'&' => escaped.extend_from_slice(b"&amp;"),
-            b'"' => escaped.extend_from_slice(b"&quot;"),
-
-            // This set of escapes handles characters that should be escaped
-            // in elements of xs:lists, because those characters works as
-            // delimiters of list elements
-            b'\t' => escaped.extend_from_slice(b"&#9;"),
-            b'\n' => escaped.extend_from_slice(b"&#10;"),
-            b'\r' => escaped.extend_from_slice(b"&#13;"),
-            b' ' => escaped.extend_from_slice(b"&#32;"),
-            _ => unreachable!(
-                "Only '<', '>','\', '&', '\"', '\\t', '\\r', '\\n', and ' ' are escaped"
-            ),
-        }
+        escaped.extend_from_slice(escaped_char(bytes[new_pos]).as_bytes());
         pos = new_pos + 1;
     }
 
@@ -141,6 +170,26 @@ pub(crate) fn _escape<F: Fn(u8) -> bool>(raw: &str, escape_chars: F) -> Cow<str>
     }
 }
 
+/// Returns an escaped representation of the special character `ch`
+fn escaped_char(ch: u8) -> &'static str {
+    match ch {
+        b'<' => "&lt;",
+        b'>' => "&gt;",
+        b'\'' => "&apos;",
+        b'&' => "&amp;",
+        b'"' => "&quot;",
+
+        // This set of escapes handles characters that should be escaped
+        // in elements of xs:lists, because those characters works as
+        // delimiters of list elements
+        b'\t' => "&#9;",
+        b'\n' => "&#10;",
+        b'\r' => "&#13;",
+        b' ' => "&#32;",
+        _ => unreachable!("Only '<', '>','\', '&', '\"', '\\t', '\\r', '\\n', and ' ' are escaped"),
+    }
+}
+
 /// Unescape an `&str` and replaces all xml escaped characters (`&...;`) into
 /// their corresponding value.
 ///
@@ -152,6 +201,27 @@ pub fn unescape(raw: &str) -> Result<Cow<str>, EscapeError> {
     unescape_with(raw, |_| None)
 }
 
+/// Unescape an `&str` in the same way as [`unescape`] does, but appends the
+/// result to `out` instead of allocating a new string.
+///
+/// In case of error `out` may contain a partially unescaped content. Positions
+/// in the error are relative to the start of `raw`.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::escape::unescape_into;
+///
+/// let mut out = String::new();
+/// unescape_into("&lt;tag&gt;", &mut out).unwrap();
+/// unescape_into(" &amp; ", &mut out).unwrap();
+/// assert_eq!(out, "<tag> & ");
+/// ```
+pub fn unescape_into(raw: &str, out: &mut String) -> Result<(), EscapeError> {
+    unescape_with_into(raw, UnescapeOptions::default(), |_| None, out)
+}
+
 /// Unescape an `&str` and replaces all xml escaped characters (`&...;`) into
 /// their corresponding value, using a resolver function for custom entities.
 ///
@@ -161,60 +231,330 @@ pub fn unescape(raw: &str) -> Result<Cow<str>, EscapeError> {
 /// [HTML5 escapes]: https://dev.w3.org/html5/html-author/charref
 pub fn unescape_with<'input, 'entity, F>(
     raw: &'input str,
-    mut resolve_entity: F,
+    resolve_entity: F,
 ) -> Result<Cow<'input, str>, EscapeError>
 where
     // the lifetime of the output comes from a capture or is `'static`
     F: FnMut(&str) -> Option<&'entity str>,
 {
-    let bytes = raw.as_bytes();
-    let mut unescaped = None;
-    let mut last_end = 0;
-    let mut iter = memchr2_iter(b'&', b';', bytes);
-    while let Some(start) = iter.by_ref().find(|p| bytes[*p] == b'&') {
-        match iter.next() {
-            Some(end) if bytes[end] == b';' => {
-                // append valid data
-                if unescaped.is_none() {
-                    unescaped = Some(String::with_capacity(raw.len()));
+    unescape_with_options(raw, UnescapeOptions::default(), resolve_entity)
+}
+
+/// Defines which kinds of references are replaced by [`unescape_with_options`].
+/// References of disabled kinds are kept in the output as is.
+///
+/// By default all references are replaced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnescapeOptions {
+    /// Replace character references, such as `&#65;` or `&#x41;`
+    pub char_refs: bool,
+    /// Replace entity references, such as `&lt;` or custom entities
+    pub entity_refs: bool,
+}
+
+impl Default for UnescapeOptions {
+    fn default() -> Self {
+        Self {
+            char_refs: true,
+            entity_refs: true,
+        }
+    }
+}
+
+/// Unescape an `&str` in the same way as [`unescape_with`] does, but replaces
+/// only the kinds of references enabled in `options`. Other references are
+/// returned as is and are not checked for correctness.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::escape::{unescape_with_options, UnescapeOptions};
+///
+/// let options = UnescapeOptions {
+///     char_refs: false,
+///     entity_refs: true,
+/// };
+/// let unescaped = unescape_with_options("&#65;&lt;", options, |_| None).unwrap();
+/// assert_eq!(unescaped, "&#65;<");
+/// ```
+pub fn unescape_with_options<'input, 'entity, F>(
+    raw: &'input str,
+    options: UnescapeOptions,
+    resolve_entity: F,
+) -> Result<Cow<'input, str>, EscapeError>
+where
+    // the lifetime of the output comes from a capture or is `'static`
+    F: FnMut(&str) -> Option<&'entity str>,
+{
+    if memchr(b'&', raw.as_bytes()).is_none() {
+        return Ok(Cow::Borrowed(raw));
+    }
+    let mut unescaped = String::with_capacity(raw.len());
+    unescape_with_into(raw, options, resolve_entity, &mut unescaped)?;
+    Ok(Cow::Owned(unescaped))
+}
+
+/// Unescapes `raw` using `resolve_entity` for custom entities and appends
+/// the result to `unescaped`
+fn unescape_with_into<'entity, F>(
+    raw: &str,
+    options: UnescapeOptions,
+    mut resolve_entity: F,
+    unescaped: &mut String,
+) -> Result<(), EscapeError>
+where
+    F: FnMut(&str) -> Option<&'entity str>,
+{
+    unescape_chunks_into(
+        raw,
+        options,
+        unescaped,
+        |name, range, unescaped| match resolve_entity(name) {
+            Some(value) => {
+                unescaped.push_str(value);
+                Ok(())
+            }
+            None => Err(EscapeError::UnrecognizedSymbol(range, name.to_string())),
+        },
+    )
+}
+
+/// Unescapes `raw` in the same way as [`unescape_with`] does, but custom entities
+/// are handled by `resolve_entity`, which appends replacement text to the output
+/// itself and may fail with an error of any type. The callback receives the entity
+/// name and its position in `raw` (without `&` and `;`).
+pub(crate) fn unescape_custom<'input, E, F>(
+    raw: &'input str,
+    resolve_entity: F,
+) -> Result<Cow<'input, str>, E>
+where
+    E: From<EscapeError>,
+    F: FnMut(&str, Range<usize>, &mut String) -> Result<(), E>,
+{
+    if memchr(b'&', raw.as_bytes()).is_none() {
+        return Ok(Cow::Borrowed(raw));
+    }
+    let mut unescaped = String::with_capacity(raw.len());
+    unescape_chunks_into(
+        raw,
+        UnescapeOptions::default(),
+        &mut unescaped,
+        resolve_entity,
+    )?;
+    Ok(Cow::Owned(unescaped))
+}
+
+/// Appends unescaped `raw` to `unescaped`, calling `resolve_entity` for each
+/// custom entity
+fn unescape_chunks_into<E, F>(
+    raw: &str,
+    options: UnescapeOptions,
+    unescaped: &mut String,
+    mut resolve_entity: F,
+) -> Result<(), E>
+where
+    E: From<EscapeError>,
+    F: FnMut(&str, Range<usize>, &mut String) -> Result<(), E>,
+{
+    for chunk in Chunks::new(raw, options) {
+        match chunk? {
+            Chunk::Raw(s) => unescaped.push_str(s),
+            Chunk::Char(c) => unescaped.push(c),
+            Chunk::Entity(name, range) => resolve_entity(name, range, unescaped)?,
+        }
+    }
+    Ok(())
+}
+
+/// A piece of unescaped text produced by [`Chunks`]
+enum Chunk<'input> {
+    /// Data between references, copied from the input as is
+    Raw(&'input str),
+    /// Character from a character reference or predefined entity
+    Char(char),
+    /// Name of a custom entity and its position in the input (without `&` and `;`).
+    /// Custom entities are resolved by users of [`Chunks`]
+    Entity(&'input str, Range<usize>),
+}
+
+/// Splits `raw` into pieces of unescaped text. Shared by all unescaping
+/// functions and [`Unescaper`]
+struct Chunks<'input> {
+    raw: &'input str,
+    /// Position of the first byte of `raw` that was not returned yet
+    last_end: usize,
+    /// Positions of `&` and `;` in `raw`
+    iter: Memchr2<'input>,
+    /// Positions of `&` and `;` (if found) of a reference, that was found
+    /// when returning raw data before it
+    pending: Option<(usize, Option<usize>)>,
+    /// Kinds of references that should be replaced
+    options: UnescapeOptions,
+}
+
+impl<'input> Chunks<'input> {
+    fn new(raw: &'input str, options: UnescapeOptions) -> Self {
+        Self {
+            raw,
+            last_end: 0,
+            iter: memchr2_iter(b'&', b';', raw.as_bytes()),
+            pending: None,
+            options,
+        }
+    }
+
+    /// Makes the iterator to return `None` from now on. Called after an error
+    fn stop(&mut self) {
+        self.iter = memchr2_iter(b'&', b';', b"");
+        self.last_end = self.raw.len();
+    }
+}
+
+impl<'input> Iterator for Chunks<'input> {
+    type Item = Result<Chunk<'input>, EscapeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let raw = self.raw;
+        let bytes = raw.as_bytes();
+        let (start, end) = match self.pending.take() {
+            Some(reference) => reference,
+            None => match self.iter.by_ref().find(|p| bytes[*p] == b'&') {
+                Some(start) => {
+                    let end = match self.iter.next() {
+                        Some(end) if bytes[end] == b';' => Some(end),
+                        _ => None,
+                    };
+                    if self.last_end < start {
+                        // return valid data before the reference
+                        self.pending = Some((start, end));
+                        let data = &raw[self.last_end..start];
+                        self.last_end = start;
+                        return Some(Ok(Chunk::Raw(data)));
+                    }
+                    (start, end)
                 }
-                let unescaped = unescaped.as_mut().expect("initialized");
-                unescaped.push_str(&raw[last_end..start]);
-
-                // search for character correctness
-                let pat = &raw[start + 1..end];
-                if let Some(entity) = pat.strip_prefix('#') {
-                    let codepoint = parse_number(entity, start..end)?;
-                    unescaped.push_str(codepoint.encode_utf8(&mut [0u8; 4]));
-                } else if let Some(value) = named_entity(pat) {
-                    unescaped.push_str(value);
-                } else if let Some(value) = resolve_entity(pat) {
-                    unescaped.push_str(value);
-                } else {
-                    return Err(EscapeError::UnrecognizedSymbol(
-                        start + 1..end,
-                        pat.to_string(),
-                    ));
+                None if self.last_end < raw.len() => {
+                    let data = &raw[self.last_end..];
+                    self.last_end = raw.len();
+                    return Some(Ok(Chunk::Raw(data)));
                 }
-
-                last_end = end + 1;
+                None => return None,
+            },
+        };
+        let end = match end {
+            Some(end) => end,
+            None => {
+                self.stop();
+                return Some(Err(EscapeError::UnterminatedEntity(start..raw.len())));
             }
-            _ => return Err(EscapeError::UnterminatedEntity(start..raw.len())),
+        };
+        self.last_end = end + 1;
+
+        // search for character correctness
+        let pat = &raw[start + 1..end];
+        let keep = if pat.starts_with('#') {
+            !self.options.char_refs
+        } else {
+            !self.options.entity_refs
+        };
+        let chunk = if keep {
+            Ok(Chunk::Raw(&raw[start..=end]))
+        } else if let Some(entity) = pat.strip_prefix('#') {
+            parse_number(entity, start..end).map(Chunk::Char)
+        } else if let Some(value) = named_entity(pat) {
+            Ok(Chunk::Raw(value))
+        } else {
+            Ok(Chunk::Entity(pat, start + 1..end))
+        };
+        if chunk.is_err() {
+            self.stop();
         }
+        Some(chunk)
     }
+}
 
-    if let Some(mut unescaped) = unescaped {
-        if let Some(raw) = raw.get(last_end..) {
-            unescaped.push_str(raw);
+/// An iterator over characters of an unescaped string. Can be used to process
+/// large texts without allocating a string for the whole unescaped content.
+///
+/// Recognizes the same references as [`unescape`] and [`unescape_with`] do.
+/// Iteration stops after the first error.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::escape::Unescaper;
+///
+/// let count = Unescaper::new("&lt;tag&gt; &amp; &#x1F600;")
+///     .filter(|c| matches!(c, Ok('<' | '>' | '&')))
+///     .count();
+/// assert_eq!(count, 3);
+///
+/// let unescaped: Result<String, _> = Unescaper::new("&lt;tag&gt;").collect();
+/// assert_eq!(unescaped.unwrap(), "<tag>");
+/// ```
+pub struct Unescaper<'input, F> {
+    chunks: Chunks<'input>,
+    resolve_entity: F,
+    /// Characters of the current chunk that was not returned yet
+    current: std::str::Chars<'input>,
+}
+
+impl<'input> Unescaper<'input, fn(&str) -> Option<&'input str>> {
+    /// Creates an iterator over unescaped characters of `raw`
+    pub fn new(raw: &'input str) -> Self {
+        Self::with(raw, |_| None)
+    }
+}
+
+impl<'input, F> Unescaper<'input, F>
+where
+    F: FnMut(&str) -> Option<&'input str>,
+{
+    /// Creates an iterator over unescaped characters of `raw` which uses
+    /// `resolve_entity` for custom entities, as [`unescape_with`] does
+    pub fn with(raw: &'input str, resolve_entity: F) -> Self {
+        Self {
+            chunks: Chunks::new(raw, UnescapeOptions::default()),
+            resolve_entity,
+            current: "".chars(),
+        }
+    }
+}
+
+impl<'input, F> Iterator for Unescaper<'input, F>
+where
+    F: FnMut(&str) -> Option<&'input str>,
+{
+    type Item = Result<char, EscapeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(c) = self.current.next() {
+                return Some(Ok(c));
+            }
+            match self.chunks.next()? {
+                Ok(Chunk::Raw(s)) => self.current = s.chars(),
+                Ok(Chunk::Char(c)) => return Some(Ok(c)),
+                Ok(Chunk::Entity(name, range)) => match (self.resolve_entity)(name) {
+                    Some(s) => self.current = s.chars(),
+                    None => {
+                        self.chunks.stop();
+                        let error = EscapeError::UnrecognizedSymbol(range, name.to_string());
+                        return Some(Err(error));
+                    }
+                },
+                Err(e) => return Some(Err(e)),
+            }
         }
-        Ok(Cow::Owned(unescaped))
-    } else {
-        Ok(Cow::Borrowed(raw))
     }
 }
 
+impl<'input, F> FusedIterator for Unescaper<'input, F> where F: FnMut(&str) -> Option<&'input str> {}
+
 #[cfg(not(feature = "escape-html"))]
-fn named_entity(name: &str) -> Option<&str> {
+pub(crate) fn named_entity(name: &str) -> Option<&str> {
     // match over strings are not allowed in const functions
     let s = match name.as_bytes() {
         b"lt" => "<",
@@ -227,7 +567,7 @@ fn named_entity(name: &str) -> Option<&str> {
     Some(s)
 }
 #[cfg(feature = "escape-html")]
-fn named_entity(name: &str) -> Option<&str> {
+pub(crate) fn named_entity(name: &str) -> Option<&str> {
     // imported from https://dev.w3.org/html5/html-author/charref
     // match over strings are not allowed in const functions
     //TODO: automate up-to-dating using https://html.spec.whatwg.org/entities.json
@@ -1700,6 +2040,7 @@ fn parse_number(bytes: &str, range: Range<usize>) -> Result<char, EscapeError> {
     if code == 0 {
         return Err(EscapeError::EntityWithNull(range));
     }
+    // `from_u32` rejects surrogates (0xD800..=0xDFFF) and codes above 0x10FFFF
     match std::char::from_u32(code) {
         Some(c) => Ok(c),
         None => Err(EscapeError::InvalidCodepoint(code)),
@@ -1707,6 +2048,8 @@ fn parse_number(bytes: &str, range: Range<usize>) -> Result<char, EscapeError> {
 }
 
 fn parse_hexadecimal(bytes: &str) -> Result<u32, EscapeError> {
+    // leading zeroes are allowed and do not change the value
+    let bytes = bytes.trim_start_matches('0');
     // maximum code is 0x10FFFF => 6 characters
     if bytes.len() > 6 {
         return Err(EscapeError::TooLongHexadecimal);
@@ -1725,6 +2068,8 @@ fn parse_hexadecimal(bytes: &str) -> Result<u32, EscapeError> {
 }
 
 fn parse_decimal(bytes: &str) -> Result<u32, EscapeError> {
+    // leading zeroes are allowed and do not change the value
+    let bytes = bytes.trim_start_matches('0');
     // maximum code is 0x10FFFF = 1114111 => 7 characters
     if bytes.len() > 7 {
         return Err(EscapeError::TooLongDecimal);
@@ -1755,6 +2100,51 @@ fn test_unescape() {
     assert!(unescape("&foo;").is_err());
 }
 
+#[test]
+fn test_unescape_into() {
+    let mut out = String::from("prefix ");
+    unescape_into("test", &mut out).unwrap();
+    unescape_into("&lt;test&gt;", &mut out).unwrap();
+    unescape_into("&#x30;&#49;", &mut out).unwrap();
+    assert_eq!(out, "prefix test<test>01");
+
+    // Positions are relative to the input, not to the output
+    let mut out = String::from("prefix ");
+    match unescape_into("a&lt;&foo;", &mut out) {
+        Err(EscapeError::UnrecognizedSymbol(range, name)) => {
+            assert_eq!(range, 6..9);
+            assert_eq!(name, "foo");
+        }
+        x => panic!("Expected `Err(UnrecognizedSymbol(..))`, but got `{:?}`", x),
+    }
+    match unescape_into("&foo", &mut out) {
+        Err(EscapeError::UnterminatedEntity(range)) => assert_eq!(range, 0..4),
+        x => panic!("Expected `Err(UnterminatedEntity(..))`, but got `{:?}`", x),
+    }
+}
+
+#[test]
+fn test_unescape_astral() {
+    assert_eq!(unescape("&#128512;").unwrap(), "\u{1F600}");
+    assert_eq!(unescape("&#x1F600;").unwrap(), "\u{1F600}");
+    assert_eq!(unescape("&#x0001F600;").unwrap(), "\u{1F600}");
+    assert_eq!(unescape("&#x10FFFF;").unwrap(), "\u{10FFFF}");
+
+    // Surrogates cannot be represented in UTF-8
+    assert!(matches!(
+        unescape("&#xD800;"),
+        Err(EscapeError::InvalidCodepoint(0xD800))
+    ));
+    assert!(matches!(
+        unescape("&#57343;"),
+        Err(EscapeError::InvalidCodepoint(0xDFFF))
+    ));
+    assert!(matches!(
+        unescape("&#x110000;"),
+        Err(EscapeError::InvalidCodepoint(0x110000))
+    ));
+}
+
 #[test]
 fn test_unescape_with() {
     let custom_entities = |ent: &str| match ent {
@@ -1779,6 +2169,82 @@ fn test_unescape_with() {
     assert!(unescape_with("&fop;", custom_entities).is_err());
 }
 
+#[test]
+fn test_unescape_with_options() {
+    let custom_entities = |ent: &str| match ent {
+        "foo" => Some("BAR"),
+        _ => None,
+    };
+    let unescape = |raw, char_refs, entity_refs| {
+        let options = UnescapeOptions {
+            char_refs,
+            entity_refs,
+        };
+        unescape_with_options(raw, options, custom_entities)
+    };
+
+    let raw = "&#65;&#x42;&lt;&foo;";
+    assert_eq!(unescape(raw, true, true).unwrap(), "AB<BAR");
+    assert_eq!(unescape(raw, false, true).unwrap(), "&#65;&#x42;<BAR");
+    assert_eq!(unescape(raw, true, false).unwrap(), "AB&lt;&foo;");
+    assert_eq!(unescape(raw, false, false).unwrap(), raw);
+
+    // Kept references are not checked
+    assert_eq!(unescape("&#xZ;", false, true).unwrap(), "&#xZ;");
+    assert_eq!(unescape("&fop;", true, false).unwrap(), "&fop;");
+    assert!(unescape("&fop;", false, true).is_err());
+    assert!(unescape("&#65", false, false).is_err());
+}
+
+#[test]
+fn test_unescaper() {
+    let mut raw = String::new();
+    for i in 0..1000 {
+        raw.push_str("chunk &amp; ");
+        raw.push_str(&i.to_string());
+        raw.push_str("&#x20;&lt;&#62;");
+    }
+    let expected = unescape(&raw).unwrap();
+    let unescaped: String = Unescaper::new(&raw).map(Result::unwrap).collect();
+    assert_eq!(unescaped, expected);
+
+    let unescaped: Result<String, _> = Unescaper::new("no references").collect();
+    assert_eq!(unescaped.unwrap(), "no references");
+    let unescaped: Result<String, _> = Unescaper::new("").collect();
+    assert_eq!(unescaped.unwrap(), "");
+    let unescaped: Result<String, _> = Unescaper::new("&amp;&amp;").collect();
+    assert_eq!(unescaped.unwrap(), "&&");
+}
+
+#[test]
+fn test_unescaper_with() {
+    let custom_entities = |ent: &str| match ent {
+        "foo" => Some("BAR"),
+        _ => None,
+    };
+    let unescaped: Result<String, _> = Unescaper::with("&lt;&foo;&gt;", custom_entities).collect();
+    assert_eq!(unescaped.unwrap(), "<BAR>");
+}
+
+#[test]
+fn test_unescaper_error() {
+    let mut iter = Unescaper::new("a&foo;b&amp;");
+    assert_eq!(iter.next().unwrap().unwrap(), 'a');
+    assert!(matches!(
+        iter.next(),
+        Some(Err(EscapeError::UnrecognizedSymbol(_, _)))
+    ));
+    assert!(iter.next().is_none());
+
+    let mut iter = Unescaper::new("a&b &amp;");
+    assert_eq!(iter.next().unwrap().unwrap(), 'a');
+    assert!(matches!(
+        iter.next(),
+        Some(Err(EscapeError::UnterminatedEntity(_)))
+    ));
+    assert!(iter.next().is_none());
+}
+
 #[test]
 fn test_escape() {
     let unchanged = escape("test");
@@ -1797,6 +2263,23 @@ fn test_escape() {
     );
 }
 
+#[test]
+fn test_escape_into() {
+    let inputs = ["test", "<test>", "\"a\"bc", "", "prefix_\"a\"b&<>c", "'"];
+
+    let mut out = String::new();
+    let mut expected = String::new();
+    for input in inputs {
+        escape_into(input, &mut out);
+        expected += &escape(input);
+        assert_eq!(out, expected);
+    }
+    assert_eq!(
+        out,
+        "test&lt;test&gt;&quot;a&quot;bcprefix_&quot;a&quot;b&amp;&lt;&gt;c&apos;"
+    );
+}
+
 #[test]
 fn test_partial_escape() {
     let unchanged = partial_escape("test");
@@ -1814,3 +2297,21 @@ fn test_partial_escape() {
         "prefix_\"a\"b&amp;&lt;&gt;c"
     );
 }
+
+#[test]
+fn test_minimal_escape() {
+    let unchanged = minimal_escape("test");
+    // assert_eq does not check that Cow is borrowed, but we explicitly use Cow
+    // because it influences diff
+    // TODO: use assert_matches! when stabilized and other features will bump MSRV
+    assert_eq!(unchanged, Cow::Borrowed("test"));
+    assert!(matches!(unchanged, Cow::Borrowed(_)));
+
+    assert_eq!(minimal_escape("<test>"), "&lt;test>");
+    assert_eq!(minimal_escape("\"a\"bc"), "\"a\"bc");
+    assert_eq!(minimal_escape("'a'b&c"), "'a'b&amp;c");
+    assert_eq!(
+        minimal_escape("prefix_\"a\"b&<>c"),
+        "prefix_\"a\"b&amp;&lt;>c"
+    );
+}
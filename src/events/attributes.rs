@@ -3,7 +3,7 @@
 //! Provides an iterator over attributes key/value pairs
 
 use crate::errors::Result as XmlResult;
-use crate::escape::{escape, unescape_with};
+use crate::escape::{escape, unescape_with_options, UnescapeOptions};
 use crate::name::QName;
 use crate::reader::{is_whitespace, Reader};
 use crate::utils::{write_byte_string, write_cow_string, Bytes};
@@ -65,6 +65,42 @@ impl<'a> Attribute<'a> {
     pub fn unescape_value_with<'entity>(
         &self,
         resolve_entity: impl FnMut(&str) -> Option<&'entity str>,
+    ) -> XmlResult<Cow<'a, str>> {
+        self.unescape_value_with_options(UnescapeOptions::default(), resolve_entity)
+    }
+
+    /// Decodes using UTF-8 then unescapes the value, replacing only the kinds
+    /// of references enabled in `options`. Other references, for example
+    /// `&#65;`, are kept as is, which is useful for lossless editing.
+    ///
+    /// See also [`unescape_value_with()`](Self::unescape_value_with)
+    ///
+    /// This method is available only if [`encoding`] feature is **not** enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::escape::UnescapeOptions;
+    /// use quick_xml::events::BytesStart;
+    ///
+    /// let start = BytesStart::from_content(r#"tag foo="&#65;&amp;""#, 3);
+    /// let attr = start.attributes().next().unwrap().unwrap();
+    ///
+    /// let options = UnescapeOptions {
+    ///     char_refs: false,
+    ///     entity_refs: true,
+    /// };
+    /// assert_eq!(attr.unescape_value_with_options(options, |_| None).unwrap(), "&#65;&");
+    /// assert_eq!(attr.unescape_value().unwrap(), "A&");
+    /// ```
+    ///
+    /// [`encoding`]: ../../index.html#encoding
+    #[cfg(any(doc, not(feature = "encoding")))]
+    pub fn unescape_value_with_options<'entity>(
+        &self,
+        options: UnescapeOptions,
+        resolve_entity: impl FnMut(&str) -> Option<&'entity str>,
     ) -> XmlResult<Cow<'a, str>> {
         // from_utf8 should never fail because content is always UTF-8 encoded
         let decoded = match &self.value {
@@ -73,7 +109,7 @@ impl<'a> Attribute<'a> {
             Cow::Owned(bytes) => Cow::Owned(std::str::from_utf8(bytes)?.to_string()),
         };
 
-        match unescape_with(&decoded, resolve_entity)? {
+        match unescape_with_options(&decoded, options, resolve_entity)? {
             // Because result is borrowed, no replacements was done and we can use original string
             Cow::Borrowed(_) => Ok(decoded),
             Cow::Owned(s) => Ok(s.into()),
@@ -96,6 +132,24 @@ impl<'a> Attribute<'a> {
         &self,
         reader: &Reader<B>,
         resolve_entity: impl FnMut(&str) -> Option<&'entity str>,
+    ) -> XmlResult<Cow<'a, str>> {
+        self.decode_and_unescape_value_with_options(
+            reader,
+            UnescapeOptions::default(),
+            resolve_entity,
+        )
+    }
+
+    /// Decodes then unescapes the value, replacing only the kinds of references
+    /// enabled in `options`. Other references are kept as is.
+    ///
+    /// This will allocate if the value contains any replaced references or in
+    /// non-UTF-8 encoding.
+    pub fn decode_and_unescape_value_with_options<'entity, B>(
+        &self,
+        reader: &Reader<B>,
+        options: UnescapeOptions,
+        resolve_entity: impl FnMut(&str) -> Option<&'entity str>,
     ) -> XmlResult<Cow<'a, str>> {
         let decoded = match &self.value {
             Cow::Borrowed(bytes) => reader.decoder().decode(bytes)?,
@@ -103,7 +157,7 @@ impl<'a> Attribute<'a> {
             Cow::Owned(bytes) => reader.decoder().decode(bytes)?.into_owned().into(),
         };
 
-        match unescape_with(&decoded, resolve_entity)? {
+        match unescape_with_options(&decoded, options, resolve_entity)? {
             // Because result is borrowed, no replacements was done and we can use original string
             Cow::Borrowed(_) => Ok(decoded),
             Cow::Owned(s) => Ok(s.into()),
@@ -190,6 +244,8 @@ pub struct Attributes<'a> {
     bytes: &'a [u8],
     /// Iterator state, independent from the actual source of bytes
     state: IterState,
+    /// If `true`, whitespaces in the attribute values are normalized
+    normalize_values: bool,
 }
 
 impl<'a> Attributes<'a> {
@@ -199,6 +255,7 @@ impl<'a> Attributes<'a> {
         Self {
             bytes: buf,
             state: IterState::new(pos, html),
+            normalize_values: false,
         }
     }
 
@@ -222,6 +279,48 @@ impl<'a> Attributes<'a> {
         self.state.check_duplicates = val;
         self
     }
+
+    /// Changes whether whitespaces in attribute values should be normalized.
+    ///
+    /// When enabled, leading and trailing whitespaces are removed from values
+    /// and every sequence of whitespaces inside them is replaced by a single
+    /// space, like for the values of [`xs:token`] type. Normalization is applied
+    /// to the raw value, so whitespaces inserted by character references, such
+    /// as `&#x20;`, are preserved after unescaping, as required by [the specification].
+    ///
+    /// (`false` by default)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::BytesStart;
+    ///
+    /// let start = BytesStart::from_content(r#"tag foo="  a   b  ""#, 3);
+    ///
+    /// let mut attributes = start.attributes();
+    /// let attr = attributes.normalize_values(true).next().unwrap().unwrap();
+    /// assert_eq!(attr.value, "a b".as_bytes());
+    ///
+    /// let attr = start.attributes().next().unwrap().unwrap();
+    /// assert_eq!(attr.value, "  a   b  ".as_bytes());
+    /// ```
+    ///
+    /// [`xs:token`]: https://www.w3.org/TR/xmlschema11-2/#token
+    /// [the specification]: https://www.w3.org/TR/xml11/#AVNormalize
+    pub fn normalize_values(&mut self, val: bool) -> &mut Attributes<'a> {
+        self.normalize_values = val;
+        self
+    }
+
+    /// Converts this iterator into an iterator that yields attributes together
+    /// with the style of their quoting. Such attributes can be added to another
+    /// tag keeping the style with [`BytesStart::push_attr`].
+    ///
+    /// [`BytesStart::push_attr`]: crate::events::BytesStart::push_attr
+    pub fn quoted(self) -> QuotedAttributes<'a> {
+        QuotedAttributes(self)
+    }
 }
 
 impl<'a> Iterator for Attributes<'a> {
@@ -231,7 +330,15 @@ impl<'a> Iterator for Attributes<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         match self.state.next(self.bytes) {
             None => None,
-            Some(Ok(a)) => Some(Ok(a.map(|range| &self.bytes[range]).into())),
+            Some(Ok(a)) => {
+                let mut attr: Attribute = a.map(|range| &self.bytes[range]).into();
+                if self.normalize_values {
+                    if let Cow::Borrowed(value) = attr.value {
+                        attr.value = normalize_whitespace(value);
+                    }
+                }
+                Some(Ok(attr))
+            }
             Some(Err(e)) => Some(Err(e)),
         }
     }
@@ -239,6 +346,61 @@ impl<'a> Iterator for Attributes<'a> {
 
 impl<'a> FusedIterator for Attributes<'a> {}
 
+/// Removes leading and trailing whitespaces and replaces each sequence of
+/// whitespaces with a single space. Borrows if no replacements are required.
+fn normalize_whitespace(value: &[u8]) -> Cow<'_, [u8]> {
+    let start = value
+        .iter()
+        .position(|&b| !is_whitespace(b))
+        .unwrap_or(value.len());
+    let end = value
+        .iter()
+        .rposition(|&b| !is_whitespace(b))
+        .map_or(start, |i| i + 1);
+    let trimmed = &value[start..end];
+
+    let normalized = trimmed
+        .windows(2)
+        .all(|w| !is_whitespace(w[1]) || (w[1] == b' ' && !is_whitespace(w[0])));
+    if normalized {
+        return Cow::Borrowed(trimmed);
+    }
+
+    let mut result = Vec::with_capacity(trimmed.len());
+    for part in trimmed
+        .split(|&b| is_whitespace(b))
+        .filter(|p| !p.is_empty())
+    {
+        if !result.is_empty() {
+            result.push(b' ');
+        }
+        result.extend_from_slice(part);
+    }
+    Cow::Owned(result)
+}
+
+/// Iterator over XML attributes which yields [`Attr`]s that store the original
+/// quoting style of each attribute.
+///
+/// Created by the [`Attributes::quoted`] method.
+#[derive(Clone, Debug)]
+pub struct QuotedAttributes<'a>(Attributes<'a>);
+
+impl<'a> Iterator for QuotedAttributes<'a> {
+    type Item = Result<Attr<&'a [u8]>, AttrError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.0.bytes;
+        self.0
+            .state
+            .next(bytes)
+            .map(|result| result.map(|a| a.map(|range| &bytes[range])))
+    }
+}
+
+impl<'a> FusedIterator for QuotedAttributes<'a> {}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// Errors that can be raised during parsing attributes.
@@ -527,6 +689,12 @@ impl IterState {
         }
     }
 
+    /// Enables or disables the check for duplicated attribute names
+    #[inline]
+    pub fn check_duplicates(&mut self, val: bool) {
+        self.check_duplicates = val;
+    }
+
     /// Recover from an error that could have been made on a previous step.
     /// Returns an offset from which parsing should continue.
     /// If there no input left, returns `None`.
@@ -2245,3 +2413,151 @@ mod html {
         assert_eq!(iter.next(), None);
     }
 }
+
+/// Checks unescaping of attribute values with custom entities
+#[cfg(test)]
+mod unescape {
+    use super::*;
+    use crate::errors::Error;
+    use crate::escape::EscapeError;
+    use pretty_assertions::assert_eq;
+
+    fn resolve(entity: &str) -> Option<&'static str> {
+        match entity {
+            "custom" => Some("X"),
+            _ => None,
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "encoding"))]
+    fn custom() {
+        let attr = Attribute::from((&b"foo"[..], &b"a &custom; b"[..]));
+        assert_eq!(attr.unescape_value_with(resolve).unwrap(), "a X b");
+    }
+
+    #[test]
+    #[cfg(not(feature = "encoding"))]
+    fn unknown() {
+        let attr = Attribute::from((&b"foo"[..], &b"a &unknown; b"[..]));
+        match attr.unescape_value_with(resolve) {
+            Err(Error::EscapeError(EscapeError::UnrecognizedSymbol(range, entity))) => {
+                assert_eq!(range, 3..10);
+                assert_eq!(entity, "unknown");
+            }
+            x => panic!(
+                "Expected `Err(UnrecognizedSymbol(_, _))`, but got `{:?}`",
+                x
+            ),
+        }
+    }
+
+    #[test]
+    fn decode_custom() {
+        let reader = Reader::from_str("");
+        let attr = Attribute::from((&b"foo"[..], &b"a &custom; b"[..]));
+        assert_eq!(
+            attr.decode_and_unescape_value_with(&reader, resolve)
+                .unwrap(),
+            "a X b"
+        );
+    }
+
+    #[test]
+    fn decode_unknown() {
+        let reader = Reader::from_str("");
+        let attr = Attribute::from((&b"foo"[..], &b"a &unknown; b"[..]));
+        match attr.decode_and_unescape_value_with(&reader, resolve) {
+            Err(Error::EscapeError(EscapeError::UnrecognizedSymbol(range, entity))) => {
+                assert_eq!(range, 3..10);
+                assert_eq!(entity, "unknown");
+            }
+            x => panic!(
+                "Expected `Err(UnrecognizedSymbol(_, _))`, but got `{:?}`",
+                x
+            ),
+        }
+    }
+
+    const KEEP_CHAR_REFS: UnescapeOptions = UnescapeOptions {
+        char_refs: false,
+        entity_refs: true,
+    };
+
+    #[test]
+    #[cfg(not(feature = "encoding"))]
+    fn char_refs() {
+        let attr = Attribute::from((&b"foo"[..], &b"&#65;"[..]));
+        assert_eq!(attr.unescape_value().unwrap(), "A");
+        assert_eq!(
+            attr.unescape_value_with_options(KEEP_CHAR_REFS, resolve)
+                .unwrap(),
+            "&#65;"
+        );
+    }
+
+    #[test]
+    fn decode_char_refs() {
+        let reader = Reader::from_str("");
+        let attr = Attribute::from((&b"foo"[..], &b"&#65; &custom;"[..]));
+        assert_eq!(
+            attr.decode_and_unescape_value_with(&reader, resolve)
+                .unwrap(),
+            "A X"
+        );
+        assert_eq!(
+            attr.decode_and_unescape_value_with_options(&reader, KEEP_CHAR_REFS, resolve)
+                .unwrap(),
+            "&#65; X"
+        );
+    }
+}
+
+/// Checks normalization of whitespaces in attribute values
+#[cfg(test)]
+mod normalize {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn value(attributes: &str, normalize: bool) -> Cow<'_, [u8]> {
+        let mut iter = Attributes::new(attributes, 0);
+        iter.normalize_values(normalize);
+        iter.next().unwrap().unwrap().value
+    }
+
+    #[test]
+    fn disabled() {
+        assert_eq!(value(r#"foo="  a   b  ""#, false), &b"  a   b  "[..]);
+        assert_eq!(value("foo='\ta\r\nb'", false), &b"\ta\r\nb"[..]);
+    }
+
+    #[test]
+    fn enabled() {
+        assert_eq!(value(r#"foo="  a   b  ""#, true), &b"a b"[..]);
+        assert_eq!(value("foo='\ta\r\nb'", true), &b"a b"[..]);
+        assert_eq!(value(r#"foo="   ""#, true), &b""[..]);
+    }
+
+    /// Trimming alone should not allocate
+    #[test]
+    fn borrowed_when_only_trimmed() {
+        assert!(matches!(
+            value(r#"foo=" a b ""#, true),
+            Cow::Borrowed(b"a b")
+        ));
+        assert!(matches!(value(r#"foo="a b""#, true), Cow::Borrowed(b"a b")));
+    }
+
+    /// Whitespaces inserted by character references are not normalized
+    #[test]
+    fn character_references() {
+        let mut iter = Attributes::new(r#"foo=" a&#x20;&#x20;b ""#, 0);
+        iter.normalize_values(true);
+        let attr = iter.next().unwrap().unwrap();
+        assert_eq!(
+            attr.decode_and_unescape_value(&Reader::from_str(""))
+                .unwrap(),
+            "a  b"
+        );
+    }
+}
@@ -0,0 +1,333 @@
+//! Provides a parser of the document type declaration `<!DOCTYPE ...>`.
+//!
+//! Use [`BytesDocType::parse`] to get a [`DocTypeDecl`] from the [`Event::DocType`].
+//!
+//! [`BytesDocType::parse`]: crate::events::BytesDocType::parse
+//! [`Event::DocType`]: crate::events::Event::DocType
+
+use crate::errors::{Error, IllFormedError, Result};
+
+/// Parsed document type declaration
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DocTypeDecl {
+    /// Name of the root element of the document
+    pub name: String,
+    /// Public identifier, specified after the `PUBLIC` keyword
+    pub public_id: Option<String>,
+    /// System identifier (usually an URI of external DTD), specified after the
+    /// `SYSTEM` keyword or after the public identifier
+    pub system_id: Option<String>,
+    /// General entities, declared in the internal subset, in the order of
+    /// their declaration
+    pub entities: Vec<EntityDecl>,
+}
+
+/// Declaration of an internal general entity: `<!ENTITY name "value">`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntityDecl {
+    /// Name of the entity
+    pub name: String,
+    /// Replacement text of the entity as written in the declaration, without
+    /// quotes. Character and entity references inside it are not expanded
+    pub value: String,
+}
+
+/// Returns an error describing why a DOCTYPE declaration cannot be parsed
+fn invalid(reason: &str) -> Error {
+    Error::IllFormed(IllFormedError::InvalidDocType(reason.to_string()))
+}
+
+#[inline]
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\r' | '\n')
+}
+
+/// Parses content of `<!DOCTYPE ...>` without the `<!DOCTYPE` prefix and the
+/// closing `>`
+pub(crate) fn parse(content: &str) -> Result<DocTypeDecl> {
+    let mut input = content.trim_start_matches(is_whitespace);
+
+    let end = input
+        .find(|c| is_whitespace(c) || c == '[')
+        .unwrap_or(input.len());
+    if end == 0 {
+        return Err(invalid("root element name is missing"));
+    }
+    let mut decl = DocTypeDecl {
+        name: input[..end].to_string(),
+        ..DocTypeDecl::default()
+    };
+    input = input[end..].trim_start_matches(is_whitespace);
+
+    if let Some(rest) = input.strip_prefix("PUBLIC") {
+        let (public_id, rest) = literal(rest)?;
+        let (system_id, rest) = literal(rest)?;
+        decl.public_id = Some(public_id.to_string());
+        decl.system_id = Some(system_id.to_string());
+        input = rest.trim_start_matches(is_whitespace);
+    } else if let Some(rest) = input.strip_prefix("SYSTEM") {
+        let (system_id, rest) = literal(rest)?;
+        decl.system_id = Some(system_id.to_string());
+        input = rest.trim_start_matches(is_whitespace);
+    }
+
+    if let Some(rest) = input.strip_prefix('[') {
+        input = internal_subset(rest, &mut decl.entities)?;
+        input = input.trim_start_matches(is_whitespace);
+    }
+
+    if input.is_empty() {
+        Ok(decl)
+    } else {
+        Err(invalid("unexpected content after the declaration"))
+    }
+}
+
+/// Skips leading whitespaces and reads a quoted literal. Returns content of
+/// the literal without quotes and the rest of the input after the literal
+fn literal(input: &str) -> Result<(&str, &str)> {
+    let input = input.trim_start_matches(is_whitespace);
+    let quote = match input.chars().next() {
+        Some(q @ ('"' | '\'')) => q,
+        _ => return Err(invalid("quoted literal expected")),
+    };
+    let input = &input[1..];
+    match input.find(quote) {
+        Some(end) => Ok((&input[..end], &input[end + 1..])),
+        None => Err(invalid("unclosed quoted literal")),
+    }
+}
+
+/// Returns the rest of the `input` after the first occurrence of `end`
+fn skip_after<'i>(input: &'i str, end: &str) -> Result<&'i str> {
+    match input.find(end) {
+        Some(i) => Ok(&input[i + end.len()..]),
+        None => Err(invalid("unclosed markup in the internal subset")),
+    }
+}
+
+/// Returns the rest of the `input` after the `>` that closes the current
+/// markup declaration. `>` inside quoted literals is ignored
+fn skip_declaration(mut input: &str) -> Result<&str> {
+    loop {
+        match input.find(['"', '\'', '>']) {
+            Some(i) if input[i..].starts_with('>') => return Ok(&input[i + 1..]),
+            Some(i) => input = literal(&input[i..])?.1,
+            None => return Err(invalid("unclosed markup in the internal subset")),
+        }
+    }
+}
+
+/// Reads the internal subset up to and including the closing `]`, collecting
+/// declared general entities to `entities`. Returns the rest of the input
+fn internal_subset<'i>(mut input: &'i str, entities: &mut Vec<EntityDecl>) -> Result<&'i str> {
+    loop {
+        input = input.trim_start_matches(is_whitespace);
+        if let Some(rest) = input.strip_prefix(']') {
+            return Ok(rest);
+        } else if let Some(rest) = input.strip_prefix("<!--") {
+            input = skip_after(rest, "-->")?;
+        } else if let Some(rest) = input.strip_prefix("<?") {
+            input = skip_after(rest, "?>")?;
+        } else if let Some(rest) = input.strip_prefix("<!ENTITY") {
+            input = entity(rest, entities)?;
+        } else if let Some(rest) = input.strip_prefix("<!") {
+            input = skip_declaration(rest)?;
+        } else if let Some(rest) = input.strip_prefix('%') {
+            // Reference to a parameter entity
+            input = skip_after(rest, ";")?;
+        } else if input.is_empty() {
+            return Err(invalid("unclosed internal subset"));
+        } else {
+            return Err(invalid("unexpected content in the internal subset"));
+        }
+    }
+}
+
+/// Reads an entity declaration after the `<!ENTITY` prefix. Declarations of
+/// internal general entities are added to `entities`, other declarations are
+/// skipped. Returns the rest of the input after the declaration
+fn entity<'i>(input: &'i str, entities: &mut Vec<EntityDecl>) -> Result<&'i str> {
+    let input = input.trim_start_matches(is_whitespace);
+    // Parameter entities are not interesting for us
+    if input.starts_with('%') {
+        return skip_declaration(input);
+    }
+    let end = input
+        .find(|c| is_whitespace(c) || c == '>')
+        .unwrap_or(input.len());
+    if end == 0 {
+        return Err(invalid("entity name is missing"));
+    }
+    let name = &input[..end];
+    let rest = input[end..].trim_start_matches(is_whitespace);
+    // External entities (with `SYSTEM` or `PUBLIC` identifiers) are not
+    // interesting for us
+    if !rest.starts_with(['"', '\'']) {
+        return skip_declaration(rest);
+    }
+    let (value, rest) = literal(rest)?;
+    match rest.trim_start_matches(is_whitespace).strip_prefix('>') {
+        Some(rest) => {
+            entities.push(EntityDecl {
+                name: name.to_string(),
+                value: value.to_string(),
+            });
+            Ok(rest)
+        }
+        None => Err(invalid("`>` expected after the entity value")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::BytesDocType;
+    use pretty_assertions::assert_eq;
+
+    fn entity(name: &str, value: &str) -> EntityDecl {
+        EntityDecl {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    #[test]
+    fn name_only() {
+        let decl = BytesDocType::new("html").parse().unwrap();
+        assert_eq!(
+            decl,
+            DocTypeDecl {
+                name: "html".into(),
+                public_id: None,
+                system_id: None,
+                entities: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn system() {
+        let decl = BytesDocType::new(r#"root SYSTEM "root.dtd""#)
+            .parse()
+            .unwrap();
+        assert_eq!(
+            decl,
+            DocTypeDecl {
+                name: "root".into(),
+                public_id: None,
+                system_id: Some("root.dtd".into()),
+                entities: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn public() {
+        let decl = BytesDocType::new(
+            r#"html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN"
+            'http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd'"#,
+        )
+        .parse()
+        .unwrap();
+        assert_eq!(
+            decl,
+            DocTypeDecl {
+                name: "html".into(),
+                public_id: Some("-//W3C//DTD XHTML 1.0 Strict//EN".into()),
+                system_id: Some("http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd".into()),
+                entities: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn internal_subset() {
+        let decl = BytesDocType::new(
+            r#"root [
+                <!-- <!ENTITY commented "ignored"> -->
+                <!ELEMENT root (#PCDATA)>
+                <!ATTLIST root attr CDATA "with > inside">
+                <!ENTITY e1 "entity 1">
+                <!ENTITY % parameter "ignored">
+                <!ENTITY external SYSTEM "external.xml">
+                %parameter;
+                <?pi <!ENTITY in-pi "ignored">?>
+                <!ENTITY e2 'entity "2" &amp; &e1;'>
+            ]"#,
+        )
+        .parse()
+        .unwrap();
+        assert_eq!(
+            decl,
+            DocTypeDecl {
+                name: "root".into(),
+                public_id: None,
+                system_id: None,
+                entities: vec![
+                    entity("e1", "entity 1"),
+                    entity("e2", r#"entity "2" &amp; &e1;"#),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn external_id_and_internal_subset() {
+        let decl = BytesDocType::new(r#"root SYSTEM "root.dtd"[<!ENTITY e "v">]"#)
+            .parse()
+            .unwrap();
+        assert_eq!(
+            decl,
+            DocTypeDecl {
+                name: "root".into(),
+                public_id: None,
+                system_id: Some("root.dtd".into()),
+                entities: vec![entity("e", "v")],
+            }
+        );
+    }
+
+    mod errors {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn error(content: &str) -> String {
+            match BytesDocType::new(content).parse() {
+                Err(Error::IllFormed(IllFormedError::InvalidDocType(reason))) => reason,
+                x => panic!("Expected `Err(InvalidDocType(_))`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn missing_name() {
+            assert_eq!(error("[]"), "root element name is missing");
+        }
+
+        #[test]
+        fn public_without_system_id() {
+            assert_eq!(error(r#"html PUBLIC "public""#), "quoted literal expected");
+        }
+
+        #[test]
+        fn unclosed_literal() {
+            assert_eq!(error(r#"root SYSTEM "root.dtd"#), "unclosed quoted literal");
+        }
+
+        #[test]
+        fn unclosed_subset() {
+            assert_eq!(
+                error(r#"root [<!ENTITY e "v">"#),
+                "unclosed internal subset"
+            );
+        }
+
+        #[test]
+        fn trailing_content() {
+            assert_eq!(
+                error("root [] trailing"),
+                "unexpected content after the declaration"
+            );
+        }
+    }
+}
@@ -36,6 +36,7 @@
 //! [`Event`]: crate::events::Event
 
 pub mod attributes;
+pub mod doctype;
 
 #[cfg(feature = "encoding")]
 use encoding_rs::Encoding;
@@ -52,7 +53,8 @@ use crate::reader::is_whitespace;
 use crate::utils::write_cow_string;
 #[cfg(feature = "serialize")]
 use crate::utils::CowRef;
-use attributes::{Attribute, Attributes};
+use attributes::{Attr, Attribute, Attributes};
+use doctype::DocTypeDecl;
 use std::mem::replace;
 
 /// Opening tag data (`Event::Start`), with optional attributes.
@@ -237,18 +239,139 @@ impl<'a> BytesStart<'a> {
         self
     }
 
+    /// Add additional attributes to this tag from an iterator of `(key, value)`
+    /// pairs. Unlike [`extend_attributes`], both keys and values can be owned.
+    ///
+    /// Values are escaped, so the result is the same as pushing each pair as
+    /// a `(&str, &str)` tuple with [`push_attribute`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::events::BytesStart;
+    /// let mut start = BytesStart::new("e");
+    /// start.extend_attribute_pairs(vec![("a".to_string(), "1".to_string())]);
+    ///
+    /// assert_eq!(&*start, br#"e a="1""#);
+    /// ```
+    ///
+    /// [`extend_attributes`]: Self::extend_attributes
+    /// [`push_attribute`]: Self::push_attribute
+    pub fn extend_attribute_pairs<'b, I, K, V>(&mut self, attributes: I) -> &mut BytesStart<'a>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Cow<'b, str>>,
+        V: Into<Cow<'b, str>>,
+    {
+        for (key, value) in attributes {
+            let (key, value) = (key.into(), value.into());
+            self.push_attribute((key.as_ref(), value.as_ref()));
+        }
+        self
+    }
+
+    /// Copies all attributes yielded by `attrs`, for example, attributes of another
+    /// tag, to this element. Values are copied as is, because they are already escaped.
+    ///
+    /// If `attrs` yields an error, it is returned and this element is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::BytesStart;
+    ///
+    /// let source = BytesStart::from_content(r#"source a="1" b='&lt;'"#, 6);
+    ///
+    /// let mut copy = BytesStart::new("copy");
+    /// copy.with_attributes_from(source.attributes())
+    ///     .unwrap()
+    ///     .push_attribute(("c", "3"));
+    ///
+    /// assert_eq!(&*copy, br#"copy a="1" b="&lt;" c="3""#);
+    /// ```
+    pub fn with_attributes_from(&mut self, attrs: Attributes) -> Result<&mut BytesStart<'a>> {
+        let len = self.buf.len();
+        for attr in attrs {
+            match attr {
+                Ok(attr) => self.push_attribute(attr),
+                Err(e) => {
+                    self.buf.to_mut().truncate(len);
+                    return Err(e.into());
+                }
+            }
+        }
+        Ok(self)
+    }
+
     /// Adds an attribute to this element.
+    ///
+    /// The value is enclosed in double quotes, unless it contains a `"` character
+    /// (which is possible for raw values of attributes that were enclosed in single
+    /// quotes in the source document). Such values are enclosed in single quotes.
+    ///
+    /// Use [`push_attr`] to specify quotes explicitly.
+    ///
+    /// [`push_attr`]: Self::push_attr
     pub fn push_attribute<'b, A>(&mut self, attr: A)
     where
         A: Into<Attribute<'b>>,
     {
         let a = attr.into();
+        let quote = choose_quote(&a.value);
+        self.push_quoted(a.key.as_ref(), &a.value, quote);
+    }
+
+    /// Adds an attribute to this element keeping its quoting style. Attributes
+    /// with original quoting style can be obtained with [`Attributes::quoted`].
+    ///
+    /// [`Attr::Empty`] attributes are written without a value. Values of
+    /// [`Attr::Unquoted`] attributes are enclosed in quotes as in [`push_attribute`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::BytesStart;
+    ///
+    /// let source = BytesStart::from_content(r#"source a="1" b='2'"#, 6);
+    ///
+    /// let mut copy = BytesStart::new("copy");
+    /// for attr in source.attributes().quoted() {
+    ///     copy.push_attr(attr.unwrap());
+    /// }
+    ///
+    /// assert_eq!(&*copy, br#"copy a="1" b='2'"#);
+    /// ```
+    ///
+    /// [`Attributes::quoted`]: attributes::Attributes::quoted
+    /// [`push_attribute`]: Self::push_attribute
+    pub fn push_attr<T: AsRef<[u8]>>(&mut self, attr: Attr<T>) {
+        match attr {
+            Attr::DoubleQ(key, value) => self.push_quoted(key.as_ref(), value.as_ref(), b'"'),
+            Attr::SingleQ(key, value) => self.push_quoted(key.as_ref(), value.as_ref(), b'\''),
+            Attr::Unquoted(key, value) => {
+                let value = value.as_ref();
+                self.push_quoted(key.as_ref(), value, choose_quote(value));
+            }
+            Attr::Empty(key) => {
+                let bytes = self.buf.to_mut();
+                bytes.push(b' ');
+                bytes.extend_from_slice(key.as_ref());
+            }
+        }
+    }
+
+    /// Adds an attribute with the specified raw `value` enclosed in `quote`s
+    fn push_quoted(&mut self, key: &[u8], value: &[u8], quote: u8) {
         let bytes = self.buf.to_mut();
         bytes.push(b' ');
-        bytes.extend_from_slice(a.key.as_ref());
-        bytes.extend_from_slice(b"=\"");
-        bytes.extend_from_slice(a.value.as_ref());
-        bytes.push(b'"');
+        bytes.extend_from_slice(key);
+        bytes.push(b'=');
+        bytes.push(quote);
+        bytes.extend_from_slice(value);
+        bytes.push(quote);
     }
 
     /// Remove all attributes from the ByteStart
@@ -257,6 +380,72 @@ impl<'a> BytesStart<'a> {
         self
     }
 
+    /// Remove all attributes with the specified raw (possibly prefixed) name.
+    /// Other attributes keep their order and quoting style.
+    ///
+    /// If any attribute cannot be parsed, the tag is left unchanged. Use
+    /// [`try_remove_attribute`] to know whether attributes were removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::BytesStart;
+    ///
+    /// let mut start = BytesStart::from_content(r#"e a="1" x:b='2' c="3""#, 1);
+    /// start.remove_attribute("x:b").push_attribute(("d", "4"));
+    ///
+    /// assert_eq!(&*start, br#"e a="1" c="3" d="4""#);
+    /// ```
+    ///
+    /// [`try_remove_attribute`]: Self::try_remove_attribute
+    pub fn remove_attribute(&mut self, name: &str) -> &mut BytesStart<'a> {
+        let _ = self.try_remove_attribute(name);
+        self
+    }
+
+    /// Try to remove all attributes with the specified raw (possibly prefixed) name.
+    /// Returns `true` if at least one attribute was removed.
+    ///
+    /// If any attribute cannot be parsed, an error is returned and the tag is
+    /// left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::BytesStart;
+    ///
+    /// let mut start = BytesStart::from_content(r#"e a="1" x:b='2'"#, 1);
+    ///
+    /// assert_eq!(start.try_remove_attribute("x:b").unwrap(), true);
+    /// assert_eq!(start.try_remove_attribute("x:b").unwrap(), false);
+    /// assert_eq!(&*start, br#"e a="1""#);
+    /// ```
+    pub fn try_remove_attribute(&mut self, name: &str) -> Result<bool> {
+        let mut iter = self.attributes();
+        iter.with_checks(false);
+        let mut removed = false;
+        let attributes = iter
+            .quoted()
+            .filter(|a| match a {
+                Ok(a) if a.key().as_ref() == name.as_bytes() => {
+                    removed = true;
+                    false
+                }
+                _ => true,
+            })
+            .map(|a| a.map(|a| a.map(|bytes| bytes.to_vec())))
+            .collect::<std::result::Result<Vec<Attr<Vec<u8>>>, _>>()?;
+        if removed {
+            self.clear_attributes();
+            for attr in attributes {
+                self.push_attr(attr);
+            }
+        }
+        Ok(removed)
+    }
+
     /// Returns an iterator over the attributes of this tag.
     pub fn attributes(&self) -> Attributes {
         Attributes::wrap(&self.buf, self.name_len, false)
@@ -289,6 +478,17 @@ impl<'a> BytesStart<'a> {
     }
 }
 
+/// Returns a quote character that can enclose the raw attribute `value`
+/// without escaping: a double quote, unless the value contains it
+#[inline]
+fn choose_quote(value: &[u8]) -> u8 {
+    if value.contains(&b'"') {
+        b'\''
+    } else {
+        b'"'
+    }
+}
+
 impl<'a> Debug for BytesStart<'a> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "BytesStart {{ buf: ")?;
@@ -689,6 +889,26 @@ impl<'a> BytesText<'a> {
     }
 
     /// Creates a new `BytesText` from an escaped string.
+    ///
+    /// The content is not checked and not processed in any way, so [`Writer`]
+    /// writes it byte-for-byte. Use this method to write already escaped text
+    /// or pre-rendered markup without escaping it twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesText, Event};
+    /// use quick_xml::writer::Writer;
+    ///
+    /// let mut writer = Writer::new(Vec::new());
+    /// writer.write_event(Event::Text(BytesText::from_escaped("a &lt; b"))).unwrap();
+    /// writer.write_event(Event::Text(BytesText::new("a &lt; b"))).unwrap();
+    ///
+    /// assert_eq!(writer.into_inner(), b"a &lt; ba &amp;lt; b");
+    /// ```
+    ///
+    /// [`Writer`]: crate::writer::Writer
     #[inline]
     pub fn from_escaped<C: Into<Cow<'a, str>>>(content: C) -> Self {
         Self::wrap(str_cow_to_bytes(content), Decoder::utf8())
@@ -726,6 +946,18 @@ impl<'a> BytesText<'a> {
         }
     }
 
+    /// Decodes the content of the event using the encoding of the document,
+    /// without unescaping. Useful for the content of comments, which is not escaped.
+    ///
+    /// This will allocate in non-UTF-8 encoding.
+    pub fn decode(&self) -> Result<Cow<'a, str>> {
+        Ok(match &self.content {
+            Cow::Borrowed(bytes) => self.decoder.decode(bytes)?,
+            // Convert to owned, because otherwise Cow will be bound with wrong lifetime
+            Cow::Owned(bytes) => self.decoder.decode(bytes)?.into_owned().into(),
+        })
+    }
+
     /// Decodes then unescapes the content of the event.
     ///
     /// This will allocate if the value contains any escape sequences or in
@@ -829,9 +1061,12 @@ impl<'a> BytesCData<'a> {
 
     /// Creates a new `BytesCData` from a string.
     ///
-    /// # Warning
+    /// `content` may contain the `]]>` sequence, which cannot appear inside
+    /// a CDATA section. The [`Writer`] splits such content into several
+    /// adjacent CDATA sections when writing it; their concatenated content is
+    /// equal to `content`.
     ///
-    /// `content` must not contain the `]]>` sequence.
+    /// [`Writer`]: crate::writer::Writer
     #[inline]
     pub fn new<C: Into<Cow<'a, str>>>(content: C) -> Self {
         Self::wrap(str_cow_to_bytes(content), Decoder::utf8())
@@ -911,8 +1146,11 @@ impl<'a> BytesCData<'a> {
         ))
     }
 
-    /// Gets content of this text buffer in the specified encoding
-    pub(crate) fn decode(&self) -> Result<Cow<'a, str>> {
+    /// Decodes the content of the section using the encoding of the document.
+    /// The content is returned as is, because CDATA sections are not escaped.
+    ///
+    /// This will allocate in non-UTF-8 encoding.
+    pub fn decode(&self) -> Result<Cow<'a, str>> {
         Ok(match &self.content {
             Cow::Borrowed(bytes) => self.decoder.decode(bytes)?,
             // Convert to owned, because otherwise Cow will be bound with wrong lifetime
@@ -949,6 +1187,319 @@ impl<'a> arbitrary::Arbitrary<'a> for BytesCData<'a> {
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// Content of the document type declaration `<!DOCTYPE ...>` without the
+/// `<!DOCTYPE` prefix and the closing `>`.
+///
+/// Raw content is accessible via [`Deref`] implementation. Use [`Self::parse`]
+/// to get the root element name, external identifiers and entities, declared
+/// in the internal subset.
+#[derive(Clone, Eq, PartialEq)]
+pub struct BytesDocType<'a> {
+    content: Cow<'a, [u8]>,
+    /// Encoding in which the `content` is stored inside the event
+    decoder: Decoder,
+}
+
+impl<'a> BytesDocType<'a> {
+    /// Creates a new `BytesDocType` from a byte sequence in the specified encoding.
+    #[inline]
+    pub(crate) fn wrap<C: Into<Cow<'a, [u8]>>>(content: C, decoder: Decoder) -> Self {
+        Self {
+            content: content.into(),
+            decoder,
+        }
+    }
+
+    /// Creates a new `BytesDocType` from a string. The string should contain
+    /// content of the declaration without the `<!DOCTYPE` prefix and the
+    /// closing `>`, for example, `html` or `root SYSTEM "root.dtd"`.
+    #[inline]
+    pub fn new<C: Into<Cow<'a, str>>>(content: C) -> Self {
+        Self::wrap(str_cow_to_bytes(content), Decoder::utf8())
+    }
+
+    /// Ensures that all data is owned to extend the object's lifetime if
+    /// necessary.
+    #[inline]
+    pub fn into_owned(self) -> BytesDocType<'static> {
+        BytesDocType {
+            content: self.content.into_owned().into(),
+            decoder: self.decoder,
+        }
+    }
+
+    /// Extracts the inner `Cow` from the `BytesDocType` event container.
+    #[inline]
+    pub fn into_inner(self) -> Cow<'a, [u8]> {
+        self.content
+    }
+
+    /// Converts the event into a borrowed event.
+    #[inline]
+    pub fn borrow(&self) -> BytesDocType<'_> {
+        BytesDocType {
+            content: Cow::Borrowed(&self.content),
+            decoder: self.decoder,
+        }
+    }
+
+    /// Decodes the content of the declaration using the encoding of the document.
+    pub fn decode(&self) -> Result<Cow<'a, str>> {
+        Ok(match &self.content {
+            Cow::Borrowed(bytes) => self.decoder.decode(bytes)?,
+            // Convert to owned, because otherwise Cow will be bound with wrong lifetime
+            Cow::Owned(bytes) => self.decoder.decode(bytes)?.into_owned().into(),
+        })
+    }
+
+    /// Parses the declaration and returns the root element name, `PUBLIC` and
+    /// `SYSTEM` identifiers and general entities, declared in the internal subset.
+    ///
+    /// Other markup declarations (elements, attribute lists, notations and
+    /// parameter entities) are skipped. References to parameter entities are
+    /// not expanded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::doctype::EntityDecl;
+    /// use quick_xml::events::Event;
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str(r#"
+    ///     <!DOCTYPE root SYSTEM "root.dtd" [
+    ///         <!ENTITY e "entity value">
+    ///     ]>
+    ///     <root/>
+    /// "#);
+    /// reader.trim_text(true);
+    ///
+    /// match reader.read_event().unwrap() {
+    ///     Event::DocType(e) => {
+    ///         let decl = e.parse().unwrap();
+    ///         assert_eq!(decl.name, "root");
+    ///         assert_eq!(decl.public_id, None);
+    ///         assert_eq!(decl.system_id.as_deref(), Some("root.dtd"));
+    ///         assert_eq!(decl.entities, vec![EntityDecl {
+    ///             name: "e".into(),
+    ///             value: "entity value".into(),
+    ///         }]);
+    ///     }
+    ///     e => panic!("Expected DocType, found {:?}", e),
+    /// }
+    /// ```
+    pub fn parse(&self) -> Result<DocTypeDecl> {
+        doctype::parse(&self.decode()?)
+    }
+}
+
+impl<'a> Debug for BytesDocType<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "BytesDocType {{ content: ")?;
+        write_cow_string(f, &self.content)?;
+        write!(f, " }}")
+    }
+}
+
+impl<'a> Deref for BytesDocType<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.content
+    }
+}
+
+/// Converts the declaration content into the [`BytesText`], which was stored
+/// in [`Event::DocType`] before [`BytesDocType`] was introduced. The content
+/// is not escaped.
+impl<'a> From<BytesDocType<'a>> for BytesText<'a> {
+    #[inline]
+    fn from(doctype: BytesDocType<'a>) -> Self {
+        BytesText::wrap(doctype.content, doctype.decoder)
+    }
+}
+
+/// Converts the content of the [`BytesText`], which was stored in
+/// [`Event::DocType`] before [`BytesDocType`] was introduced, into the
+/// declaration content. The content is not unescaped.
+impl<'a> From<BytesText<'a>> for BytesDocType<'a> {
+    #[inline]
+    fn from(text: BytesText<'a>) -> Self {
+        BytesDocType::wrap(text.content, text.decoder)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for BytesDocType<'a> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(<&str>::arbitrary(u)?))
+    }
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <&str as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Content of the processing instruction `<?...?>` without the `<?` and `?>`
+/// delimiters.
+///
+/// Raw content is accessible via [`Deref`] implementation. Use [`Self::target`]
+/// and [`Self::content`] to get the target of the instruction and its data.
+#[derive(Clone, Eq, PartialEq)]
+pub struct BytesPI<'a> {
+    content: Cow<'a, [u8]>,
+    /// Encoding in which the `content` is stored inside the event
+    decoder: Decoder,
+}
+
+impl<'a> BytesPI<'a> {
+    /// Creates a new `BytesPI` from a byte sequence in the specified encoding.
+    #[inline]
+    pub(crate) fn wrap<C: Into<Cow<'a, [u8]>>>(content: C, decoder: Decoder) -> Self {
+        Self {
+            content: content.into(),
+            decoder,
+        }
+    }
+
+    /// Creates a new `BytesPI` from a string. The string should contain the
+    /// target and the data of the instruction separated by a whitespace,
+    /// for example, `xml-stylesheet href="style.css"`.
+    #[inline]
+    pub fn new<C: Into<Cow<'a, str>>>(content: C) -> Self {
+        Self::wrap(str_cow_to_bytes(content), Decoder::utf8())
+    }
+
+    /// Creates a new `BytesPI` from the target and the data of the instruction.
+    /// If `content` is empty, the instruction will consist of the target only.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::BytesPI;
+    ///
+    /// let pi = BytesPI::from_parts("xml-stylesheet", r#"href="style.css""#);
+    /// assert_eq!(&*pi, br#"xml-stylesheet href="style.css""#);
+    ///
+    /// let pi = BytesPI::from_parts("target", "");
+    /// assert_eq!(&*pi, b"target");
+    /// ```
+    pub fn from_parts(target: &str, content: &str) -> BytesPI<'static> {
+        let mut buf = String::with_capacity(target.len() + content.len() + 1);
+        buf.push_str(target);
+        if !content.is_empty() {
+            buf.push(' ');
+            buf.push_str(content);
+        }
+        BytesPI::new(buf)
+    }
+
+    /// Ensures that all data is owned to extend the object's lifetime if
+    /// necessary.
+    #[inline]
+    pub fn into_owned(self) -> BytesPI<'static> {
+        BytesPI {
+            content: self.content.into_owned().into(),
+            decoder: self.decoder,
+        }
+    }
+
+    /// Extracts the inner `Cow` from the `BytesPI` event container.
+    #[inline]
+    pub fn into_inner(self) -> Cow<'a, [u8]> {
+        self.content
+    }
+
+    /// Converts the event into a borrowed event.
+    #[inline]
+    pub fn borrow(&self) -> BytesPI<'_> {
+        BytesPI {
+            content: Cow::Borrowed(&self.content),
+            decoder: self.decoder,
+        }
+    }
+
+    /// Returns the target of the processing instruction, that is, everything
+    /// up to the first whitespace.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesPI, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str(r#"<?xml-stylesheet type="text/xsl" href="x"?>"#);
+    /// match reader.read_event().unwrap() {
+    ///     Event::PI(e) => {
+    ///         assert_eq!(e.target(), b"xml-stylesheet");
+    ///         assert_eq!(e.content(), br#"type="text/xsl" href="x""#);
+    ///     }
+    ///     e => panic!("Expected PI, found {:?}", e),
+    /// }
+    /// ```
+    pub fn target(&self) -> &[u8] {
+        let len = self
+            .content
+            .iter()
+            .position(|&b| is_whitespace(b))
+            .unwrap_or(self.content.len());
+        &self.content[..len]
+    }
+
+    /// Returns the data of the processing instruction, that is, everything after
+    /// the target and the whitespaces that follow it. Returns an empty slice if
+    /// the instruction has no data.
+    pub fn content(&self) -> &[u8] {
+        let rest = &self.content[self.target().len()..];
+        let start = rest
+            .iter()
+            .position(|&b| !is_whitespace(b))
+            .unwrap_or(rest.len());
+        &rest[start..]
+    }
+
+    /// Decodes the whole content of the instruction using the encoding of the document.
+    pub fn decode(&self) -> Result<Cow<'a, str>> {
+        Ok(match &self.content {
+            Cow::Borrowed(bytes) => self.decoder.decode(bytes)?,
+            // Convert to owned, because otherwise Cow will be bound with wrong lifetime
+            Cow::Owned(bytes) => self.decoder.decode(bytes)?.into_owned().into(),
+        })
+    }
+}
+
+impl<'a> Debug for BytesPI<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "BytesPI {{ content: ")?;
+        write_cow_string(f, &self.content)?;
+        write!(f, " }}")
+    }
+}
+
+impl<'a> Deref for BytesPI<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.content
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for BytesPI<'a> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self::new(<&str>::arbitrary(u)?))
+    }
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <&str as arbitrary::Arbitrary>::size_hint(depth)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
 /// Event emitted by [`Reader::read_event_into`].
 ///
 /// [`Reader::read_event_into`]: crate::reader::Reader::read_event_into
@@ -970,9 +1521,9 @@ pub enum Event<'a> {
     /// XML declaration `<?xml ...?>`.
     Decl(BytesDecl<'a>),
     /// Processing instruction `<?...?>`.
-    PI(BytesText<'a>),
+    PI(BytesPI<'a>),
     /// Document type definition data (DTD) stored in `<!DOCTYPE ...>`.
-    DocType(BytesText<'a>),
+    DocType(BytesDocType<'a>),
     /// End of XML document.
     Eof,
 }
@@ -1011,6 +1562,44 @@ impl<'a> Event<'a> {
             Event::Eof => Event::Eof,
         }
     }
+
+    /// Decodes the content of a text-bearing event using the encoding of the
+    /// document:
+    ///
+    /// - [`Text`] is decoded and unescaped;
+    /// - [`CData`], [`Comment`], [`PI`] and [`DocType`] are decoded as is.
+    ///
+    /// Returns `None` for other events.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<tag>a &amp; b<![CDATA[c &amp; d]]><!--e &amp; f--></tag>");
+    ///
+    /// assert!(reader.read_event().unwrap().decode().is_none());
+    /// assert_eq!(reader.read_event().unwrap().decode().unwrap().unwrap(), "a & b");
+    /// assert_eq!(reader.read_event().unwrap().decode().unwrap().unwrap(), "c &amp; d");
+    /// assert_eq!(reader.read_event().unwrap().decode().unwrap().unwrap(), "e &amp; f");
+    /// ```
+    ///
+    /// [`Text`]: Self::Text
+    /// [`CData`]: Self::CData
+    /// [`Comment`]: Self::Comment
+    /// [`PI`]: Self::PI
+    /// [`DocType`]: Self::DocType
+    pub fn decode(&self) -> Option<Result<Cow<'a, str>>> {
+        match self {
+            Event::Text(e) => Some(e.unescape()),
+            Event::CData(e) => Some(e.decode()),
+            Event::Comment(e) => Some(e.decode()),
+            Event::PI(e) => Some(e.decode()),
+            Event::DocType(e) => Some(e.decode()),
+            Event::Start(_) | Event::End(_) | Event::Empty(_) | Event::Decl(_) | Event::Eof => None,
+        }
+    }
 }
 
 impl<'a> Deref for Event<'a> {
@@ -1100,6 +1689,46 @@ mod test {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn event_decode() {
+        let decoder = Decoder::utf8();
+        let decode = |event: Event| event.decode().map(|r| r.unwrap().into_owned());
+
+        assert_eq!(
+            decode(Event::Text(BytesText::from_escaped("a &lt; b"))),
+            Some("a < b".into())
+        );
+        assert_eq!(
+            decode(Event::CData(BytesCData::new("a &lt; b"))),
+            Some("a &lt; b".into())
+        );
+        assert_eq!(
+            decode(Event::Comment(BytesText::wrap(&b"a &lt; b"[..], decoder))),
+            Some("a &lt; b".into())
+        );
+        assert_eq!(decode(Event::PI(BytesPI::new("pi a"))), Some("pi a".into()));
+        assert_eq!(
+            decode(Event::DocType(BytesDocType::new("root"))),
+            Some("root".into())
+        );
+        assert_eq!(decode(Event::Start(BytesStart::new("tag"))), None);
+        assert_eq!(decode(Event::End(BytesEnd::new("tag"))), None);
+        assert_eq!(decode(Event::Eof), None);
+
+        match Event::Text(BytesText::from_escaped("&unknown;")).decode() {
+            Some(Err(Error::EscapeError(_))) => {}
+            x => panic!("Expected `Some(Err(EscapeError(_)))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn doctype_to_text() {
+        let doctype = BytesDocType::new(r#"root SYSTEM "a&b.dtd""#);
+        let text = BytesText::from(doctype.clone());
+        assert_eq!(&*text, br#"root SYSTEM "a&b.dtd""#);
+        assert_eq!(BytesDocType::from(text), doctype);
+    }
+
     #[test]
     fn bytestart_create() {
         let b = BytesStart::new("test");
@@ -1131,4 +1760,88 @@ mod test {
         assert_eq!(b.len(), 4);
         assert_eq!(b.name(), QName(b"test"));
     }
+
+    #[test]
+    fn bytestart_remove_attribute() {
+        let mut b = BytesStart::new("test");
+        b.push_attribute(("a", "1"));
+        b.push_attribute(("x:b", "2"));
+        b.push_attr(Attr::SingleQ("c", "3"));
+        assert_eq!(b.try_remove_attribute("b").unwrap(), false);
+        assert_eq!(b.attributes_raw(), br#" a="1" x:b="2" c='3'"#);
+        assert_eq!(b.try_remove_attribute("x:b").unwrap(), true);
+        assert_eq!(b.attributes_raw(), br#" a="1" c='3'"#);
+        b.remove_attribute("a").push_attribute(("d", "4"));
+        assert_eq!(b.attributes_raw(), br#" c='3' d="4""#);
+        assert_eq!(b.name(), QName(b"test"));
+    }
+
+    #[test]
+    fn bytestart_remove_attribute_malformed() {
+        let mut b = BytesStart::from_content(r#"test a="1" b c="3""#, 4);
+        assert!(b.try_remove_attribute("a").is_err());
+        assert_eq!(b.attributes_raw(), br#" a="1" b c="3""#);
+        b.remove_attribute("a");
+        assert_eq!(b.attributes_raw(), br#" a="1" b c="3""#);
+    }
+
+    #[test]
+    fn bytestart_push_attribute_quotes() {
+        let mut b = BytesStart::new("e");
+        b.push_attribute(("a", "'\""));
+        b.push_attribute(("b".as_bytes(), "'".as_bytes()));
+        b.push_attribute(("c".as_bytes(), "\"".as_bytes()));
+        assert_eq!(b.attributes_raw(), br#" a="&apos;&quot;" b="'" c='"'"#);
+    }
+
+    #[test]
+    fn bytestart_push_attr() {
+        let mut b = BytesStart::new("e");
+        b.push_attr(Attr::DoubleQ("a", "'"));
+        b.push_attr(Attr::SingleQ("b", "\""));
+        b.push_attr(Attr::Empty("c"));
+        b.push_attr(Attr::Unquoted("d", "1"));
+        b.push_attr(Attr::Unquoted("e", "\""));
+        assert_eq!(b.attributes_raw(), br#" a="'" b='"' c d="1" e='"'"#);
+    }
+
+    #[test]
+    fn bytestart_with_attributes_from() {
+        let source = BytesStart::from_content(r#"source a="1" x:b='"' c="&amp;""#, 6);
+        let mut b = BytesStart::new("e");
+        b.push_attribute(("d", "0"));
+        b.with_attributes_from(source.attributes()).unwrap();
+        assert_eq!(b.attributes_raw(), br#" d="0" a="1" x:b='"' c="&amp;""#);
+        assert_eq!(
+            b.attributes().skip(1).collect::<Vec<_>>(),
+            source.attributes().collect::<Vec<_>>()
+        );
+
+        let malformed = BytesStart::from_content(r#"source a="1" b"#, 6);
+        let mut b = BytesStart::new("e");
+        b.push_attribute(("d", "0"));
+        match b.with_attributes_from(malformed.attributes()) {
+            Err(Error::InvalidAttr(_)) => {}
+            x => panic!("Expected `Err(InvalidAttr(_))`, but got `{:?}`", x),
+        }
+        assert_eq!(b.attributes_raw(), br#" d="0""#);
+    }
+
+    #[test]
+    fn bytestart_extend_attribute_pairs() {
+        let attrs = vec![
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+            ("c".to_string(), "<".to_string()),
+        ];
+        let mut b = BytesStart::new("e");
+        b.extend_attribute_pairs(attrs);
+        assert_eq!(&*b, br#"e a="1" b="2" c="&lt;""#);
+
+        let mut expected = BytesStart::new("e");
+        expected.push_attribute(("a", "1"));
+        expected.push_attribute(("b", "2"));
+        expected.push_attribute(("c", "<"));
+        assert_eq!(b, expected);
+    }
 }
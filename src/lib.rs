@@ -58,7 +58,10 @@ mod errors;
 mod escapei;
 pub mod escape {
     //! Manage xml character escapes
-    pub use crate::escapei::{escape, partial_escape, unescape, unescape_with, EscapeError};
+    pub use crate::escapei::{
+        escape, escape_into, minimal_escape, partial_escape, unescape, unescape_into,
+        unescape_with, unescape_with_options, EscapeError, UnescapeOptions, Unescaper,
+    };
 }
 pub mod events;
 pub mod name;
@@ -67,6 +70,8 @@ pub mod reader;
 pub mod se;
 #[cfg(feature = "serde-types")]
 pub mod serde_helpers;
+#[cfg(feature = "tree")]
+pub mod tree;
 /// Not an official API, public for integration tests
 #[doc(hidden)]
 pub mod utils;
@@ -76,6 +81,6 @@ pub mod writer;
 pub use crate::encoding::Decoder;
 #[cfg(feature = "serialize")]
 pub use crate::errors::serialize::DeError;
-pub use crate::errors::{Error, Result};
+pub use crate::errors::{Error, IllFormedError, Result};
 pub use crate::reader::{NsReader, Reader};
 pub use crate::writer::{ElementWriter, Writer};
@@ -523,8 +523,15 @@ impl NamespaceResolver {
         Ok(())
     }
 
+    /// Begins a new scope without any namespace bindings. Used to keep nesting
+    /// levels in sync with the document when bindings are not tracked.
+    #[inline]
+    pub fn push_empty(&mut self) {
+        self.nesting_level += 1;
+    }
+
     /// Ends a top-most scope by popping all [namespace binding], that was added by
-    /// last call to [`Self::push()`].
+    /// last call to [`Self::push()`] or [`Self::push_empty()`].
     ///
     /// [namespace binding]: https://www.w3.org/TR/xml-names11/#dt-NSDecl
     pub fn pop(&mut self) {
@@ -547,6 +554,29 @@ impl NamespaceResolver {
         }
     }
 
+    /// Returns all [namespace binding]s of the top-most scope, i.e. bindings
+    /// that was added by the last call to [`Self::push()`], in the order of
+    /// declaration.
+    ///
+    /// [namespace binding]: https://www.w3.org/TR/xml-names11/#dt-NSDecl
+    pub fn current_scope(
+        &self,
+    ) -> impl Iterator<Item = (PrefixDeclaration<'_>, ResolveResult<'_>)> {
+        let level = self.nesting_level;
+        let start = self
+            .bindings
+            .iter()
+            .rposition(|n| n.level < level)
+            .map_or(0, |pos| pos + 1);
+        self.bindings[start..].iter().map(move |n| {
+            let prefix = match n.prefix(&self.buffer) {
+                Some(prefix) => PrefixDeclaration::Named(prefix.into_inner()),
+                None => PrefixDeclaration::Default,
+            };
+            (prefix, n.namespace(&self.buffer))
+        })
+    }
+
     /// Resolves a potentially qualified **element name** or **attribute name**
     /// into (namespace name, local name).
     ///
@@ -1109,6 +1139,16 @@ mod namespaces {
     /// Checks how the QName is decomposed to a prefix and a local name
     #[test]
     fn prefix_and_local_name() {
+        let name = QName(b"bus");
+        assert_eq!(name.prefix(), None);
+        assert_eq!(name.local_name(), LocalName(b"bus"));
+        assert_eq!(name.decompose(), (LocalName(b"bus"), None));
+
+        let name = QName(b"");
+        assert_eq!(name.prefix(), None);
+        assert_eq!(name.local_name(), LocalName(b""));
+        assert_eq!(name.decompose(), (LocalName(b""), None));
+
         let name = QName(b"foo:bus");
         assert_eq!(name.prefix(), Some(Prefix(b"foo")));
         assert_eq!(name.local_name(), LocalName(b"bus"));
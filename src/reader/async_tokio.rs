@@ -8,7 +8,7 @@ use crate::events::Event;
 use crate::name::{QName, ResolveResult};
 use crate::reader::buffered_reader::impl_buffered_source;
 use crate::reader::{
-    is_whitespace, BangType, NsReader, ParseState, ReadElementState, Reader, Span,
+    is_name_start, is_whitespace, BangType, NsReader, ParseState, ReadElementState, Reader, Span,
 };
 use crate::{Error, Result};
 
@@ -70,10 +70,56 @@ impl<R: AsyncBufRead + Unpin> Reader<R> {
     /// ```
     ///
     /// [`read_event_into()`]: Reader::read_event_into
-    pub async fn read_event_into_async<'b>(
+    pub async fn read_event_into_async<'b>(&mut self, buf: &'b mut Vec<u8>) -> Result<Event<'b>> {
+        read_coalesced_event!(
+            self,
+            self.read_event_impl_async(buf),
+            self.read_event_impl_async(&mut Vec::new()),
+            await
+        )
+    }
+
+    /// An asynchronous version of [`read_event_into_with_span()`]. Reads the next
+    /// event into given buffer and returns it together with the range of bytes
+    /// of the input from which it was produced.
+    ///
+    /// See [`Reader::read_event_with_span`] for the description of the ranges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # tokio_test::block_on(async {
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesEnd, BytesStart, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let xml = "<tag></tag>";
+    /// let mut reader = Reader::from_reader(xml.as_bytes());
+    /// let mut buf = Vec::new();
+    ///
+    /// let (event, span) = reader.read_event_into_with_span_async(&mut buf).await.unwrap();
+    /// assert_eq!(event, Event::Start(BytesStart::new("tag")));
+    /// assert_eq!(&xml[span], "<tag>");
+    ///
+    /// let (event, span) = reader.read_event_into_with_span_async(&mut buf).await.unwrap();
+    /// assert_eq!(event, Event::End(BytesEnd::new("tag")));
+    /// assert_eq!(&xml[span], "</tag>");
+    /// # }) // tokio_test::block_on
+    /// ```
+    ///
+    /// [`read_event_into_with_span()`]: Reader::read_event_into_with_span
+    pub async fn read_event_into_with_span_async<'b>(
         &mut self,
-        mut buf: &'b mut Vec<u8>,
-    ) -> Result<Event<'b>> {
+        buf: &'b mut Vec<u8>,
+    ) -> Result<(Event<'b>, Span)> {
+        let event = self.read_event_into_async(buf).await?;
+        let span = self.event_span(&event);
+        Ok((event, span))
+    }
+
+    /// An asynchronous version of `read_event_impl()`, that does not merge
+    /// text events.
+    async fn read_event_impl_async<'b>(&mut self, mut buf: &'b mut Vec<u8>) -> Result<Event<'b>> {
         read_event_impl!(
             self, buf,
             TokioAdapter(&mut self.reader),
@@ -138,7 +184,7 @@ impl<R: AsyncBufRead + Unpin> Reader<R> {
         end: QName<'n>,
         buf: &mut Vec<u8>,
     ) -> Result<Span> {
-        Ok(read_to_end!(self, end, buf, read_event_into_async, { buf.clear(); }, await))
+        Ok(read_to_end!(self, end, buf, read_event_impl_async, { buf.clear(); }, await))
     }
 
     /// Read until '<' is found, moves reader to an `OpenedTag` state and returns a `Text` event.
@@ -2,7 +2,7 @@
 //! underlying byte stream.
 
 use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Seek};
 use std::path::Path;
 
 use memchr;
@@ -14,17 +14,15 @@ use crate::reader::{is_whitespace, BangType, ReadElementState, Reader, Span, Xml
 
 macro_rules! impl_buffered_source {
     ($($lf:lifetime, $reader:tt, $async:ident, $await:ident)?) => {
-        #[cfg(not(feature = "encoding"))]
-        $($async)? fn remove_utf8_bom(&mut self) -> Result<()> {
-            use crate::encoding::UTF8_BOM;
-
+        $($async)? fn detect_bom(&mut self, strip: bool) -> Result<Option<crate::encoding::Bom>> {
             loop {
                 break match self $(.$reader)? .fill_buf() $(.$await)? {
                     Ok(n) => {
-                        if n.starts_with(UTF8_BOM) {
-                            self $(.$reader)? .consume(UTF8_BOM.len());
+                        let bom = crate::encoding::Bom::detect(n);
+                        if let (Some(bom), true) = (bom, strip) {
+                            self $(.$reader)? .consume(bom.as_bytes().len());
                         }
-                        Ok(())
+                        Ok(bom)
                     },
                     Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
                     Err(e) => Err(Error::Io(e.into())),
@@ -302,7 +300,25 @@ impl<R: BufRead> Reader<R> {
     /// ```
     #[inline]
     pub fn read_event_into<'b>(&mut self, buf: &'b mut Vec<u8>) -> Result<Event<'b>> {
-        self.read_event_impl(buf)
+        read_coalesced_event!(
+            self,
+            self.read_event_impl(buf),
+            self.read_event_impl(&mut Vec::new())
+        )
+    }
+
+    /// Reads the next event into given buffer and returns it together with
+    /// the range of bytes of the input from which it was produced.
+    ///
+    /// See [`Reader::read_event_with_span`] for the description of the ranges.
+    #[inline]
+    pub fn read_event_into_with_span<'b>(
+        &mut self,
+        buf: &'b mut Vec<u8>,
+    ) -> Result<(Event<'b>, Span)> {
+        let event = self.read_event_into(buf)?;
+        let span = self.event_span(&event);
+        Ok((event, span))
     }
 
     /// Reads until end element is found using provided buffer as intermediate
@@ -400,6 +416,53 @@ impl<R: BufRead> Reader<R> {
     }
 }
 
+impl<R: BufRead + Seek> Reader<R> {
+    /// Returns the position in the underlying stream, that corresponds to the
+    /// [`buffer_position()`]. Unlike the latter, the result is the absolute offset,
+    /// consistent with [`Seek::stream_position`] of the underlying reader, so
+    /// it can be used to seek to the position later, even if the reader did not
+    /// start at the beginning of the stream.
+    ///
+    /// Bytes buffered by the underlying reader, but not consumed by this reader
+    /// yet, are accounted by the [`Seek`] implementation of the underlying reader,
+    /// for example, [`BufReader`].
+    ///
+    /// The position may be inaccurate after an error was returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use std::io::{Cursor, Seek, SeekFrom};
+    /// use quick_xml::events::{BytesStart, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut cursor = Cursor::new(b"garbage<root><child/></root>".to_vec());
+    /// cursor.seek(SeekFrom::Start(7)).unwrap();
+    ///
+    /// let mut reader = Reader::from_reader(cursor);
+    /// let mut buf = Vec::new();
+    ///
+    /// assert_eq!(reader.read_event_into(&mut buf).unwrap(), Event::Start(BytesStart::new("root")));
+    /// let position = reader.stream_position().unwrap();
+    /// assert_eq!(position, 13);
+    ///
+    /// // Seek back and read `<child/>` again with a new reader
+    /// let mut cursor = reader.into_inner();
+    /// cursor.seek(SeekFrom::Start(position)).unwrap();
+    /// let mut reader = Reader::from_reader(cursor);
+    /// assert_eq!(reader.read_event_into(&mut buf).unwrap(), Event::Empty(BytesStart::new("child")));
+    /// ```
+    ///
+    /// [`buffer_position()`]: Self::buffer_position
+    pub fn stream_position(&mut self) -> io::Result<u64> {
+        // When a markup was opened, `<` is already consumed from the underlying
+        // reader, but `buffer_position()` points to it
+        let unreported = self.state.offset - self.buffer_position();
+        Ok(self.reader.stream_position()? - unreported as u64)
+    }
+}
+
 impl Reader<BufReader<File>> {
     /// Creates an XML reader from a file path.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -0,0 +1,295 @@
+//! Contains a push-based (SAX-style) API for the [`Reader`].
+
+use std::io::BufRead;
+
+use crate::errors::Error;
+use crate::events::{BytesCData, BytesEnd, BytesPI, BytesStart, BytesText, Event};
+use crate::reader::Reader;
+
+/// A receiver of XML events for the push-based (SAX-style) parsing.
+///
+/// All methods have a default implementation that does nothing, so you should
+/// override only methods for events you are interested in. The XML declaration
+/// and the DOCTYPE definition are not reported.
+///
+/// If any method returns an error, parsing stops and that error is returned
+/// from [`Reader::parse`] or [`Reader::parse_into`].
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::events::{BytesStart, BytesText};
+/// use quick_xml::reader::{Handler, Reader};
+/// use quick_xml::Error;
+///
+/// #[derive(Default)]
+/// struct Counter {
+///     elements: usize,
+///     text: String,
+/// }
+///
+/// impl Handler for Counter {
+///     type Error = Error;
+///
+///     fn start_element(&mut self, _e: &BytesStart) -> Result<(), Error> {
+///         self.elements += 1;
+///         Ok(())
+///     }
+///
+///     fn characters(&mut self, e: &BytesText) -> Result<(), Error> {
+///         self.text += &e.unescape()?;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut counter = Counter::default();
+/// let mut reader = Reader::from_str("<root><item>one</item><item/>&amp;</root>");
+/// reader.parse(&mut counter).unwrap();
+///
+/// assert_eq!(counter.elements, 3);
+/// assert_eq!(counter.text, "one&");
+/// ```
+pub trait Handler {
+    /// The error type that handler can return. Should be convertible from the
+    /// reader errors
+    type Error: From<Error>;
+
+    /// Called for each start tag. For empty elements (`<tag/>`) this method
+    /// is called before the call of [`Self::end_element`]
+    fn start_element(&mut self, _e: &BytesStart) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called for each end tag and after [`Self::start_element`] for empty
+    /// elements (`<tag/>`)
+    fn end_element(&mut self, _e: &BytesEnd) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called for each text node. Text is passed in the escaped form
+    fn characters(&mut self, _e: &BytesText) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called for each CDATA section
+    fn cdata(&mut self, _e: &BytesCData) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called for each comment
+    fn comment(&mut self, _e: &BytesText) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Called for each processing instruction
+    fn processing_instruction(&mut self, _e: &BytesPI) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<H: Handler> Handler for &mut H {
+    type Error = H::Error;
+
+    #[inline]
+    fn start_element(&mut self, e: &BytesStart) -> Result<(), Self::Error> {
+        (**self).start_element(e)
+    }
+
+    #[inline]
+    fn end_element(&mut self, e: &BytesEnd) -> Result<(), Self::Error> {
+        (**self).end_element(e)
+    }
+
+    #[inline]
+    fn characters(&mut self, e: &BytesText) -> Result<(), Self::Error> {
+        (**self).characters(e)
+    }
+
+    #[inline]
+    fn cdata(&mut self, e: &BytesCData) -> Result<(), Self::Error> {
+        (**self).cdata(e)
+    }
+
+    #[inline]
+    fn comment(&mut self, e: &BytesText) -> Result<(), Self::Error> {
+        (**self).comment(e)
+    }
+
+    #[inline]
+    fn processing_instruction(&mut self, e: &BytesPI) -> Result<(), Self::Error> {
+        (**self).processing_instruction(e)
+    }
+}
+
+/// Passes `event` to the appropriate method of the `handler`. Returns `false`
+/// if `event` is [`Event::Eof`]
+fn dispatch<H: Handler>(handler: &mut H, event: Event) -> Result<bool, H::Error> {
+    match event {
+        Event::Start(e) => handler.start_element(&e)?,
+        Event::End(e) => handler.end_element(&e)?,
+        Event::Empty(e) => {
+            handler.start_element(&e)?;
+            handler.end_element(&e.to_end())?;
+        }
+        Event::Text(e) => handler.characters(&e)?,
+        Event::CData(e) => handler.cdata(&e)?,
+        Event::Comment(e) => handler.comment(&e)?,
+        Event::PI(e) => handler.processing_instruction(&e)?,
+        Event::Decl(_) | Event::DocType(_) => {}
+        Event::Eof => return Ok(false),
+    }
+    Ok(true)
+}
+
+impl Reader<&[u8]> {
+    /// Reads all remaining events and passes them to the `handler`.
+    ///
+    /// Stops at the first error returned either by the reader or by the handler.
+    /// Pass `&mut handler` if you need to access the handler after parsing.
+    ///
+    /// See [`Handler`] for an example.
+    pub fn parse<H: Handler>(&mut self, mut handler: H) -> Result<(), H::Error> {
+        while dispatch(&mut handler, self.read_event()?)? {}
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Reader<R> {
+    /// Reads all remaining events and passes them to the `handler`. `buf` is
+    /// used to store data of each event and is cleared before reading each event.
+    ///
+    /// Stops at the first error returned either by the reader or by the handler.
+    /// Pass `&mut handler` if you need to access the handler after parsing.
+    ///
+    /// See [`Handler`] for an example.
+    pub fn parse_into<H: Handler>(
+        &mut self,
+        mut handler: H,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), H::Error> {
+        loop {
+            buf.clear();
+            if !dispatch(&mut handler, self.read_event_into(buf)?)? {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = "<?xml version='1.0'?>\
+        <!DOCTYPE root>\
+        <root attr='value'>\
+            text &amp; more\
+            <![CDATA[cdata]]>\
+            <!--comment-->\
+            <?pi data?>\
+            <empty/>\
+        </root>";
+
+    /// Collects all reported events
+    #[derive(Default)]
+    struct Collector(Vec<Event<'static>>);
+
+    impl Handler for Collector {
+        type Error = Error;
+
+        fn start_element(&mut self, e: &BytesStart) -> Result<(), Error> {
+            self.0.push(Event::Start(e.clone().into_owned()));
+            Ok(())
+        }
+
+        fn end_element(&mut self, e: &BytesEnd) -> Result<(), Error> {
+            self.0.push(Event::End(e.clone().into_owned()));
+            Ok(())
+        }
+
+        fn characters(&mut self, e: &BytesText) -> Result<(), Error> {
+            self.0.push(Event::Text(e.clone().into_owned()));
+            Ok(())
+        }
+
+        fn cdata(&mut self, e: &BytesCData) -> Result<(), Error> {
+            self.0.push(Event::CData(e.clone().into_owned()));
+            Ok(())
+        }
+
+        fn comment(&mut self, e: &BytesText) -> Result<(), Error> {
+            self.0.push(Event::Comment(e.clone().into_owned()));
+            Ok(())
+        }
+
+        fn processing_instruction(&mut self, e: &BytesPI) -> Result<(), Error> {
+            self.0.push(Event::PI(e.clone().into_owned()));
+            Ok(())
+        }
+    }
+
+    /// Reads events using pull-based API, expanding empty elements
+    fn pull() -> Vec<Event<'static>> {
+        let mut reader = Reader::from_str(XML);
+        reader.expand_empty_elements(true);
+
+        let mut events = Vec::new();
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Decl(_) | Event::DocType(_) => {}
+                Event::Eof => break,
+                e => events.push(e.into_owned()),
+            }
+        }
+        events
+    }
+
+    #[test]
+    fn borrowed() {
+        let mut collector = Collector::default();
+        Reader::from_str(XML).parse(&mut collector).unwrap();
+
+        assert_eq!(collector.0, pull());
+    }
+
+    #[test]
+    fn buffered() {
+        let mut collector = Collector::default();
+        Reader::from_reader(XML.as_bytes())
+            .parse_into(&mut collector, &mut Vec::new())
+            .unwrap();
+
+        assert_eq!(collector.0, pull());
+    }
+
+    #[test]
+    fn handler_error() {
+        struct Stop(usize);
+
+        impl Handler for Stop {
+            type Error = Error;
+
+            fn start_element(&mut self, _e: &BytesStart) -> Result<(), Error> {
+                self.0 += 1;
+                Err(Error::TextNotFound)
+            }
+        }
+
+        let mut stop = Stop(0);
+        match Reader::from_str(XML).parse(&mut stop) {
+            Err(Error::TextNotFound) => {}
+            x => panic!("Expected `Err(TextNotFound)`, but got `{:?}`", x),
+        }
+        assert_eq!(stop.0, 1);
+    }
+
+    #[test]
+    fn reader_error() {
+        match Reader::from_str("<root></other>").parse(Collector::default()) {
+            Err(Error::EndEventMismatch { .. }) => {}
+            x => panic!("Expected `Err(EndEventMismatch)`, but got `{:?}`", x),
+        }
+    }
+}
@@ -2,11 +2,12 @@
 
 #[cfg(feature = "encoding")]
 use encoding_rs::Encoding;
-use std::ops::Range;
+use std::ops::{Deref, DerefMut, Range};
 
-use crate::encoding::Decoder;
+use crate::encoding::{Bom, Decoder};
 use crate::errors::{Error, Result};
 use crate::events::Event;
+use crate::name::QName;
 use crate::reader::state::ReaderState;
 
 use memchr;
@@ -31,7 +32,7 @@ macro_rules! configure_methods {
         /// [`End`]: Event::End
         /// [`check_end_names`]: Self::check_end_names
         pub fn expand_empty_elements(&mut self, val: bool) -> &mut Self {
-            self $(.$holder)? .state.expand_empty_elements = val;
+            self $(.$holder)? .state.config.expand_empty_elements = val;
             self
         }
 
@@ -58,8 +59,8 @@ macro_rules! configure_methods {
         /// [`BytesText::inplace_trim_start`]: crate::events::BytesText::inplace_trim_start
         /// [`BytesText::inplace_trim_end`]: crate::events::BytesText::inplace_trim_end
         pub fn trim_text(&mut self, val: bool) -> &mut Self {
-            self $(.$holder)? .state.trim_text_start = val;
-            self $(.$holder)? .state.trim_text_end = val;
+            self $(.$holder)? .state.config.trim_text_start = val;
+            self $(.$holder)? .state.config.trim_text_end = val;
             self
         }
 
@@ -83,7 +84,7 @@ macro_rules! configure_methods {
         /// [`BytesText::inplace_trim_start`]: crate::events::BytesText::inplace_trim_start
         /// [`BytesText::inplace_trim_end`]: crate::events::BytesText::inplace_trim_end
         pub fn trim_text_end(&mut self, val: bool) -> &mut Self {
-            self $(.$holder)? .state.trim_text_end = val;
+            self $(.$holder)? .state.config.trim_text_end = val;
             self
         }
 
@@ -99,7 +100,7 @@ macro_rules! configure_methods {
         ///
         /// [`End`]: Event::End
         pub fn trim_markup_names_in_closing_tags(&mut self, val: bool) -> &mut Self {
-            self $(.$holder)? .state.trim_markup_names_in_closing_tags = val;
+            self $(.$holder)? .state.config.trim_markup_names_in_closing_tags = val;
             self
         }
 
@@ -137,7 +138,7 @@ macro_rules! configure_methods {
         /// [`End`]: Event::End
         /// [`expand_empty_elements`]: Self::expand_empty_elements
         pub fn check_end_names(&mut self, val: bool) -> &mut Self {
-            self $(.$holder)? .state.check_end_names = val;
+            self $(.$holder)? .state.config.check_end_names = val;
             self
         }
 
@@ -152,7 +153,149 @@ macro_rules! configure_methods {
         ///
         /// [`Comment`]: Event::Comment
         pub fn check_comments(&mut self, val: bool) -> &mut Self {
-            self $(.$holder)? .state.check_comments = val;
+            self $(.$holder)? .state.config.check_comments = val;
+            self
+        }
+
+        /// Changes whether text and tags should be checked for [restricted characters].
+        ///
+        /// When set to `true`, raw C0 control characters other than tab, line feed
+        /// and carriage return (which XML 1.1 allows only as character references)
+        /// in [`Text`] events and inside tags, including attribute values, are
+        /// reported as [`Error::IllegalCharacter`]. The default value is `false`
+        /// to improve performance.
+        ///
+        /// (`false` by default)
+        ///
+        /// [restricted characters]: https://www.w3.org/TR/xml11/#NT-RestrictedChar
+        /// [`Text`]: Event::Text
+        /// [`Error::IllegalCharacter`]: crate::Error::IllegalCharacter
+        pub fn check_restricted_chars(&mut self, val: bool) -> &mut Self {
+            self $(.$holder)? .state.config.check_restricted_chars = val;
+            self
+        }
+
+        /// Changes whether element and attribute names should be checked for
+        /// entity and character references.
+        ///
+        /// When set to `true`, a `&` in the name of an element or an attribute
+        /// (for example, `<a&amp;b/>` or `<a x&amp;y="1"/>`), which is not allowed
+        /// by the XML grammar, is reported as [`IllFormedError::EntityInName`].
+        /// When set to `false`, such references are returned as a part of the name.
+        ///
+        /// (`false` by default)
+        ///
+        /// [`IllFormedError::EntityInName`]: crate::IllFormedError::EntityInName
+        pub fn check_entities_in_names(&mut self, val: bool) -> &mut Self {
+            self $(.$holder)? .state.config.check_entities_in_names = val;
+            self
+        }
+
+        /// Changes whether the reader should recover from minor errors instead
+        /// of returning them.
+        ///
+        /// When set to `true`:
+        /// - an end tag that does not match the last opened element (see
+        ///   [`check_end_names`]), but matches one of the outer opened elements,
+        ///   closes that element: [`End`] events are generated for all elements
+        ///   up to and including that element. An end tag that does not match
+        ///   any opened element is returned as a [`Text`] event and does not
+        ///   close anything;
+        /// - a `<` in the text which cannot start a markup (for example, `a < b`)
+        ///   is returned as a separate [`Text`] event with the escaped `&lt;` content.
+        ///
+        /// Each recovered error is recorded and can be inspected with
+        /// [`recovered_errors`] or drained with [`take_recovered_errors`].
+        ///
+        /// (`false` by default)
+        ///
+        /// [`check_end_names`]: Self::check_end_names
+        /// [`End`]: Event::End
+        /// [`Text`]: Event::Text
+        /// [`recovered_errors`]: Reader::recovered_errors
+        /// [`take_recovered_errors`]: Reader::take_recovered_errors
+        pub fn error_recovery(&mut self, val: bool) -> &mut Self {
+            self $(.$holder)? .state.config.error_recovery = val;
+            self
+        }
+
+        /// Changes whether a byte order mark (BOM) at the start of the input
+        /// is removed.
+        ///
+        /// When set to `false`, the BOM is left in the input and will be a part
+        /// of the first [`Text`] event. The BOM is recognized regardless of this
+        /// setting and can be inspected with [`bom`].
+        ///
+        /// (`true` by default)
+        ///
+        /// [`Text`]: Event::Text
+        /// [`bom`]: Reader::bom
+        pub fn strip_bom(&mut self, val: bool) -> &mut Self {
+            self $(.$holder)? .state.config.strip_bom = val;
+            self
+        }
+
+        /// Changes whether consecutive text and CDATA sections should be merged
+        /// into one event.
+        ///
+        /// When set to `true`, each run of character data that consists of
+        /// [`Text`] and [`CData`] events, for example, `a<![CDATA[b]]>c`, is
+        /// returned as one [`Text`] event. The content of CDATA sections is
+        /// escaped, so [`unescape()`] of the merged event returns the text as
+        /// it is seen by an XML processor. A lone CDATA section is returned as
+        /// a [`Text`] event too.
+        ///
+        /// The [`trim_text`] and [`trim_text_end`] options are applied to the
+        /// whole run rather than to each part of it. If [`trim_text`] is set and
+        /// nothing remains after trimming, the event is not returned.
+        ///
+        /// Note, that setting this to `true` will lead to additional allocations
+        /// when a run consists of several parts. Also, the event that follows
+        /// the text should be read in advance, and it is stored in the reader
+        /// as an owned event until the next read.
+        ///
+        /// (`false` by default)
+        ///
+        /// [`Text`]: Event::Text
+        /// [`CData`]: Event::CData
+        /// [`unescape()`]: crate::events::BytesText::unescape
+        /// [`trim_text`]: Self::trim_text
+        /// [`trim_text_end`]: Self::trim_text_end
+        pub fn coalesce_text(&mut self, val: bool) -> &mut Self {
+            self $(.$holder)? .state.config.coalesce_text = val;
+            self
+        }
+
+        /// Changes whether the input is required to be encoded in UTF-8 (which
+        /// includes ASCII).
+        ///
+        /// When set to `true`, the reader does not try to detect the encoding
+        /// of the input from its first bytes, a byte order mark (BOM) or the XML
+        /// declaration, so the [`decoder`] always decodes UTF-8. Use this for
+        /// input that must be UTF-8 to reject documents that claim to be in other
+        /// encoding instead of silently decoding them from that encoding.
+        ///
+        /// This option does not make parsing faster: the encoding is detected
+        /// only once at the start of the document, and the content of events is
+        /// still checked when it is decoded into strings.
+        ///
+        /// If the input starts with a BOM of other encoding or, with the [`encoding`]
+        /// feature, declares other encoding in the XML declaration, the reader
+        /// returns an [`Error::UnexpectedEncoding`] with the name of that encoding.
+        ///
+        /// In debug builds each event is also checked to be valid UTF-8 and an
+        /// [`Error::NonDecodable`] is returned for malformed input, so reading is
+        /// slower there. Release builds do not validate events, but decoding of
+        /// the event content into strings still reports malformed sequences as errors.
+        ///
+        /// (`false` by default)
+        ///
+        /// [`decoder`]: Reader::decoder
+        /// [`encoding`]: ../index.html#encoding
+        /// [`Error::UnexpectedEncoding`]: crate::errors::Error::UnexpectedEncoding
+        /// [`Error::NonDecodable`]: crate::errors::Error::NonDecodable
+        pub fn assume_utf8(&mut self, val: bool) -> &mut Self {
+            self $(.$holder)? .state.config.assume_utf8 = val;
             self
         }
     };
@@ -166,24 +309,35 @@ macro_rules! read_event_impl {
         $read_until_close:ident
         $(, $await:ident)?
     ) => {{
+        $self.state.coalesced_span = None;
+        if let Some(event) = $self.state.pending.take() {
+            return event;
+        }
         let event = loop {
             match $self.state.state {
                 ParseState::Init => { // Go to OpenedTag state
+                    let bom = $reader.detect_bom($self.state.config.strip_bom) $(.$await)? ?;
+                    $self.state.bom = bom;
+                    match bom {
+                        Some(bom) if $self.state.config.assume_utf8 && bom != crate::encoding::Bom::Utf8 => {
+                            break Err(crate::errors::Error::UnexpectedEncoding(bom.name()));
+                        }
+                        _ => {}
+                    }
+
                     // If encoding set explicitly, we not need to detect it. For example,
-                    // explicit UTF-8 set automatically if Reader was created using `from_str`.
-                    // But we still need to remove BOM for consistency with no encoding
-                    // feature enabled path
+                    // explicit UTF-8 set automatically if Reader was created using `from_str`
                     #[cfg(feature = "encoding")]
-                    if let Some(encoding) = $reader.detect_encoding() $(.$await)? ? {
-                        if $self.state.encoding.can_be_refined() {
+                    if !$self.state.config.assume_utf8 && $self.state.encoding.can_be_refined() {
+                        let encoding = match bom {
+                            Some(bom) => Some(bom.encoding()),
+                            None => $reader.detect_encoding() $(.$await)? ?,
+                        };
+                        if let Some(encoding) = encoding {
                             $self.state.encoding = crate::reader::EncodingRef::BomDetected(encoding);
                         }
                     }
 
-                    // Removes UTF-8 BOM if it is present
-                    #[cfg(not(feature = "encoding"))]
-                    $reader.remove_utf8_bom() $(.$await)? ?;
-
                     // Go to OpenedTag state
                     match $self.$read_until_open($buf) $(.$await)? {
                         Ok(Ok(ev)) => break Ok(ev),
@@ -204,6 +358,16 @@ macro_rules! read_event_impl {
                 ParseState::Exit => break Ok(Event::Eof),
             };
         };
+        // Check the assumption only in debug builds, as `debug_assert!` does
+        let event = match event {
+            Ok(e) if cfg!(debug_assertions) && $self.state.config.assume_utf8 => {
+                match std::str::from_utf8(&e) {
+                    Ok(_) => Ok(e),
+                    Err(err) => Err(crate::errors::Error::NonDecodable(Some(err))),
+                }
+            }
+            event => event,
+        };
         match event {
             Err(_) | Ok(Event::Eof) => $self.state.state = ParseState::Exit,
             _ => {}
@@ -212,6 +376,78 @@ macro_rules! read_event_impl {
     }};
 }
 
+/// Reads an event using `$read_event` and, if [`coalesce_text`] is set and that
+/// event is a `Text` or a `CData` event, merges it with all immediately following
+/// `Text` and `CData` events, read using `$read_next`. The first event that
+/// cannot be merged is stored in the [`ReaderState::pending`].
+///
+/// `$read_next` should return events that do not borrow the buffer passed to
+/// `$read_event`.
+///
+/// [`coalesce_text`]: Reader::coalesce_text
+macro_rules! read_coalesced_event {
+    (
+        $self:ident,
+        $read_event:expr,
+        $read_next:expr
+        $(, $await:ident)?
+    ) => {{
+        if !$self.state.config.coalesce_text {
+            return $read_event $(.$await)?;
+        }
+        let trim_start = $self.state.config.trim_text_start;
+        let trim_end = $self.state.config.trim_text_end;
+        // Spaces at the end of a text are not trailing if CDATA follows it
+        $self.state.config.trim_text_end = false;
+
+        // The second element is the length of the text if it is not a CDATA
+        let first = match $read_event $(.$await)? {
+            Ok(Event::Text(e)) => Ok((e.len(), e)),
+            Ok(Event::CData(e)) => Ok((0, $self.state.cdata_to_text(e))),
+            event => Err(event),
+        };
+        let event = match first {
+            Ok((mut tail, mut text)) => {
+                let start = $self.state.event_start;
+                // Spaces at the start of a text are not leading if CDATA precedes it
+                $self.state.config.trim_text_start = false;
+                loop {
+                    match $read_next $(.$await)? {
+                        Ok(Event::Text(e)) => {
+                            tail = e.len();
+                            text = $self.state.append_text(text, &e);
+                        }
+                        Ok(Event::CData(e)) => {
+                            tail = 0;
+                            let e = $self.state.cdata_to_text(e);
+                            text = $self.state.append_text(text, &e);
+                        }
+                        event => {
+                            $self.state.pending = Some(event.map(Event::into_owned));
+                            break;
+                        }
+                    }
+                }
+                $self.state.config.trim_text_start = trim_start;
+                let len = text.len();
+                if trim_end && text.inplace_trim_end() && trim_start {
+                    // Skip runs of spaces, as it is done for the ordinary text
+                    $self.state.pending.take().expect("event after text should be read")
+                } else {
+                    // Trimmed spaces are not covered by the span, unless they
+                    // are inside of the last CDATA section
+                    let trimmed = (len - text.len()).min(tail);
+                    $self.state.coalesced_span = Some(start..$self.state.event_start - trimmed);
+                    Ok(Event::Text(text))
+                }
+            }
+            Err(event) => event,
+        };
+        $self.state.config.trim_text_end = trim_end;
+        event
+    }};
+}
+
 /// Read bytes up to `<` and skip it. If current byte (after skipping all space
 /// characters if [`Parser::trim_text_start`] is `true`) is already `<`, then
 /// returns the next event, otherwise stay at position just after the `<` symbol.
@@ -230,7 +466,7 @@ macro_rules! read_until_open {
     ) => {{
         $self.state.state = ParseState::OpenedTag;
 
-        if $self.state.trim_text_start {
+        if $self.state.config.trim_text_start {
             $reader.skip_whitespace(&mut $self.state.offset) $(.$await)? ?;
         }
 
@@ -240,6 +476,7 @@ macro_rules! read_until_open {
             return Ok(Err($buf));
         }
 
+        $self.state.event_start = $self.state.offset;
         match $reader
             .read_bytes_until(b'<', $buf, &mut $self.state.offset)
             $(.$await)?
@@ -279,6 +516,8 @@ macro_rules! read_until_close {
         $(, $await:ident)?
     ) => {{
         $self.state.state = ParseState::ClosedTag;
+        // `<` was already consumed
+        $self.state.event_start = $self.state.offset.saturating_sub(1);
 
         match $reader.peek_one() $(.$await)? {
             // `<!` - comment, CDATA or DOCTYPE declaration
@@ -308,6 +547,10 @@ macro_rules! read_until_close {
                 Ok(Some(bytes)) => $self.state.emit_question_mark(bytes),
                 Err(e) => Err(e),
             },
+            // `<` that cannot start a markup
+            Ok(Some(byte)) if $self.state.config.error_recovery && !is_name_start(byte) => {
+                Ok($self.state.recover_unescaped_lt())
+            }
             // `<...` - opening or self-closed tag
             Ok(Some(_)) => match $reader
                 .read_element($buf, &mut $self.state.offset)
@@ -360,10 +603,12 @@ macro_rules! read_to_end {
 #[cfg(feature = "async-tokio")]
 mod async_tokio;
 mod buffered_reader;
+mod handler;
 mod ns_reader;
 mod slice_reader;
 mod state;
 
+pub use handler::Handler;
 pub use ns_reader::NsReader;
 
 /// Range of input in bytes, that corresponds to some piece of XML
@@ -409,7 +654,12 @@ enum ParseState {
     /// [`Event::Start`] event. The next event emitted will be an [`Event::End`],
     /// after which reader returned to the `ClosedTag` state.
     ///
+    /// In the [`error_recovery`] mode reader also enters to this state when an
+    /// end tag closes several opened elements and stays in it until all of them
+    /// are closed.
+    ///
     /// [`expand_empty_elements`]: Parser::expand_empty_elements
+    /// [`error_recovery`]: Reader::error_recovery
     Empty,
     /// Reader enters this state when `Eof` event generated or an error occurred.
     /// This is the last state, the reader stay in it forever.
@@ -465,6 +715,122 @@ impl EncodingRef {
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// Parsing options of a [`Reader`].
+///
+/// All options have the same meaning and defaults as the corresponding setters
+/// of the [`Reader`]. The configuration can be saved and restored using
+/// [`Reader::config()`] and [`Reader::config_mut()`] or passed to a new reader
+/// with [`Reader::from_reader_with_config()`].
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::events::{BytesStart, Event};
+/// use quick_xml::reader::{Config, Reader};
+///
+/// let mut config = Config::default();
+/// config.trim_text(true);
+/// config.expand_empty_elements = true;
+///
+/// let mut reader = Reader::from_reader_with_config(b"<a/>".as_ref(), config.clone());
+/// assert_eq!(reader.config(), &config);
+/// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("a")));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Config {
+    /// Whether empty elements should be split into an `Open` and a `Close` event.
+    /// See [`Reader::expand_empty_elements`].
+    ///
+    /// (`false` by default)
+    pub expand_empty_elements: bool,
+    /// Whether whitespace before character data should be removed.
+    /// See [`Reader::trim_text`].
+    ///
+    /// (`false` by default)
+    pub trim_text_start: bool,
+    /// Whether whitespace after character data should be removed.
+    /// See [`Reader::trim_text_end`].
+    ///
+    /// (`false` by default)
+    pub trim_text_end: bool,
+    /// Whether trailing whitespace after the markup name are trimmed in closing
+    /// tags `</a >`. See [`Reader::trim_markup_names_in_closing_tags`].
+    ///
+    /// (`true` by default)
+    pub trim_markup_names_in_closing_tags: bool,
+    /// Whether mismatched closing tag names should be detected.
+    /// See [`Reader::check_end_names`].
+    ///
+    /// (`true` by default)
+    pub check_end_names: bool,
+    /// Whether comments should be validated. See [`Reader::check_comments`].
+    ///
+    /// (`false` by default)
+    pub check_comments: bool,
+    /// Whether restricted characters should be reported as errors.
+    /// See [`Reader::check_restricted_chars`].
+    ///
+    /// (`false` by default)
+    pub check_restricted_chars: bool,
+    /// Whether entity references in element and attribute names should be
+    /// reported as errors. See [`Reader::check_entities_in_names`].
+    ///
+    /// (`false` by default)
+    pub check_entities_in_names: bool,
+    /// Whether the reader should recover from minor errors.
+    /// See [`Reader::error_recovery`].
+    ///
+    /// (`false` by default)
+    pub error_recovery: bool,
+    /// Whether a byte order mark should be removed. See [`Reader::strip_bom`].
+    ///
+    /// (`true` by default)
+    pub strip_bom: bool,
+    /// Whether consecutive text and CDATA sections should be merged.
+    /// See [`Reader::coalesce_text`].
+    ///
+    /// (`false` by default)
+    pub coalesce_text: bool,
+    /// Whether the input is assumed to be encoded in UTF-8.
+    /// See [`Reader::assume_utf8`].
+    ///
+    /// (`false` by default)
+    pub assume_utf8: bool,
+}
+
+impl Config {
+    /// Sets both [`Self::trim_text_start`] and [`Self::trim_text_end`] to
+    /// the same value.
+    #[inline]
+    pub fn trim_text(&mut self, trim: bool) {
+        self.trim_text_start = trim;
+        self.trim_text_end = trim;
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            expand_empty_elements: false,
+            trim_text_start: false,
+            trim_text_end: false,
+            trim_markup_names_in_closing_tags: true,
+            check_end_names: true,
+            check_comments: false,
+            check_restricted_chars: false,
+            check_entities_in_names: false,
+            error_recovery: false,
+            strip_bom: true,
+            coalesce_text: false,
+            assume_utf8: false,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
 /// A low level encoding-agnostic XML event reader.
 ///
 /// Consumes bytes and streams XML [`Event`]s.
@@ -537,11 +903,34 @@ impl<R> Reader<R> {
         }
     }
 
+    /// Creates a `Reader` that reads from a given reader and uses the given
+    /// parsing options.
+    pub fn from_reader_with_config(reader: R, config: Config) -> Self {
+        let mut reader = Self::from_reader(reader);
+        reader.state.config = config;
+        reader
+    }
+
     configure_methods!();
+
+    /// Returns a mutable reference to the parsing options. Changes take effect
+    /// starting from the next read event.
+    ///
+    /// The setters of the reader change the same options.
+    #[inline]
+    pub fn config_mut(&mut self) -> &mut Config {
+        &mut self.state.config
+    }
 }
 
 /// Getters
 impl<R> Reader<R> {
+    /// Returns the parsing options of this reader.
+    #[inline]
+    pub fn config(&self) -> &Config {
+        &self.state.config
+    }
+
     /// Consumes `Reader` returning the underlying reader
     ///
     /// Can be used to compute line and column of a parsing error position
@@ -622,6 +1011,23 @@ impl<R> Reader<R> {
         }
     }
 
+    /// Returns the range of bytes of the input from which the `event`, just
+    /// returned by this reader, was produced
+    fn event_span(&self, event: &Event) -> Span {
+        if let Some(span) = &self.state.coalesced_span {
+            return span.clone();
+        }
+        let start = self.state.event_start;
+        match event {
+            // Trailing spaces could be trimmed from the text, so the end of
+            // the text is not necessary at the `<`
+            Event::Text(e) if matches!(self.state.state, ParseState::OpenedTag) => {
+                start..start + e.len()
+            }
+            _ => start..self.buffer_position(),
+        }
+    }
+
     /// Get the decoder, used to decode bytes, read by this reader, to the strings.
     ///
     /// If [`encoding`] feature is enabled, the used encoding may change after
@@ -635,8 +1041,230 @@ impl<R> Reader<R> {
     pub fn decoder(&self) -> Decoder {
         self.state.decoder()
     }
+
+    /// Returns the byte order mark (BOM) found at the start of the input, or
+    /// `None` if the input does not start with a BOM or was not read yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::encoding::Bom;
+    /// use quick_xml::events::{BytesStart, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_reader(b"\xEF\xBB\xBF<root/>".as_ref());
+    ///
+    /// assert_eq!(reader.read_event().unwrap(), Event::Empty(BytesStart::new("root")));
+    /// assert_eq!(reader.bom(), Some(Bom::Utf8));
+    /// ```
+    #[inline]
+    pub fn bom(&self) -> Option<Bom> {
+        self.state.bom
+    }
+
+    /// Returns errors from which reader has recovered in the [`error_recovery`]
+    /// mode, in the order of their occurrence. Each error is accompanied by
+    /// the position of the `<` character of the markup that caused it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesEnd, BytesStart, Event};
+    /// use quick_xml::reader::Reader;
+    /// use quick_xml::Error;
+    ///
+    /// let mut reader = Reader::from_str("<outer><inner></outer>");
+    /// reader.error_recovery(true);
+    ///
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("outer")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("inner")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("inner")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("outer")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    ///
+    /// match reader.recovered_errors() {
+    ///     [(14, Error::EndEventMismatch { expected, found })] => {
+    ///         assert_eq!(expected, "inner");
+    ///         assert_eq!(found, "outer");
+    ///     }
+    ///     x => panic!("Expected one `EndEventMismatch` error, but got {:?}", x),
+    /// }
+    /// ```
+    ///
+    /// [`error_recovery`]: Self::error_recovery
+    pub fn recovered_errors(&self) -> &[(usize, Error)] {
+        &self.state.recovered_errors
+    }
+
+    /// Removes and returns errors from which reader has recovered in the
+    /// [`error_recovery`] mode since the last call of this method. Errors are
+    /// accumulated for the whole life of the reader, so call this method
+    /// periodically when reading long streams.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesStart, BytesText, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<a></x></a><b></y></b>");
+    /// reader.error_recovery(true);
+    ///
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(BytesStart::new("a")));
+    /// assert_eq!(reader.read_event().unwrap(), Event::Text(BytesText::from_escaped("&lt;/x>")));
+    /// assert_eq!(reader.take_recovered_errors().len(), 1);
+    /// assert_eq!(reader.recovered_errors().len(), 0);
+    ///
+    /// while reader.read_event().unwrap() != Event::Eof {}
+    /// assert_eq!(reader.take_recovered_errors().len(), 1);
+    /// ```
+    ///
+    /// [`error_recovery`]: Self::error_recovery
+    pub fn take_recovered_errors(&mut self) -> Vec<(usize, Error)> {
+        std::mem::take(&mut self.state.recovered_errors)
+    }
+
+    /// Returns an iterator over names of the currently opened elements, from
+    /// the outermost one to the innermost one. The iterator reflects the state
+    /// after the last read event: a name appears after reading its [`Event::Start`]
+    /// and disappears after reading the corresponding [`Event::End`].
+    ///
+    /// Elements, reported as [`Event::Empty`], are never opened. Names are
+    /// tracked even when [`check_end_names`] is disabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::Event;
+    /// use quick_xml::name::QName;
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<table><row><cell/></row></table>");
+    /// loop {
+    ///     match reader.read_event().unwrap() {
+    ///         Event::Empty(e) if e.name().as_ref() == b"cell" => break,
+    ///         _ => {}
+    ///     }
+    /// }
+    ///
+    /// let path: Vec<_> = reader.open_elements().collect();
+    /// assert_eq!(path, [QName(b"table"), QName(b"row")]);
+    /// ```
+    ///
+    /// [`check_end_names`]: Self::check_end_names
+    pub fn open_elements(&self) -> OpenElements<'_> {
+        self.state.open_elements()
+    }
+
+    /// Temporarily disables [`check_end_names`]. The returned guard gives access
+    /// to the reader and restores the previous value of the setting when dropped.
+    ///
+    /// Names of the opened elements are still tracked, so checking can be
+    /// resumed at any point of the document.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesEnd, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<a><b></c></a>");
+    /// reader.read_event().unwrap();
+    /// reader.read_event().unwrap();
+    /// {
+    ///     let mut reader = reader.relax_end_names();
+    ///     assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("c")));
+    /// }
+    /// // `<b>` was closed by `</c>`, so `</a>` closes `<a>`
+    /// assert_eq!(reader.read_event().unwrap(), Event::End(BytesEnd::new("a")));
+    /// ```
+    ///
+    /// [`check_end_names`]: Self::check_end_names
+    pub fn relax_end_names(&mut self) -> RelaxedEndNames<'_, R> {
+        let check_end_names = self.state.config.check_end_names;
+        self.state.config.check_end_names = false;
+        RelaxedEndNames {
+            reader: self,
+            check_end_names,
+        }
+    }
+}
+
+/// A guard that disables checking of end names in the reader while it is alive.
+/// Dereferences to the [`Reader`].
+///
+/// This struct is created by the [`Reader::relax_end_names`] method.
+pub struct RelaxedEndNames<'a, R> {
+    reader: &'a mut Reader<R>,
+    /// The value of the `check_end_names` setting to restore
+    check_end_names: bool,
+}
+
+impl<'a, R> Deref for RelaxedEndNames<'a, R> {
+    type Target = Reader<R>;
+
+    fn deref(&self) -> &Reader<R> {
+        self.reader
+    }
+}
+
+impl<'a, R> DerefMut for RelaxedEndNames<'a, R> {
+    fn deref_mut(&mut self) -> &mut Reader<R> {
+        self.reader
+    }
+}
+
+impl<'a, R> Drop for RelaxedEndNames<'a, R> {
+    fn drop(&mut self) {
+        self.reader.state.config.check_end_names = self.check_end_names;
+    }
+}
+
+/// An iterator over names of the currently opened elements, from the outermost
+/// one to the innermost one.
+///
+/// This struct is created by the [`Reader::open_elements`] method.
+#[derive(Clone, Debug)]
+pub struct OpenElements<'a> {
+    /// Concatenated names of the elements
+    buffer: &'a [u8],
+    /// Start indexes of names in the `buffer`
+    starts: &'a [usize],
+}
+
+impl<'a> Iterator for OpenElements<'a> {
+    type Item = QName<'a>;
+
+    fn next(&mut self) -> Option<QName<'a>> {
+        let (&start, rest) = self.starts.split_first()?;
+        let end = rest.first().copied().unwrap_or(self.buffer.len());
+        self.starts = rest;
+        Some(QName(&self.buffer[start..end]))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.starts.len(), Some(self.starts.len()))
+    }
+}
+
+impl<'a> DoubleEndedIterator for OpenElements<'a> {
+    fn next_back(&mut self) -> Option<QName<'a>> {
+        let (&start, rest) = self.starts.split_last()?;
+        let (buffer, name) = self.buffer.split_at(start);
+        self.buffer = buffer;
+        self.starts = rest;
+        Some(QName(name))
+    }
 }
 
+impl<'a> ExactSizeIterator for OpenElements<'a> {}
+
 /// Private sync reading methods
 impl<R> Reader<R> {
     /// Read text into the given buffer, and return an event that borrows from
@@ -687,9 +1315,8 @@ impl<R> Reader<R> {
 /// - `B`: a type of a buffer that can be used to store data read from `Self` and
 ///   from which events can borrow
 trait XmlSource<'r, B> {
-    /// Removes UTF-8 BOM if it is present
-    #[cfg(not(feature = "encoding"))]
-    fn remove_utf8_bom(&mut self) -> Result<()>;
+    /// Recognizes a BOM at the start of input and removes it, if `strip` is `true`
+    fn detect_bom(&mut self, strip: bool) -> Result<Option<Bom>>;
 
     /// Determines encoding from the start of input and removes BOM if it is present
     #[cfg(feature = "encoding")]
@@ -926,6 +1553,13 @@ pub(crate) const fn is_whitespace(b: u8) -> bool {
     matches!(b, b' ' | b'\r' | b'\n' | b'\t')
 }
 
+/// A function to check whether the byte can start a name of an element. All
+/// non-ASCII bytes are accepted, because they are parts of multi-byte characters
+#[inline]
+const fn is_name_start(b: u8) -> bool {
+    matches!(b, b'a'..=b'z' | b'A'..=b'Z' | b'_' | b':' | 0x80..=0xFF)
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 #[cfg(test)]
@@ -1697,7 +2331,8 @@ mod test {
 
             /// Ensures, that no empty `Text` events are generated
             mod $read_event {
-                use crate::events::{BytesCData, BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+                use crate::encoding::Bom;
+                use crate::events::{BytesCData, BytesDecl, BytesDocType, BytesEnd, BytesPI, BytesStart, BytesText, Event};
                 use crate::reader::Reader;
                 use pretty_assertions::assert_eq;
 
@@ -1741,6 +2376,41 @@ mod test {
                     );
                 }
 
+                /// BOM is reported, but not stripped when `strip_bom` is disabled
+                #[$test]
+                $($async)? fn bom_kept() {
+                    let mut reader = Reader::from_reader("\u{feff}<root/>".as_bytes());
+                    reader.strip_bom(false);
+
+                    assert_eq!(
+                        reader.$read_event($buf) $(.$await)? .unwrap(),
+                        Event::Text(BytesText::from_escaped("\u{feff}"))
+                    );
+                    assert_eq!(reader.bom(), Some(Bom::Utf8));
+
+                    assert_eq!(
+                        reader.$read_event($buf) $(.$await)? .unwrap(),
+                        Event::Empty(BytesStart::new("root"))
+                    );
+                }
+
+                /// UTF-16 BOMs are recognized even if UTF-16 cannot be decoded
+                #[$test]
+                $($async)? fn bom_utf16() {
+                    let mut reader = Reader::from_reader(b"\xFF\xFE<\0r\0/\0>\0".as_ref());
+                    assert_eq!(reader.bom(), None);
+                    let _ = reader.$read_event($buf) $(.$await)?;
+                    assert_eq!(reader.bom(), Some(Bom::Utf16Le));
+
+                    let mut reader = Reader::from_reader(b"\xFE\xFF\0<\0r\0/\0>".as_ref());
+                    let _ = reader.$read_event($buf) $(.$await)?;
+                    assert_eq!(reader.bom(), Some(Bom::Utf16Be));
+
+                    let mut reader = Reader::from_reader(b"<root/>".as_ref());
+                    let _ = reader.$read_event($buf) $(.$await)?;
+                    assert_eq!(reader.bom(), None);
+                }
+
                 #[$test]
                 $($async)? fn declaration() {
                     let mut reader = Reader::from_str("<?xml ?>");
@@ -1757,7 +2427,7 @@ mod test {
 
                     assert_eq!(
                         reader.$read_event($buf) $(.$await)? .unwrap(),
-                        Event::DocType(BytesText::from_escaped("x"))
+                        Event::DocType(BytesDocType::new("x"))
                     );
                 }
 
@@ -1767,7 +2437,7 @@ mod test {
 
                     assert_eq!(
                         reader.$read_event($buf) $(.$await)? .unwrap(),
-                        Event::PI(BytesText::from_escaped("xml-stylesheet"))
+                        Event::PI(BytesPI::new("xml-stylesheet"))
                     );
                 }
 
@@ -1855,7 +2525,7 @@ mod test {
             $(, $async:ident, $await:ident)?
         ) => {
             mod small_buffers {
-                use crate::events::{BytesCData, BytesDecl, BytesStart, BytesText, Event};
+                use crate::events::{BytesCData, BytesDecl, BytesPI, BytesStart, BytesText, Event};
                 use crate::reader::Reader;
                 use pretty_assertions::assert_eq;
 
@@ -1889,7 +2559,7 @@ mod test {
 
                     assert_eq!(
                         reader.$read_event(&mut buf) $(.$await)? .unwrap(),
-                        Event::PI(BytesText::new("pi"))
+                        Event::PI(BytesPI::new("pi"))
                     );
                     assert_eq!(
                         reader.$read_event(&mut buf) $(.$await)? .unwrap(),
@@ -10,10 +10,11 @@ use std::io::{BufRead, BufReader};
 use std::ops::Deref;
 use std::path::Path;
 
-use crate::errors::Result;
-use crate::events::Event;
-use crate::name::{LocalName, NamespaceResolver, QName, ResolveResult};
-use crate::reader::{Reader, Span, XmlSource};
+use crate::errors::{Error, Result};
+use crate::events::attributes::{Attr, IterState};
+use crate::events::{BytesStart, Event};
+use crate::name::{LocalName, NamespaceResolver, PrefixDeclaration, QName, ResolveResult};
+use crate::reader::{Config, Reader, Span};
 
 /// A low level encoding-agnostic XML event reader that performs namespace resolution.
 ///
@@ -25,8 +26,20 @@ pub struct NsReader<R> {
     ns_resolver: NamespaceResolver,
     /// We cannot pop data from the namespace stack until returned `Empty` or `End`
     /// event will be processed by the user, so we only mark that we should that
-    /// in the next [`Self::read_event_impl()`] call.
+    /// in the next call of one of `read_*event*()` methods.
     pending_pop: bool,
+    /// If `false`, namespace declarations are not recorded and all names are
+    /// resolved to [`ResolveResult::Unbound`].
+    track_namespaces: bool,
+    /// If `true`, attributes of start and empty tags are checked for duplicated
+    /// expanded names
+    check_duplicate_attributes: bool,
+    /// If `true`, namespace bindings of the scope opened or closed by the last
+    /// event are available through [`NsReader::namespace_declarations`]
+    report_namespace_declarations: bool,
+    /// `true` if the last returned event was `Start`, `Empty` or `End` and
+    /// reporting of namespace declarations is enabled
+    scope_event: bool,
 }
 
 /// Builder methods
@@ -38,6 +51,117 @@ impl<R> NsReader<R> {
     }
 
     configure_methods!(reader);
+
+    /// Returns a mutable reference to the parsing options. Changes take effect
+    /// starting from the next read event.
+    ///
+    /// The setters of the reader change the same options.
+    #[inline]
+    pub fn config_mut(&mut self) -> &mut Config {
+        self.reader.config_mut()
+    }
+
+    /// Stops recording namespace declarations until [`enable_namespace_tracking()`]
+    /// is called. Useful when namespaces are irrelevant for some part of the
+    /// document and the cost of maintaining the namespace buffer is not wanted.
+    ///
+    /// Events are still parsed as usual, but all names are resolved to
+    /// [`ResolveResult::Unbound`] while tracking is disabled. Declarations that
+    /// were seen before the call remain in scope and are resolved again after
+    /// tracking is re-enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::Event;
+    /// use quick_xml::name::{Namespace, ResolveResult::*};
+    /// use quick_xml::reader::NsReader;
+    ///
+    /// let mut reader = NsReader::from_str("<a xmlns='ns'><b/><c/></a>");
+    ///
+    /// let (ns, _) = reader.read_resolved_event().unwrap();
+    /// assert_eq!(ns, Bound(Namespace(b"ns")));
+    ///
+    /// reader.disable_namespace_tracking();
+    /// let (ns, _) = reader.read_resolved_event().unwrap();
+    /// assert_eq!(ns, Unbound);
+    ///
+    /// reader.enable_namespace_tracking();
+    /// let (ns, _) = reader.read_resolved_event().unwrap();
+    /// assert_eq!(ns, Bound(Namespace(b"ns")));
+    /// ```
+    ///
+    /// [`enable_namespace_tracking()`]: Self::enable_namespace_tracking
+    pub fn disable_namespace_tracking(&mut self) -> &mut Self {
+        self.track_namespaces = false;
+        self
+    }
+
+    /// Resumes recording of namespace declarations after a call to
+    /// [`disable_namespace_tracking()`]. Declarations that were encountered
+    /// while tracking was disabled are not recovered.
+    ///
+    /// Namespace tracking is enabled by default.
+    ///
+    /// [`disable_namespace_tracking()`]: Self::disable_namespace_tracking
+    pub fn enable_namespace_tracking(&mut self) -> &mut Self {
+        self.track_namespaces = true;
+        self
+    }
+
+    /// Changes whether attributes of each start and empty tag should be checked
+    /// for duplicates after namespace resolution.
+    ///
+    /// When set to `true`, two attributes that have the same local name and are
+    /// bound to the same namespace are reported as [`Error::DuplicateAttribute`],
+    /// even if they are written with different prefixes. Unprefixed attributes
+    /// are never bound to the default namespace, so they are compared by their
+    /// local names only.
+    ///
+    /// When set to `false`, tags are returned as is and duplicates can be
+    /// detected only by the attribute iterator (see [`Attributes::with_checks`])
+    /// which compares raw names.
+    ///
+    /// (`false` by default)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::reader::NsReader;
+    /// use quick_xml::Error;
+    ///
+    /// let mut reader = NsReader::from_str(r#"
+    ///     <e xmlns:p="urn:ns" xmlns:q="urn:ns" p:a="1" q:a="2"/>
+    /// "#);
+    /// reader.trim_text(true).check_duplicate_attributes(true);
+    ///
+    /// match reader.read_resolved_event() {
+    ///     Err(Error::DuplicateAttribute { name, position }) => {
+    ///         assert_eq!(name, "q:a");
+    ///         assert_eq!(position, 50);
+    ///     }
+    ///     e => panic!("expected `DuplicateAttribute` error, found {:?}", e),
+    /// }
+    /// ```
+    ///
+    /// [`Attributes::with_checks`]: crate::events::attributes::Attributes::with_checks
+    pub fn check_duplicate_attributes(&mut self, val: bool) -> &mut Self {
+        self.check_duplicate_attributes = val;
+        self
+    }
+
+    /// Changes whether namespace declarations of the last `Start`, `Empty` or
+    /// `End` event should be available through [`namespace_declarations()`].
+    ///
+    /// (`false` by default)
+    ///
+    /// [`namespace_declarations()`]: Self::namespace_declarations
+    pub fn report_namespace_declarations(&mut self, val: bool) -> &mut Self {
+        self.report_namespace_declarations = val;
+        self
+    }
 }
 
 /// Private methods
@@ -48,18 +172,13 @@ impl<R> NsReader<R> {
             reader,
             ns_resolver: NamespaceResolver::default(),
             pending_pop: false,
+            track_namespaces: true,
+            check_duplicate_attributes: false,
+            report_namespace_declarations: false,
+            scope_event: false,
         }
     }
 
-    fn read_event_impl<'i, B>(&mut self, buf: B) -> Result<Event<'i>>
-    where
-        R: XmlSource<'i, B>,
-    {
-        self.pop();
-        let event = self.reader.read_event_impl(buf);
-        self.process_event(event)
-    }
-
     pub(super) fn pop(&mut self) {
         if self.pending_pop {
             self.ns_resolver.pop();
@@ -68,20 +187,27 @@ impl<R> NsReader<R> {
     }
 
     pub(super) fn process_event<'i>(&mut self, event: Result<Event<'i>>) -> Result<Event<'i>> {
+        self.scope_event = self.report_namespace_declarations
+            && matches!(
+                event,
+                Ok(Event::Start(_)) | Ok(Event::Empty(_)) | Ok(Event::End(_))
+            );
         match event {
             Ok(Event::Start(e)) => {
-                self.ns_resolver.push(&e)?;
+                self.push(&e)?;
+                self.check_attributes(&e)?;
                 Ok(Event::Start(e))
             }
             Ok(Event::Empty(e)) => {
-                self.ns_resolver.push(&e)?;
-                // notify next `read_event_impl()` invocation that it needs to pop this
-                // namespace scope
+                self.push(&e)?;
+                // notify next `read_*event*()` invocation that it needs to pop this
+                // namespace scope even if the check below fails
                 self.pending_pop = true;
+                self.check_attributes(&e)?;
                 Ok(Event::Empty(e))
             }
             Ok(Event::End(e)) => {
-                // notify next `read_event_impl()` invocation that it needs to pop this
+                // notify next `read_*event*()` invocation that it needs to pop this
                 // namespace scope
                 self.pending_pop = true;
                 Ok(Event::End(e))
@@ -90,14 +216,85 @@ impl<R> NsReader<R> {
         }
     }
 
+    #[inline]
+    fn push(&mut self, start: &BytesStart) -> Result<()> {
+        if self.track_namespaces {
+            self.ns_resolver.push(start)
+        } else {
+            // keep nesting levels in sync so tracking could be re-enabled later
+            self.ns_resolver.push_empty();
+            Ok(())
+        }
+    }
+
+    /// Checks that all attributes of the tag have different expanded names,
+    /// if that check is enabled. Must be called after the [`Self::push`], so
+    /// namespace declarations of the tag itself are taken into account
+    fn check_attributes(&self, start: &BytesStart) -> Result<()> {
+        if !self.check_duplicate_attributes {
+            return Ok(());
+        }
+        let mut seen = Vec::new();
+        let mut iter = IterState::new(start.name_len, false);
+        // Raw duplicates are reported below with an absolute position
+        iter.check_duplicates(false);
+        while let Some(attr) = iter.next(&start.buf) {
+            let key = match attr? {
+                Attr::DoubleQ(key, _) => key,
+                Attr::SingleQ(key, _) => key,
+                Attr::Unquoted(key, _) => key,
+                Attr::Empty(key) => key,
+            };
+            let name = QName(&start.buf[key.clone()]);
+            // Without namespace tracking all names are unbound, so compare raw names
+            let resolved = if self.track_namespaces {
+                let (ns, local) = self.ns_resolver.resolve(name, false);
+                (ns, local.into_inner())
+            } else {
+                (ResolveResult::Unbound, name.into_inner())
+            };
+            if seen.contains(&resolved) {
+                return Err(Error::DuplicateAttribute {
+                    name: String::from_utf8_lossy(name.as_ref()).into_owned(),
+                    // `buf` does not include the opening `<`
+                    position: self.reader.state.event_start + 1 + key.start,
+                });
+            }
+            seen.push(resolved);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn find(&self, name: QName) -> ResolveResult<'_> {
+        if self.track_namespaces {
+            self.ns_resolver.find(name)
+        } else {
+            ResolveResult::Unbound
+        }
+    }
+
+    #[inline]
+    fn resolve_name<'n>(
+        &self,
+        name: QName<'n>,
+        use_default: bool,
+    ) -> (ResolveResult<'_>, LocalName<'n>) {
+        if self.track_namespaces {
+            self.ns_resolver.resolve(name, use_default)
+        } else {
+            (ResolveResult::Unbound, name.local_name())
+        }
+    }
+
     pub(super) fn resolve_event<'i>(
         &mut self,
         event: Result<Event<'i>>,
     ) -> Result<(ResolveResult, Event<'i>)> {
         match event {
-            Ok(Event::Start(e)) => Ok((self.ns_resolver.find(e.name()), Event::Start(e))),
-            Ok(Event::Empty(e)) => Ok((self.ns_resolver.find(e.name()), Event::Empty(e))),
-            Ok(Event::End(e)) => Ok((self.ns_resolver.find(e.name()), Event::End(e))),
+            Ok(Event::Start(e)) => Ok((self.find(e.name()), Event::Start(e))),
+            Ok(Event::Empty(e)) => Ok((self.find(e.name()), Event::Empty(e))),
+            Ok(Event::End(e)) => Ok((self.find(e.name()), Event::End(e))),
             Ok(e) => Ok((ResolveResult::Unbound, e)),
             Err(e) => Err(e),
         }
@@ -119,6 +316,62 @@ impl<R> NsReader<R> {
         self.reader.get_mut()
     }
 
+    /// Removes and returns errors from which reader has recovered in the
+    /// error recovery mode since the last call of this method.
+    ///
+    /// See the [`Reader::take_recovered_errors`] for examples
+    #[inline]
+    pub fn take_recovered_errors(&mut self) -> Vec<(usize, Error)> {
+        self.reader.take_recovered_errors()
+    }
+
+    /// Returns namespace bindings declared on the element of the last returned
+    /// event, in the order of declaration. For `Start` and `Empty` events these
+    /// are the bindings that come into scope, and for `End` events these are the
+    /// bindings that go out of scope. An undeclared prefix (`xmlns:prefix=""`)
+    /// is reported as [`ResolveResult::Unbound`].
+    ///
+    /// Nothing is returned after other events, while namespace tracking is
+    /// [disabled], or unless reporting was enabled by the
+    /// [`report_namespace_declarations()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::name::{Namespace, PrefixDeclaration, ResolveResult::*};
+    /// use quick_xml::reader::NsReader;
+    ///
+    /// let mut reader = NsReader::from_str(r#"<a xmlns="urn:a" xmlns:b="urn:b"><c/></a>"#);
+    /// reader.report_namespace_declarations(true);
+    ///
+    /// reader.read_event().unwrap(); // <a>
+    /// assert_eq!(
+    ///     reader.namespace_declarations().collect::<Vec<_>>(),
+    ///     vec![
+    ///         (PrefixDeclaration::Default, Bound(Namespace(b"urn:a"))),
+    ///         (PrefixDeclaration::Named(b"b"), Bound(Namespace(b"urn:b"))),
+    ///     ]
+    /// );
+    ///
+    /// reader.read_event().unwrap(); // <c/>
+    /// assert_eq!(reader.namespace_declarations().count(), 0);
+    ///
+    /// reader.read_event().unwrap(); // </a>
+    /// assert_eq!(reader.namespace_declarations().count(), 2);
+    /// ```
+    ///
+    /// [`report_namespace_declarations()`]: Self::report_namespace_declarations
+    /// [disabled]: Self::disable_namespace_tracking
+    pub fn namespace_declarations(
+        &self,
+    ) -> impl Iterator<Item = (PrefixDeclaration<'_>, ResolveResult<'_>)> {
+        let scope_event = self.scope_event;
+        self.ns_resolver
+            .current_scope()
+            .filter(move |_| scope_event)
+    }
+
     /// Resolves a potentially qualified **element name** or **attribute name**
     /// into _(namespace name, local name)_.
     ///
@@ -148,6 +401,31 @@ impl<R> NsReader<R> {
     ///   lifetime as the name in question.
     /// - returned namespace name will be bound to the reader itself
     ///
+    /// # Examples
+    ///
+    /// This method can resolve any name in the current namespace scope, for example,
+    /// a QName stored in an attribute value:
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::Event;
+    /// use quick_xml::name::{Namespace, QName, ResolveResult::*};
+    /// use quick_xml::reader::NsReader;
+    ///
+    /// let mut reader = NsReader::from_str(r#"<item xmlns:x="urn:x" type="x:Foo"/>"#);
+    ///
+    /// match reader.read_event().unwrap() {
+    ///     Event::Empty(e) => {
+    ///         let value = e.try_get_attribute("type").unwrap().unwrap().value;
+    ///         assert_eq!(
+    ///             reader.resolve(QName(&value), false),
+    ///             (Bound(Namespace(b"urn:x")), QName(b"Foo").into())
+    ///         );
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
     /// [`Bound`]: ResolveResult::Bound
     /// [`Unbound`]: ResolveResult::Unbound
     /// [`Unknown`]: ResolveResult::Unknown
@@ -155,7 +433,7 @@ impl<R> NsReader<R> {
     /// [`resolve_element()`]: Self::resolve_element()
     #[inline]
     pub fn resolve<'n>(&self, name: QName<'n>, attribute: bool) -> (ResolveResult, LocalName<'n>) {
-        self.ns_resolver.resolve(name, !attribute)
+        self.resolve_name(name, !attribute)
     }
 
     /// Resolves a potentially qualified **element name** into _(namespace name, local name)_.
@@ -211,7 +489,7 @@ impl<R> NsReader<R> {
     /// [`read_resolved_event()`]: Self::read_resolved_event
     #[inline]
     pub fn resolve_element<'n>(&self, name: QName<'n>) -> (ResolveResult, LocalName<'n>) {
-        self.ns_resolver.resolve(name, true)
+        self.resolve_name(name, true)
     }
 
     /// Resolves a potentially qualified **attribute name** into _(namespace name, local name)_.
@@ -282,7 +560,7 @@ impl<R> NsReader<R> {
     /// [`Unknown`]: ResolveResult::Unknown
     #[inline]
     pub fn resolve_attribute<'n>(&self, name: QName<'n>) -> (ResolveResult, LocalName<'n>) {
-        self.ns_resolver.resolve(name, false)
+        self.resolve_name(name, false)
     }
 }
 
@@ -341,7 +619,9 @@ impl<R: BufRead> NsReader<R> {
     /// [`read_resolved_event_into()`]: Self::read_resolved_event_into
     #[inline]
     pub fn read_event_into<'b>(&mut self, buf: &'b mut Vec<u8>) -> Result<Event<'b>> {
-        self.read_event_impl(buf)
+        self.pop();
+        let event = self.reader.read_event_into(buf);
+        self.process_event(event)
     }
 
     /// Reads the next event into given buffer and resolves its namespace (if applicable).
@@ -405,7 +685,7 @@ impl<R: BufRead> NsReader<R> {
         &mut self,
         buf: &'b mut Vec<u8>,
     ) -> Result<(ResolveResult, Event<'b>)> {
-        let event = self.read_event_impl(buf);
+        let event = self.read_event_into(buf);
         self.resolve_event(event)
     }
 
@@ -585,7 +865,9 @@ impl<'i> NsReader<&'i [u8]> {
     /// [`read_resolved_event()`]: Self::read_resolved_event
     #[inline]
     pub fn read_event(&mut self) -> Result<Event<'i>> {
-        self.read_event_impl(())
+        self.pop();
+        let event = self.reader.read_event();
+        self.process_event(event)
     }
 
     /// Reads the next event, borrow its content from the input buffer, and resolves
@@ -649,7 +931,7 @@ impl<'i> NsReader<&'i [u8]> {
     /// [`read_event()`]: Self::read_event
     #[inline]
     pub fn read_resolved_event(&mut self) -> Result<(ResolveResult, Event<'i>)> {
-        let event = self.read_event_impl(());
+        let event = self.read_event();
         self.resolve_event(event)
     }
 
@@ -818,6 +1100,47 @@ impl<'i> NsReader<&'i [u8]> {
     pub fn read_text(&mut self, end: QName) -> Result<Cow<'i, str>> {
         self.reader.read_text(end)
     }
+
+    /// Reads text content between start and end tags. This function is supposed
+    /// to be called after you already read a [`Start`] event.
+    ///
+    /// All [`Text`] and [`CData`] events up to the end tag are concatenated.
+    /// Text is decoded using the XML current [`decoder()`] and unescaped, CDATA
+    /// content is only decoded. A nested element is an error.
+    ///
+    /// See [`Reader::read_text_content()`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesStart, Event};
+    /// use quick_xml::reader::NsReader;
+    ///
+    /// let mut reader = NsReader::from_str("<message>Fish &amp; <![CDATA[<chips>]]></message>");
+    ///
+    /// let start = BytesStart::new("message");
+    /// let end   = start.to_end().into_owned();
+    ///
+    /// // First, we read a start event...
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(start));
+    ///
+    /// // ...then, we could read text content until close tag
+    /// let text = reader.read_text_content(end.name()).unwrap();
+    /// assert_eq!(text, "Fish & <chips>");
+    ///
+    /// // At the end we should get an Eof event, because we ate the whole XML
+    /// assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    /// ```
+    ///
+    /// [`Start`]: Event::Start
+    /// [`Text`]: Event::Text
+    /// [`CData`]: Event::CData
+    /// [`decoder()`]: Reader::decoder()
+    #[inline]
+    pub fn read_text_content(&mut self, end: QName) -> Result<Cow<'i, str>> {
+        self.reader.read_text_content(end)
+    }
 }
 
 impl<R> Deref for NsReader<R> {
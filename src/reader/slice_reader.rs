@@ -9,6 +9,7 @@ use crate::reader::EncodingRef;
 #[cfg(feature = "encoding")]
 use encoding_rs::{Encoding, UTF_8};
 
+use crate::encoding::Bom;
 use crate::errors::{Error, Result};
 use crate::events::Event;
 use crate::name::QName;
@@ -71,7 +72,53 @@ impl<'a> Reader<&'a [u8]> {
     /// ```
     #[inline]
     pub fn read_event(&mut self) -> Result<Event<'a>> {
-        self.read_event_impl(())
+        read_coalesced_event!(self, self.read_event_impl(()), self.read_event_impl(()))
+    }
+
+    /// Read an event that borrows from the input rather than a buffer and return
+    /// it together with the range of bytes of the input from which it was produced.
+    ///
+    /// Offsets are counted in the same way as [`buffer_position()`] does. Ranges
+    /// of [`Start`], [`Empty`], [`End`] and other markup events cover the markup
+    /// from `<` to `>` inclusive. The range of a [`Text`] event does not include
+    /// spaces trimmed from the text. [`End`] events generated for empty elements
+    /// when [`expand_empty_elements()`] is set, as well as [`Eof`], have an empty
+    /// range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let xml = "<tag>text</tag>";
+    /// let mut reader = Reader::from_str(xml);
+    ///
+    /// let (event, span) = reader.read_event_with_span().unwrap();
+    /// assert_eq!(event, Event::Start(BytesStart::new("tag")));
+    /// assert_eq!(&xml[span], "<tag>");
+    ///
+    /// let (event, span) = reader.read_event_with_span().unwrap();
+    /// assert_eq!(event, Event::Text(BytesText::new("text")));
+    /// assert_eq!(&xml[span], "text");
+    ///
+    /// let (event, span) = reader.read_event_with_span().unwrap();
+    /// assert_eq!(event, Event::End(BytesEnd::new("tag")));
+    /// assert_eq!(&xml[span], "</tag>");
+    /// ```
+    ///
+    /// [`buffer_position()`]: Self::buffer_position
+    /// [`expand_empty_elements()`]: Self::expand_empty_elements
+    /// [`Start`]: Event::Start
+    /// [`Empty`]: Event::Empty
+    /// [`End`]: Event::End
+    /// [`Text`]: Event::Text
+    /// [`Eof`]: Event::Eof
+    pub fn read_event_with_span(&mut self) -> Result<(Event<'a>, Span)> {
+        let event = self.read_event()?;
+        let span = self.event_span(&event);
+        Ok((event, span))
     }
 
     /// Reads until end element is found. This function is supposed to be called
@@ -231,6 +278,84 @@ impl<'a> Reader<&'a [u8]> {
 
         self.decoder().decode(&buffer[0..span.len()])
     }
+
+    /// Reads text content between start and end tags. This function is supposed
+    /// to be called after you already read a [`Start`] event.
+    ///
+    /// All [`Text`] and [`CData`] events up to the end tag are concatenated.
+    /// Text is decoded using the XML current [`decoder()`] and unescaped, CDATA
+    /// content is only decoded. Comments and processing instructions are skipped.
+    ///
+    /// The returned string borrows from the input if the content consists of
+    /// a single text or CDATA event that does not require unescaping.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedToken`] if a nested element or an end tag with
+    /// a name other than `end` is found and [`Error::UnexpectedEof`] if the end
+    /// tag was not found.
+    ///
+    /// If you need the content "as is", including any markup, use [`read_text()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesStart, Event};
+    /// use quick_xml::reader::Reader;
+    ///
+    /// let mut reader = Reader::from_str("<message>Fish &amp; <![CDATA[<chips>]]></message>");
+    ///
+    /// let start = BytesStart::new("message");
+    /// let end   = start.to_end().into_owned();
+    ///
+    /// // First, we read a start event...
+    /// assert_eq!(reader.read_event().unwrap(), Event::Start(start));
+    ///
+    /// // ...then, we could read text content until close tag
+    /// let text = reader.read_text_content(end.name()).unwrap();
+    /// assert_eq!(text, "Fish & <chips>");
+    ///
+    /// // At the end we should get an Eof event, because we ate the whole XML
+    /// assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    /// ```
+    ///
+    /// [`Start`]: Event::Start
+    /// [`Text`]: Event::Text
+    /// [`CData`]: Event::CData
+    /// [`decoder()`]: Self::decoder()
+    /// [`read_text()`]: Self::read_text()
+    pub fn read_text_content(&mut self, end: QName) -> Result<Cow<'a, str>> {
+        let mut text = Cow::Borrowed("");
+        loop {
+            let part = match self.read_event_impl(())? {
+                Event::Text(e) => e.unescape()?,
+                Event::CData(e) => match e.into_inner() {
+                    Cow::Borrowed(bytes) => self.decoder().decode(bytes)?,
+                    Cow::Owned(bytes) => self.decoder().decode(&bytes)?.into_owned().into(),
+                },
+                Event::Start(e) | Event::Empty(e) => {
+                    let name = self.decoder().decode(e.name().as_ref())?.into_owned();
+                    return Err(Error::UnexpectedToken(format!("<{}>", name)));
+                }
+                Event::End(e) if e.name() == end => return Ok(text),
+                Event::End(e) => {
+                    let name = self.decoder().decode(e.name().as_ref())?.into_owned();
+                    return Err(Error::UnexpectedToken(format!("</{}>", name)));
+                }
+                Event::Eof => {
+                    let name = self.decoder().decode(end.as_ref())?;
+                    return Err(Error::UnexpectedEof(format!("</{}>", name)));
+                }
+                _ => continue,
+            };
+            if text.is_empty() {
+                text = part;
+            } else {
+                text.to_mut().push_str(&part);
+            }
+        }
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -238,12 +363,12 @@ impl<'a> Reader<&'a [u8]> {
 /// Implementation of `XmlSource` for `&[u8]` reader using a `Self` as buffer
 /// that will be borrowed by events. This implementation provides a zero-copy deserialization
 impl<'a> XmlSource<'a, ()> for &'a [u8] {
-    #[cfg(not(feature = "encoding"))]
-    fn remove_utf8_bom(&mut self) -> Result<()> {
-        if self.starts_with(crate::encoding::UTF8_BOM) {
-            *self = &self[crate::encoding::UTF8_BOM.len()..];
+    fn detect_bom(&mut self, strip: bool) -> Result<Option<Bom>> {
+        let bom = Bom::detect(self);
+        if let (Some(bom), true) = (bom, strip) {
+            *self = &self[bom.as_bytes().len()..];
         }
-        Ok(())
+        Ok(bom)
     }
 
     #[cfg(feature = "encoding")]
@@ -368,6 +493,80 @@ mod test {
         ()
     );
 
+    mod read_text_content {
+        use crate::errors::Error;
+        use crate::events::{BytesStart, Event};
+        use crate::reader::Reader;
+        use pretty_assertions::assert_eq;
+        use std::borrow::Cow;
+
+        /// Reads the root start tag and then calls `read_text_content`
+        fn read_text(xml: &str) -> crate::errors::Result<Cow<'_, str>> {
+            let mut reader = Reader::from_str(xml);
+            reader.check_end_names(false);
+            let start = BytesStart::new("root");
+            let end = start.to_end().into_owned();
+
+            assert_eq!(reader.read_event().unwrap(), Event::Start(start));
+            reader.read_text_content(end.name())
+        }
+
+        #[test]
+        fn text() {
+            let text = read_text("<root>a &lt;b&gt; c</root>").unwrap();
+            assert_eq!(text, "a <b> c");
+        }
+
+        #[test]
+        fn text_borrowed() {
+            let text = read_text("<root>text</root>").unwrap();
+            assert!(matches!(text, Cow::Borrowed("text")));
+        }
+
+        #[test]
+        fn cdata() {
+            let text = read_text("<root><![CDATA[a &lt;b&gt; c]]></root>").unwrap();
+            assert_eq!(text, "a &lt;b&gt; c");
+        }
+
+        #[test]
+        fn mixed() {
+            let text =
+                read_text("<root>a &amp; <![CDATA[&amp;]]><!-- comment --> b</root>").unwrap();
+            assert_eq!(text, "a & &amp; b");
+        }
+
+        #[test]
+        fn empty() {
+            let text = read_text("<root></root>").unwrap();
+            assert_eq!(text, "");
+        }
+
+        #[test]
+        fn nested() {
+            match read_text("<root>text<child/></root>") {
+                Err(Error::UnexpectedToken(token)) => assert_eq!(token, "<child>"),
+                x => panic!("Expected `Err(UnexpectedToken(_))`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn mismatched_end() {
+            match read_text("<root>text</other>") {
+                Err(Error::UnexpectedToken(token)) => assert_eq!(token, "</other>"),
+                x => panic!("Expected `Err(UnexpectedToken(_))`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn eof() {
+            match read_text("<root>text") {
+                Err(Error::UnexpectedEof(tag)) => assert_eq!(tag, "</root>"),
+                x => panic!("Expected `Err(UnexpectedEof(_))`, but got `{:?}`", x),
+            }
+        }
+    }
+
     #[cfg(feature = "encoding")]
     mod encoding {
         use crate::events::Event;
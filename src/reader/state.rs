@@ -1,12 +1,14 @@
 #[cfg(feature = "encoding")]
 use encoding_rs::UTF_8;
 
-use crate::encoding::Decoder;
-use crate::errors::{Error, Result};
-use crate::events::{BytesCData, BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use crate::encoding::{Bom, Decoder};
+use crate::errors::{Error, IllFormedError, Result};
+use crate::events::{
+    BytesCData, BytesDecl, BytesDocType, BytesEnd, BytesPI, BytesStart, BytesText, Event,
+};
 #[cfg(feature = "encoding")]
 use crate::reader::EncodingRef;
-use crate::reader::{is_whitespace, BangType, ParseState};
+use crate::reader::{is_whitespace, BangType, Config, OpenElements, ParseState, Span};
 
 use memchr;
 
@@ -17,20 +19,29 @@ use memchr;
 pub(super) struct ReaderState {
     /// Number of bytes read from the source of data since the reader was created
     pub offset: usize,
+    /// Offset of the first byte of the last read event
+    pub event_start: usize,
     /// Defines how to process next byte
     pub state: ParseState,
-    /// Expand empty element into an opening and closing element
-    pub expand_empty_elements: bool,
-    /// Trims leading whitespace in Text events, skip the element if text is empty
-    pub trim_text_start: bool,
-    /// Trims trailing whitespace in Text events.
-    pub trim_text_end: bool,
-    /// Trims trailing whitespaces from markup names in closing tags `</a >`
-    pub trim_markup_names_in_closing_tags: bool,
-    /// Check if [`Event::End`] nodes match last [`Event::Start`] node
-    pub check_end_names: bool,
-    /// Check if comments contains `--` (false per default)
-    pub check_comments: bool,
+    /// Configuration of the parser
+    pub config: Config,
+    /// An event (or an error) that follows a run of merged text and CDATA events
+    /// when [`Config::coalesce_text`] is set. It is read to find the end of the run
+    /// and is returned by the next read
+    pub pending: Option<Result<Event<'static>>>,
+    /// The span of the last merged text event. The usual calculation cannot be
+    /// used for it because the pending event has been read after it
+    pub coalesced_span: Option<Span>,
+    /// Byte order mark found at the start of the input
+    pub bom: Option<Bom>,
+    /// Errors from which reader has recovered in the `error_recovery` mode
+    /// with positions of markup that caused them
+    pub recovered_errors: Vec<(usize, Error)>,
+    /// Number of [`Event::End`] events that should be generated in the
+    /// [`ParseState::Empty`] state in addition to the one that always generated
+    /// in that state. Non-zero only when recovering from mismatched end tag
+    /// that closes several elements
+    pending_ends: usize,
     /// All currently Started elements which didn't have a matching
     /// End element yet.
     ///
@@ -66,9 +77,10 @@ impl ReaderState {
     ///
     /// [`Text`]: Event::Text
     pub fn emit_text<'b>(&mut self, bytes: &'b [u8]) -> Result<Event<'b>> {
+        self.check_chars(bytes, self.event_start)?;
         let mut content = bytes;
 
-        if self.trim_text_end {
+        if self.config.trim_text_end {
             // Skip the ending '<'
             let len = bytes
                 .iter()
@@ -91,7 +103,7 @@ impl ReaderState {
         match bang_type {
             BangType::Comment if buf.starts_with(b"!--") => {
                 debug_assert!(buf.ends_with(b"--"));
-                if self.check_comments {
+                if self.config.check_comments {
                     // search if '--' not in comments
                     if let Some(p) = memchr::memchr_iter(b'-', &buf[3..len - 2])
                         .position(|p| buf[3 + p + 1] == b'-')
@@ -120,7 +132,7 @@ impl ReaderState {
                 if start + 8 >= len {
                     return Err(Error::EmptyDocType);
                 }
-                Ok(Event::DocType(BytesText::wrap(
+                Ok(Event::DocType(BytesDocType::wrap(
                     &buf[8 + start..],
                     self.decoder(),
                 )))
@@ -130,11 +142,11 @@ impl ReaderState {
     }
 
     /// Wraps content of `buf` into the [`Event::End`] event. Does the check that
-    /// end name matches the last opened start name if `self.check_end_names` is set.
+    /// end name matches the last opened start name if `self.config.check_end_names` is set.
     pub fn emit_end<'b>(&mut self, buf: &'b [u8]) -> Result<Event<'b>> {
         // XML standard permits whitespaces after the markup name in closing tags.
         // Let's strip them from the buffer before comparing tag names.
-        let name = if self.trim_markup_names_in_closing_tags {
+        let name = if self.config.trim_markup_names_in_closing_tags {
             if let Some(pos_end_name) = buf[1..].iter().rposition(|&b| !is_whitespace(b)) {
                 let (name, _) = buf[1..].split_at(pos_end_name + 1);
                 name
@@ -145,6 +157,11 @@ impl ReaderState {
             &buf[1..]
         };
 
+        if self.config.check_entities_in_names && memchr::memchr(b'&', name).is_some() {
+            self.offset -= buf.len();
+            return Err(self.entity_in_name(buf));
+        }
+
         let decoder = self.decoder();
         let mismatch_err = |expected: String, found: &[u8], offset: &mut usize| {
             *offset -= buf.len();
@@ -157,8 +174,11 @@ impl ReaderState {
         // Get the index in self.opened_buffer of the name of the last opened tag
         match self.opened_starts.pop() {
             Some(start) => {
-                if self.check_end_names {
+                if self.config.check_end_names {
                     let expected = &self.opened_buffer[start..];
+                    if name != expected && self.config.error_recovery {
+                        return Ok(self.recover_end_mismatch(start, name, buf));
+                    }
                     if name != expected {
                         let expected = decoder.decode(expected).unwrap_or_default().into_owned();
                         // #513: In order to allow error recovery we should drop content of the buffer
@@ -171,7 +191,10 @@ impl ReaderState {
                 self.opened_buffer.truncate(start);
             }
             None => {
-                if self.check_end_names {
+                if self.config.check_end_names && self.config.error_recovery {
+                    return Ok(self.recover_unmatched_end(String::new(), buf));
+                }
+                if self.config.check_end_names {
                     return mismatch_err("".to_string(), &buf[1..], &mut self.offset);
                 }
             }
@@ -180,6 +203,91 @@ impl ReaderState {
         Ok(Event::End(BytesEnd::wrap(name.into())))
     }
 
+    /// Closes the last opened element, which name starts at `start` in the
+    /// [`Self::opened_buffer`], instead of returning [`Error::EndEventMismatch`],
+    /// if `found` is a name of one of the outer elements. Switches to the
+    /// [`ParseState::Empty`] state to close all elements up to that element.
+    ///
+    /// If `found` is not a name of any opened element, the end tag is returned
+    /// as a text and the last opened element remains opened, so a single stray
+    /// end tag does not cause mismatches of the following end tags.
+    ///
+    /// # Parameters
+    /// - `start`: the start of the last opened name in the [`Self::opened_buffer`]
+    /// - `found`: the name in the end tag
+    /// - `buf`: content of the end tag between `<` and `>`
+    fn recover_end_mismatch(&mut self, start: usize, found: &[u8], buf: &[u8]) -> Event<'static> {
+        let decoder = self.decoder();
+        let expected = decoder
+            .decode(&self.opened_buffer[start..])
+            .unwrap_or_default()
+            .into_owned();
+
+        let mut end = start;
+        let outer = self.opened_starts.iter().rposition(|&start| {
+            let matched = &self.opened_buffer[start..end] == found;
+            end = start;
+            matched
+        });
+        match outer {
+            Some(i) => {
+                self.recovered_errors.push((
+                    self.offset - buf.len() - 2,
+                    Error::EndEventMismatch {
+                        expected,
+                        found: decoder.decode(found).unwrap_or_default().into_owned(),
+                    },
+                ));
+                self.pending_ends = self.opened_starts.len() - i - 1;
+                self.state = ParseState::Empty;
+
+                let name = self.opened_buffer.split_off(start);
+                Event::End(BytesEnd::wrap(name.into()))
+            }
+            None => {
+                self.opened_starts.push(start);
+                self.recover_unmatched_end(expected, buf)
+            }
+        }
+    }
+
+    /// Returns an end tag that does not close any opened element as a [`Text`]
+    /// event with the escaped content instead of returning [`Error::EndEventMismatch`].
+    ///
+    /// # Parameters
+    /// - `expected`: the name of the last opened element, or an empty string
+    ///   if there are no opened elements
+    /// - `buf`: content of the end tag between `<` and `>`
+    ///
+    /// [`Text`]: Event::Text
+    fn recover_unmatched_end(&mut self, expected: String, buf: &[u8]) -> Event<'static> {
+        let decoder = self.decoder();
+        self.recovered_errors.push((
+            self.offset - buf.len() - 2,
+            Error::EndEventMismatch {
+                expected,
+                found: decoder.decode(&buf[1..]).unwrap_or_default().into_owned(),
+            },
+        ));
+
+        let mut text = b"&lt;".to_vec();
+        text.extend_from_slice(buf);
+        text.push(b'>');
+        Event::Text(BytesText::wrap(text, decoder))
+    }
+
+    /// Returns a `<` that cannot start a markup as an escaped [`Text`] event
+    /// instead of trying to parse a tag from it.
+    ///
+    /// [`Text`]: Event::Text
+    pub fn recover_unescaped_lt(&mut self) -> Event<'static> {
+        self.recovered_errors.push((
+            self.offset - 1,
+            Error::IllFormed(IllFormedError::UnescapedLt),
+        ));
+        Event::Text(BytesText::wrap(&b"&lt;"[..], self.decoder()))
+    }
+
     /// reads `BytesElement` starting with a `?`,
     /// return `Decl` or `PI` event
     pub fn emit_question_mark<'b>(&mut self, buf: &'b [u8]) -> Result<Event<'b>> {
@@ -190,15 +298,22 @@ impl ReaderState {
 
                 // Try getting encoding from the declaration event
                 #[cfg(feature = "encoding")]
-                if self.encoding.can_be_refined() {
+                if !self.config.assume_utf8 && self.encoding.can_be_refined() {
                     if let Some(encoding) = event.encoder() {
                         self.encoding = EncodingRef::XmlDetected(encoding);
                     }
                 }
+                #[cfg(feature = "encoding")]
+                if self.config.assume_utf8 {
+                    match event.encoder() {
+                        Some(e) if e != UTF_8 => return Err(Error::UnexpectedEncoding(e.name())),
+                        _ => {}
+                    }
+                }
 
                 Ok(Event::Decl(event))
             } else {
-                Ok(Event::PI(BytesText::wrap(&buf[1..len - 1], self.decoder())))
+                Ok(Event::PI(BytesPI::wrap(&buf[1..len - 1], self.decoder())))
             }
         } else {
             self.offset -= len;
@@ -211,7 +326,13 @@ impl ReaderState {
     /// # Parameters
     /// - `content`: Content of a tag between `<` and `>`
     pub fn emit_start<'b>(&mut self, content: &'b [u8]) -> Result<Event<'b>> {
+        // Skip the starting '<'
+        self.check_chars(content, self.event_start + 1)?;
         let len = content.len();
+        if self.config.check_entities_in_names && find_reference_outside_quotes(content).is_some() {
+            self.offset -= len;
+            return Err(self.entity_in_name(content));
+        }
         let name_end = content
             .iter()
             .position(|&b| is_whitespace(b))
@@ -221,7 +342,7 @@ impl ReaderState {
             let name_len = if name_end < len { name_end } else { len - 1 };
             let event = BytesStart::wrap(&content[..len - 1], name_len);
 
-            if self.expand_empty_elements {
+            if self.config.expand_empty_elements {
                 self.state = ParseState::Empty;
                 self.opened_starts.push(self.opened_buffer.len());
                 self.opened_buffer.extend(&content[..name_len]);
@@ -239,15 +360,81 @@ impl ReaderState {
         }
     }
 
+    /// Returns an error if [`Config::check_restricted_chars`] is set and `bytes`
+    /// contains a character that is not allowed to appear unescaped in XML 1.1.
+    ///
+    /// # Parameters
+    /// - `bytes`: checked data
+    /// - `start`: offset of the first byte of `bytes` in the input
+    fn check_chars(&self, bytes: &[u8], start: usize) -> Result<()> {
+        if self.config.check_restricted_chars {
+            if let Some(i) = bytes.iter().position(|&b| is_restricted_char(b)) {
+                return Err(Error::IllegalCharacter {
+                    byte: bytes[i],
+                    position: start + i,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates an error about an entity reference in a name inside the tag
+    /// with the specified `content`
+    fn entity_in_name(&self, content: &[u8]) -> Error {
+        let tag = self.decoder().decode(content).unwrap_or_default();
+        Error::IllFormed(IllFormedError::EntityInName(tag.into_owned()))
+    }
+
     #[inline]
     pub fn close_expanded_empty(&mut self) -> Result<Event<'static>> {
-        self.state = ParseState::ClosedTag;
+        // Generated event does not correspond to any bytes of input
+        self.event_start = self.offset;
+        if self.pending_ends > 0 {
+            // Stay in the `Empty` state to close the next element
+            self.pending_ends -= 1;
+        } else {
+            self.state = ParseState::ClosedTag;
+        }
         let name = self
             .opened_buffer
             .split_off(self.opened_starts.pop().unwrap());
         Ok(Event::End(BytesEnd::wrap(name.into())))
     }
 
+    /// Converts a CDATA section to a text with the same unescaped content.
+    /// Escaping is performed over bytes, so the text remains in the document encoding
+    pub fn cdata_to_text<'b>(&self, cdata: BytesCData<'b>) -> BytesText<'b> {
+        let content = cdata.into_inner();
+        if memchr::memchr3(b'&', b'<', b'>', &content).is_none() {
+            return BytesText::wrap(content, self.decoder());
+        }
+        let mut escaped = Vec::with_capacity(content.len());
+        for &b in content.iter() {
+            match b {
+                b'&' => escaped.extend_from_slice(b"&amp;"),
+                b'<' => escaped.extend_from_slice(b"&lt;"),
+                b'>' => escaped.extend_from_slice(b"&gt;"),
+                _ => escaped.push(b),
+            }
+        }
+        BytesText::wrap(escaped, self.decoder())
+    }
+
+    /// Appends the content of `next` to the content of `text`
+    pub fn append_text<'b>(&self, text: BytesText<'b>, next: &[u8]) -> BytesText<'b> {
+        let mut content = text.into_inner().into_owned();
+        content.extend_from_slice(next);
+        BytesText::wrap(content, self.decoder())
+    }
+
+    /// Returns an iterator over names of the currently opened elements
+    pub fn open_elements(&self) -> OpenElements<'_> {
+        OpenElements {
+            buffer: &self.opened_buffer,
+            starts: &self.opened_starts,
+        }
+    }
+
     /// Get the decoder, used to decode bytes, read by this reader, to the strings.
     ///
     /// If [`encoding`] feature is enabled, the used encoding may change after
@@ -265,17 +452,46 @@ impl ReaderState {
     }
 }
 
+/// Returns the position of the first `&` in the `content` of a start tag that
+/// is not inside an attribute value, i.e. position of an entity reference in
+/// an element or an attribute name.
+fn find_reference_outside_quotes(content: &[u8]) -> Option<usize> {
+    // Fast path: the most tags do not have references at all
+    memchr::memchr(b'&', content)?;
+
+    let mut quote = None;
+    for (i, &b) in content.iter().enumerate() {
+        match (quote, b) {
+            (None, b'"' | b'\'') => quote = Some(b),
+            (Some(q), _) if q == b => quote = None,
+            (None, b'&') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Checks whether the byte is a C0 control character which is not allowed to
+/// appear unescaped in an XML 1.1 document. The NUL character is not allowed at all.
+///
+/// See <https://www.w3.org/TR/xml11/#NT-RestrictedChar>
+#[inline]
+const fn is_restricted_char(b: u8) -> bool {
+    matches!(b, 0x00..=0x08 | 0x0B | 0x0C | 0x0E..=0x1F)
+}
+
 impl Default for ReaderState {
     fn default() -> Self {
         Self {
             offset: 0,
+            event_start: 0,
             state: ParseState::Init,
-            expand_empty_elements: false,
-            trim_text_start: false,
-            trim_text_end: false,
-            trim_markup_names_in_closing_tags: true,
-            check_end_names: true,
-            check_comments: false,
+            config: Config::default(),
+            pending: None,
+            coalesced_span: None,
+            bom: None,
+            recovered_errors: Vec::new(),
+            pending_ends: 0,
             opened_buffer: Vec::new(),
             opened_starts: Vec::new(),
 
@@ -1,9 +1,11 @@
 //! Contains serializer for content of an XML element
 
+use crate::de::BytesFormat;
 use crate::errors::serialize::DeError;
 use crate::se::element::{ElementSerializer, Struct, Tuple};
 use crate::se::simple_type::{QuoteTarget, SimpleTypeSerializer};
-use crate::se::{Indent, QuoteLevel, XmlName};
+use crate::se::{CDataPolicy, Finalizer, FloatFormat, Indent, QuoteLevel, XmlName};
+use crate::writer::AttributeQuote;
 use serde::ser::{
     Impossible, Serialize, SerializeSeq, SerializeTuple, SerializeTupleStruct, Serializer,
 };
@@ -59,6 +61,39 @@ pub struct ContentSerializer<'w, 'i, W: Write> {
     // If `true`, then empty elements will be serialized as `<element></element>`
     // instead of `<element/>`.
     pub expand_empty_elements: bool,
+    /// If `true`, then [`WithRaw`] values will be serialized by writing their
+    /// stored XML verbatim instead of serializing the parsed value.
+    ///
+    /// [`WithRaw`]: crate::de::WithRaw
+    pub write_raw_xml: bool,
+    /// If `true`, then struct fields with primitive values will be serialized
+    /// as attributes even without the `@` prefix in their names.
+    pub primitives_as_attributes: bool,
+    /// If set, then unit and struct enum variants will be serialized as
+    /// elements named by the field name with the variant name written to
+    /// an attribute with that name.
+    pub variant_attribute: Option<&'static str>,
+    /// Defines when strings in a text content are written as CDATA sections
+    pub cdata: CDataPolicy,
+    /// If `true`, then boolean attributes will be written as a bare attribute
+    /// name when `true` and will be omitted when `false`.
+    pub minimized_bool_attrs: bool,
+    /// If set, `None` values are written as empty elements with the
+    /// `{prefix}:nil="true"` attribute instead of just empty elements
+    pub nil_prefix: Option<&'static str>,
+    /// Name of a field which value is written as a text content of an element
+    pub text_key: &'static str,
+    /// Prefix of names of fields which are written as attributes
+    pub attribute_prefix: &'static str,
+    /// If set, records elements which start tags were written, but which
+    /// were not closed yet
+    pub finalizer: Option<Finalizer>,
+    /// Defines how `f32` and `f64` values are written
+    pub float_format: FloatFormat,
+    /// Defines how binary data is written. If `None`, binary data cannot be serialized
+    pub bytes_format: Option<BytesFormat>,
+    /// Quote character used to enclose attribute values
+    pub attribute_quote: AttributeQuote,
     //TODO: add settings to disallow consequent serialization of primitives
 }
 
@@ -66,11 +101,13 @@ impl<'w, 'i, W: Write> ContentSerializer<'w, 'i, W> {
     /// Turns this serializer into serializer of a text content
     #[inline]
     pub fn into_simple_type_serializer(self) -> SimpleTypeSerializer<'i, &'w mut W> {
-        //TODO: Customization point: choose between CDATA and Text representation
         SimpleTypeSerializer {
             writer: self.writer,
             target: QuoteTarget::Text,
             level: self.level,
+            cdata: self.cdata,
+            float_format: self.float_format,
+            bytes_format: self.bytes_format,
             indent: if self.write_indent {
                 self.indent
             } else {
@@ -89,6 +126,18 @@ impl<'w, 'i, W: Write> ContentSerializer<'w, 'i, W> {
             indent: self.indent.borrow(),
             write_indent: self.write_indent,
             expand_empty_elements: self.expand_empty_elements,
+            write_raw_xml: self.write_raw_xml,
+            primitives_as_attributes: self.primitives_as_attributes,
+            variant_attribute: self.variant_attribute,
+            cdata: self.cdata,
+            minimized_bool_attrs: self.minimized_bool_attrs,
+            nil_prefix: self.nil_prefix,
+            text_key: self.text_key,
+            attribute_prefix: self.attribute_prefix,
+            finalizer: self.finalizer.clone(),
+            float_format: self.float_format,
+            bytes_format: self.bytes_format,
+            attribute_quote: self.attribute_quote,
         }
     }
 
@@ -110,6 +159,29 @@ impl<'w, 'i, W: Write> ContentSerializer<'w, 'i, W> {
         Ok(())
     }
 
+    /// Writes an empty element with the `{prefix}:nil="true"` attribute,
+    /// which is how XML Schema represents absent values
+    pub(super) fn write_nil(mut self, name: XmlName, prefix: &str) -> Result<(), DeError> {
+        self.write_indent()?;
+        self.writer.write_char('<')?;
+        self.writer.write_str(name.0)?;
+        self.writer.write_char(' ')?;
+        self.writer.write_str(prefix)?;
+        self.writer.write_str(":nil=")?;
+        self.writer.write_str(match self.attribute_quote {
+            AttributeQuote::Double => "\"true\"",
+            AttributeQuote::Single => "'true'",
+        })?;
+        if self.expand_empty_elements {
+            self.writer.write_str("></")?;
+            self.writer.write_str(name.0)?;
+            self.writer.write_char('>')?;
+        } else {
+            self.writer.write_str("/>")?;
+        }
+        Ok(())
+    }
+
     /// Writes simple type content between `name` tags
     pub(super) fn write_wrapped<S>(mut self, name: XmlName, serialize: S) -> Result<(), DeError>
     where
@@ -371,6 +443,7 @@ impl<'w, 'i, W: Write> SerializeTupleStruct for ContentSerializer<'w, 'i, W> {
 #[cfg(test)]
 pub(super) mod tests {
     use super::*;
+    use crate::de::{ATTRIBUTES_KEY, TEXT_KEY};
     use crate::utils::Bytes;
     use serde::Serialize;
     use std::collections::BTreeMap;
@@ -496,6 +569,18 @@ pub(super) mod tests {
                         indent: Indent::None,
                         write_indent: false,
                         expand_empty_elements: false,
+                        write_raw_xml: false,
+                        primitives_as_attributes: false,
+                        variant_attribute: None,
+                        cdata: CDataPolicy::Never,
+                        minimized_bool_attrs: false,
+                        nil_prefix: None,
+                        text_key: TEXT_KEY,
+                        attribute_prefix: ATTRIBUTES_KEY,
+                        finalizer: None,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
+                        attribute_quote: AttributeQuote::Double,
                     };
 
                     $data.serialize(ser).unwrap();
@@ -517,6 +602,18 @@ pub(super) mod tests {
                         indent: Indent::None,
                         write_indent: false,
                         expand_empty_elements: false,
+                        write_raw_xml: false,
+                        primitives_as_attributes: false,
+                        variant_attribute: None,
+                        cdata: CDataPolicy::Never,
+                        minimized_bool_attrs: false,
+                        nil_prefix: None,
+                        text_key: TEXT_KEY,
+                        attribute_prefix: ATTRIBUTES_KEY,
+                        finalizer: None,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
+                        attribute_quote: AttributeQuote::Double,
                     };
 
                     match $data.serialize(ser).unwrap_err() {
@@ -687,6 +784,18 @@ pub(super) mod tests {
                         indent: Indent::Owned(Indentation::new(b' ', 2)),
                         write_indent: false,
                         expand_empty_elements: false,
+                        write_raw_xml: false,
+                        primitives_as_attributes: false,
+                        variant_attribute: None,
+                        cdata: CDataPolicy::Never,
+                        minimized_bool_attrs: false,
+                        nil_prefix: None,
+                        text_key: TEXT_KEY,
+                        attribute_prefix: ATTRIBUTES_KEY,
+                        finalizer: None,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
+                        attribute_quote: AttributeQuote::Double,
                     };
 
                     $data.serialize(ser).unwrap();
@@ -708,6 +817,18 @@ pub(super) mod tests {
                         indent: Indent::Owned(Indentation::new(b' ', 2)),
                         write_indent: false,
                         expand_empty_elements: false,
+                        write_raw_xml: false,
+                        primitives_as_attributes: false,
+                        variant_attribute: None,
+                        cdata: CDataPolicy::Never,
+                        minimized_bool_attrs: false,
+                        nil_prefix: None,
+                        text_key: TEXT_KEY,
+                        attribute_prefix: ATTRIBUTES_KEY,
+                        finalizer: None,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
+                        attribute_quote: AttributeQuote::Double,
                     };
 
                     match $data.serialize(ser).unwrap_err() {
@@ -1,14 +1,15 @@
 //! Contains serializer for an XML element
 
-use crate::de::{TEXT_KEY, VALUE_KEY};
+use crate::de::{VALUE_KEY, WITH_RAW};
 use crate::errors::serialize::DeError;
 use crate::se::content::ContentSerializer;
-use crate::se::key::QNameSerializer;
+use crate::se::key::{MapKeySerializer, QNameSerializer};
 use crate::se::simple_type::{QuoteTarget, SimpleSeq, SimpleTypeSerializer};
-use crate::se::{Indent, XmlName};
+use crate::se::{CDataPolicy, Indent, XmlName};
+use crate::writer::AttributeQuote;
 use serde::ser::{
-    Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
-    SerializeTupleStruct, SerializeTupleVariant, Serializer,
+    Impossible, Serialize, SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant,
+    SerializeTuple, SerializeTupleStruct, SerializeTupleVariant, Serializer,
 };
 use serde::serde_if_integer128;
 use std::fmt::Write;
@@ -86,9 +87,14 @@ impl<'w, 'k, W: Write> Serializer for ElementSerializer<'w, 'k, W> {
     /// - content of the element, or
     /// - attribute value
     ///
-    /// So serialization of `None` works the same as [serialization of `()`](#method.serialize_unit)
+    /// So serialization of `None` works the same as [serialization of `()`](#method.serialize_unit),
+    /// unless [`ContentSerializer::nil_prefix`] is set, in which case the empty
+    /// element gets the `{prefix}:nil="true"` attribute.
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        self.serialize_unit()
+        match self.ser.nil_prefix {
+            Some(prefix) => self.ser.write_nil(self.key, prefix),
+            None => self.serialize_unit(),
+        }
     }
 
     fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
@@ -109,15 +115,19 @@ impl<'w, 'k, W: Write> Serializer for ElementSerializer<'w, 'k, W> {
         _variant_index: u32,
         variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        if variant == TEXT_KEY {
+        if variant == self.ser.text_key {
             // We should write some text but we don't known what text to write
             Err(DeError::Unsupported(
                 format!(
-                    "cannot serialize enum unit variant `{}::$text` as text content value",
-                    name
+                    "cannot serialize enum unit variant `{}::{}` as text content value",
+                    name, variant
                 )
                 .into(),
             ))
+        } else if let Some(attribute) = self.ser.variant_attribute {
+            let mut ser = self.serialize_struct(name, 0)?;
+            ser.write_attribute(XmlName::try_from(attribute)?, variant)?;
+            SerializeStruct::end(ser)
         } else {
             let name = XmlName::try_from(variant)?;
             self.ser.write_empty(name)
@@ -134,14 +144,16 @@ impl<'w, 'k, W: Write> Serializer for ElementSerializer<'w, 'k, W> {
 
     fn serialize_newtype_variant<T: ?Sized + Serialize>(
         mut self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
         variant: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error> {
-        if variant == TEXT_KEY {
+        if variant == self.ser.text_key {
             value.serialize(self.ser.into_simple_type_serializer())?;
             Ok(())
+        } else if self.ser.variant_attribute.is_some() {
+            Err(unsupported_variant("newtype", name, variant))
         } else {
             self.key = XmlName::try_from(variant)?;
             value.serialize(self)
@@ -175,11 +187,13 @@ impl<'w, 'k, W: Write> Serializer for ElementSerializer<'w, 'k, W> {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        if variant == TEXT_KEY {
+        if variant == self.ser.text_key {
             self.ser
                 .into_simple_type_serializer()
                 .serialize_tuple_struct(name, len)
                 .map(Tuple::Text)
+        } else if self.ser.variant_attribute.is_some() {
+            Err(unsupported_variant("tuple", name, variant))
         } else {
             self.key = XmlName::try_from(variant)?;
             self.serialize_tuple_struct(name, len).map(Tuple::Element)
@@ -196,17 +210,29 @@ impl<'w, 'k, W: Write> Serializer for ElementSerializer<'w, 'k, W> {
     #[inline]
     fn serialize_struct(
         mut self,
-        _name: &'static str,
+        name: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
+        if name == WITH_RAW {
+            // Both the value and the raw XML are written with their own tags
+            return Ok(Struct {
+                ser: self,
+                children: String::new(),
+                with_raw: true,
+            });
+        }
         self.ser.write_indent()?;
         self.ser.indent.increase();
 
         self.ser.writer.write_char('<')?;
         self.ser.writer.write_str(self.key.0)?;
+        if let Some(finalizer) = &self.ser.finalizer {
+            finalizer.open(self.key.0);
+        }
         Ok(Struct {
             ser: self,
             children: String::new(),
+            with_raw: false,
         })
     }
 
@@ -218,14 +244,18 @@ impl<'w, 'k, W: Write> Serializer for ElementSerializer<'w, 'k, W> {
         variant: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        if variant == TEXT_KEY {
+        if variant == self.ser.text_key {
             Err(DeError::Unsupported(
                 format!(
-                    "cannot serialize enum struct variant `{}::$text` as text content value",
-                    name
+                    "cannot serialize enum struct variant `{}::{}` as text content value",
+                    name, variant
                 )
                 .into(),
             ))
+        } else if let Some(attribute) = self.ser.variant_attribute {
+            let mut ser = self.serialize_struct(name, len)?;
+            ser.write_attribute(XmlName::try_from(attribute)?, variant)?;
+            Ok(ser)
         } else {
             self.key = XmlName::try_from(variant)?;
             self.serialize_struct(name, len)
@@ -233,6 +263,18 @@ impl<'w, 'k, W: Write> Serializer for ElementSerializer<'w, 'k, W> {
     }
 }
 
+/// Creates an error for enum variants of the `kind` that cannot be serialized
+/// when the [`ContentSerializer::variant_attribute`] is set
+fn unsupported_variant(kind: &str, name: &str, variant: &str) -> DeError {
+    DeError::Unsupported(
+        format!(
+            "cannot serialize enum {} variant `{}::{}` with the variant name in an attribute",
+            kind, name, variant
+        )
+        .into(),
+    )
+}
+
 impl<'w, 'k, W: Write> SerializeSeq for ElementSerializer<'w, 'k, W> {
     type Ok = ();
     type Error = DeError;
@@ -344,6 +386,12 @@ pub struct Struct<'w, 'k, W: Write> {
     // attributes should be listed first. Fail, if attribute encountered after
     // element. Use feature to configure
     children: String,
+    /// `true` if this struct is a [`WithRaw`] wrapper. Such structs are not
+    /// written as elements, instead either the parsed value or the raw XML
+    /// is written, depending on the [`ContentSerializer::write_raw_xml`] setting
+    ///
+    /// [`WithRaw`]: crate::de::WithRaw
+    with_raw: bool,
 }
 
 impl<'w, 'k, W: Write> Struct<'w, 'k, W> {
@@ -353,7 +401,7 @@ impl<'w, 'k, W: Write> Struct<'w, 'k, W> {
         T: ?Sized + Serialize,
     {
         //TODO: Customization point: allow user to determine if field is attribute or not
-        if let Some(key) = key.strip_prefix('@') {
+        if let Some(key) = key.strip_prefix(self.ser.ser.attribute_prefix) {
             let key = XmlName::try_from(key)?;
             self.write_attribute(key, value)
         } else {
@@ -367,28 +415,43 @@ impl<'w, 'k, W: Write> Struct<'w, 'k, W> {
     where
         T: ?Sized + Serialize,
     {
+        if self.ser.ser.minimized_bool_attrs {
+            if let Ok(Some(value)) = value.serialize(PrimitiveSerializer) {
+                if value {
+                    self.ser.ser.writer.write_char(' ')?;
+                    self.ser.ser.writer.write_str(key.0)?;
+                }
+                return Ok(());
+            }
+        }
         //TODO: Customization point: each attribute on new line
         self.ser.ser.writer.write_char(' ')?;
         self.ser.ser.writer.write_str(key.0)?;
         self.ser.ser.writer.write_char('=')?;
 
-        //TODO: Customization point: preferred quote style
-        self.ser.ser.writer.write_char('"')?;
+        let (quote, target) = match self.ser.ser.attribute_quote {
+            AttributeQuote::Double => ('"', QuoteTarget::DoubleQAttr),
+            AttributeQuote::Single => ('\'', QuoteTarget::SingleQAttr),
+        };
+        self.ser.ser.writer.write_char(quote)?;
         value.serialize(SimpleTypeSerializer {
             writer: &mut self.ser.ser.writer,
-            target: QuoteTarget::DoubleQAttr,
+            target,
             level: self.ser.ser.level,
+            cdata: CDataPolicy::Never,
+            float_format: self.ser.ser.float_format,
+            bytes_format: self.ser.ser.bytes_format,
             indent: Indent::None,
         })?;
-        self.ser.ser.writer.write_char('"')?;
+        self.ser.ser.writer.write_char(quote)?;
 
         Ok(())
     }
 
     /// Writes `value` either as a text content, or as an element.
     ///
-    /// If `key` has a magic value [`TEXT_KEY`], then `value` serialized as a
-    /// [simple type].
+    /// If `key` is equal to the [`ContentSerializer::text_key`], then `value`
+    /// serialized as a [simple type].
     ///
     /// If `key` has a magic value [`VALUE_KEY`], then `value` serialized as a
     /// [content] without wrapping in tags, otherwise it is wrapped in
@@ -406,9 +469,21 @@ impl<'w, 'k, W: Write> Struct<'w, 'k, W> {
             indent: self.ser.ser.indent.borrow(),
             write_indent: true,
             expand_empty_elements: self.ser.ser.expand_empty_elements,
+            write_raw_xml: self.ser.ser.write_raw_xml,
+            primitives_as_attributes: self.ser.ser.primitives_as_attributes,
+            variant_attribute: self.ser.ser.variant_attribute,
+            cdata: self.ser.ser.cdata,
+            minimized_bool_attrs: self.ser.ser.minimized_bool_attrs,
+            nil_prefix: self.ser.ser.nil_prefix,
+            text_key: self.ser.ser.text_key,
+            attribute_prefix: self.ser.ser.attribute_prefix,
+            finalizer: self.ser.ser.finalizer.clone(),
+            float_format: self.ser.ser.float_format,
+            bytes_format: self.ser.ser.bytes_format,
+            attribute_quote: self.ser.ser.attribute_quote,
         };
 
-        if key == TEXT_KEY {
+        if key == ser.text_key {
             value.serialize(ser.into_simple_type_serializer())?;
         } else if key == VALUE_KEY {
             value.serialize(ser)?;
@@ -420,6 +495,146 @@ impl<'w, 'k, W: Write> Struct<'w, 'k, W> {
         }
         Ok(())
     }
+
+    /// Writes the `value` field of a [`WithRaw`] using the tag name of this
+    /// struct, or writes the `raw` field verbatim, depending on the
+    /// [`ContentSerializer::write_raw_xml`] setting. Another field is skipped.
+    ///
+    /// [`WithRaw`]: crate::de::WithRaw
+    fn write_with_raw_field<T>(&mut self, key: &str, value: &T) -> Result<(), DeError>
+    where
+        T: ?Sized + Serialize,
+    {
+        let ser = &mut self.ser.ser;
+        match (key, ser.write_raw_xml) {
+            ("value", false) => value.serialize(ElementSerializer {
+                ser: ser.new_seq_element_serializer(),
+                key: self.ser.key,
+            }),
+            ("raw", true) => {
+                ser.write_indent()?;
+                value.serialize(QNameSerializer {
+                    writer: &mut *ser.writer,
+                })?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Returns `true` if `value` is a primitive that can be written as an attribute
+/// value: a boolean, a number, a string, a unit enum variant, or a newtype or
+/// an option of them
+fn is_primitive<T>(value: &T) -> bool
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(PrimitiveSerializer).is_ok()
+}
+
+/// Implements methods of [`PrimitiveSerializer`] for non-primitive types
+macro_rules! not_primitive {
+    ($($method:ident($($arg:ty),*) -> $ret:ty;)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<$ret, Self::Error> {
+                Err(DeError::Unsupported("not a primitive".into()))
+            }
+        )*
+    };
+}
+
+/// Implements methods of [`PrimitiveSerializer`] for primitive types
+/// other than booleans
+macro_rules! primitive {
+    ($($method:ident($($arg:ty),*);)*) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok, Self::Error> {
+                Ok(None)
+            }
+        )*
+    };
+}
+
+/// A serializer that succeeds only for values accepted by [`is_primitive`]
+/// without writing them anywhere. Returns the value of booleans, which is
+/// used to write minimized boolean attributes, and `None` for other primitives
+struct PrimitiveSerializer;
+
+impl Serializer for PrimitiveSerializer {
+    type Ok = Option<bool>;
+    type Error = DeError;
+
+    type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
+
+    fn serialize_bool(self, value: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(Some(value))
+    }
+
+    primitive! {
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_f32(f32);
+        serialize_f64(f64);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_none();
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    serde_if_integer128! {
+        primitive! {
+            serialize_i128(i128);
+            serialize_u128(u128);
+        }
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        Err(DeError::Unsupported("not a primitive".into()))
+    }
+
+    not_primitive! {
+        serialize_bytes(&[u8]) -> Self::Ok;
+        serialize_unit() -> Self::Ok;
+        serialize_unit_struct(&'static str) -> Self::Ok;
+        serialize_seq(Option<usize>) -> Self::SerializeSeq;
+        serialize_tuple(usize) -> Self::SerializeTuple;
+        serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+        serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+        serialize_map(Option<usize>) -> Self::SerializeMap;
+        serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+        serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+    }
 }
 
 impl<'w, 'k, W: Write> SerializeStruct for Struct<'w, 'k, W> {
@@ -430,10 +645,24 @@ impl<'w, 'k, W: Write> SerializeStruct for Struct<'w, 'k, W> {
     where
         T: ?Sized + Serialize,
     {
+        if self.with_raw {
+            return self.write_with_raw_field(key, value);
+        }
+        if self.ser.ser.primitives_as_attributes
+            && key != self.ser.ser.text_key
+            && key != VALUE_KEY
+            && !key.starts_with(self.ser.ser.attribute_prefix)
+            && is_primitive(value)
+        {
+            return self.write_attribute(XmlName::try_from(key)?, value);
+        }
         self.write_field(key, value)
     }
 
     fn end(mut self) -> Result<Self::Ok, Self::Error> {
+        if self.with_raw {
+            return Ok(());
+        }
         self.ser.ser.indent.decrease();
 
         if self.children.is_empty() {
@@ -448,6 +677,9 @@ impl<'w, 'k, W: Write> SerializeStruct for Struct<'w, 'k, W> {
             self.ser.ser.writer.write_str(self.ser.key.0)?;
             self.ser.ser.writer.write_char('>')?;
         }
+        if let Some(finalizer) = &self.ser.ser.finalizer {
+            finalizer.close(self.ser.key.0);
+        }
         Ok(())
     }
 }
@@ -474,7 +706,7 @@ impl<'w, 'k, W: Write> SerializeStructVariant for Struct<'w, 'k, W> {
 
 pub struct Map<'w, 'k, W: Write> {
     ser: Struct<'w, 'k, W>,
-    /// Key, serialized by `QNameSerializer` if consumer uses `serialize_key` +
+    /// Key, serialized by `MapKeySerializer` if consumer uses `serialize_key` +
     /// `serialize_value` calls instead of `serialize_entry`
     key: Option<String>,
 }
@@ -484,7 +716,7 @@ impl<'w, 'k, W: Write> Map<'w, 'k, W> {
     where
         T: ?Sized + Serialize,
     {
-        key.serialize(QNameSerializer {
+        key.serialize(MapKeySerializer {
             writer: String::new(),
         })
     }
@@ -543,8 +775,9 @@ impl<'w, 'k, W: Write> SerializeMap for Map<'w, 'k, W> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::de::{ATTRIBUTES_KEY, TEXT_KEY};
     use crate::se::content::tests::*;
-    use crate::se::{Indent, QuoteLevel};
+    use crate::se::{FloatFormat, Indent, QuoteLevel};
     use crate::utils::Bytes;
     use serde::Serialize;
     use std::collections::BTreeMap;
@@ -584,6 +817,18 @@ mod tests {
                             indent: Indent::None,
                             write_indent: false,
                             expand_empty_elements: false,
+                            write_raw_xml: false,
+                            primitives_as_attributes: false,
+                            variant_attribute: None,
+                            cdata: CDataPolicy::Never,
+                            minimized_bool_attrs: false,
+                            nil_prefix: None,
+                            text_key: TEXT_KEY,
+                            attribute_prefix: ATTRIBUTES_KEY,
+                            finalizer: None,
+                            float_format: FloatFormat::Shortest,
+                            bytes_format: None,
+                            attribute_quote: AttributeQuote::Double,
                         },
                         key: XmlName("root"),
                     };
@@ -608,6 +853,18 @@ mod tests {
                             indent: Indent::None,
                             write_indent: false,
                             expand_empty_elements: false,
+                            write_raw_xml: false,
+                            primitives_as_attributes: false,
+                            variant_attribute: None,
+                            cdata: CDataPolicy::Never,
+                            minimized_bool_attrs: false,
+                            nil_prefix: None,
+                            text_key: TEXT_KEY,
+                            attribute_prefix: ATTRIBUTES_KEY,
+                            finalizer: None,
+                            float_format: FloatFormat::Shortest,
+                            bytes_format: None,
+                            attribute_quote: AttributeQuote::Double,
                         },
                         key: XmlName("root"),
                     };
@@ -1545,6 +1802,18 @@ mod tests {
                             indent: Indent::Owned(Indentation::new(b' ', 2)),
                             write_indent: false,
                             expand_empty_elements: false,
+                            write_raw_xml: false,
+                            primitives_as_attributes: false,
+                            variant_attribute: None,
+                            cdata: CDataPolicy::Never,
+                            minimized_bool_attrs: false,
+                            nil_prefix: None,
+                            text_key: TEXT_KEY,
+                            attribute_prefix: ATTRIBUTES_KEY,
+                            finalizer: None,
+                            float_format: FloatFormat::Shortest,
+                            bytes_format: None,
+                            attribute_quote: AttributeQuote::Double,
                         },
                         key: XmlName("root"),
                     };
@@ -1569,6 +1838,18 @@ mod tests {
                             indent: Indent::Owned(Indentation::new(b' ', 2)),
                             write_indent: false,
                             expand_empty_elements: false,
+                            write_raw_xml: false,
+                            primitives_as_attributes: false,
+                            variant_attribute: None,
+                            cdata: CDataPolicy::Never,
+                            minimized_bool_attrs: false,
+                            nil_prefix: None,
+                            text_key: TEXT_KEY,
+                            attribute_prefix: ATTRIBUTES_KEY,
+                            finalizer: None,
+                            float_format: FloatFormat::Shortest,
+                            bytes_format: None,
+                            attribute_quote: AttributeQuote::Double,
                         },
                         key: XmlName("root"),
                     };
@@ -2564,6 +2845,18 @@ mod tests {
                             indent: Indent::None,
                             write_indent: false,
                             expand_empty_elements: true,
+                            write_raw_xml: false,
+                            primitives_as_attributes: false,
+                            variant_attribute: None,
+                            cdata: CDataPolicy::Never,
+                            minimized_bool_attrs: false,
+                            nil_prefix: None,
+                            text_key: TEXT_KEY,
+                            attribute_prefix: ATTRIBUTES_KEY,
+                            finalizer: None,
+                            float_format: FloatFormat::Shortest,
+                            bytes_format: None,
+                            attribute_quote: AttributeQuote::Double,
                         },
                         key: XmlName("root"),
                     };
@@ -2588,6 +2881,18 @@ mod tests {
                             indent: Indent::None,
                             write_indent: false,
                             expand_empty_elements: false,
+                            write_raw_xml: false,
+                            primitives_as_attributes: false,
+                            variant_attribute: None,
+                            cdata: CDataPolicy::Never,
+                            minimized_bool_attrs: false,
+                            nil_prefix: None,
+                            text_key: TEXT_KEY,
+                            attribute_prefix: ATTRIBUTES_KEY,
+                            finalizer: None,
+                            float_format: FloatFormat::Shortest,
+                            bytes_format: None,
+                            attribute_quote: AttributeQuote::Double,
                         },
                         key: XmlName("root"),
                     };
@@ -36,6 +36,14 @@ impl<W: Write> Serializer for QNameSerializer<W> {
     type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
 
     write_primitive!();
+    write_primitive!(serialize_f32(f32));
+    write_primitive!(serialize_f64(f64));
+
+    fn serialize_bytes(self, _value: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Err(DeError::Unsupported(
+            "`serialize_bytes` not supported yet".into(),
+        ))
+    }
 
     fn serialize_str(mut self, value: &str) -> Result<Self::Ok, Self::Error> {
         self.write_str(value)?;
@@ -151,6 +159,181 @@ impl<W: Write> Serializer for QNameSerializer<W> {
     }
 }
 
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Generates a method that rejects a map key of the `$ty` type
+macro_rules! reject_key {
+    ($method:ident ( $ty:ty )) => {
+        fn $method(self, value: $ty) -> Result<Self::Ok, Self::Error> {
+            Err(DeError::Unsupported(
+                format!(
+                    "cannot serialize map key `{}` of type `{}` as an XML tag name, only string keys are supported",
+                    value,
+                    stringify!($ty)
+                )
+                .into(),
+            ))
+        }
+    };
+}
+
+/// A serializer of map keys, which are used as names of elements. Unlike
+/// [`QNameSerializer`] it accepts only string-like keys: strings, chars and
+/// unit enum variants, or newtypes and options of them.
+pub struct MapKeySerializer<W: Write> {
+    /// Writer to which this serializer writes content
+    pub writer: W,
+}
+
+impl<W: Write> MapKeySerializer<W> {
+    #[inline]
+    fn inner(self) -> QNameSerializer<W> {
+        QNameSerializer {
+            writer: self.writer,
+        }
+    }
+}
+
+impl<W: Write> Serializer for MapKeySerializer<W> {
+    type Ok = W;
+    type Error = DeError;
+
+    type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleVariant = Impossible<Self::Ok, Self::Error>;
+    type SerializeMap = Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = Impossible<Self::Ok, Self::Error>;
+    type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
+
+    reject_key!(serialize_bool(bool));
+
+    reject_key!(serialize_i8(i8));
+    reject_key!(serialize_i16(i16));
+    reject_key!(serialize_i32(i32));
+    reject_key!(serialize_i64(i64));
+
+    reject_key!(serialize_u8(u8));
+    reject_key!(serialize_u16(u16));
+    reject_key!(serialize_u32(u32));
+    reject_key!(serialize_u64(u64));
+
+    serde_if_integer128! {
+        reject_key!(serialize_i128(i128));
+        reject_key!(serialize_u128(u128));
+    }
+
+    reject_key!(serialize_f32(f32));
+    reject_key!(serialize_f64(f64));
+
+    fn serialize_char(self, value: char) -> Result<Self::Ok, Self::Error> {
+        self.inner().serialize_char(value)
+    }
+
+    fn serialize_str(self, value: &str) -> Result<Self::Ok, Self::Error> {
+        self.inner().serialize_str(value)
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.inner().serialize_bytes(value)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.inner().serialize_none()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.inner().serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.inner().serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.inner()
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, DeError> {
+        self.inner()
+            .serialize_newtype_variant(name, variant_index, variant, value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.inner().serialize_seq(len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.inner().serialize_tuple(len)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.inner().serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.inner()
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.inner().serialize_map(len)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.inner().serialize_struct(name, len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.inner()
+            .serialize_struct_variant(name, variant_index, variant, len)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,15 @@
 //! Module to handle custom serde `Serializer`
 
 /// Implements writing primitives to the underlying writer.
-/// Implementor must provide `write_str(self, &str) -> Result<(), DeError>` method
+/// Implementor must provide `write_str(self, &str) -> Result<(), DeError>` method.
+///
+/// Floating-point numbers are not written by `write_primitive!()`, use either
+/// `write_primitive!(serialize_f64(f64))` or, if implementor has a `float_format`
+/// field, `write_primitive!(serialize_f64(f64) => format_f64)`.
+///
+/// Byte arrays are not written by `write_primitive!()`, use either
+/// `write_primitive!(serialize_bytes)` if implementor has a `bytes_format` field,
+/// or implement `serialize_bytes` manually
 macro_rules! write_primitive {
     ($method:ident ( $ty:ty )) => {
         fn $method(mut self, value: $ty) -> Result<Self::Ok, Self::Error> {
@@ -9,6 +17,26 @@ macro_rules! write_primitive {
             Ok(self.writer)
         }
     };
+    ($method:ident ( $ty:ty ) => $format:ident) => {
+        fn $method(mut self, value: $ty) -> Result<Self::Ok, Self::Error> {
+            let value = self.float_format.$format(value);
+            self.write_str(&value)?;
+            Ok(self.writer)
+        }
+    };
+    (serialize_bytes) => {
+        fn serialize_bytes(mut self, value: &[u8]) -> Result<Self::Ok, Self::Error> {
+            match self.bytes_format {
+                Some(format) => {
+                    self.write_str(&format.encode(value))?;
+                    Ok(self.writer)
+                }
+                None => Err(DeError::Unsupported(
+                    "`serialize_bytes` not supported yet".into(),
+                )),
+            }
+        }
+    };
     () => {
         fn serialize_bool(mut self, value: bool) -> Result<Self::Ok, Self::Error> {
             self.write_str(if value { "true" } else { "false" })?;
@@ -30,20 +58,10 @@ macro_rules! write_primitive {
             write_primitive!(serialize_u128(u128));
         }
 
-        write_primitive!(serialize_f32(f32));
-        write_primitive!(serialize_f64(f64));
-
         fn serialize_char(self, value: char) -> Result<Self::Ok, Self::Error> {
             self.serialize_str(&value.to_string())
         }
 
-        fn serialize_bytes(self, _value: &[u8]) -> Result<Self::Ok, Self::Error> {
-            //TODO: customization point - allow user to decide how to encode bytes
-            Err(DeError::Unsupported(
-                "`serialize_bytes` not supported yet".into(),
-            ))
-        }
-
         fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
             Ok(self.writer)
         }
@@ -80,12 +98,14 @@ pub(crate) mod simple_type;
 
 use self::content::ContentSerializer;
 use self::element::ElementSerializer;
+use crate::de::{BytesFormat, ATTRIBUTES_KEY, TEXT_KEY, WITH_RAW};
 use crate::errors::serialize::DeError;
-use crate::writer::Indentation;
+use crate::writer::{AttributeQuote, Indentation};
 use serde::ser::{self, Serialize};
 use serde::serde_if_integer128;
 use std::fmt::Write;
 use std::str::from_utf8;
+use std::sync::{Arc, Mutex, PoisonError};
 
 /// Serialize struct into a `Write`r.
 ///
@@ -307,6 +327,126 @@ pub enum QuoteLevel {
     Minimal,
 }
 
+/// Defines when strings in a text content are written as CDATA sections
+/// instead of escaping special characters. Attribute values are always escaped.
+///
+/// The `]]>` sequence cannot appear inside a CDATA section, so sections with
+/// such content are split into several sections (`]]]]><![CDATA[>`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CDataPolicy {
+    /// Never write CDATA sections. This is the default
+    Never,
+    /// Write a string as a CDATA section if it contains more than the specified
+    /// number of `<`, `>` and `&` characters
+    Threshold(usize),
+    /// Write all non-empty strings as CDATA sections
+    Always,
+}
+
+impl CDataPolicy {
+    /// Returns `true` if `value` should be written as a CDATA section
+    pub(crate) fn is_cdata(&self, value: &str) -> bool {
+        match *self {
+            Self::Never => false,
+            Self::Threshold(max) => {
+                value
+                    .bytes()
+                    .filter(|b| matches!(b, b'<' | b'>' | b'&'))
+                    .count()
+                    > max
+            }
+            Self::Always => !value.is_empty(),
+        }
+    }
+}
+
+/// Defines how `f32` and `f64` values are written. Applied to text content,
+/// attribute values and items of `xs:list`s, but not to names of elements.
+#[derive(Debug, Clone, Copy)]
+pub enum FloatFormat {
+    /// The shortest representation from which the same value is parsed back.
+    /// This is the default
+    Shortest,
+    /// The specified number of digits after the decimal point. The value is
+    /// rounded, so it does not round-trip in general
+    Fixed(usize),
+    /// A function that appends a representation of the value to the string.
+    /// `f32` values are converted to `f64` before passing to the function
+    Custom(fn(f64, &mut String)),
+}
+
+impl FloatFormat {
+    /// Returns a representation of `value` according to this format
+    pub(crate) fn format_f32(&self, value: f32) -> String {
+        match *self {
+            Self::Shortest => value.to_string(),
+            Self::Fixed(places) => format!("{:.*}", places, value),
+            Self::Custom(format) => {
+                let mut result = String::new();
+                format(value as f64, &mut result);
+                result
+            }
+        }
+    }
+
+    /// Returns a representation of `value` according to this format
+    pub(crate) fn format_f64(&self, value: f64) -> String {
+        match *self {
+            Self::Shortest => value.to_string(),
+            Self::Fixed(places) => format!("{:.*}", places, value),
+            Self::Custom(format) => {
+                let mut result = String::new();
+                format(value, &mut result);
+                result
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A handle that checks that all elements started by a [`Serializer`] were
+/// closed. Create it with [`Serializer::finalizer`] before the serializer will
+/// be consumed and call [`Self::finalize`] after serialization.
+///
+/// The `Serialize` implementations generated by serde always complete the
+/// started structs and maps, so this check is useful when the serializer is
+/// driven manually, for example, by a custom `Serialize` implementation or
+/// by the code that calls `serde::Serializer` methods directly.
+#[derive(Clone, Debug, Default)]
+pub struct Finalizer {
+    /// Names of elements whose start tags were written, but which were not closed
+    open: Arc<Mutex<Vec<String>>>,
+}
+
+impl Finalizer {
+    /// Returns [`DeError::NotFinalized`] with the names of the elements that
+    /// were started, but not closed, if any.
+    pub fn finalize(&self) -> Result<(), DeError> {
+        let open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        if open.is_empty() {
+            Ok(())
+        } else {
+            Err(DeError::NotFinalized(open.clone()))
+        }
+    }
+
+    /// Records that the start tag of the element `name` was written
+    fn open(&self, name: &str) {
+        let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        open.push(name.to_string());
+    }
+
+    /// Records that the element `name` was closed. Elements that were opened
+    /// after it are not closed properly, but they remain recorded
+    fn close(&self, name: &str) {
+        let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(i) = open.iter().rposition(|n| n == name) {
+            open.remove(i);
+        }
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// Implements serialization method by forwarding it to the serializer created by
@@ -424,11 +564,11 @@ impl<'i> Indent<'i> {
         match self {
             Self::None => {}
             Self::Owned(i) => {
-                writer.write_char('\n')?;
+                writer.write_str(from_utf8(i.newline())?)?;
                 writer.write_str(from_utf8(i.current())?)?;
             }
             Self::Borrow(i) => {
-                writer.write_char('\n')?;
+                writer.write_str(from_utf8(i.newline())?)?;
                 writer.write_str(from_utf8(i.current())?)?;
             }
         }
@@ -438,11 +578,48 @@ impl<'i> Indent<'i> {
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A Serializer
+/// A Serializer.
+///
+/// Fields of structs which names start with the [attribute prefix] (`@` by
+/// default) are written as attributes of the element and all other fields are
+/// written as its content. Attributes are always written in the declaration
+/// order of the fields, before any content, even if some of them are declared
+/// after the fields written as elements. That makes the output reproducible
+/// regardless of how attribute and element fields are interleaved.
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// # use serde::Serialize;
+/// # use quick_xml::se::Serializer;
+/// #[derive(Serialize)]
+/// struct Item {
+///     name: &'static str,
+///     #[serde(rename = "@id")]
+///     id: u32,
+///     size: u32,
+///     #[serde(rename = "@kind")]
+///     kind: &'static str,
+/// }
+///
+/// let mut buffer = String::new();
+/// let item = Item { name: "first", id: 1, size: 2, kind: "box" };
+/// item.serialize(Serializer::new(&mut buffer)).unwrap();
+/// assert_eq!(
+///     buffer,
+///     r#"<Item id="1" kind="box"><name>first</name><size>2</size></Item>"#
+/// );
+/// ```
+///
+/// [attribute prefix]: Self::attribute_prefix
 pub struct Serializer<'w, 'r, W: Write> {
     ser: ContentSerializer<'w, 'r, W>,
     /// Name of the root tag. If not specified, deduced from the structure name
     root_tag: Option<XmlName<'r>>,
+    /// Version, encoding and standalone attributes of the XML declaration
+    /// that is written before the root element
+    declaration: Option<(&'r str, Option<&'r str>, Option<&'r str>)>,
+    /// Content of the DOCTYPE that is written before the root element
+    doctype: Option<&'r str>,
 }
 
 impl<'w, 'r, W: Write> Serializer<'w, 'r, W> {
@@ -459,8 +636,22 @@ impl<'w, 'r, W: Write> Serializer<'w, 'r, W> {
                 indent: Indent::None,
                 write_indent: false,
                 expand_empty_elements: false,
+                write_raw_xml: false,
+                primitives_as_attributes: false,
+                variant_attribute: None,
+                cdata: CDataPolicy::Never,
+                minimized_bool_attrs: false,
+                nil_prefix: None,
+                text_key: TEXT_KEY,
+                attribute_prefix: ATTRIBUTES_KEY,
+                finalizer: None,
+                float_format: FloatFormat::Shortest,
+                bytes_format: None,
+                attribute_quote: AttributeQuote::Double,
             },
             root_tag: None,
+            declaration: None,
+            doctype: None,
         }
     }
 
@@ -515,6 +706,22 @@ impl<'w, 'r, W: Write> Serializer<'w, 'r, W> {
     /// );
     /// ```
     ///
+    /// Tuples and sequences are serialized as repeated elements with the `root_tag`
+    /// name, one element per item, which is the same representation from which
+    /// they are deserialized:
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    ///
+    /// let mut buffer = String::new();
+    /// let ser = Serializer::with_root(&mut buffer, Some("r")).unwrap();
+    ///
+    /// (42.0, "a").serialize(ser).unwrap();
+    /// assert_eq!(buffer, "<r>42</r><r>a</r>");
+    /// ```
+    ///
     /// [XML name]: https://www.w3.org/TR/xml11/#NT-Name
     pub fn with_root(writer: &'w mut W, root_tag: Option<&'r str>) -> Result<Self, DeError> {
         Ok(Self {
@@ -524,8 +731,22 @@ impl<'w, 'r, W: Write> Serializer<'w, 'r, W> {
                 indent: Indent::None,
                 write_indent: false,
                 expand_empty_elements: false,
+                write_raw_xml: false,
+                primitives_as_attributes: false,
+                variant_attribute: None,
+                cdata: CDataPolicy::Never,
+                minimized_bool_attrs: false,
+                nil_prefix: None,
+                text_key: TEXT_KEY,
+                attribute_prefix: ATTRIBUTES_KEY,
+                finalizer: None,
+                float_format: FloatFormat::Shortest,
+                bytes_format: None,
+                attribute_quote: AttributeQuote::Double,
             },
             root_tag: root_tag.map(|tag| XmlName::try_from(tag)).transpose()?,
+            declaration: None,
+            doctype: None,
         })
     }
 
@@ -562,6 +783,608 @@ impl<'w, 'r, W: Write> Serializer<'w, 'r, W> {
         self
     }
 
+    /// Set the level of quoting that will be used for text content and attribute
+    /// values. Defaults to [`QuoteLevel::Full`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::{QuoteLevel, Serializer};
+    ///
+    /// #[derive(Debug, PartialEq, Serialize)]
+    /// struct Struct {
+    ///     #[serde(rename = "@attr")]
+    ///     attr: &'static str,
+    ///     #[serde(rename = "$text")]
+    ///     text: &'static str,
+    /// }
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_quote_level(QuoteLevel::Minimal);
+    ///
+    /// let data = Struct {
+    ///     attr: "'\"<&>",
+    ///     text: "'\"<&>",
+    /// };
+    ///
+    /// data.serialize(ser).unwrap();
+    /// assert_eq!(
+    ///     buffer,
+    ///     "<Struct attr=\"'&quot;&lt;&amp;>\">'\"&lt;&amp;></Struct>"
+    /// );
+    /// ```
+    pub fn set_quote_level(&mut self, level: QuoteLevel) -> &mut Self {
+        self.ser.level = level;
+        self
+    }
+
+    /// Set the quote character that will enclose attribute values. Defaults to
+    /// [`AttributeQuote::Double`]. The selected quote character is always escaped
+    /// inside the values, even with [`QuoteLevel::Minimal`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::{QuoteLevel, Serializer};
+    /// # use quick_xml::writer::AttributeQuote;
+    ///
+    /// #[derive(Debug, PartialEq, Serialize)]
+    /// struct Struct {
+    ///     #[serde(rename = "@attr")]
+    ///     attr: &'static str,
+    /// }
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.set_quote_level(QuoteLevel::Minimal);
+    /// ser.set_attribute_quote(AttributeQuote::Single);
+    ///
+    /// let data = Struct { attr: "'\"<&>" };
+    ///
+    /// data.serialize(ser).unwrap();
+    /// assert_eq!(buffer, "<Struct attr='&apos;\"&lt;&amp;>'/>");
+    /// ```
+    pub fn set_attribute_quote(&mut self, quote: AttributeQuote) -> &mut Self {
+        self.ser.attribute_quote = quote;
+        self
+    }
+
+    /// Enable or disable writing of the stored XML of [`WithRaw`] values
+    /// verbatim. Defaults to `false`, which means that the parsed value is
+    /// serialized and the stored XML is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::{Deserialize, Serialize};
+    /// # use quick_xml::de::{from_str, WithRaw};
+    /// # use quick_xml::se::Serializer;
+    ///
+    /// #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    /// struct Root {
+    ///     item: WithRaw<u32>,
+    /// }
+    ///
+    /// let mut data: Root = from_str("<Root><item>0042</item></Root>").unwrap();
+    /// data.item.value += 1;
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.write_raw_xml(true);
+    ///
+    /// data.serialize(ser).unwrap();
+    /// assert_eq!(buffer, "<Root><item>0042</item></Root>");
+    /// ```
+    ///
+    /// [`WithRaw`]: crate::de::WithRaw
+    pub fn write_raw_xml(&mut self, write: bool) -> &mut Self {
+        self.ser.write_raw_xml = write;
+        self
+    }
+
+    /// Enable or disable serialization of struct fields with primitive values
+    /// (booleans, numbers, strings, unit enum variants and newtypes or options
+    /// of them) as attributes. Defaults to `false`, which means that only fields
+    /// which names start with `@` are serialized as attributes.
+    ///
+    /// When enabled, fields with other values are serialized as elements.
+    /// Fields named `$text` and `$value` keep their special meaning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    /// #[derive(Serialize)]
+    /// struct Item {
+    ///     id: u32,
+    ///     name: &'static str,
+    ///     tags: Vec<&'static str>,
+    /// }
+    ///
+    /// let item = Item {
+    ///     id: 42,
+    ///     name: "answer",
+    ///     tags: vec!["one", "two"],
+    /// };
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.primitives_as_attributes(true);
+    ///
+    /// item.serialize(ser).unwrap();
+    /// assert_eq!(
+    ///     buffer,
+    ///     r#"<Item id="42" name="answer"><tags>one</tags><tags>two</tags></Item>"#
+    /// );
+    /// ```
+    pub fn primitives_as_attributes(&mut self, enable: bool) -> &mut Self {
+        self.ser.primitives_as_attributes = enable;
+        self
+    }
+
+    /// Serialize unit and struct enum variants as elements named by the field
+    /// (or by the root tag) with the variant name written to the attribute
+    /// `name`. By default the variant name is used as the element name.
+    ///
+    /// Fields of struct variants are written to the same element. Newtype and
+    /// tuple variants cannot be serialized in this mode, except `$text` variants
+    /// and variants serialized inside `$value` fields, which have no name to use.
+    ///
+    /// Use [`Deserializer::variant_attribute`] to read such enums back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    /// #[derive(Serialize)]
+    /// enum Shape {
+    ///     Circle {
+    ///         #[serde(rename = "@radius")]
+    ///         radius: f64,
+    ///     },
+    ///     Point,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Drawing {
+    ///     shape: Vec<Shape>,
+    /// }
+    ///
+    /// let drawing = Drawing {
+    ///     shape: vec![Shape::Circle { radius: 1.5 }, Shape::Point],
+    /// };
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.variant_attribute("type");
+    ///
+    /// drawing.serialize(ser).unwrap();
+    /// assert_eq!(
+    ///     buffer,
+    ///     r#"<Drawing><shape type="Circle" radius="1.5"/><shape type="Point"/></Drawing>"#
+    /// );
+    /// ```
+    ///
+    /// [`Deserializer::variant_attribute`]: crate::de::Deserializer::variant_attribute
+    pub fn variant_attribute(&mut self, name: &'static str) -> &mut Self {
+        self.ser.variant_attribute = Some(name);
+        self
+    }
+
+    /// Set when strings in a text content are written as CDATA sections.
+    /// Defaults to [`CDataPolicy::Never`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::{CDataPolicy, Serializer};
+    /// #[derive(Serialize)]
+    /// struct Snippet {
+    ///     title: &'static str,
+    ///     code: &'static str,
+    /// }
+    ///
+    /// let snippet = Snippet {
+    ///     title: "Q&A",
+    ///     code: "<p>Q&amp;A</p>",
+    /// };
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.cdata_policy(CDataPolicy::Threshold(2));
+    ///
+    /// snippet.serialize(ser).unwrap();
+    /// assert_eq!(
+    ///     buffer,
+    ///     "<Snippet><title>Q&amp;A</title><code><![CDATA[<p>Q&amp;A</p>]]></code></Snippet>"
+    /// );
+    /// ```
+    pub fn cdata_policy(&mut self, policy: CDataPolicy) -> &mut Self {
+        self.ser.cdata = policy;
+        self
+    }
+
+    /// Enable or disable minimization of boolean attributes. Defaults to `false`.
+    ///
+    /// When enabled, attributes with the `true` value are written as a bare
+    /// attribute name without a value (`<input disabled/>`) and attributes
+    /// with the `false` value are not written at all. Newtypes of booleans and
+    /// `Some` booleans are minimized too, `None` is written as usual.
+    ///
+    /// Note, that such attributes are not allowed by the XML specification.
+    /// Use [`Deserializer::minimized_bool_attrs`] to read such elements back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    /// #[derive(Serialize)]
+    /// struct Input {
+    ///     #[serde(rename = "@disabled")]
+    ///     disabled: bool,
+    ///     #[serde(rename = "@checked")]
+    ///     checked: bool,
+    /// }
+    ///
+    /// let input = Input {
+    ///     disabled: true,
+    ///     checked: false,
+    /// };
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::with_root(&mut buffer, Some("input")).unwrap();
+    /// ser.minimized_bool_attrs(true);
+    ///
+    /// input.serialize(ser).unwrap();
+    /// assert_eq!(buffer, "<input disabled/>");
+    /// ```
+    ///
+    /// [`Deserializer::minimized_bool_attrs`]: crate::de::Deserializer::minimized_bool_attrs
+    pub fn minimized_bool_attrs(&mut self, enable: bool) -> &mut Self {
+        self.ser.minimized_bool_attrs = enable;
+        self
+    }
+
+    /// Set the prefix of the [`xsi:nil`] attribute that is written to elements
+    /// that represent `None` values. Defaults to `None`, in which case such
+    /// elements are written as empty elements without attributes.
+    ///
+    /// Only elements are affected, `None` attributes and `None` text content
+    /// are written as before. The prefix should be bound to the
+    /// `http://www.w3.org/2001/XMLSchema-instance` namespace by the user, for
+    /// example, with an `@xmlns:xsi` field of the root struct.
+    ///
+    /// Use [`Deserializer::xsi_nil`] to read such elements back as `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    /// #[derive(Serialize)]
+    /// struct Person {
+    ///     #[serde(rename = "@xmlns:xsi")]
+    ///     xsi: &'static str,
+    ///     name: &'static str,
+    ///     email: Option<&'static str>,
+    /// }
+    ///
+    /// let person = Person {
+    ///     xsi: "http://www.w3.org/2001/XMLSchema-instance",
+    ///     name: "Alice",
+    ///     email: None,
+    /// };
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.nil_prefix(Some("xsi"));
+    ///
+    /// person.serialize(ser).unwrap();
+    /// assert_eq!(
+    ///     buffer,
+    ///     "<Person xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">\
+    ///         <name>Alice</name>\
+    ///         <email xsi:nil=\"true\"/>\
+    ///     </Person>"
+    /// );
+    /// ```
+    ///
+    /// [`xsi:nil`]: https://www.w3.org/TR/xmlschema-1/#xsi_nil
+    /// [`Deserializer::xsi_nil`]: crate::de::Deserializer::xsi_nil
+    pub fn nil_prefix(&mut self, prefix: Option<&'static str>) -> &mut Self {
+        self.ser.nil_prefix = prefix;
+        self
+    }
+
+    /// Set the name of a field which value is written as a text content of
+    /// an element. Defaults to `$text`. Enum variants with that name are also
+    /// written as a text content.
+    ///
+    /// Use [`Deserializer::text_field_name`] to read such fields back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    /// #[derive(Serialize)]
+    /// struct Note {
+    ///     #[serde(rename = "#text")]
+    ///     content: &'static str,
+    /// }
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.text_field_name("#text");
+    ///
+    /// Note { content: "content" }.serialize(ser).unwrap();
+    /// assert_eq!(buffer, "<Note>content</Note>");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is empty.
+    ///
+    /// [`Deserializer::text_field_name`]: crate::de::Deserializer::text_field_name
+    pub fn text_field_name(&mut self, name: &'static str) -> &mut Self {
+        assert!(!name.is_empty(), "text field name must not be empty");
+        self.ser.text_key = name;
+        self
+    }
+
+    /// Set the prefix of names of fields which are written as attributes.
+    /// Defaults to `@`. The prefix is removed from the name of an attribute.
+    ///
+    /// Use [`Deserializer::attribute_prefix`] to read such fields back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    /// #[derive(Serialize)]
+    /// struct Item {
+    ///     #[serde(rename = "attr_id")]
+    ///     id: u32,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.attribute_prefix("attr_");
+    ///
+    /// Item { id: 1, name: "first" }.serialize(ser).unwrap();
+    /// assert_eq!(buffer, r#"<Item id="1"><name>first</name></Item>"#);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `prefix` is empty, because every field would be written
+    /// as an attribute in that case.
+    ///
+    /// [`Deserializer::attribute_prefix`]: crate::de::Deserializer::attribute_prefix
+    pub fn attribute_prefix(&mut self, prefix: &'static str) -> &mut Self {
+        assert!(!prefix.is_empty(), "attribute prefix must not be empty");
+        self.ser.attribute_prefix = prefix;
+        self
+    }
+
+    /// Returns a [`Finalizer`] that can be used to check that all elements
+    /// started by this serializer were closed, after the serializer will be
+    /// consumed. Each call returns a handle to the same state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::se::Serializer;
+    /// use quick_xml::DeError;
+    /// use serde::ser::{SerializeStruct, Serializer as _};
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// let finalizer = ser.finalizer();
+    ///
+    /// let mut root = ser.serialize_struct("root", 1).unwrap();
+    /// root.serialize_field("@id", &1).unwrap();
+    /// // `root.end()` was forgotten
+    /// drop(root);
+    ///
+    /// assert_eq!(buffer, r#"<root id="1""#);
+    /// match finalizer.finalize() {
+    ///     Err(DeError::NotFinalized(open)) => assert_eq!(open, vec!["root"]),
+    ///     e => panic!("expected `NotFinalized` error, found {:?}", e),
+    /// }
+    /// ```
+    pub fn finalizer(&mut self) -> Finalizer {
+        self.ser
+            .finalizer
+            .get_or_insert_with(Finalizer::default)
+            .clone()
+    }
+
+    /// Set how `f32` and `f64` values are written. Defaults to [`FloatFormat::Shortest`],
+    /// which gives a representation from which the same value is parsed back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::{FloatFormat, Serializer};
+    /// #[derive(Serialize)]
+    /// struct Point {
+    ///     #[serde(rename = "@x")]
+    ///     x: f64,
+    ///     y: f64,
+    /// }
+    ///
+    /// let point = Point { x: 0.1 + 0.2, y: 1.0 / 3.0 };
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.float_format(FloatFormat::Fixed(2));
+    ///
+    /// point.serialize(ser).unwrap();
+    /// assert_eq!(buffer, r#"<Point x="0.30"><y>0.33</y></Point>"#);
+    /// ```
+    pub fn float_format(&mut self, format: FloatFormat) -> &mut Self {
+        self.ser.float_format = format;
+        self
+    }
+
+    /// Set how binary data is written. Affects types that serialize themselves
+    /// as bytes, for example [`serde_bytes::ByteBuf`]. Applied to text content
+    /// and attribute values.
+    ///
+    /// By default binary data cannot be serialized and [`DeError::Unsupported`]
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::de::BytesFormat;
+    /// # use quick_xml::se::Serializer;
+    /// # use quick_xml::utils::ByteBuf;
+    /// #[derive(Serialize)]
+    /// struct Root {
+    ///     #[serde(rename = "@hash")]
+    ///     hash: ByteBuf,
+    ///     data: ByteBuf,
+    /// }
+    ///
+    /// let root = Root {
+    ///     hash: ByteBuf(vec![0xCA, 0xFE]),
+    ///     data: ByteBuf(vec![1, 2, 3]),
+    /// };
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.bytes_format(BytesFormat::Hex);
+    ///
+    /// root.serialize(ser).unwrap();
+    /// assert_eq!(buffer, r#"<Root hash="cafe"><data>010203</data></Root>"#);
+    /// ```
+    ///
+    /// [`serde_bytes::ByteBuf`]: https://docs.rs/serde_bytes/latest/serde_bytes/struct.ByteBuf.html
+    pub fn bytes_format(&mut self, format: BytesFormat) -> &mut Self {
+        self.ser.bytes_format = Some(format);
+        self
+    }
+
+    /// Write an XML declaration before the root element. Nothing is written
+    /// if nothing is serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    /// #[derive(Serialize)]
+    /// struct Root {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.write_declaration("1.0", Some("UTF-8"), None);
+    ///
+    /// Root { value: 42 }.serialize(ser).unwrap();
+    /// assert_eq!(
+    ///     buffer,
+    ///     r#"<?xml version="1.0" encoding="UTF-8"?><Root><value>42</value></Root>"#
+    /// );
+    /// ```
+    pub fn write_declaration(
+        &mut self,
+        version: &'r str,
+        encoding: Option<&'r str>,
+        standalone: Option<&'r str>,
+    ) -> &mut Self {
+        self.declaration = Some((version, encoding, standalone));
+        self
+    }
+
+    /// Write a DOCTYPE with the specified content before the root element and
+    /// after the [XML declaration], if it is configured. Nothing is written
+    /// if nothing is serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::se::Serializer;
+    /// #[derive(Serialize)]
+    /// struct Root {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut buffer = String::new();
+    /// let mut ser = Serializer::new(&mut buffer);
+    /// ser.write_doctype(r#"Root SYSTEM "root.dtd""#);
+    ///
+    /// Root { value: 42 }.serialize(ser).unwrap();
+    /// assert_eq!(
+    ///     buffer,
+    ///     r#"<!DOCTYPE Root SYSTEM "root.dtd"><Root><value>42</value></Root>"#
+    /// );
+    /// ```
+    ///
+    /// [XML declaration]: Self::write_declaration
+    pub fn write_doctype(&mut self, doctype: &'r str) -> &mut Self {
+        self.doctype = Some(doctype);
+        self
+    }
+
+    /// Writes the XML declaration and the DOCTYPE, if they are configured.
+    /// Each of them is followed by a new line if indentation is enabled
+    fn write_preamble(&mut self) -> Result<(), DeError> {
+        if let Some((version, encoding, standalone)) = self.declaration {
+            let writer = &mut *self.ser.writer;
+            writer.write_str("<?xml version=\"")?;
+            writer.write_str(version)?;
+            if let Some(encoding) = encoding {
+                writer.write_str("\" encoding=\"")?;
+                writer.write_str(encoding)?;
+            }
+            if let Some(standalone) = standalone {
+                writer.write_str("\" standalone=\"")?;
+                writer.write_str(standalone)?;
+            }
+            writer.write_str("\"?>")?;
+            self.ser.indent.write_indent(&mut *self.ser.writer)?;
+        }
+        if let Some(doctype) = self.doctype {
+            let writer = &mut *self.ser.writer;
+            writer.write_str("<!DOCTYPE ")?;
+            writer.write_str(doctype)?;
+            writer.write_char('>')?;
+            self.ser.indent.write_indent(&mut *self.ser.writer)?;
+        }
+        Ok(())
+    }
+
     /// Configure indent for a serializer
     pub fn indent(&mut self, indent_char: char, indent_size: usize) -> &mut Self {
         self.ser.indent = Indent::Owned(Indentation::new(indent_char as u8, indent_size));
@@ -576,8 +1399,9 @@ impl<'w, 'r, W: Write> Serializer<'w, 'r, W> {
 
     /// Creates actual serializer or returns an error if root tag is not defined.
     /// In that case `err` contains the name of type that cannot be serialized.
-    fn ser(self, err: &str) -> Result<ElementSerializer<'w, 'r, W>, DeError> {
+    fn ser(mut self, err: &str) -> Result<ElementSerializer<'w, 'r, W>, DeError> {
         if let Some(key) = self.root_tag {
+            self.write_preamble()?;
             Ok(ElementSerializer { ser: self.ser, key })
         } else {
             Err(DeError::Unsupported(
@@ -589,14 +1413,13 @@ impl<'w, 'r, W: Write> Serializer<'w, 'r, W> {
     /// Creates actual serializer using root tag or a specified `key` if root tag
     /// is not defined. Returns an error if root tag is not defined and a `key`
     /// does not conform [XML rules](XmlName::try_from) for names.
-    fn ser_name(self, key: &'static str) -> Result<ElementSerializer<'w, 'r, W>, DeError> {
-        Ok(ElementSerializer {
-            ser: self.ser,
-            key: match self.root_tag {
-                Some(key) => key,
-                None => XmlName::try_from(key)?,
-            },
-        })
+    fn ser_name(mut self, key: &'static str) -> Result<ElementSerializer<'w, 'r, W>, DeError> {
+        let key = match self.root_tag {
+            Some(key) => key,
+            None => XmlName::try_from(key)?,
+        };
+        self.write_preamble()?;
+        Ok(ElementSerializer { ser: self.ser, key })
     }
 }
 
@@ -640,7 +1463,11 @@ impl<'w, 'r, W: Write> ser::Serializer for Serializer<'w, 'r, W> {
     forward!(serialize_bytes(&[u8]));
 
     fn serialize_none(self) -> Result<Self::Ok, DeError> {
-        Ok(())
+        if self.ser.nil_prefix.is_some() && self.root_tag.is_some() {
+            self.ser("`None`")?.serialize_none()
+        } else {
+            Ok(())
+        }
     }
 
     fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, DeError> {
@@ -720,6 +1547,9 @@ impl<'w, 'r, W: Write> ser::Serializer for Serializer<'w, 'r, W> {
         name: &'static str,
         len: usize,
     ) -> Result<Self::SerializeStruct, DeError> {
+        if name == WITH_RAW {
+            return self.ser("`WithRaw`")?.serialize_struct(name, len);
+        }
         self.ser_name(name)?.serialize_struct(name, len)
     }
 
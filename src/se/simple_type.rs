@@ -3,9 +3,11 @@
 //! [simple types]: https://www.w3schools.com/xml/el_simpletype.asp
 //! [as defined]: https://www.w3.org/TR/xmlschema11-1/#Simple_Type_Definition
 
+use crate::de::BytesFormat;
 use crate::errors::serialize::DeError;
 use crate::escapei::_escape;
-use crate::se::{Indent, QuoteLevel};
+use crate::se::{CDataPolicy, FloatFormat, Indent, QuoteLevel};
+use crate::writer::find_cdata_end;
 use serde::ser::{
     Impossible, Serialize, SerializeSeq, SerializeTuple, SerializeTupleStruct, Serializer,
 };
@@ -173,6 +175,10 @@ pub struct AtomicSerializer<W: Write> {
     pub target: QuoteTarget,
     /// Defines which XML characters need to be escaped
     pub level: QuoteLevel,
+    /// Defines how `f32` and `f64` values are written
+    pub float_format: FloatFormat,
+    /// Defines how binary data is written. If `None`, binary data cannot be serialized
+    pub bytes_format: Option<BytesFormat>,
 }
 
 impl<W: Write> AtomicSerializer<W> {
@@ -196,6 +202,9 @@ impl<W: Write> Serializer for AtomicSerializer<W> {
     type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
 
     write_primitive!();
+    write_primitive!(serialize_bytes);
+    write_primitive!(serialize_f32(f32) => format_f32);
+    write_primitive!(serialize_f64(f64) => format_f64);
 
     fn serialize_str(mut self, value: &str) -> Result<Self::Ok, Self::Error> {
         self.write_str(value)?;
@@ -330,6 +339,13 @@ pub struct SimpleTypeSerializer<'i, W: Write> {
     pub target: QuoteTarget,
     /// Defines which XML characters need to be escaped
     pub level: QuoteLevel,
+    /// Defines when strings are written as CDATA sections. Used only for
+    /// the [`QuoteTarget::Text`] target
+    pub cdata: CDataPolicy,
+    /// Defines how `f32` and `f64` values are written
+    pub float_format: FloatFormat,
+    /// Defines how binary data is written. If `None`, binary data cannot be serialized
+    pub bytes_format: Option<BytesFormat>,
     /// Indent that should be written before the content if content is not an empty string
     pub(crate) indent: Indent<'i>,
 }
@@ -341,6 +357,22 @@ impl<'i, W: Write> SimpleTypeSerializer<'i, W> {
             .writer
             .write_str(&escape_list(value, self.target, self.level))?)
     }
+
+    /// Writes `value` as a CDATA section, splitting it on each `]]>`
+    fn write_cdata(&mut self, value: &str) -> Result<(), DeError> {
+        self.indent.write_indent(&mut self.writer)?;
+        self.writer.write_str("<![CDATA[")?;
+        let mut content = value;
+        // `]]>` cannot appear inside CDATA section, so split section
+        // between `]]` and `>`
+        while let Some(i) = find_cdata_end(content.as_bytes()) {
+            self.writer.write_str(&content[..i + 2])?;
+            self.writer.write_str("]]><![CDATA[")?;
+            content = &content[i + 2..];
+        }
+        self.writer.write_str(content)?;
+        Ok(self.writer.write_str("]]>")?)
+    }
 }
 
 impl<'i, W: Write> Serializer for SimpleTypeSerializer<'i, W> {
@@ -356,12 +388,19 @@ impl<'i, W: Write> Serializer for SimpleTypeSerializer<'i, W> {
     type SerializeStructVariant = Impossible<Self::Ok, Self::Error>;
 
     write_primitive!();
+    write_primitive!(serialize_bytes);
+    write_primitive!(serialize_f32(f32) => format_f32);
+    write_primitive!(serialize_f64(f64) => format_f64);
 
     fn serialize_str(mut self, value: &str) -> Result<Self::Ok, Self::Error> {
         if value.is_empty() {
             self.indent = Indent::None;
         }
-        self.write_str(value)?;
+        if self.target == QuoteTarget::Text && self.cdata.is_cdata(value) {
+            self.write_cdata(value)?;
+        } else {
+            self.write_str(value)?;
+        }
         Ok(self.writer)
     }
 
@@ -395,6 +434,8 @@ impl<'i, W: Write> Serializer for SimpleTypeSerializer<'i, W> {
             writer: self.writer,
             target: self.target,
             level: self.level,
+            float_format: self.float_format,
+            bytes_format: self.bytes_format,
             first: true,
             indent: self.indent,
         })
@@ -464,6 +505,8 @@ pub struct SimpleSeq<'i, W: Write> {
     writer: W,
     target: QuoteTarget,
     level: QuoteLevel,
+    float_format: FloatFormat,
+    bytes_format: Option<BytesFormat>,
     /// If `true`, nothing was written yet
     first: bool,
     /// Indent that should be written before the content if content is not an empty string
@@ -490,6 +533,8 @@ impl<'i, W: Write> SerializeSeq for SimpleSeq<'i, W> {
             writer: &mut self.writer,
             target: self.target,
             level: self.level,
+            float_format: self.float_format,
+            bytes_format: self.bytes_format,
         })?;
         Ok(())
     }
@@ -833,6 +878,8 @@ mod tests {
                         writer: String::new(),
                         target: QuoteTarget::Text,
                         level: QuoteLevel::Full,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
                     };
 
                     let buffer = $data.serialize(ser).unwrap();
@@ -852,6 +899,8 @@ mod tests {
                         writer: &mut buffer,
                         target: QuoteTarget::Text,
                         level: QuoteLevel::Full,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
                     };
 
                     match $data.serialize(ser).unwrap_err() {
@@ -949,6 +998,9 @@ mod tests {
                         writer: String::new(),
                         target: QuoteTarget::Text,
                         level: QuoteLevel::Full,
+                        cdata: CDataPolicy::Never,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
                         indent: Indent::None,
                     };
 
@@ -969,6 +1021,9 @@ mod tests {
                         writer: &mut buffer,
                         target: QuoteTarget::Text,
                         level: QuoteLevel::Full,
+                        cdata: CDataPolicy::Never,
+                        float_format: FloatFormat::Shortest,
+                        bytes_format: None,
                         indent: Indent::None,
                     };
 
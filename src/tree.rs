@@ -0,0 +1,389 @@
+//! An owned tree representation of XML elements.
+//!
+//! Sometimes it is more convenient to work with a small part of a document as
+//! a whole instead of handling events one by one. The [`Reader::read_element`]
+//! and [`Reader::read_element_into`] methods read the next element with all
+//! its content into an [`Element`] which owns all its data and therefore can be
+//! kept after the reader is gone.
+//!
+//! Names, attribute values, text and CDATA content are decoded using the
+//! reader's [`decoder()`], text and attribute values are also unescaped.
+//! Processing instructions inside an element are skipped.
+//!
+//! [`decoder()`]: Reader::decoder
+
+use std::io::{BufRead, Write};
+
+use crate::encoding::Decoder;
+use crate::errors::{Error, Result};
+use crate::escape::unescape;
+use crate::events::{BytesCData, BytesStart, BytesText, Event};
+use crate::reader::Reader;
+use crate::writer::Writer;
+
+/// An XML element with its attributes and content.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Element {
+    /// The qualified name of the element, as it is written in the document
+    pub name: String,
+    /// Names and unescaped values of the element attributes in the document order
+    pub attributes: Vec<(String, String)>,
+    /// Content of the element
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    /// Returns the unescaped value of the attribute with the given qualified
+    /// name, if it is present.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Writes this element with all its content to the `writer`. Elements
+    /// without children are written as empty elements (`<name/>`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::reader::Reader;
+    /// use quick_xml::writer::Writer;
+    ///
+    /// let mut reader = Reader::from_str(r#"<root a="1"><child>Fish &amp; chips</child></root>"#);
+    /// let element = reader.read_element().unwrap();
+    ///
+    /// let mut writer = Writer::new(Vec::new());
+    /// element.to_writer(&mut writer).unwrap();
+    ///
+    /// assert_eq!(
+    ///     std::str::from_utf8(&writer.into_inner()).unwrap(),
+    ///     r#"<root a="1"><child>Fish &amp; chips</child></root>"#
+    /// );
+    /// ```
+    pub fn to_writer<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
+        let mut start = BytesStart::new(self.name.as_str());
+        start.extend_attributes(
+            self.attributes
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_str())),
+        );
+        if self.children.is_empty() {
+            return writer.write_event(Event::Empty(start));
+        }
+        writer.write_event(Event::Start(start.borrow()))?;
+        for child in &self.children {
+            child.to_writer(writer)?;
+        }
+        writer.write_event(Event::End(start.to_end()))
+    }
+
+    fn from_start(start: &BytesStart, decoder: Decoder) -> Result<Self> {
+        let name = decoder.decode(start.name().as_ref())?.into_owned();
+        let mut attributes = Vec::new();
+        for attr in start.attributes() {
+            let attr = attr?;
+            let key = decoder.decode(attr.key.as_ref())?.into_owned();
+            let value = decoder.decode(&attr.value)?;
+            let value = unescape(&value)?.into_owned();
+            attributes.push((key, value));
+        }
+        Ok(Self {
+            name,
+            attributes,
+            children: Vec::new(),
+        })
+    }
+}
+
+/// A piece of content of an [`Element`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node {
+    /// A nested element
+    Element(Element),
+    /// Unescaped text content
+    Text(String),
+    /// Content of a CDATA section
+    CData(String),
+    /// Content of a comment
+    Comment(String),
+}
+
+impl Node {
+    /// Writes this node to the `writer`. Text is escaped, CDATA and comment
+    /// content is written as is.
+    pub fn to_writer<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
+        match self {
+            Node::Element(e) => e.to_writer(writer),
+            Node::Text(text) => writer.write_event(Event::Text(BytesText::new(text))),
+            Node::CData(text) => writer.write_event(Event::CData(BytesCData::new(text.as_str()))),
+            Node::Comment(text) => {
+                writer.write_event(Event::Comment(BytesText::from_escaped(text.as_str())))
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Collects events into a tree of elements. Events before the first start
+/// of element are skipped.
+#[derive(Default)]
+struct TreeBuilder {
+    /// Elements which are not closed yet. The first element is the root
+    stack: Vec<Element>,
+}
+
+impl TreeBuilder {
+    /// Adds an event to the tree and returns the root element when it is complete
+    fn feed(&mut self, event: Event, decoder: Decoder) -> Result<Option<Element>> {
+        let node = match event {
+            Event::Start(e) => {
+                self.stack.push(Element::from_start(&e, decoder)?);
+                return Ok(None);
+            }
+            Event::Empty(e) => Node::Element(Element::from_start(&e, decoder)?),
+            Event::End(e) => match self.stack.pop() {
+                Some(element) => Node::Element(element),
+                None => {
+                    let name = decoder.decode(e.name().as_ref())?.into_owned();
+                    return Err(Error::UnexpectedToken(format!("</{}>", name)));
+                }
+            },
+            Event::Text(e) => Node::Text(e.unescape()?.into_owned()),
+            Event::CData(e) => Node::CData(decoder.decode(&e)?.into_owned()),
+            Event::Comment(e) => Node::Comment(decoder.decode(&e)?.into_owned()),
+            Event::Decl(_) | Event::PI(_) | Event::DocType(_) => return Ok(None),
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(match self.stack.last() {
+                    Some(element) => format!("</{}>", element.name),
+                    None => "Element".to_string(),
+                }))
+            }
+        };
+        match (self.stack.last_mut(), node) {
+            (Some(parent), node) => parent.children.push(node),
+            (None, Node::Element(element)) => return Ok(Some(element)),
+            // Content outside of an element is skipped
+            (None, _) => {}
+        }
+        Ok(None)
+    }
+}
+
+impl<R: BufRead> Reader<R> {
+    /// Reads the next element with all its content into an owned [`Element`].
+    ///
+    /// All events before the next [`Start`] or [`Empty`] event are skipped. The
+    /// reader stops right after the matching [`End`] event (or after the
+    /// [`Empty`] event), so reading could be continued with other methods.
+    ///
+    /// The buffer is cleared after each event and could be reused afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedToken`] if an end tag is met before an element
+    /// starts and [`Error::UnexpectedEof`] if the input ends before the element
+    /// is complete.
+    ///
+    /// [`Start`]: Event::Start
+    /// [`Empty`]: Event::Empty
+    /// [`End`]: Event::End
+    pub fn read_element_into(&mut self, buf: &mut Vec<u8>) -> Result<Element> {
+        let mut builder = TreeBuilder::default();
+        loop {
+            let decoder = self.decoder();
+            let event = self.read_event_into(buf)?;
+            if let Some(element) = builder.feed(event, decoder)? {
+                buf.clear();
+                return Ok(element);
+            }
+            buf.clear();
+        }
+    }
+}
+
+impl Reader<&[u8]> {
+    /// Reads the next element with all its content into an owned [`Element`].
+    ///
+    /// All events before the next [`Start`] or [`Empty`] event are skipped. The
+    /// reader stops right after the matching [`End`] event (or after the
+    /// [`Empty`] event), so reading could be continued with other methods.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnexpectedToken`] if an end tag is met before an element
+    /// starts and [`Error::UnexpectedEof`] if the input ends before the element
+    /// is complete.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::reader::Reader;
+    /// use quick_xml::tree::{Element, Node};
+    ///
+    /// let mut reader = Reader::from_str("<list><item id='1'>one</item><item/></list>");
+    /// let list = reader.read_element().unwrap();
+    ///
+    /// assert_eq!(list.name, "list");
+    /// assert_eq!(list.children.len(), 2);
+    /// match &list.children[0] {
+    ///     Node::Element(item) => {
+    ///         assert_eq!(item.attribute("id"), Some("1"));
+    ///         assert_eq!(item.children, vec![Node::Text("one".into())]);
+    ///     }
+    ///     node => panic!("unexpected node {:?}", node),
+    /// }
+    /// ```
+    ///
+    /// [`Start`]: Event::Start
+    /// [`Empty`]: Event::Empty
+    /// [`End`]: Event::End
+    pub fn read_element(&mut self) -> Result<Element> {
+        let mut builder = TreeBuilder::default();
+        loop {
+            let decoder = self.decoder();
+            let event = self.read_event()?;
+            if let Some(element) = builder.feed(event, decoder)? {
+                return Ok(element);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = r#"<?xml version="1.0"?>
+<!-- prolog -->
+<library name="City &amp; County">
+    <book id="1" lang="en"><title>Fish &amp; chips</title><![CDATA[<raw>]]></book>
+    <!--second-->
+    <book id="2"/>
+</library>"#;
+
+    fn text(s: &str) -> Node {
+        Node::Text(s.to_string())
+    }
+
+    fn expected() -> Element {
+        Element {
+            name: "library".into(),
+            attributes: vec![("name".into(), "City & County".into())],
+            children: vec![
+                Node::Element(Element {
+                    name: "book".into(),
+                    attributes: vec![("id".into(), "1".into()), ("lang".into(), "en".into())],
+                    children: vec![
+                        Node::Element(Element {
+                            name: "title".into(),
+                            attributes: vec![],
+                            children: vec![text("Fish & chips")],
+                        }),
+                        Node::CData("<raw>".into()),
+                    ],
+                }),
+                Node::Comment("second".into()),
+                Node::Element(Element {
+                    name: "book".into(),
+                    attributes: vec![("id".into(), "2".into())],
+                    children: vec![],
+                }),
+            ],
+        }
+    }
+
+    #[test]
+    fn read_element() {
+        let mut reader = Reader::from_str(XML);
+        reader.trim_text(true);
+
+        assert_eq!(reader.read_element().unwrap(), expected());
+        assert_eq!(reader.read_event().unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn read_element_into() {
+        let mut reader = Reader::from_reader(XML.as_bytes());
+        reader.trim_text(true);
+
+        let mut buf = Vec::new();
+        assert_eq!(reader.read_element_into(&mut buf).unwrap(), expected());
+        assert_eq!(reader.read_event_into(&mut buf).unwrap(), Event::Eof);
+    }
+
+    #[test]
+    fn read_nested() {
+        let mut reader = Reader::from_str("<root><a><b/></a><c>text</c></root>");
+
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Event::Start(BytesStart::new("root"))
+        );
+        let a = reader.read_element().unwrap();
+        assert_eq!(a.name, "a");
+        assert_eq!(
+            a.children,
+            vec![Node::Element(Element {
+                name: "b".into(),
+                ..Element::default()
+            })]
+        );
+        let c = reader.read_element().unwrap();
+        assert_eq!(c.children, vec![text("text")]);
+
+        match reader.read_element() {
+            Err(Error::UnexpectedToken(token)) => assert_eq!(token, "</root>"),
+            x => panic!("Expected `Err(UnexpectedToken(_))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn unexpected_eof() {
+        let mut reader = Reader::from_str("<root><child>");
+        match reader.read_element() {
+            Err(Error::UnexpectedEof(token)) => assert_eq!(token, "</child>"),
+            x => panic!("Expected `Err(UnexpectedEof(_))`, but got `{:?}`", x),
+        }
+
+        let mut reader = Reader::from_str("<!-- no elements -->");
+        match reader.read_element() {
+            Err(Error::UnexpectedEof(token)) => assert_eq!(token, "Element"),
+            x => panic!("Expected `Err(UnexpectedEof(_))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn to_writer() {
+        let mut reader = Reader::from_str(XML);
+        reader.trim_text(true);
+        let element = reader.read_element().unwrap();
+
+        let mut writer = Writer::new(Vec::new());
+        element.to_writer(&mut writer).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&writer.into_inner()).unwrap(),
+            r#"<library name="City &amp; County"><book id="1" lang="en"><title>Fish &amp; chips</title><![CDATA[<raw>]]></book><!--second--><book id="2"/></library>"#
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut reader = Reader::from_str(XML);
+        reader.trim_text(true);
+        let element = reader.read_element().unwrap();
+
+        let mut writer = Writer::new_with_indent(Vec::new(), b' ', 2);
+        element.to_writer(&mut writer).unwrap();
+        let xml = String::from_utf8(writer.into_inner()).unwrap();
+
+        let mut reader = Reader::from_str(&xml);
+        reader.trim_text(true);
+        assert_eq!(reader.read_element().unwrap(), element);
+    }
+}
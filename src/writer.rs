@@ -5,10 +5,15 @@ use std::result::Result as StdResult;
 
 use crate::encoding::UTF8_BOM;
 use crate::errors::{Error, Result};
-use crate::events::{attributes::Attribute, BytesCData, BytesStart, BytesText, Event};
+use crate::escapei::{_escape, minimal_escape, unescape};
+use crate::events::attributes::{Attr, Attribute};
+use crate::events::{BytesCData, BytesPI, BytesStart, BytesText, Event};
 
 #[cfg(feature = "async-tokio")]
 mod async_tokio;
+mod shared;
+
+pub use shared::SharedWriter;
 
 /// XML writer. Writes XML [`Event`]s to a [`std::io::Write`] or [`tokio::io::AsyncWrite`] implementor.
 #[cfg(feature = "serialize")]
@@ -60,11 +65,28 @@ use {crate::de::DeError, serde::Serialize};
 /// let expected = r#"<my_elem k1="v1" k2="v2" my-key="some value"><child>text</child></my_elem>"#;
 /// assert_eq!(result, expected.as_bytes());
 /// ```
-#[derive(Clone)]
-pub struct Writer<W> {
+///
+/// The second type parameter is the type of the event filter, see
+/// [`Writer::with_event_filter`].
+pub struct Writer<W, F = fn(&mut Event) -> FilterAction> {
     /// underlying writer
     writer: W,
     indent: Option<Indentation>,
+    /// If `true`, [`Writer::write_serializable`] moves namespace declarations
+    /// to the root element of the serialized value
+    #[cfg(feature = "serialize")]
+    hoist_namespaces: bool,
+    /// Quote character used to enclose attribute values added by [`ElementWriter`]
+    attribute_quote: AttributeQuote,
+    /// Characters escaped in attribute values and text added by [`ElementWriter`]
+    escape_level: EscapeLevel,
+    /// A function that is applied to each event before it will be written
+    filter: Option<F>,
+    /// A predicate that returns `true` for elements, that should be written
+    /// without line breaks and indentation around them and their content
+    inline: Option<fn(&BytesStart) -> bool>,
+    /// The number of inline elements that are currently opened
+    inline_depth: usize,
 }
 
 impl<W> Writer<W> {
@@ -73,6 +95,13 @@ impl<W> Writer<W> {
         Writer {
             writer: inner,
             indent: None,
+            #[cfg(feature = "serialize")]
+            hoist_namespaces: false,
+            attribute_quote: AttributeQuote::Double,
+            escape_level: EscapeLevel::Full,
+            filter: None,
+            inline: None,
+            inline_depth: 0,
         }
     }
 
@@ -81,8 +110,107 @@ impl<W> Writer<W> {
         Writer {
             writer: inner,
             indent: Some(Indentation::new(indent_char, indent_size)),
+            #[cfg(feature = "serialize")]
+            hoist_namespaces: false,
+            attribute_quote: AttributeQuote::Double,
+            escape_level: EscapeLevel::Full,
+            filter: None,
+            inline: None,
+            inline_depth: 0,
+        }
+    }
+
+    /// Creates a `Writer` with all pretty-printing options configured at once
+    /// from a generic writer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::events::{BytesStart, Event};
+    /// # use quick_xml::writer::{FormatOptions, Writer};
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new_with_formatting(&mut buffer, FormatOptions {
+    ///     newline: b"\r\n",
+    ///     indent_char: b'\t',
+    ///     indent_size: 1,
+    /// });
+    ///
+    /// writer.write_event(Event::Start(BytesStart::new("root"))).unwrap();
+    /// writer.write_event(Event::Empty(BytesStart::new("child"))).unwrap();
+    /// writer.write_event(Event::End(BytesStart::new("root").to_end())).unwrap();
+    ///
+    /// assert_eq!(buffer, b"<root>\r\n\t<child/>\r\n</root>");
+    /// ```
+    pub fn new_with_formatting(inner: W, options: FormatOptions) -> Writer<W> {
+        Writer {
+            writer: inner,
+            indent: Some(Indentation::with_options(options)),
+            #[cfg(feature = "serialize")]
+            hoist_namespaces: false,
+            attribute_quote: AttributeQuote::Double,
+            escape_level: EscapeLevel::Full,
+            filter: None,
+            inline: None,
+            inline_depth: 0,
+        }
+    }
+
+    /// Creates a `Writer` which uses the given indentation from a generic writer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use quick_xml::events::{BytesStart, Event};
+    /// # use quick_xml::writer::{Indentation, Writer};
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new_with_indentation(&mut buffer, Indentation::new(b'-', 2));
+    ///
+    /// writer.write_event(Event::Start(BytesStart::new("root"))).unwrap();
+    /// assert_eq!(writer.indentation().unwrap().current(), b"--");
+    /// writer.write_event(Event::Empty(BytesStart::new("child"))).unwrap();
+    /// writer.write_event(Event::End(BytesStart::new("root").to_end())).unwrap();
+    ///
+    /// assert_eq!(buffer, b"<root>\n--<child/>\n</root>");
+    /// ```
+    pub fn new_with_indentation(inner: W, indentation: Indentation) -> Writer<W> {
+        Writer {
+            writer: inner,
+            indent: Some(indentation),
+            #[cfg(feature = "serialize")]
+            hoist_namespaces: false,
+            attribute_quote: AttributeQuote::Double,
+            escape_level: EscapeLevel::Full,
+            filter: None,
+            inline: None,
+            inline_depth: 0,
+        }
+    }
+}
+
+impl<W: Clone> Clone for Writer<W> {
+    fn clone(&self) -> Self {
+        Writer {
+            writer: self.writer.clone(),
+            indent: self.indent.clone(),
+            #[cfg(feature = "serialize")]
+            hoist_namespaces: self.hoist_namespaces,
+            attribute_quote: self.attribute_quote,
+            escape_level: self.escape_level,
+            filter: self.filter,
+            inline: self.inline,
+            inline_depth: self.inline_depth,
         }
     }
+}
+
+impl<W, F> Writer<W, F> {
+    /// Returns the indentation state of this `Writer`, or `None` if it writes
+    /// without indentation.
+    pub fn indentation(&self) -> Option<&Indentation> {
+        self.indent.as_ref()
+    }
 
     /// Consumes this `Writer`, returning the underlying writer.
     pub fn into_inner(self) -> W {
@@ -99,6 +227,243 @@ impl<W> Writer<W> {
         &self.writer
     }
 
+    /// If set, [`Self::write_serializable`] declares all namespaces, used in
+    /// the serialized value, once on its root element instead of on the elements
+    /// where they are declared.
+    ///
+    /// Only prefixed declarations (`xmlns:prefix="..."`) are moved. Default
+    /// namespace declarations (`xmlns="..."`) and prefixes that are bound to
+    /// different namespaces in different elements are left in place. If the
+    /// value is serialized to several sibling elements (for example, when it
+    /// is a sequence), each of them receives declarations used inside it.
+    ///
+    /// Because value is serialized to an intermediate buffer before it will be
+    /// written, this mode requires additional memory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// # use serde::Serialize;
+    /// # use quick_xml::writer::Writer;
+    /// #[derive(Serialize)]
+    /// struct Item {
+    ///     #[serde(rename = "@xmlns:ns")]
+    ///     ns: &'static str,
+    ///     #[serde(rename = "ns:name")]
+    ///     name: &'static str,
+    /// }
+    ///
+    /// #[derive(Serialize)]
+    /// struct Root {
+    ///     item: Vec<Item>,
+    /// }
+    ///
+    /// let item = Item { ns: "urn:ns", name: "name" };
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new(&mut buffer);
+    /// writer.hoist_namespaces(true);
+    /// writer.write_serializable("root", &Root { item: vec![item] }).unwrap();
+    ///
+    /// assert_eq!(
+    ///     std::str::from_utf8(&buffer).unwrap(),
+    ///     r#"<root xmlns:ns="urn:ns"><item><ns:name>name</ns:name></item></root>"#
+    /// );
+    /// ```
+    #[cfg(feature = "serialize")]
+    pub fn hoist_namespaces(&mut self, hoist: bool) -> &mut Self {
+        self.hoist_namespaces = hoist;
+        self
+    }
+
+    /// Set the quote character that encloses attribute values added by
+    /// [`ElementWriter::with_attribute`], [`ElementWriter::with_attributes`] and
+    /// [`ElementWriter::with_namespace`], and attribute values written by
+    /// [`write_serializable()`]. A value that contains the selected quote
+    /// character unescaped is enclosed in the other one.
+    ///
+    /// Events passed to [`write_event()`] are written as is.
+    ///
+    /// (`AttributeQuote::Double` by default)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::writer::{AttributeQuote, Writer};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new(&mut buffer);
+    /// writer.attribute_quote(AttributeQuote::Single);
+    ///
+    /// writer
+    ///     .create_element("tag")
+    ///     .with_attribute(("attr", r#"say "hello""#))
+    ///     .write_empty()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(buffer, br#"<tag attr='say &quot;hello&quot;'/>"#);
+    /// ```
+    ///
+    /// [`write_event()`]: Self::write_event
+    /// [`write_serializable()`]: Self::write_serializable
+    pub fn attribute_quote(&mut self, quote: AttributeQuote) -> &mut Self {
+        self.attribute_quote = quote;
+        self
+    }
+
+    /// Set which characters are escaped in attribute values added by
+    /// [`ElementWriter::with_attribute`], [`ElementWriter::with_attributes`] and
+    /// [`ElementWriter::with_namespace`], in text written by
+    /// [`ElementWriter::write_text_content`], and in the output of
+    /// [`write_serializable()`].
+    ///
+    /// Attributes and text events are escaped when they are created, so in the
+    /// [`EscapeLevel::Minimal`] mode their content is unescaped and escaped again.
+    /// Content that cannot be unescaped, for example because it contains
+    /// references to unknown entities, is written as is. Events passed to
+    /// [`write_event()`] are always written as is.
+    ///
+    /// (`EscapeLevel::Full` by default)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::BytesText;
+    /// use quick_xml::writer::{EscapeLevel, Writer};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new(&mut buffer);
+    /// writer.escape_level(EscapeLevel::Minimal);
+    ///
+    /// writer
+    ///     .create_element("tag")
+    ///     .with_attribute(("attr", r#"a>b'c"d&"#))
+    ///     .write_text_content(BytesText::new("a>b<c"))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(buffer, br#"<tag attr="a>b'c&quot;d&amp;">a>b&lt;c</tag>"#);
+    /// ```
+    ///
+    /// [`write_event()`]: Self::write_event
+    /// [`write_serializable()`]: Self::write_serializable
+    pub fn escape_level(&mut self, level: EscapeLevel) -> &mut Self {
+        self.escape_level = level;
+        self
+    }
+
+    /// Set a function that will be called for each event passed to
+    /// [`write_event()`] before it will be written. The function can modify
+    /// the event in place and decides, what should be written, by returning
+    /// a [`FilterAction`]. Events returned in [`FilterAction::Replace`] are
+    /// written as is, without passing them to the filter again.
+    ///
+    /// The filter is not applied to the raw content written by
+    /// [`write_indent()`], [`write_bom()`] or [`write_serializable()`].
+    /// The filter becomes a part of the `Writer` type, so a `Writer` with
+    /// a filter does not implement [`Clone`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesStart, BytesText, Event};
+    /// use quick_xml::writer::{FilterAction, Writer};
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new(&mut buffer).with_event_filter(|event| match event {
+    ///     Event::Comment(_) => FilterAction::Drop,
+    ///     _ => FilterAction::Keep,
+    /// });
+    ///
+    /// writer.write_event(Event::Start(BytesStart::new("root"))).unwrap();
+    /// writer.write_event(Event::Comment(BytesText::new("secret"))).unwrap();
+    /// writer.write_event(Event::End(BytesStart::new("root").to_end())).unwrap();
+    ///
+    /// assert_eq!(buffer, b"<root></root>");
+    /// ```
+    ///
+    /// [`write_event()`]: Self::write_event
+    /// [`write_indent()`]: Self::write_indent
+    /// [`write_bom()`]: Self::write_bom
+    /// [`write_serializable()`]: Self::write_serializable
+    pub fn with_event_filter<G>(self, filter: G) -> Writer<W, G>
+    where
+        G: FnMut(&mut Event) -> FilterAction,
+    {
+        Writer {
+            writer: self.writer,
+            indent: self.indent,
+            #[cfg(feature = "serialize")]
+            hoist_namespaces: self.hoist_namespaces,
+            attribute_quote: self.attribute_quote,
+            escape_level: self.escape_level,
+            filter: Some(filter),
+            inline: self.inline,
+            inline_depth: self.inline_depth,
+        }
+    }
+
+    /// Set a predicate that returns `true` for inline elements. When indentation
+    /// is enabled, no line breaks and indentation are written before and after
+    /// an inline element and inside it, even around its nested elements. Other
+    /// elements are indented as usual.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesStart, BytesText, Event};
+    /// use quick_xml::writer::Writer;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new_with_indent(&mut buffer, b' ', 2);
+    /// writer.with_inline_elements(|e| e.name().as_ref() == b"i");
+    ///
+    /// writer.write_event(Event::Start(BytesStart::new("div"))).unwrap();
+    /// writer.write_event(Event::Start(BytesStart::new("p"))).unwrap();
+    /// writer.write_event(Event::Text(BytesText::new("an "))).unwrap();
+    /// writer.write_event(Event::Start(BytesStart::new("i"))).unwrap();
+    /// writer.write_event(Event::Text(BytesText::new("inline"))).unwrap();
+    /// writer.write_event(Event::End(BytesStart::new("i").to_end())).unwrap();
+    /// writer.write_event(Event::End(BytesStart::new("p").to_end())).unwrap();
+    /// writer.write_event(Event::End(BytesStart::new("div").to_end())).unwrap();
+    ///
+    /// assert_eq!(
+    ///     std::str::from_utf8(&buffer).unwrap(),
+    ///     "<div>\n  <p>an <i>inline</i></p>\n</div>"
+    /// );
+    /// ```
+    pub fn with_inline_elements(&mut self, is_inline: fn(&BytesStart) -> bool) -> &mut Self {
+        self.inline = Some(is_inline);
+        self
+    }
+
+    /// Tracks opened inline elements and returns `true` if the event should be
+    /// written without line breaks and indentation around it.
+    fn update_inline_depth(&mut self, event: &Event<'_>) -> bool {
+        let is_inline = match self.inline {
+            Some(is_inline) => is_inline,
+            None => return false,
+        };
+        match event {
+            Event::Start(e) if self.inline_depth > 0 || is_inline(e) => {
+                self.inline_depth += 1;
+                true
+            }
+            Event::Start(_) => false,
+            Event::End(_) if self.inline_depth > 0 => {
+                self.inline_depth -= 1;
+                true
+            }
+            Event::End(_) => false,
+            Event::Empty(e) => self.inline_depth > 0 || is_inline(e),
+            _ => self.inline_depth > 0,
+        }
+    }
+
     /// Provides a simple, high-level API for writing XML elements.
     ///
     /// Returns an [`ElementWriter`] that simplifies setting attributes and writing
@@ -143,7 +508,7 @@ impl<W> Writer<W> {
     /// # }
     /// ```
     #[must_use]
-    pub fn create_element<'a, N>(&'a mut self, name: &'a N) -> ElementWriter<W>
+    pub fn create_element<'a, N>(&'a mut self, name: &'a N) -> ElementWriter<W, F>
     where
         N: 'a + AsRef<str> + ?Sized,
     {
@@ -154,7 +519,7 @@ impl<W> Writer<W> {
     }
 }
 
-impl<W: Write> Writer<W> {
+impl<W: Write, F: FnMut(&mut Event) -> FilterAction> Writer<W, F> {
     /// Write a [Byte-Order-Mark] character to the document.
     ///
     /// # Example
@@ -190,9 +555,45 @@ impl<W: Write> Writer<W> {
     }
 
     /// Writes the given event to the underlying writer.
+    ///
+    /// When indentation is enabled, no line breaks are inserted before and
+    /// after [`Text`] and [`CData`] events, so an element whose only content is
+    /// text is always written on one line as `<tag>text</tag>`.
+    ///
+    /// If an event filter is set with [`with_event_filter()`], it is applied
+    /// to the event first.
+    ///
+    /// [`Text`]: Event::Text
+    /// [`CData`]: Event::CData
+    /// [`with_event_filter()`]: Self::with_event_filter
     pub fn write_event<'a, E: AsRef<Event<'a>>>(&mut self, event: E) -> Result<()> {
-        let mut next_should_line_break = true;
-        let result = match *event.as_ref() {
+        if let Some(filter) = &mut self.filter {
+            let mut event = event.as_ref().clone();
+            let action = filter(&mut event);
+            return match action {
+                FilterAction::Keep => self.write_event_unfiltered(&event),
+                FilterAction::Drop => Ok(()),
+                FilterAction::Replace(events) => {
+                    for event in events {
+                        self.write_event_unfiltered(&event)?;
+                    }
+                    Ok(())
+                }
+            };
+        }
+        self.write_event_unfiltered(event.as_ref())
+    }
+
+    /// Writes the given event to the underlying writer without applying the event filter.
+    fn write_event_unfiltered(&mut self, event: &Event<'_>) -> Result<()> {
+        let inline = self.update_inline_depth(event);
+        let mut next_should_line_break = !inline;
+        if inline {
+            if let Some(i) = self.indent.as_mut() {
+                i.should_line_break = false;
+            }
+        }
+        let result = match *event {
             Event::Start(ref e) => {
                 let result = self.write_wrapped(b"<", e, b">");
                 if let Some(i) = self.indent.as_mut() {
@@ -215,7 +616,15 @@ impl<W: Write> Writer<W> {
             Event::CData(ref e) => {
                 next_should_line_break = false;
                 self.write(b"<![CDATA[")?;
-                self.write(e)?;
+                let mut content: &[u8] = e;
+                // `]]>` cannot appear inside CDATA section, so split section
+                // between `]]` and `>`
+                while let Some(i) = find_cdata_end(content) {
+                    self.write(&content[..i + 2])?;
+                    self.write(b"]]><![CDATA[")?;
+                    content = &content[i + 2..];
+                }
+                self.write(content)?;
                 self.write(b"]]>")
             }
             Event::Decl(ref e) => self.write_wrapped(b"<?", e, b"?>"),
@@ -229,6 +638,116 @@ impl<W: Write> Writer<W> {
         result
     }
 
+    /// Writes all events from the iterator to the underlying writer, stopping
+    /// at the first error. The result is the same as if each event was written
+    /// with [`write_event()`], including indentation.
+    ///
+    /// # Example
+    ///
+    /// Copy a document renaming all `foo` elements to `bar`:
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::events::{BytesEnd, Event};
+    /// use quick_xml::reader::Reader;
+    /// use quick_xml::writer::Writer;
+    ///
+    /// let mut reader = Reader::from_str("<root><foo>text</foo><foo/></root>");
+    /// let events = std::iter::from_fn(|| match reader.read_event().unwrap() {
+    ///     Event::Eof => None,
+    ///     Event::Start(mut e) if e.name().as_ref() == b"foo" => {
+    ///         e.set_name(b"bar");
+    ///         Some(Event::Start(e))
+    ///     }
+    ///     Event::Empty(mut e) if e.name().as_ref() == b"foo" => {
+    ///         e.set_name(b"bar");
+    ///         Some(Event::Empty(e))
+    ///     }
+    ///     Event::End(e) if e.name().as_ref() == b"foo" => Some(Event::End(BytesEnd::new("bar"))),
+    ///     e => Some(e),
+    /// });
+    ///
+    /// let mut buffer = Vec::new();
+    /// Writer::new(&mut buffer).write_events(events).unwrap();
+    ///
+    /// assert_eq!(buffer, b"<root><bar>text</bar><bar/></root>");
+    /// ```
+    ///
+    /// [`write_event()`]: Self::write_event
+    pub fn write_events<'a, I>(&mut self, events: I) -> Result<()>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<Event<'a>>,
+    {
+        for event in events {
+            self.write_event(event)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `content` either as an escaped text node or as a CDATA section,
+    /// whichever is shorter. Plain strings are written as text, while strings
+    /// with a lot of markup characters, such as code snippets, are written as
+    /// CDATA. If the lengths are equal, a text node is written.
+    ///
+    /// Like with [`write_event()`], a CDATA section is split into several
+    /// sections if `content` contains `]]>`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use quick_xml::writer::Writer;
+    ///
+    /// let mut buffer = Vec::new();
+    /// let mut writer = Writer::new(&mut buffer);
+    ///
+    /// writer.write_cdata_or_text("a & b").unwrap();
+    /// writer.write_cdata_or_text("<p>a &amp; b</p>").unwrap();
+    ///
+    /// assert_eq!(buffer, b"a &amp; b<![CDATA[<p>a &amp; b</p>]]>".as_ref());
+    /// ```
+    ///
+    /// [`write_event()`]: Self::write_event
+    pub fn write_cdata_or_text(&mut self, content: &str) -> Result<()> {
+        let text = BytesText::new(content);
+        // Each `]]>` starts a new section
+        let sections = 1 + content.matches("]]>").count();
+        let cdata_len = content.len() + sections * "<![CDATA[]]>".len();
+        if text.len() > cdata_len {
+            self.write_event(Event::CData(BytesCData::new(content)))
+        } else {
+            self.write_event(Event::Text(text))
+        }
+    }
+
+    /// Flushes the underlying writer, ensuring that all written data reaches
+    /// its destination.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush().map_err(Into::into)
+    }
+
+    /// Flushes the underlying writer and returns it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pretty_assertions::assert_eq;
+    /// use std::io::BufWriter;
+    /// use quick_xml::events::{BytesStart, Event};
+    /// use quick_xml::writer::Writer;
+    ///
+    /// let mut writer = Writer::new(BufWriter::new(Vec::new()));
+    /// writer.write_event(Event::Empty(BytesStart::new("root"))).unwrap();
+    ///
+    /// let inner = writer.finish().unwrap();
+    /// assert_eq!(inner.get_ref(), b"<root/>");
+    /// ```
+    pub fn finish(mut self) -> Result<W> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+
     /// Writes bytes
     #[inline]
     pub(crate) fn write(&mut self, value: &[u8]) -> Result<()> {
@@ -239,7 +758,7 @@ impl<W: Write> Writer<W> {
     fn write_wrapped(&mut self, before: &[u8], value: &[u8], after: &[u8]) -> Result<()> {
         if let Some(ref i) = self.indent {
             if i.should_line_break {
-                self.writer.write_all(b"\n")?;
+                self.writer.write_all(i.newline())?;
                 self.writer.write_all(i.current())?;
             }
         }
@@ -262,7 +781,7 @@ impl<W: Write> Writer<W> {
     /// [`new_with_indent`]: Self::new_with_indent
     pub fn write_indent(&mut self) -> Result<()> {
         if let Some(ref i) = self.indent {
-            self.writer.write_all(b"\n")?;
+            self.writer.write_all(i.newline())?;
             self.writer.write_all(i.current())?;
         }
         Ok(())
@@ -319,11 +838,35 @@ impl<W: Write> Writer<W> {
         tag_name: &str,
         content: &T,
     ) -> std::result::Result<(), DeError> {
-        use crate::se::{Indent, Serializer};
+        use crate::se::{Indent, QuoteLevel, Serializer};
+
+        let level = match self.escape_level {
+            EscapeLevel::Full => QuoteLevel::Full,
+            EscapeLevel::Minimal => QuoteLevel::Minimal,
+        };
 
         self.write_indent()?;
+        if self.hoist_namespaces {
+            let mut buffer = String::new();
+            let mut serializer = Serializer::with_root(&mut buffer, Some(tag_name))?;
+
+            serializer.set_attribute_quote(self.attribute_quote);
+            serializer.set_quote_level(level);
+            if let Some(indent) = &mut self.indent {
+                serializer.set_indent(Indent::Borrow(indent));
+            }
+
+            content.serialize(serializer)?;
+
+            let xml = hoist_namespace_declarations(&buffer)?;
+            self.writer.write_all(&xml).map_err(Error::from)?;
+            return Ok(());
+        }
+
         let mut fmt = ToFmtWrite(&mut self.writer);
         let mut serializer = Serializer::with_root(&mut fmt, Some(tag_name))?;
+        serializer.set_attribute_quote(self.attribute_quote);
+        serializer.set_quote_level(level);
 
         if let Some(indent) = &mut self.indent {
             serializer.set_indent(Indent::Borrow(indent));
@@ -337,18 +880,18 @@ impl<W: Write> Writer<W> {
 
 /// A struct to write an element. Contains methods to add attributes and inner
 /// elements to the element
-pub struct ElementWriter<'a, W> {
-    writer: &'a mut Writer<W>,
+pub struct ElementWriter<'a, W, F = fn(&mut Event) -> FilterAction> {
+    writer: &'a mut Writer<W, F>,
     start_tag: BytesStart<'a>,
 }
 
-impl<'a, W> ElementWriter<'a, W> {
+impl<'a, W, F> ElementWriter<'a, W, F> {
     /// Adds an attribute to this element.
     pub fn with_attribute<'b, I>(mut self, attr: I) -> Self
     where
         I: Into<Attribute<'b>>,
     {
-        self.start_tag.push_attribute(attr);
+        self.push_attribute(attr.into());
         self
     }
 
@@ -360,14 +903,97 @@ impl<'a, W> ElementWriter<'a, W> {
         I: IntoIterator,
         I::Item: Into<Attribute<'b>>,
     {
-        self.start_tag.extend_attributes(attributes);
+        for attr in attributes {
+            self.push_attribute(attr.into());
+        }
+        self
+    }
+
+    /// Declares a namespace on this element by adding an `xmlns:prefix="uri"`
+    /// attribute, or an `xmlns="uri"` attribute if `prefix` is `None`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use quick_xml::writer::Writer;
+    /// let mut writer = Writer::new(Vec::new());
+    ///
+    /// writer
+    ///     .create_element("root")
+    ///     .with_namespace(None, "d")
+    ///     .with_namespace(Some("x"), "urn:x")
+    ///     .write_inner_content(|writer| {
+    ///         writer.create_element("x:child").write_empty()?;
+    ///         Ok::<(), quick_xml::Error>(())
+    ///     })?;
+    ///
+    /// assert_eq!(
+    ///     std::str::from_utf8(&writer.into_inner()).unwrap(),
+    ///     r#"<root xmlns="d" xmlns:x="urn:x"><x:child/></root>"#
+    /// );
+    /// # Ok::<(), quick_xml::Error>(())
+    /// ```
+    pub fn with_namespace(mut self, prefix: Option<&str>, uri: &str) -> Self {
+        match prefix {
+            Some(prefix) => {
+                let key = format!("xmlns:{}", prefix);
+                self.push_attribute((key.as_str(), uri).into());
+            }
+            None => self.push_attribute(("xmlns", uri).into()),
+        }
         self
     }
+
+    /// Adds an attribute enclosed in quotes selected by [`Writer::attribute_quote`]
+    /// and escaped according to [`Writer::escape_level`]
+    fn push_attribute(&mut self, attr: Attribute) {
+        if self.writer.escape_level == EscapeLevel::Minimal {
+            let value = std::str::from_utf8(&attr.value)
+                .ok()
+                .and_then(|value| unescape(value).ok());
+            if let Some(value) = value {
+                let key = attr.key.as_ref();
+                match self.writer.attribute_quote {
+                    AttributeQuote::Double => {
+                        let value = _escape(&value, |ch| matches!(ch, b'<' | b'&' | b'"'));
+                        self.start_tag
+                            .push_attr(Attr::DoubleQ(key, value.as_bytes()));
+                    }
+                    AttributeQuote::Single => {
+                        let value = _escape(&value, |ch| matches!(ch, b'<' | b'&' | b'\''));
+                        self.start_tag
+                            .push_attr(Attr::SingleQ(key, value.as_bytes()));
+                    }
+                }
+                return;
+            }
+        }
+        match self.writer.attribute_quote {
+            AttributeQuote::Single if !attr.value.contains(&b'\'') => self
+                .start_tag
+                .push_attr(Attr::SingleQ(attr.key.as_ref(), attr.value.as_ref())),
+            _ => self.start_tag.push_attribute(attr),
+        }
+    }
+
+    /// Escapes the text again according to [`Writer::escape_level`]
+    fn escape_text<'b>(&self, text: BytesText<'b>) -> BytesText<'b> {
+        match self.writer.escape_level {
+            EscapeLevel::Minimal => match text.unescape() {
+                Ok(content) => BytesText::from_escaped(minimal_escape(&content).into_owned()),
+                Err(_) => text,
+            },
+            EscapeLevel::Full => text,
+        }
+    }
 }
 
-impl<'a, W: Write> ElementWriter<'a, W> {
+impl<'a, W: Write, F: FnMut(&mut Event) -> FilterAction> ElementWriter<'a, W, F> {
     /// Write some text inside the current element.
-    pub fn write_text_content(self, text: BytesText) -> Result<&'a mut Writer<W>> {
+    ///
+    /// The text is escaped again according to [`Writer::escape_level`].
+    pub fn write_text_content(self, text: BytesText) -> Result<&'a mut Writer<W, F>> {
+        let text = self.escape_text(text);
         self.writer
             .write_event(Event::Start(self.start_tag.borrow()))?;
         self.writer.write_event(Event::Text(text))?;
@@ -377,7 +1003,7 @@ impl<'a, W: Write> ElementWriter<'a, W> {
     }
 
     /// Write a CData event `<![CDATA[...]]>` inside the current element.
-    pub fn write_cdata_content(self, text: BytesCData) -> Result<&'a mut Writer<W>> {
+    pub fn write_cdata_content(self, text: BytesCData) -> Result<&'a mut Writer<W, F>> {
         self.writer
             .write_event(Event::Start(self.start_tag.borrow()))?;
         self.writer.write_event(Event::CData(text))?;
@@ -387,25 +1013,25 @@ impl<'a, W: Write> ElementWriter<'a, W> {
     }
 
     /// Write a processing instruction `<?...?>` inside the current element.
-    pub fn write_pi_content(self, text: BytesText) -> Result<&'a mut Writer<W>> {
+    pub fn write_pi_content(self, pi: BytesPI) -> Result<&'a mut Writer<W, F>> {
         self.writer
             .write_event(Event::Start(self.start_tag.borrow()))?;
-        self.writer.write_event(Event::PI(text))?;
+        self.writer.write_event(Event::PI(pi))?;
         self.writer
             .write_event(Event::End(self.start_tag.to_end()))?;
         Ok(self.writer)
     }
 
     /// Write an empty (self-closing) tag.
-    pub fn write_empty(self) -> Result<&'a mut Writer<W>> {
+    pub fn write_empty(self) -> Result<&'a mut Writer<W, F>> {
         self.writer.write_event(Event::Empty(self.start_tag))?;
         Ok(self.writer)
     }
 
     /// Create a new scope for writing XML inside the current element.
-    pub fn write_inner_content<F, E>(self, closure: F) -> StdResult<&'a mut Writer<W>, E>
+    pub fn write_inner_content<C, E>(self, closure: C) -> StdResult<&'a mut Writer<W, F>, E>
     where
-        F: FnOnce(&mut Writer<W>) -> StdResult<(), E>,
+        C: FnOnce(&mut Writer<W, F>) -> StdResult<(), E>,
         E: From<Error>,
     {
         self.writer
@@ -419,21 +1045,210 @@ impl<'a, W: Write> ElementWriter<'a, W> {
 #[cfg(feature = "serialize")]
 struct ToFmtWrite<T>(pub T);
 
+/// Moves prefixed namespace declarations (`xmlns:prefix="..."`) found anywhere
+/// in each top-level element of `xml` to that element. Declarations of prefixes
+/// that are bound to different namespaces in different elements are left in place
 #[cfg(feature = "serialize")]
-impl<T> std::fmt::Write for ToFmtWrite<T>
-where
-    T: std::io::Write,
-{
-    fn write_str(&mut self, s: &str) -> std::fmt::Result {
-        self.0.write_all(s.as_bytes()).map_err(|_| std::fmt::Error)
+fn hoist_namespace_declarations(xml: &str) -> Result<Vec<u8>> {
+    use crate::name::PrefixDeclaration;
+    use crate::reader::Reader;
+
+    /// Declared prefixes with their namespaces. `None` if prefix is bound to
+    /// different namespaces and cannot be hoisted
+    type Declarations = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+    // Declarations for each top-level element, because declarations of one
+    // element are not in scope of its siblings
+    let mut declarations: Vec<Declarations> = Vec::new();
+
+    let mut reader = Reader::from_str(xml);
+    let mut depth = 0usize;
+    loop {
+        let event = reader.read_event()?;
+        let is_root = depth == 0;
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => {}
+        }
+        match event {
+            Event::Start(e) | Event::Empty(e) => {
+                if is_root {
+                    declarations.push(Vec::new());
+                }
+                let root = declarations.len() - 1;
+                for attr in e.attributes() {
+                    let attr = attr?;
+                    if let Some(PrefixDeclaration::Named(_)) = attr.key.as_namespace_binding() {
+                        match declarations[root]
+                            .iter_mut()
+                            .find(|(k, _)| k == attr.key.as_ref())
+                        {
+                            Some((_, ns)) if ns.as_deref() != Some(&attr.value) => *ns = None,
+                            Some(_) => {}
+                            None => declarations[root]
+                                .push((attr.key.as_ref().to_vec(), Some(attr.value.into_owned()))),
+                        }
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let mut writer = Writer::new(Vec::with_capacity(xml.len()));
+    let mut reader = Reader::from_str(xml);
+    let mut roots = declarations.iter();
+    let mut current: &[_] = &[];
+    let mut depth = 0usize;
+    loop {
+        let event = reader.read_event()?;
+        let is_root = depth == 0;
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth -= 1,
+            _ => {}
+        }
+        if is_root && matches!(event, Event::Start(_) | Event::Empty(_)) {
+            current = roots.next().map_or(&[], Vec::as_slice);
+        }
+        let event = match event {
+            Event::Start(e) => Event::Start(rewrite(&e, is_root, current)?),
+            Event::Empty(e) => Event::Empty(rewrite(&e, is_root, current)?),
+            Event::Eof => break,
+            e => e,
+        };
+        writer.write_event(event)?;
     }
+    Ok(writer.into_inner())
 }
 
-#[derive(Clone)]
-pub(crate) struct Indentation {
-    /// todo: this is an awkward fit as it has no impact on indentation logic, but it is
-    /// only applicable when an indentation exists. Potentially refactor later
+/// Removes hoisted namespace declarations from the element or, if it is the
+/// root element, adds all hoisted declarations to it
+#[cfg(feature = "serialize")]
+fn rewrite<'a>(
+    e: &BytesStart<'a>,
+    is_root: bool,
+    declarations: &[(Vec<u8>, Option<Vec<u8>>)],
+) -> Result<BytesStart<'a>> {
+    let mut result = e.clone();
+    result.clear_attributes();
+    for attr in e.attributes() {
+        let attr = attr?;
+        let is_hoisted = declarations
+            .iter()
+            .any(|(k, ns)| k == attr.key.as_ref() && ns.is_some());
+        if is_root || !is_hoisted {
+            result.push_attribute(attr);
+        }
+    }
+    if is_root {
+        for (key, ns) in declarations {
+            if let Some(ns) = ns {
+                if e.try_get_attribute(key)?.is_none() {
+                    result.push_attribute((key.as_slice(), ns.as_slice()));
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(feature = "serialize")]
+impl<T> std::fmt::Write for ToFmtWrite<T>
+where
+    T: std::io::Write,
+{
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.write_all(s.as_bytes()).map_err(|_| std::fmt::Error)
+    }
+}
+
+/// Defines which quote character encloses attribute values written by
+/// [`Writer`] and the serializer. See [`Writer::attribute_quote`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeQuote {
+    /// Enclose values in double quotes: `attr="value"`
+    Double,
+    /// Enclose values in single quotes: `attr='value'`
+    Single,
+}
+
+/// Defines which characters are escaped in attribute values and text written
+/// by [`Writer`] and the serializer. See [`Writer::escape_level`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscapeLevel {
+    /// Escape all characters that could have special meaning in XML: `<`, `>`,
+    /// `&`, `'` and `"`
+    Full,
+    /// Escape only strictly necessary characters: `<` and `&`, and in attribute
+    /// values also the quote character that encloses the value
+    Minimal,
+}
+
+/// Defines what [`Writer`] should do with an event passed to the filter
+/// set by [`Writer::with_event_filter`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FilterAction {
+    /// Write the event, including all changes that were made by the filter
+    Keep,
+    /// Do not write the event
+    Drop,
+    /// Write the specified events instead of the event
+    Replace(Vec<Event<'static>>),
+}
+
+/// Pretty-printing options of a [`Writer`], used by [`Writer::new_with_formatting`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    /// The line break written before each indentation (e.g. `\n` or `\r\n`)
+    pub newline: &'static [u8],
+    /// The character code to be used for indentations (e.g. ` ` or `\t`)
+    pub indent_char: u8,
+    /// How many instances of the indent character ought to be used for each level of indentation
+    pub indent_size: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            newline: b"\n",
+            indent_char: b' ',
+            indent_size: 4,
+        }
+    }
+}
+
+/// Indentation state of a [`Writer`]: the indentation unit and the current
+/// nesting level.
+///
+/// Can be created explicitly and passed to [`Writer::new_with_indentation`],
+/// and inspected with [`Writer::indentation`] to build custom formatting on
+/// top of the writer.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::writer::Indentation;
+///
+/// let mut indent = Indentation::new(b' ', 4);
+/// assert_eq!(indent.current(), b"");
+///
+/// indent.grow();
+/// indent.grow();
+/// assert_eq!(indent.current(), b"        ");
+///
+/// indent.shrink();
+/// assert_eq!(indent.current(), b"    ");
+/// ```
+#[derive(Clone, Debug)]
+pub struct Indentation {
+    /// todo: this is an awkward fit as it has no impact on indentation logic, but it is
+    /// only applicable when an indentation exists. Potentially refactor later
     should_line_break: bool,
+    /// The line break to be written before indentations (e.g. `\n` or `\r\n`)
+    newline: &'static [u8],
     /// The character code to be used for indentations (e.g. ` ` or `\t`)
     indent_char: u8,
     /// How many instances of the indent character ought to be used for each level of indentation
@@ -445,9 +1260,26 @@ pub(crate) struct Indentation {
 }
 
 impl Indentation {
+    /// Creates an indentation which uses `indent_size` instances of `indent_char`
+    /// for each level and `\n` as a line break.
     pub fn new(indent_char: u8, indent_size: usize) -> Self {
+        Self::with_options(FormatOptions {
+            newline: b"\n",
+            indent_char,
+            indent_size,
+        })
+    }
+
+    /// Creates an indentation from the pretty-printing options.
+    pub fn with_options(options: FormatOptions) -> Self {
+        let FormatOptions {
+            newline,
+            indent_char,
+            indent_size,
+        } = options;
         Self {
             should_line_break: false,
+            newline,
             indent_char,
             indent_size,
             indents: vec![indent_char; 128],
@@ -473,6 +1305,17 @@ impl Indentation {
     pub fn current(&self) -> &[u8] {
         &self.indents[..self.current_indent_len]
     }
+
+    /// Returns the line break written before indentation
+    pub fn newline(&self) -> &'static [u8] {
+        self.newline
+    }
+}
+
+/// Returns position of the first `]]>` sequence in the CDATA content
+#[inline]
+pub(crate) fn find_cdata_end(content: &[u8]) -> Option<usize> {
+    content.windows(3).position(|w| w == b"]]>")
 }
 
 #[cfg(test)]
@@ -605,6 +1448,37 @@ mod indentation {
         );
     }
 
+    /// Elements with only text or CDATA content are written on one line
+    #[test]
+    fn text_only() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 4);
+
+        let events = [
+            Event::Start(BytesStart::new("root")),
+            Event::Start(BytesStart::new("text")),
+            Event::Text(BytesText::new("text")),
+            Event::End(BytesEnd::new("text")),
+            Event::Start(BytesStart::new("cdata")),
+            Event::CData(BytesCData::new("cdata")),
+            Event::End(BytesEnd::new("cdata")),
+            Event::Empty(BytesStart::new("empty")),
+            Event::End(BytesEnd::new("root")),
+        ];
+        for event in events {
+            writer.write_event(event).expect("write event failed");
+        }
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<root>
+    <text>text</text>
+    <cdata><![CDATA[cdata]]></cdata>
+    <empty/>
+</root>"#
+        );
+    }
+
     #[test]
     fn nested() {
         let mut buffer = Vec::new();
@@ -706,6 +1580,193 @@ mod indentation {
         );
     }
 
+    /// Serialized value should be indented according to the current nesting level
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn serializable_nested() {
+        #[derive(Serialize)]
+        struct Item {
+            #[serde(rename = "@id")]
+            id: usize,
+            name: &'static str,
+        }
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 2);
+
+        writer
+            .write_event(Event::Start(BytesStart::new("outer")))
+            .expect("write start tag failed");
+        writer
+            .write_event(Event::Start(BytesStart::new("inner")))
+            .expect("write start tag failed");
+        writer
+            .write_serializable(
+                "item",
+                &Item {
+                    id: 1,
+                    name: "first",
+                },
+            )
+            .expect("write serializable inner contents failed");
+        writer
+            .write_serializable(
+                "item",
+                &Item {
+                    id: 2,
+                    name: "second",
+                },
+            )
+            .expect("write serializable inner contents failed");
+        writer
+            .write_event(Event::End(BytesEnd::new("inner")))
+            .expect("write end tag failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("after")))
+            .expect("write empty tag failed");
+        writer
+            .write_event(Event::End(BytesEnd::new("outer")))
+            .expect("write end tag failed");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<outer>
+  <inner>
+    <item id="1">
+      <name>first</name>
+    </item>
+    <item id="2">
+      <name>second</name>
+    </item>
+  </inner>
+  <after/>
+</outer>"#
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn serializable_hoist_namespaces() {
+        #[derive(Serialize)]
+        struct Leaf {
+            #[serde(rename = "@xmlns:a")]
+            a: &'static str,
+            #[serde(rename = "@xmlns:b")]
+            b: &'static str,
+            #[serde(rename = "@xmlns")]
+            default: &'static str,
+            #[serde(rename = "a:value")]
+            value: &'static str,
+        }
+
+        #[derive(Serialize)]
+        struct Middle {
+            #[serde(rename = "@xmlns:a")]
+            a: &'static str,
+            leaf: Leaf,
+        }
+
+        #[derive(Serialize)]
+        struct Root {
+            middle: Middle,
+            #[serde(rename = "b:other")]
+            other: Other,
+        }
+
+        #[derive(Serialize)]
+        struct Other {
+            #[serde(rename = "@xmlns:b")]
+            b: &'static str,
+        }
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 4);
+        writer.hoist_namespaces(true);
+
+        let content = Root {
+            middle: Middle {
+                a: "urn:a",
+                leaf: Leaf {
+                    a: "urn:a",
+                    b: "urn:b1",
+                    default: "urn:default",
+                    value: "value",
+                },
+            },
+            other: Other { b: "urn:b2" },
+        };
+
+        writer
+            .write_event(Event::Start(BytesStart::new("paired")))
+            .expect("write start tag failed");
+        writer
+            .write_serializable("root", &content)
+            .expect("write serializable inner contents failed");
+        writer
+            .write_event(Event::End(BytesEnd::new("paired")))
+            .expect("write end tag failed");
+
+        // `xmlns:a` is declared on the root element, `xmlns:b` is bound to
+        // different namespaces and the default namespace declaration is not hoisted
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<paired>
+    <root xmlns:a="urn:a">
+        <middle>
+            <leaf xmlns:b="urn:b1" xmlns="urn:default">
+                <a:value>value</a:value>
+            </leaf>
+        </middle>
+        <b:other xmlns:b="urn:b2"/>
+    </root>
+</paired>"#
+        );
+    }
+
+    /// Each top-level element of a sequence gets its own declarations, because
+    /// declarations on one element are not in scope of its siblings
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn serializable_hoist_namespaces_sequence() {
+        #[derive(Serialize)]
+        struct Plain {
+            name: &'static str,
+        }
+
+        #[derive(Serialize)]
+        struct Leaf {
+            #[serde(rename = "@xmlns:ns")]
+            ns: &'static str,
+            #[serde(rename = "ns:name")]
+            name: &'static str,
+        }
+
+        #[derive(Serialize)]
+        struct Item {
+            leaf: Leaf,
+        }
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+        writer.hoist_namespaces(true);
+
+        let item = |name| Item {
+            leaf: Leaf { ns: "urn:ns", name },
+        };
+        writer
+            .write_serializable("item", &(Plain { name: "a" }, item("b"), item("c")))
+            .expect("write serializable inner contents failed");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            concat!(
+                r#"<item><name>a</name></item>"#,
+                r#"<item xmlns:ns="urn:ns"><leaf><ns:name>b</ns:name></leaf></item>"#,
+                r#"<item xmlns:ns="urn:ns"><leaf><ns:name>c</ns:name></leaf></item>"#,
+            )
+        );
+    }
+
     #[test]
     fn element_writer_empty() {
         let mut buffer = Vec::new();
@@ -742,6 +1803,31 @@ mod indentation {
         );
     }
 
+    /// Already escaped text should not be escaped again or reformatted
+    #[test]
+    fn element_writer_escaped_text() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 4);
+
+        writer
+            .create_element("outer")
+            .write_inner_content::<_, Error>(|writer| {
+                writer
+                    .create_element("inner")
+                    .write_text_content(BytesText::from_escaped("a &lt; b\n  <b>c</b>"))?;
+                Ok(())
+            })
+            .expect("failure");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<outer>
+    <inner>a &lt; b
+  <b>c</b></inner>
+</outer>"#
+        );
+    }
+
     #[test]
     fn element_writer_nested() {
         let mut buffer = Vec::new();
@@ -781,4 +1867,501 @@ mod indentation {
 </outer>"#
         );
     }
+
+    #[test]
+    fn custom_indentation() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indentation(&mut buffer, Indentation::new(b' ', 4));
+
+        let outer = BytesStart::new("outer");
+        let inner = BytesStart::new("inner");
+        writer
+            .write_event(Event::Start(outer.clone()))
+            .expect("write start tag failed");
+        writer
+            .write_event(Event::Start(inner.clone()))
+            .expect("write inner start tag failed");
+        assert_eq!(writer.indentation().unwrap().current(), b"        ");
+        writer
+            .write_event(Event::Empty(BytesStart::new("empty")))
+            .expect("write empty tag failed");
+        writer
+            .write_event(Event::End(inner.to_end()))
+            .expect("write inner end tag failed");
+        writer
+            .write_event(Event::End(outer.to_end()))
+            .expect("write end tag failed");
+        assert_eq!(writer.indentation().unwrap().current(), b"");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            "<outer>\n    <inner>\n        <empty/>\n    </inner>\n</outer>"
+        );
+    }
+
+    #[test]
+    fn no_indentation() {
+        let writer = Writer::new(Vec::<u8>::new());
+        assert!(writer.indentation().is_none());
+    }
+
+    #[test]
+    fn formatting_crlf_tab() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_formatting(
+            &mut buffer,
+            FormatOptions {
+                newline: b"\r\n",
+                indent_char: b'\t',
+                indent_size: 1,
+            },
+        );
+
+        let start = BytesStart::new("outer");
+        let inner = BytesStart::new("inner");
+        writer
+            .write_event(Event::Start(start.clone()))
+            .expect("write start tag failed");
+        writer
+            .write_event(Event::Start(inner.clone()))
+            .expect("write inner start tag failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("empty")))
+            .expect("write empty tag failed");
+        writer
+            .write_event(Event::End(inner.to_end()))
+            .expect("write inner end tag failed");
+        writer
+            .write_event(Event::End(start.to_end()))
+            .expect("write end tag failed");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            "<outer>\r\n\t<inner>\r\n\t\t<empty/>\r\n\t</inner>\r\n</outer>"
+        );
+    }
+}
+
+#[cfg(test)]
+mod flush {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn buffered() {
+        let mut writer = Writer::new(std::io::BufWriter::new(Vec::new()));
+        writer
+            .write_event(Event::Empty(BytesStart::new("root")))
+            .expect("write tag failed");
+        assert_eq!(writer.get_ref().get_ref(), b"");
+
+        writer.flush().expect("flush failed");
+        assert_eq!(writer.get_ref().get_ref(), b"<root/>");
+    }
+
+    #[test]
+    fn finish() {
+        let mut writer = Writer::new(std::io::BufWriter::new(Vec::new()));
+        writer
+            .write_event(Event::Empty(BytesStart::new("root")))
+            .expect("write tag failed");
+        assert_eq!(writer.get_ref().get_ref(), b"");
+
+        let inner = writer.finish().expect("finish failed");
+        assert_eq!(inner.get_ref(), b"<root/>");
+    }
+}
+
+#[cfg(test)]
+mod element_writer {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn namespaces() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+
+        writer
+            .create_element("root")
+            .with_namespace(None, "d")
+            .with_namespace(Some("x"), "urn:x")
+            .with_attribute(("attr", "value"))
+            .write_inner_content(|writer| {
+                writer.create_element("x:child").write_empty()?;
+                Ok::<(), Error>(())
+            })
+            .expect("failure");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<root xmlns="d" xmlns:x="urn:x" attr="value"><x:child/></root>"#
+        );
+    }
+
+    /// Attributes written with single quotes are read back unchanged
+    #[test]
+    fn single_quoted_attributes() {
+        use crate::reader::Reader;
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+        writer.attribute_quote(AttributeQuote::Single);
+
+        writer
+            .create_element("root")
+            .with_namespace(Some("x"), "urn:x")
+            .with_attribute(("quotes", r#"'single' and "double""#))
+            .with_attributes([("raw".as_bytes(), "it's".as_bytes())])
+            .write_empty()
+            .expect("failure");
+
+        let xml = std::str::from_utf8(&buffer).unwrap();
+        assert_eq!(
+            xml,
+            r#"<root xmlns:x='urn:x' quotes='&apos;single&apos; and &quot;double&quot;' raw="it's"/>"#
+        );
+
+        let mut reader = Reader::from_str(xml);
+        match reader.read_event().unwrap() {
+            Event::Empty(e) => {
+                let attrs: Vec<_> = e
+                    .attributes()
+                    .map(|a| {
+                        let a = a.unwrap();
+                        let value = a.decode_and_unescape_value(&reader).unwrap().into_owned();
+                        (a.key.as_ref().to_vec(), value)
+                    })
+                    .collect();
+                assert_eq!(
+                    attrs,
+                    vec![
+                        (b"xmlns:x".to_vec(), "urn:x".to_string()),
+                        (b"quotes".to_vec(), r#"'single' and "double""#.to_string()),
+                        (b"raw".to_vec(), "it's".to_string()),
+                    ]
+                );
+            }
+            e => panic!("Expected `Empty` event, but got {:?}", e),
+        }
+    }
+
+    /// Only `<`, `&` and the enclosing quote are escaped in the minimal mode
+    #[test]
+    fn minimal_escaping() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+        writer.escape_level(EscapeLevel::Minimal);
+
+        writer
+            .create_element("root")
+            .with_attribute(("attr", "a>b'c"))
+            .with_attribute(("quotes", r#"<'&">"#))
+            .write_text_content(BytesText::new("a>b'c<&"))
+            .expect("failure");
+
+        writer.attribute_quote(AttributeQuote::Single);
+        writer
+            .create_element("single")
+            .with_attribute(("attr", r#"a>b'c""#))
+            .write_empty()
+            .expect("failure");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            concat!(
+                r#"<root attr="a>b'c" quotes="&lt;'&amp;&quot;>">a>b'c&lt;&amp;</root>"#,
+                r#"<single attr='a>b&apos;c"'/>"#,
+            )
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn minimal_escaping_serializable() {
+        #[derive(Serialize)]
+        struct Root {
+            #[serde(rename = "@attr")]
+            attr: &'static str,
+            #[serde(rename = "$text")]
+            text: &'static str,
+        }
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+        writer.escape_level(EscapeLevel::Minimal);
+
+        writer
+            .write_serializable(
+                "root",
+                &Root {
+                    attr: "a>b'c",
+                    text: "a>b'c",
+                },
+            )
+            .expect("failure");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<root attr="a>b'c">a>b'c</root>"#
+        );
+    }
+}
+
+#[cfg(test)]
+mod bom {
+    use super::*;
+    use crate::events::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn before_declaration() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 4);
+
+        writer.write_bom().expect("write BOM failed");
+        writer
+            .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+            .expect("write declaration failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("root")))
+            .expect("write tag failed");
+
+        assert_eq!(&buffer[..3], [0xEF, 0xBB, 0xBF]);
+        assert_eq!(
+            std::str::from_utf8(&buffer[3..]).unwrap(),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<root/>"
+        );
+    }
+}
+
+#[cfg(test)]
+mod event_filter {
+    use super::*;
+    use crate::events::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn drop_comments() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer).with_event_filter(|event| match event {
+            Event::Comment(_) => FilterAction::Drop,
+            _ => FilterAction::Keep,
+        });
+
+        writer
+            .write_event(Event::Start(BytesStart::new("root")))
+            .expect("write start tag failed");
+        writer
+            .write_event(Event::Comment(BytesText::new("first")))
+            .expect("write comment failed");
+        writer
+            .write_event(Event::Text(BytesText::new("text")))
+            .expect("write text failed");
+        writer
+            .write_event(Event::Comment(BytesText::new("second")))
+            .expect("write comment failed");
+        writer
+            .write_event(Event::End(BytesEnd::new("root")))
+            .expect("write end tag failed");
+
+        assert_eq!(std::str::from_utf8(&buffer).unwrap(), "<root>text</root>");
+    }
+
+    #[test]
+    fn rename_elements() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 2).with_event_filter(|event| {
+            match event {
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"old" => {
+                    e.set_name(b"new");
+                }
+                Event::End(e) if e.name().as_ref() == b"old" => {
+                    *e = BytesEnd::new("new");
+                }
+                _ => {}
+            }
+            FilterAction::Keep
+        });
+
+        writer
+            .create_element("old")
+            .with_attribute(("attr", "value"))
+            .write_inner_content::<_, Error>(|writer| {
+                writer.create_element("old").write_empty()?;
+                writer.create_element("other").write_empty()?;
+                Ok(())
+            })
+            .expect("write element failed");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            "<new attr=\"value\">\n  <new/>\n  <other/>\n</new>"
+        );
+    }
+
+    #[test]
+    fn replace() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer).with_event_filter(|event| match event {
+            Event::Empty(e) if e.name().as_ref() == b"expand" => FilterAction::Replace(vec![
+                Event::Start(BytesStart::new("expanded")),
+                Event::Text(BytesText::new("content")),
+                Event::End(BytesEnd::new("expanded")),
+            ]),
+            _ => FilterAction::Keep,
+        });
+
+        writer
+            .write_event(Event::Start(BytesStart::new("root")))
+            .expect("write start tag failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("expand")))
+            .expect("write empty tag failed");
+        writer
+            .write_event(Event::End(BytesEnd::new("root")))
+            .expect("write end tag failed");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            "<root><expanded>content</expanded></root>"
+        );
+    }
+
+    /// The filter can borrow local state and is not required to be `Send`
+    #[test]
+    fn stateful() {
+        let dropped = std::cell::Cell::new(0);
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer).with_event_filter(|event| match event {
+            Event::Comment(_) => {
+                dropped.set(dropped.get() + 1);
+                FilterAction::Drop
+            }
+            _ => FilterAction::Keep,
+        });
+
+        writer
+            .write_event(Event::Comment(BytesText::new("first")))
+            .expect("write comment failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("root")))
+            .expect("write empty tag failed");
+        writer
+            .write_event(Event::Comment(BytesText::new("second")))
+            .expect("write comment failed");
+
+        assert_eq!(std::str::from_utf8(&buffer).unwrap(), "<root/>");
+        assert_eq!(dropped.get(), 2);
+    }
+}
+
+#[cfg(test)]
+mod inline_elements {
+    use super::*;
+    use crate::events::*;
+    use pretty_assertions::assert_eq;
+
+    fn is_i(e: &BytesStart) -> bool {
+        e.name().as_ref() == b"i"
+    }
+
+    #[test]
+    fn nested() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 2);
+        writer.with_inline_elements(is_i);
+
+        let div = BytesStart::new("div");
+        let i = BytesStart::new("i");
+        let b = BytesStart::new("b");
+        writer
+            .write_event(Event::Start(div.borrow()))
+            .expect("write outer start tag failed");
+        writer
+            .write_event(Event::Start(div.borrow()))
+            .expect("write inner start tag failed");
+        writer
+            .write_event(Event::Start(i.borrow()))
+            .expect("write inline start tag failed");
+        writer
+            .write_event(Event::Text(BytesText::new("x")))
+            .expect("write text failed");
+        writer
+            .write_event(Event::Start(b.borrow()))
+            .expect("write nested start tag failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("br")))
+            .expect("write nested empty tag failed");
+        writer
+            .write_event(Event::End(b.to_end()))
+            .expect("write nested end tag failed");
+        writer
+            .write_event(Event::End(i.to_end()))
+            .expect("write inline end tag failed");
+        writer
+            .write_event(Event::End(div.to_end()))
+            .expect("write inner end tag failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("hr")))
+            .expect("write empty tag failed");
+        writer
+            .write_event(Event::End(div.to_end()))
+            .expect("write outer end tag failed");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            "<div>\n  <div><i>x<b><br/></b></i></div>\n  <hr/>\n</div>"
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 2);
+        writer.with_inline_elements(is_i);
+
+        let div = BytesStart::new("div");
+        writer
+            .write_event(Event::Start(div.borrow()))
+            .expect("write start tag failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("i")))
+            .expect("write inline empty tag failed");
+        writer
+            .write_event(Event::Empty(BytesStart::new("i")))
+            .expect("write inline empty tag failed");
+        writer
+            .write_event(Event::End(div.to_end()))
+            .expect("write end tag failed");
+
+        assert_eq!(std::str::from_utf8(&buffer).unwrap(), "<div><i/><i/></div>");
+    }
+
+    #[test]
+    fn blocks_after_inline() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 2);
+        writer.with_inline_elements(is_i);
+
+        writer
+            .create_element("div")
+            .write_inner_content::<_, Error>(|writer| {
+                writer
+                    .create_element("i")
+                    .write_text_content(BytesText::new("inline"))?;
+                writer.write_indent()?;
+                writer
+                    .create_element("div")
+                    .write_text_content(BytesText::new("block"))?;
+                writer.create_element("div").write_empty()?;
+                Ok(())
+            })
+            .expect("write element failed");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            "<div><i>inline</i>\n  <div>block</div>\n  <div/>\n</div>"
+        );
+    }
 }
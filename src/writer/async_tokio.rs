@@ -4,14 +4,39 @@ use std::result::Result as StdResult;
 use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 use crate::errors::{Error, Result};
-use crate::events::{BytesCData, BytesText, Event};
+use crate::events::{BytesCData, BytesPI, BytesText, Event};
+use crate::writer::{find_cdata_end, FilterAction};
 use crate::{ElementWriter, Writer};
 
-impl<W: AsyncWrite + Unpin> Writer<W> {
+impl<W: AsyncWrite + Unpin, F: FnMut(&mut Event) -> FilterAction> Writer<W, F> {
     /// Writes the given event to the underlying writer. Async version of [`Writer::write_event`].
     pub async fn write_event_async<'a, E: AsRef<Event<'a>>>(&mut self, event: E) -> Result<()> {
-        let mut next_should_line_break = true;
-        let result = match *event.as_ref() {
+        if let Some(filter) = &mut self.filter {
+            let mut event = event.as_ref().clone();
+            let action = filter(&mut event);
+            return match action {
+                FilterAction::Keep => self.write_event_unfiltered_async(&event).await,
+                FilterAction::Drop => Ok(()),
+                FilterAction::Replace(events) => {
+                    for event in events {
+                        self.write_event_unfiltered_async(&event).await?;
+                    }
+                    Ok(())
+                }
+            };
+        }
+        self.write_event_unfiltered_async(event.as_ref()).await
+    }
+
+    async fn write_event_unfiltered_async(&mut self, event: &Event<'_>) -> Result<()> {
+        let inline = self.update_inline_depth(event);
+        let mut next_should_line_break = !inline;
+        if inline {
+            if let Some(i) = self.indent.as_mut() {
+                i.should_line_break = false;
+            }
+        }
+        let result = match *event {
             Event::Start(ref e) => {
                 let result = self.write_wrapped_async(b"<", e, b">").await;
                 if let Some(i) = self.indent.as_mut() {
@@ -34,7 +59,13 @@ impl<W: AsyncWrite + Unpin> Writer<W> {
             Event::CData(ref e) => {
                 next_should_line_break = false;
                 self.write_async(b"<![CDATA[").await?;
-                self.write_async(e).await?;
+                let mut content: &[u8] = e;
+                while let Some(i) = find_cdata_end(content) {
+                    self.write_async(&content[..i + 2]).await?;
+                    self.write_async(b"]]><![CDATA[").await?;
+                    content = &content[i + 2..];
+                }
+                self.write_async(content).await?;
                 self.write_async(b"]]>").await
             }
             Event::Decl(ref e) => self.write_wrapped_async(b"<?", e, b"?>").await,
@@ -54,12 +85,23 @@ impl<W: AsyncWrite + Unpin> Writer<W> {
     /// This method will do nothing if `Writer` was not constructed with [`Writer::new_with_indent`].
     pub async fn write_indent_async(&mut self) -> Result<()> {
         if let Some(ref i) = self.indent {
-            self.writer.write_all(b"\n").await?;
+            self.writer.write_all(i.newline()).await?;
             self.writer.write_all(i.current()).await?;
         }
         Ok(())
     }
 
+    /// Flushes the underlying writer. Async version of [`Writer::flush`].
+    pub async fn flush_async(&mut self) -> Result<()> {
+        self.writer.flush().await.map_err(Into::into)
+    }
+
+    /// Flushes the underlying writer and returns it. Async version of [`Writer::finish`].
+    pub async fn finish_async(mut self) -> Result<W> {
+        self.flush_async().await?;
+        Ok(self.writer)
+    }
+
     #[inline]
     async fn write_async(&mut self, value: &[u8]) -> Result<()> {
         self.writer.write_all(value).await.map_err(Into::into)
@@ -74,7 +116,7 @@ impl<W: AsyncWrite + Unpin> Writer<W> {
     ) -> Result<()> {
         if let Some(ref i) = self.indent {
             if i.should_line_break {
-                self.writer.write_all(b"\n").await?;
+                self.writer.write_all(i.newline()).await?;
                 self.writer.write_all(i.current()).await?;
             }
         }
@@ -85,9 +127,11 @@ impl<W: AsyncWrite + Unpin> Writer<W> {
     }
 }
 
-impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
+impl<'a, W: AsyncWrite + Unpin, F: FnMut(&mut Event) -> FilterAction> ElementWriter<'a, W, F> {
     /// Write some text inside the current element.
     ///
+    /// The text is escaped again according to [`Writer::escape_level`].
+    ///
     /// # Example
     ///
     /// ```
@@ -114,7 +158,11 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     ///     r#"<paired attr1="value1" attr2="value2">text</paired>"#
     /// );
     /// # }
-    pub async fn write_text_content_async(self, text: BytesText<'_>) -> Result<&'a mut Writer<W>> {
+    pub async fn write_text_content_async(
+        self,
+        text: BytesText<'_>,
+    ) -> Result<&'a mut Writer<W, F>> {
+        let text = self.escape_text(text);
         self.writer
             .write_event_async(Event::Start(self.start_tag.borrow()))
             .await?;
@@ -156,7 +204,7 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     pub async fn write_cdata_content_async(
         self,
         text: BytesCData<'_>,
-    ) -> Result<&'a mut Writer<W>> {
+    ) -> Result<&'a mut Writer<W, F>> {
         self.writer
             .write_event_async(Event::Start(self.start_tag.borrow()))
             .await?;
@@ -173,7 +221,7 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     ///
     /// ```
     /// # use quick_xml::writer::Writer;
-    /// # use quick_xml::events::BytesText;
+    /// # use quick_xml::events::BytesPI;
     /// # use tokio::io::AsyncWriteExt;
     /// # #[tokio::main(flavor = "current_thread")] async fn main() {
     /// let mut buffer = Vec::new();
@@ -184,9 +232,7 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     ///     .create_element("paired")
     ///     .with_attribute(("attr1", "value1"))
     ///     .with_attribute(("attr2", "value2"))
-    ///     // NOTE: We cannot use BytesText::new here, because it escapes strings,
-    ///     // but processing instruction content should not be escaped
-    ///     .write_pi_content_async(BytesText::from_escaped(r#"xml-stylesheet href="style.css""#))
+    ///     .write_pi_content_async(BytesPI::new(r#"xml-stylesheet href="style.css""#))
     ///     .await
     ///     .expect("cannot write content");
     ///
@@ -199,11 +245,11 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     /// </paired>"#
     /// );
     /// # }
-    pub async fn write_pi_content_async(self, text: BytesText<'_>) -> Result<&'a mut Writer<W>> {
+    pub async fn write_pi_content_async(self, pi: BytesPI<'_>) -> Result<&'a mut Writer<W, F>> {
         self.writer
             .write_event_async(Event::Start(self.start_tag.borrow()))
             .await?;
-        self.writer.write_event_async(Event::PI(text)).await?;
+        self.writer.write_event_async(Event::PI(pi)).await?;
         self.writer
             .write_event_async(Event::End(self.start_tag.to_end()))
             .await?;
@@ -238,7 +284,7 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     ///     r#"<empty attr1="value1" attr2="value2"/>"#
     /// );
     /// # }
-    pub async fn write_empty_async(self) -> Result<&'a mut Writer<W>> {
+    pub async fn write_empty_async(self) -> Result<&'a mut Writer<W, F>> {
         self.writer
             .write_event_async(Event::Empty(self.start_tag))
             .await?;
@@ -298,13 +344,13 @@ impl<'a, W: AsyncWrite + Unpin> ElementWriter<'a, W> {
     /// </outer>"#
     /// );
     /// # }
-    pub async fn write_inner_content_async<F, Fut, E>(
+    pub async fn write_inner_content_async<C, Fut, E>(
         mut self,
-        closure: F,
-    ) -> StdResult<&'a mut Writer<W>, E>
+        closure: C,
+    ) -> StdResult<&'a mut Writer<W, F>, E>
     where
-        F: FnOnce(&'a mut Writer<W>) -> Fut,
-        Fut: Future<Output = StdResult<&'a mut Writer<W>, E>>,
+        C: FnOnce(&'a mut Writer<W, F>) -> Fut,
+        Fut: Future<Output = StdResult<&'a mut Writer<W, F>, E>>,
         E: From<Error>,
     {
         self.writer
@@ -361,15 +407,21 @@ mod tests {
         r#"<![CDATA[this is a cdata]]>"#
     );
 
+    test!(
+        cdata_with_end,
+        Event::CData(BytesCData::new("a]]>b]]>")),
+        r#"<![CDATA[a]]]]><![CDATA[>b]]]]><![CDATA[>]]>"#
+    );
+
     test!(
         pi,
-        Event::PI(BytesText::new("this is a processing instruction")),
+        Event::PI(BytesPI::new("this is a processing instruction")),
         r#"<?this is a processing instruction?>"#
     );
 
     test!(
         doctype,
-        Event::DocType(BytesText::new("this is a doctype")),
+        Event::DocType(BytesDocType::new("this is a doctype")),
         r#"<!DOCTYPE this is a doctype>"#
     );
 
@@ -390,6 +442,26 @@ mod tests {
             r#"<tag>inner text</tag>"#
         );
     }
+
+    /// Accessors to the underlying writer do not require any bounds
+    #[tokio::test]
+    async fn accessors() {
+        let mut writer = Writer::new(Vec::new());
+
+        writer
+            .write_event_async(Event::Start(BytesStart::new("tag")))
+            .await
+            .expect("write tag failed");
+        assert_eq!(writer.get_ref().as_slice(), b"<tag>");
+
+        writer.get_mut().extend_from_slice(b"text");
+        writer
+            .write_event_async(Event::End(BytesEnd::new("tag")))
+            .await
+            .expect("write tag failed");
+
+        assert_eq!(writer.into_inner(), b"<tag>text</tag>");
+    }
 }
 
 #[cfg(test)]
@@ -535,6 +607,40 @@ mod indentation_async {
         );
     }
 
+    /// Elements with only text or CDATA content are written on one line
+    #[tokio::test]
+    async fn text_only() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 4);
+
+        let events = [
+            Event::Start(BytesStart::new("root")),
+            Event::Start(BytesStart::new("text")),
+            Event::Text(BytesText::new("text")),
+            Event::End(BytesEnd::new("text")),
+            Event::Start(BytesStart::new("cdata")),
+            Event::CData(BytesCData::new("cdata")),
+            Event::End(BytesEnd::new("cdata")),
+            Event::Empty(BytesStart::new("empty")),
+            Event::End(BytesEnd::new("root")),
+        ];
+        for event in events {
+            writer
+                .write_event_async(event)
+                .await
+                .expect("write event failed");
+        }
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<root>
+    <text>text</text>
+    <cdata><![CDATA[cdata]]></cdata>
+    <empty/>
+</root>"#
+        );
+    }
+
     #[tokio::test]
     async fn nested() {
         let mut buffer = Vec::new();
@@ -575,4 +681,178 @@ mod indentation_async {
 </paired>"#
         );
     }
+
+    #[tokio::test]
+    async fn element_writer_text() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new(&mut buffer);
+
+        writer
+            .create_element("e")
+            .with_attribute(("a", "1"))
+            .write_text_content_async(BytesText::new("text"))
+            .await
+            .expect("failure");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<e a="1">text</e>"#
+        );
+    }
+
+    /// Indentation should be the same as when written by the sync `ElementWriter`
+    #[tokio::test]
+    async fn element_writer_nested() {
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indent(&mut buffer, b' ', 4);
+
+        writer
+            .create_element("outer")
+            .with_attribute(("attr1", "value1"))
+            .with_attribute(("attr2", "value2"))
+            .write_inner_content_async::<_, _, Error>(|writer| async move {
+                let fruits = ["apple", "orange", "banana"];
+                for (quant, item) in fruits.iter().enumerate() {
+                    writer
+                        .create_element("fruit")
+                        .with_attribute(("quantity", quant.to_string().as_str()))
+                        .write_text_content_async(BytesText::new(item))
+                        .await?;
+                }
+                writer
+                    .create_element("inner")
+                    .write_inner_content_async(|writer| async move {
+                        writer.create_element("empty").write_empty_async().await
+                    })
+                    .await
+            })
+            .await
+            .expect("failure");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            r#"<outer attr1="value1" attr2="value2">
+    <fruit quantity="0">apple</fruit>
+    <fruit quantity="1">orange</fruit>
+    <fruit quantity="2">banana</fruit>
+    <inner>
+        <empty/>
+    </inner>
+</outer>"#
+        );
+    }
+
+    #[tokio::test]
+    async fn custom_indentation() {
+        use crate::writer::Indentation;
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_indentation(&mut buffer, Indentation::new(b' ', 4));
+
+        let outer = BytesStart::new("outer");
+        let inner = BytesStart::new("inner");
+        writer
+            .write_event_async(Event::Start(outer.clone()))
+            .await
+            .expect("write start tag failed");
+        writer
+            .write_event_async(Event::Start(inner.clone()))
+            .await
+            .expect("write inner start tag failed");
+        assert_eq!(writer.indentation().unwrap().current(), b"        ");
+        writer
+            .write_event_async(Event::Empty(BytesStart::new("empty")))
+            .await
+            .expect("write empty tag failed");
+        writer
+            .write_event_async(Event::End(inner.to_end()))
+            .await
+            .expect("write inner end tag failed");
+        writer
+            .write_event_async(Event::End(outer.to_end()))
+            .await
+            .expect("write end tag failed");
+        assert_eq!(writer.indentation().unwrap().current(), b"");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            "<outer>\n    <inner>\n        <empty/>\n    </inner>\n</outer>"
+        );
+    }
+
+    #[tokio::test]
+    async fn formatting_crlf_tab() {
+        use crate::writer::FormatOptions;
+
+        let mut buffer = Vec::new();
+        let mut writer = Writer::new_with_formatting(
+            &mut buffer,
+            FormatOptions {
+                newline: b"\r\n",
+                indent_char: b'\t',
+                indent_size: 1,
+            },
+        );
+
+        let start = BytesStart::new("outer");
+        let inner = BytesStart::new("inner");
+        writer
+            .write_event_async(Event::Start(start.clone()))
+            .await
+            .expect("write start tag failed");
+        writer
+            .write_event_async(Event::Start(inner.clone()))
+            .await
+            .expect("write inner start tag failed");
+        writer
+            .write_event_async(Event::Empty(BytesStart::new("empty")))
+            .await
+            .expect("write empty tag failed");
+        writer
+            .write_event_async(Event::End(inner.to_end()))
+            .await
+            .expect("write inner end tag failed");
+        writer
+            .write_event_async(Event::End(start.to_end()))
+            .await
+            .expect("write end tag failed");
+
+        assert_eq!(
+            std::str::from_utf8(&buffer).unwrap(),
+            "<outer>\r\n\t<inner>\r\n\t\t<empty/>\r\n\t</inner>\r\n</outer>"
+        );
+    }
+}
+
+#[cfg(test)]
+mod flush {
+    use super::*;
+    use crate::events::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn buffered() {
+        let mut writer = Writer::new(tokio::io::BufWriter::new(Vec::new()));
+        writer
+            .write_event_async(Event::Empty(BytesStart::new("root")))
+            .await
+            .expect("write tag failed");
+        assert_eq!(writer.get_ref().get_ref(), b"");
+
+        writer.flush_async().await.expect("flush failed");
+        assert_eq!(writer.get_ref().get_ref(), b"<root/>");
+    }
+
+    #[tokio::test]
+    async fn finish() {
+        let mut writer = Writer::new(tokio::io::BufWriter::new(Vec::new()));
+        writer
+            .write_event_async(Event::Empty(BytesStart::new("root")))
+            .await
+            .expect("write tag failed");
+        assert_eq!(writer.get_ref().get_ref(), b"");
+
+        let inner = writer.finish_async().await.expect("finish failed");
+        assert_eq!(inner.get_ref(), b"<root/>");
+    }
 }
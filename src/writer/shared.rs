@@ -0,0 +1,200 @@
+//! Contains an adapter that allows several [`Writer`]s to write into the same sink.
+//!
+//! [`Writer`]: crate::writer::Writer
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[cfg(feature = "async-tokio")]
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(feature = "async-tokio")]
+use tokio::io::AsyncWrite;
+
+/// An adapter that implements [`std::io::Write`] (and [`tokio::io::AsyncWrite`]
+/// if the `async-tokio` feature is enabled) for a sink behind an `Arc<Mutex<W>>`.
+///
+/// The lock is held only for the duration of a single write or flush call.
+/// [`Writer`] issues several such calls for one event (for example, indentation,
+/// markup and content are written separately), so output of writers from
+/// different threads can interleave even in the middle of an event. If you need
+/// to keep events intact, write each of them into a local buffer first and
+/// pass that buffer to [`Write::write_all`] of a `SharedWriter`, which writes it
+/// under one lock.
+///
+/// Cloning a `SharedWriter` creates another handle to the same sink.
+///
+/// # Example
+///
+/// ```
+/// # use pretty_assertions::assert_eq;
+/// use quick_xml::events::{BytesEnd, BytesStart, Event};
+/// use quick_xml::writer::{SharedWriter, Writer};
+/// use std::sync::{Arc, Mutex};
+///
+/// let sink = Arc::new(Mutex::new(Vec::new()));
+///
+/// let mut writer = Writer::new(SharedWriter::new(sink.clone()));
+/// writer.write_event(Event::Start(BytesStart::new("tag"))).unwrap();
+/// writer.write_event(Event::End(BytesEnd::new("tag"))).unwrap();
+///
+/// assert_eq!(sink.lock().unwrap().as_slice(), b"<tag></tag>");
+/// ```
+///
+/// [`Writer`]: crate::writer::Writer
+#[derive(Debug)]
+pub struct SharedWriter<W> {
+    inner: Arc<Mutex<W>>,
+}
+
+impl<W> SharedWriter<W> {
+    /// Creates an adapter that writes to the sink behind the `inner` lock
+    pub fn new(inner: Arc<Mutex<W>>) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the shared sink
+    pub fn get_ref(&self) -> &Arc<Mutex<W>> {
+        &self.inner
+    }
+
+    /// Consumes this adapter, returning the shared sink
+    pub fn into_inner(self) -> Arc<Mutex<W>> {
+        self.inner
+    }
+
+    /// Acquires the lock, returning an error if the lock was poisoned by
+    /// a panic in another thread
+    fn lock(&self) -> io::Result<MutexGuard<'_, W>> {
+        self.inner
+            .lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "shared writer lock is poisoned"))
+    }
+}
+
+impl<W> Clone for SharedWriter<W> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<W> From<Arc<Mutex<W>>> for SharedWriter<W> {
+    #[inline]
+    fn from(inner: Arc<Mutex<W>>) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<W: Write> Write for SharedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock()?.write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        // Keep the whole buffer together
+        self.lock()?.write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock()?.flush()
+    }
+}
+
+#[cfg(feature = "async-tokio")]
+impl<W: AsyncWrite + Unpin> AsyncWrite for SharedWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let mut inner = self.lock()?;
+        Pin::new(&mut *inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut inner = self.lock()?;
+        Pin::new(&mut *inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut inner = self.lock()?;
+        Pin::new(&mut *inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{BytesEnd, BytesStart, BytesText, Event};
+    use crate::writer::Writer;
+    use pretty_assertions::assert_eq;
+    use std::thread;
+
+    #[test]
+    fn main_thread() {
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        let mut writer = Writer::new(SharedWriter::new(sink.clone()));
+
+        writer
+            .create_element("root")
+            .with_attribute(("attr", "value"))
+            .write_text_content(BytesText::new("text"))
+            .unwrap();
+
+        assert_eq!(
+            sink.lock().unwrap().as_slice(),
+            br#"<root attr="value">text</root>"#
+        );
+    }
+
+    #[test]
+    fn several_threads() {
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let mut shared = SharedWriter::from(sink.clone());
+                thread::spawn(move || {
+                    // Build the event in a local buffer so it is written under one lock
+                    let mut writer = Writer::new(Vec::new());
+                    writer
+                        .write_event(Event::Empty(BytesStart::new("item")))
+                        .unwrap();
+                    shared.write_all(&writer.into_inner()).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let mut writer = Writer::new(SharedWriter::new(sink.clone()));
+        writer
+            .write_event(Event::End(BytesEnd::new("list")))
+            .unwrap();
+
+        assert_eq!(
+            sink.lock().unwrap().as_slice(),
+            b"<item/><item/><item/><item/></list>"
+        );
+    }
+
+    #[test]
+    fn poisoned() {
+        let sink = Arc::new(Mutex::new(Vec::<u8>::new()));
+        let poison = sink.clone();
+        let _ = thread::spawn(move || {
+            let _guard = poison.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+
+        let mut writer = Writer::new(SharedWriter::new(sink));
+        assert!(writer
+            .write_event(Event::Empty(BytesStart::new("item")))
+            .is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use pretty_assertions::assert_eq;
 use quick_xml::events::Event::*;
+use quick_xml::events::{BytesEnd, BytesStart, BytesText};
 use quick_xml::reader::Reader;
+use quick_xml::writer::{SharedWriter, Writer};
+use std::sync::{Arc, Mutex};
 
 #[tokio::test]
 async fn test_sample() {
@@ -26,3 +29,77 @@ async fn test_sample() {
     }
     assert_eq!((count, reads), (1247, 5245));
 }
+
+#[tokio::test]
+async fn test_coalesce_text() {
+    let mut reader = Reader::from_reader("<a>a<![CDATA[b]]>c</a>".as_bytes());
+    reader.coalesce_text(true);
+    let mut buf = Vec::new();
+
+    assert_eq!(
+        reader.read_event_into_async(&mut buf).await.unwrap(),
+        Start(BytesStart::new("a"))
+    );
+    assert_eq!(
+        reader.read_event_into_async(&mut buf).await.unwrap(),
+        Text(BytesText::new("abc"))
+    );
+    assert_eq!(
+        reader.read_event_into_async(&mut buf).await.unwrap(),
+        End(BytesEnd::new("a"))
+    );
+    assert_eq!(reader.read_event_into_async(&mut buf).await.unwrap(), Eof);
+}
+
+#[tokio::test]
+async fn test_event_spans() {
+    let xml = "<a>text<b/></a>";
+    let mut reader = Reader::from_reader(xml.as_bytes());
+    reader.expand_empty_elements(true);
+    let mut buf = Vec::new();
+
+    let mut spans = Vec::new();
+    loop {
+        buf.clear();
+        let (event, span) = reader
+            .read_event_into_with_span_async(&mut buf)
+            .await
+            .unwrap();
+        let event = event.into_owned();
+        let eof = event == Eof;
+        spans.push((event, span));
+        if eof {
+            break;
+        }
+    }
+
+    assert_eq!(
+        spans,
+        vec![
+            (Start(BytesStart::new("a")), 0..3),
+            (Text(BytesText::new("text")), 3..7),
+            (Start(BytesStart::new("b")), 7..11),
+            // Generated event does not correspond to any input
+            (End(BytesEnd::new("b")), 11..11),
+            (End(BytesEnd::new("a")), 11..15),
+            (Eof, 15..15),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_shared_writer() {
+    let sink = Arc::new(Mutex::new(Vec::new()));
+    let mut writer = Writer::new(SharedWriter::new(sink.clone()));
+
+    writer
+        .write_event_async(Start(BytesStart::new("tag")))
+        .await
+        .unwrap();
+    writer
+        .write_event_async(End(BytesEnd::new("tag")))
+        .await
+        .unwrap();
+
+    assert_eq!(sink.lock().unwrap().as_slice(), b"<tag></tag>");
+}
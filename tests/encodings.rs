@@ -43,6 +43,63 @@ fn test_koi8_r_encoding() {
     }
 }
 
+/// Checks that `Event::decode` uses the encoding declared in the document
+#[test]
+fn event_decode() {
+    use encoding_rs::WINDOWS_1251;
+    use pretty_assertions::assert_eq;
+
+    let xml = "<?xml version='1.0' encoding='windows-1251'?>\
+        <root>текст &amp; <![CDATA[данные &amp;]]><!--комментарий &amp;--></root>";
+    let (src, _, _) = WINDOWS_1251.encode(xml);
+    let mut r = Reader::from_reader(src.as_ref());
+    let mut buf = Vec::new();
+    let mut next = || {
+        r.read_event_into(&mut buf)
+            .unwrap()
+            .decode()
+            .map(|text| text.unwrap().into_owned())
+    };
+
+    assert_eq!(next(), None); // Decl
+    assert_eq!(next(), None); // Start
+    assert_eq!(next(), Some("текст & ".to_string()));
+    assert_eq!(next(), Some("данные &amp;".to_string()));
+    assert_eq!(next(), Some("комментарий &amp;".to_string()));
+    assert_eq!(next(), None); // End
+}
+
+/// With `assume_utf8` the encoding is not detected from the input
+mod assume_utf8 {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::Error;
+
+    #[test]
+    fn utf8_declared() {
+        let mut r = Reader::from_reader(r#"<?xml encoding="UTF-8"?><a>Привет</a>"#.as_bytes());
+        r.assume_utf8(true);
+        assert!(matches!(r.read_event().unwrap(), Event::Decl(_)));
+        assert_eq!(r.decoder().encoding(), encoding_rs::UTF_8);
+        assert!(matches!(r.read_event().unwrap(), Event::Start(_)));
+        match r.read_event().unwrap() {
+            Event::Text(e) => assert_eq!(e.unescape().unwrap(), "Привет"),
+            e => panic!("Expected `Text`, but got `{:?}`", e),
+        }
+    }
+
+    /// Declaration of other encoding is reported as an error
+    #[test]
+    fn other_declared() {
+        let mut r = Reader::from_reader(r#"<?xml encoding="windows-1251"?><a/>"#.as_bytes());
+        r.assume_utf8(true);
+        match r.read_event() {
+            Err(Error::UnexpectedEncoding(e)) => assert_eq!(e, "windows-1251"),
+            e => panic!("Expected `UnexpectedEncoding` error, got {:?}", e),
+        }
+    }
+}
+
 /// Test data generated by helper project `test-gen`, which requires checkout of
 /// an `encoding` submodule
 mod detect {
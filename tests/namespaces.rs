@@ -354,3 +354,360 @@ fn reserved_name() {
         ),
     }
 }
+
+/// QNames can be stored in attribute values (for example, in `xsi:type`), they
+/// should be resolved against the namespace scope of the element
+#[test]
+fn resolve_qname_from_attribute_value() {
+    let mut r = NsReader::from_str(
+        r#"<root xmlns="default" xmlns:x="www1">
+            <item xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:type="x:Foo"/>
+            <item xsi:type="x:Foo"/>
+        </root>"#,
+    );
+    r.trim_text(true);
+
+    // <root>
+    match r.read_event() {
+        Ok(Start(_)) => {}
+        e => panic!("Expected start element, got {:?}", e),
+    }
+
+    // <item/>
+    match r.read_event() {
+        Ok(Empty(e)) => {
+            let value = e
+                .try_get_attribute("xsi:type")
+                .unwrap()
+                .expect("`xsi:type` attribute should be present")
+                .value;
+            let qname = QName(&value);
+
+            assert_eq!(
+                r.resolve(qname, false),
+                (Bound(Namespace(b"www1")), QName(b"Foo").into())
+            );
+            assert_eq!(
+                r.resolve(qname, true),
+                (Bound(Namespace(b"www1")), QName(b"Foo").into())
+            );
+            assert_eq!(
+                r.resolve(QName(b"Foo"), false),
+                (Bound(Namespace(b"default")), QName(b"Foo").into())
+            );
+            assert_eq!(
+                r.resolve(QName(b"Foo"), true),
+                (Unbound, QName(b"Foo").into())
+            );
+            assert_eq!(
+                r.resolve(QName(b"y:Foo"), false),
+                (Unknown(b"y".to_vec()), QName(b"Foo").into())
+            );
+        }
+        e => panic!("Expected empty element, got {:?}", e),
+    }
+
+    // The `xsi` prefix is out of scope for the second <item/>
+    match r.read_event() {
+        Ok(Empty(e)) => {
+            let value = e.try_get_attribute("xsi:type").unwrap().unwrap().value;
+            assert_eq!(
+                r.resolve(QName(&value), false),
+                (Bound(Namespace(b"www1")), QName(b"Foo").into())
+            );
+            assert_eq!(
+                r.resolve(QName(b"xsi:type"), true),
+                (Unknown(b"xsi".to_vec()), QName(b"type").into())
+            );
+        }
+        e => panic!("Expected empty element, got {:?}", e),
+    }
+}
+
+#[test]
+fn disable_namespace_tracking() {
+    let mut r = NsReader::from_str(
+        "<a xmlns='default'><b xmlns:x='www1'><x:c/></b><x:d attr='v' x:attr='v'/><e/></a>",
+    );
+
+    // <a>
+    match r.read_resolved_event() {
+        Ok((ns, Start(_))) => assert_eq!(ns, Bound(Namespace(b"default"))),
+        e => panic!("Expected start element, got {:?}", e),
+    }
+
+    r.disable_namespace_tracking();
+
+    // <b>: declarations are not recorded, but events are still produced
+    match r.read_resolved_event() {
+        Ok((ns, Start(e))) => {
+            assert_eq!(ns, Unbound);
+            assert_eq!(e.name(), QName(b"b"));
+        }
+        e => panic!("Expected start element, got {:?}", e),
+    }
+    // <x:c/>
+    match r.read_resolved_event() {
+        Ok((ns, Empty(e))) => {
+            assert_eq!(ns, Unbound);
+            assert_eq!(r.resolve_element(e.name()), (Unbound, QName(b"c").into()));
+        }
+        e => panic!("Expected empty element, got {:?}", e),
+    }
+    // </b>
+    match r.read_resolved_event() {
+        Ok((ns, End(e))) => {
+            assert_eq!(ns, Unbound);
+            assert_eq!(e.name(), QName(b"b"));
+        }
+        e => panic!("Expected end element, got {:?}", e),
+    }
+
+    r.enable_namespace_tracking();
+
+    // <x:d/>: `x` was declared while tracking was disabled, so it is unknown
+    match r.read_resolved_event() {
+        Ok((ns, Empty(e))) => {
+            assert_eq!(ns, Unknown(b"x".to_vec()));
+            assert_eq!(
+                r.resolve_attribute(QName(b"attr")),
+                (Unbound, QName(b"attr").into())
+            );
+            assert_eq!(
+                r.resolve_attribute(QName(b"x:attr")),
+                (Unknown(b"x".to_vec()), QName(b"attr").into())
+            );
+            assert_eq!(e.name(), QName(b"x:d"));
+        }
+        e => panic!("Expected empty element, got {:?}", e),
+    }
+    // <e/>: default namespace declared before disabling is still in scope
+    match r.read_resolved_event() {
+        Ok((ns, Empty(_))) => assert_eq!(ns, Bound(Namespace(b"default"))),
+        e => panic!("Expected empty element, got {:?}", e),
+    }
+    // </a>
+    match r.read_resolved_event() {
+        Ok((ns, End(_))) => assert_eq!(ns, Bound(Namespace(b"default"))),
+        e => panic!("Expected end element, got {:?}", e),
+    }
+}
+
+mod namespace_declarations {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::name::PrefixDeclaration;
+
+    #[test]
+    fn disabled_by_default() {
+        let mut r = NsReader::from_str("<a xmlns='urn:a'/>");
+
+        match r.read_event() {
+            Ok(Empty(_)) => assert_eq!(r.namespace_declarations().count(), 0),
+            e => panic!("Expected empty element, got {:?}", e),
+        }
+    }
+
+    /// Returns declarations of the last event as `(prefix, namespace)` pairs,
+    /// where the default namespace has an empty prefix and an undeclared
+    /// namespace is `None`
+    fn declarations(r: &NsReader<&[u8]>) -> Vec<(String, Option<String>)> {
+        r.namespace_declarations()
+            .map(|(prefix, ns)| {
+                let prefix = match prefix {
+                    PrefixDeclaration::Default => String::new(),
+                    PrefixDeclaration::Named(p) => String::from_utf8(p.to_vec()).unwrap(),
+                };
+                let ns = match ns {
+                    Bound(Namespace(ns)) => Some(String::from_utf8(ns.to_vec()).unwrap()),
+                    _ => None,
+                };
+                (prefix, ns)
+            })
+            .collect()
+    }
+
+    fn decl(prefix: &str, ns: Option<&str>) -> (String, Option<String>) {
+        (prefix.to_string(), ns.map(ToString::to_string))
+    }
+
+    #[test]
+    fn nested() {
+        let mut r = NsReader::from_str(
+            "<a xmlns='urn:a' xmlns:x='urn:x'><b xmlns:y='urn:y'>text<c xmlns:x=''/></b><d/></a>",
+        );
+        r.report_namespace_declarations(true);
+
+        let a = vec![decl("", Some("urn:a")), decl("x", Some("urn:x"))];
+        let b = vec![decl("y", Some("urn:y"))];
+
+        match r.read_event() {
+            Ok(Start(e)) => assert_eq!(e.name(), QName(b"a")),
+            e => panic!("Expected start element, got {:?}", e),
+        }
+        assert_eq!(declarations(&r), a);
+
+        match r.read_event() {
+            Ok(Start(e)) => assert_eq!(e.name(), QName(b"b")),
+            e => panic!("Expected start element, got {:?}", e),
+        }
+        assert_eq!(declarations(&r), b);
+
+        match r.read_event() {
+            Ok(Text(_)) => {}
+            e => panic!("Expected text, got {:?}", e),
+        }
+        assert_eq!(declarations(&r), vec![]);
+
+        match r.read_event() {
+            Ok(Empty(e)) => assert_eq!(e.name(), QName(b"c")),
+            e => panic!("Expected empty element, got {:?}", e),
+        }
+        assert_eq!(declarations(&r), vec![decl("x", None)]);
+
+        match r.read_event() {
+            Ok(End(e)) => assert_eq!(e.name(), QName(b"b")),
+            e => panic!("Expected end element, got {:?}", e),
+        }
+        assert_eq!(declarations(&r), b);
+
+        match r.read_event() {
+            Ok(Empty(e)) => assert_eq!(e.name(), QName(b"d")),
+            e => panic!("Expected empty element, got {:?}", e),
+        }
+        assert_eq!(declarations(&r), vec![]);
+
+        match r.read_event() {
+            Ok(End(e)) => assert_eq!(e.name(), QName(b"a")),
+            e => panic!("Expected end element, got {:?}", e),
+        }
+        assert_eq!(declarations(&r), a);
+
+        match r.read_event() {
+            Ok(Eof) => {}
+            e => panic!("Expected end of input, got {:?}", e),
+        }
+        assert_eq!(declarations(&r), vec![]);
+    }
+}
+
+mod duplicate_attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::Error;
+
+    /// By default tags are not checked, duplicates are reported only when
+    /// attributes are iterated
+    #[test]
+    fn permissive_by_default() {
+        let mut r = NsReader::from_str(r#"<e a="1" a="2"/>"#);
+
+        match r.read_resolved_event() {
+            Ok((ns, Empty(e))) => {
+                assert_eq!(ns, Unbound);
+                assert_eq!(e.name(), QName(b"e"));
+            }
+            e => panic!("Expected empty element, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn unprefixed() {
+        let mut r = NsReader::from_str(r#"<e a="1" a="2"/>"#);
+        r.check_duplicate_attributes(true);
+
+        match r.read_resolved_event() {
+            Err(Error::DuplicateAttribute { name, position }) => {
+                assert_eq!(name, "a");
+                assert_eq!(position, 9);
+            }
+            e => panic!("Expected `DuplicateAttribute` error, got {:?}", e),
+        }
+    }
+
+    /// Different prefixes bound to the same namespace
+    #[test]
+    fn prefixed() {
+        let mut r = NsReader::from_str(
+            r#"<root><e xmlns:p="urn:ns" xmlns:q="urn:ns" p:a="1" q:a="2"></e></root>"#,
+        );
+        r.check_duplicate_attributes(true);
+
+        match r.read_resolved_event() {
+            Ok((_, Start(e))) => assert_eq!(e.name(), QName(b"root")),
+            e => panic!("Expected start element, got {:?}", e),
+        }
+        match r.read_resolved_event() {
+            Err(Error::DuplicateAttribute { name, position }) => {
+                assert_eq!(name, "q:a");
+                assert_eq!(position, 51);
+            }
+            e => panic!("Expected `DuplicateAttribute` error, got {:?}", e),
+        }
+    }
+
+    /// Prefix is declared on the parent element
+    #[test]
+    fn prefixed_inherited() {
+        let mut r = NsReader::from_str(
+            r#"<root xmlns:p="urn:ns"><e xmlns:q="urn:ns" q:a="1" p:a="2"/></root>"#,
+        );
+        r.check_duplicate_attributes(true);
+
+        match r.read_resolved_event() {
+            Ok((_, Start(e))) => assert_eq!(e.name(), QName(b"root")),
+            e => panic!("Expected start element, got {:?}", e),
+        }
+        match r.read_resolved_event() {
+            Err(Error::DuplicateAttribute { name, position }) => {
+                assert_eq!(name, "p:a");
+                assert_eq!(position, 51);
+            }
+            e => panic!("Expected `DuplicateAttribute` error, got {:?}", e),
+        }
+    }
+
+    /// The same local name in different namespaces, and a prefixed and
+    /// unprefixed attribute with the same local name are different attributes
+    #[test]
+    fn distinct() {
+        let mut r = NsReader::from_str(
+            r#"<e xmlns="urn:ns" xmlns:p="urn:ns" xmlns:q="urn:other" a="1" p:a="2" q:a="3"/>"#,
+        );
+        r.check_duplicate_attributes(true);
+
+        match r.read_resolved_event() {
+            Ok((ns, Empty(e))) => {
+                assert_eq!(ns, Bound(Namespace(b"urn:ns")));
+                assert_eq!(e.attributes().count(), 6);
+            }
+            e => panic!("Expected empty element, got {:?}", e),
+        }
+        match r.read_resolved_event() {
+            Ok((_, Eof)) => {}
+            e => panic!("Expected end of input, got {:?}", e),
+        }
+    }
+
+    /// Without namespace tracking attributes are compared by their raw names
+    #[test]
+    fn untracked() {
+        let mut r = NsReader::from_str(
+            r#"<e xmlns:p="urn:a" xmlns:q="urn:b" p:a="1" q:a="2"/><e p:a="1" p:a="2"/>"#,
+        );
+        r.check_duplicate_attributes(true);
+        r.disable_namespace_tracking();
+
+        match r.read_resolved_event() {
+            Ok((_, Empty(e))) => assert_eq!(e.attributes().count(), 4),
+            e => panic!("Expected empty element, got {:?}", e),
+        }
+        match r.read_resolved_event() {
+            Err(Error::DuplicateAttribute { name, position }) => {
+                assert_eq!(name, "p:a");
+                assert_eq!(position, 63);
+            }
+            e => panic!("Expected `DuplicateAttribute` error, got {:?}", e),
+        }
+    }
+}
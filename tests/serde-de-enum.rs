@@ -569,6 +569,108 @@ mod internally_tagged {
             );
         }
     }
+
+    /// The tag is stored in an attribute together with the attributes of the
+    /// selected variant: `<event kind="click" x="1" y="2"/>`
+    mod attribute_discriminant {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use quick_xml::de::Deserializer;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        #[serde(tag = "@kind", rename_all = "lowercase")]
+        enum Event {
+            Click {
+                //TODO: change to u32 after fixing https://github.com/serde-rs/serde/issues/1183
+                #[serde(rename = "@x")]
+                x: String,
+                #[serde(rename = "@y")]
+                y: String,
+            },
+            Key {
+                #[serde(rename = "@code")]
+                code: String,
+            },
+        }
+
+        /// The same type, but with typed fields. The variant is selected by
+        /// the deserializer itself, so fields are not buffered by serde.
+        #[derive(Debug, Deserialize, PartialEq)]
+        #[serde(rename_all = "lowercase")]
+        enum TypedEvent {
+            Click {
+                #[serde(rename = "@x")]
+                x: u32,
+                #[serde(rename = "@y")]
+                y: u32,
+            },
+            Key {
+                #[serde(rename = "@code")]
+                code: u32,
+            },
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Events {
+            event: Vec<TypedEvent>,
+        }
+
+        #[test]
+        fn variants() {
+            let data: Event = from_str(r#"<event kind="click" x="1" y="2"/>"#).unwrap();
+            assert_eq!(
+                data,
+                Event::Click {
+                    x: "1".into(),
+                    y: "2".into(),
+                }
+            );
+
+            let data: Event = from_str(r#"<event kind="key" code="13"/>"#).unwrap();
+            assert_eq!(data, Event::Key { code: "13".into() });
+        }
+
+        #[test]
+        fn unknown() {
+            match from_str::<Event>(r#"<event kind="scroll" delta="3"/>"#) {
+                Err(DeError::Custom(e)) => {
+                    assert_eq!(e, "unknown variant `scroll`, expected `click` or `key`")
+                }
+                x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn typed_variants() {
+            let mut de = Deserializer::from_str(
+                r#"<events><event kind="click" x="1" y="2"/><event code="13" kind="key"/></events>"#,
+            );
+            de.variant_attribute("kind");
+
+            assert_eq!(
+                Events::deserialize(&mut de).unwrap(),
+                Events {
+                    event: vec![
+                        TypedEvent::Click { x: 1, y: 2 },
+                        TypedEvent::Key { code: 13 }
+                    ],
+                }
+            );
+        }
+
+        #[test]
+        fn typed_unknown() {
+            let mut de = Deserializer::from_str(r#"<event kind="scroll" delta="3"/>"#);
+            de.variant_attribute("kind");
+
+            match TypedEvent::deserialize(&mut de) {
+                Err(DeError::Custom(e)) => {
+                    assert_eq!(e, "unknown variant `scroll`, expected `click` or `key`")
+                }
+                x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+            }
+        }
+    }
 }
 
 /// Enum tag selector either an attribute "tag", or a tag "tag".
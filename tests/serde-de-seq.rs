@@ -1,5 +1,6 @@
 //! Tests of deserialization of XML documents into various sequential types
 
+use quick_xml::de::Deserializer;
 use quick_xml::DeError;
 use serde::Deserialize;
 
@@ -149,6 +150,16 @@ mod top_level {
         .unwrap();
         assert_eq!(data, vec![Enum::One, Enum::Two, Enum::One]);
     }
+
+    #[test]
+    fn top_level_elements() {
+        let mut de = Deserializer::from_str("<root/><root><nested/>42</root><root>answer</root>");
+        assert_eq!(de.top_level_elements(), 0);
+
+        let data = Vec::<()>::deserialize(&mut de).unwrap();
+        assert_eq!(data, vec![(), (), ()]);
+        assert_eq!(de.top_level_elements(), 3);
+    }
 }
 
 /// Tests where each sequence item have an identical name in an XML.
@@ -260,9 +271,49 @@ mod fixed_name {
             );
 
             match data {
-                Err(DeError::Custom(e)) => assert_eq!(e, "duplicate field `item`"),
+                Err(DeError::Custom(e)) => {
+                    assert_eq!(e, "invalid length 4, expected an array of length 3")
+                }
                 e => panic!(
-                    r#"Expected `Err(Custom("duplicate field `item`"))`, but found {:?}"#,
+                    r#"Expected `Err(Custom("invalid length 4, expected an array of length 3"))`, but found {:?}"#,
+                    e
+                ),
+            }
+        }
+
+        /// Values of elements are deserialized into array items. The same error
+        /// is reported for fewer and more elements than expected
+        #[test]
+        fn values() {
+            #[derive(Debug, PartialEq, Deserialize)]
+            struct List {
+                item: [u32; 3],
+            }
+
+            assert_eq!(
+                from_str::<List>("<root><item>1</item><item>2</item><item>3</item></root>")
+                    .unwrap(),
+                List { item: [1, 2, 3] }
+            );
+
+            match from_str::<List>("<root><item>1</item><item>2</item></root>") {
+                Err(DeError::Custom(e)) => {
+                    assert_eq!(e, "invalid length 2, expected an array of length 3")
+                }
+                e => panic!(
+                    r#"Expected `Err(Custom("invalid length 2, expected an array of length 3"))`, but found {:?}"#,
+                    e
+                ),
+            }
+
+            match from_str::<List>(
+                "<root><item>1</item><item>2</item><item>3</item><item>4</item></root>",
+            ) {
+                Err(DeError::Custom(e)) => {
+                    assert_eq!(e, "invalid length 4, expected an array of length 3")
+                }
+                e => panic!(
+                    r#"Expected `Err(Custom("invalid length 4, expected an array of length 3"))`, but found {:?}"#,
                     e
                 ),
             }
@@ -4064,3 +4115,168 @@ mod variable_name {
         }
     }
 }
+
+/// Text content interleaved with elements is kept in the document order
+/// when a `$value` field is a sequence of an enum with a `$text` variant
+mod mixed_content {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    enum Inline {
+        #[serde(rename = "$text")]
+        Text(String),
+        #[serde(rename = "b")]
+        B(String),
+        #[serde(rename = "i")]
+        I(Italic),
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Italic {
+        #[serde(rename = "$value")]
+        content: Vec<Inline>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Paragraph {
+        #[serde(rename = "$value")]
+        content: Vec<Inline>,
+    }
+
+    fn untrimmed(xml: &str) -> Paragraph {
+        let mut de = Deserializer::from_str(xml);
+        de.trim_text(false);
+        Paragraph::deserialize(&mut de).unwrap()
+    }
+
+    #[test]
+    fn text_and_elements() {
+        assert_eq!(
+            untrimmed("<p>Hello <b>world</b>!</p>"),
+            Paragraph {
+                content: vec![
+                    Inline::Text("Hello ".into()),
+                    Inline::B("world".into()),
+                    Inline::Text("!".into()),
+                ],
+            }
+        );
+    }
+
+    /// By default text is trimmed, but the order of items is kept
+    #[test]
+    fn trimmed() {
+        let data: Paragraph = from_str("<p> Hello <b>world</b> ! </p>").unwrap();
+
+        assert_eq!(
+            data,
+            Paragraph {
+                content: vec![
+                    Inline::Text("Hello".into()),
+                    Inline::B("world".into()),
+                    Inline::Text("!".into()),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn nested() {
+        assert_eq!(
+            untrimmed("<p><![CDATA[<Hello>]]> <i>brave <b>new</b></i> world</p>"),
+            Paragraph {
+                content: vec![
+                    Inline::Text("<Hello> ".into()),
+                    Inline::I(Italic {
+                        content: vec![Inline::Text("brave ".into()), Inline::B("new".into())],
+                    }),
+                    Inline::Text(" world".into()),
+                ],
+            }
+        );
+    }
+
+    /// Whitespace-only text between elements is kept if text is not trimmed
+    #[test]
+    fn spaces_between_elements() {
+        assert_eq!(
+            untrimmed("<p><b>a</b> <i>b</i></p>"),
+            Paragraph {
+                content: vec![
+                    Inline::B("a".into()),
+                    Inline::Text(" ".into()),
+                    Inline::I(Italic {
+                        content: vec![Inline::Text("b".into())],
+                    }),
+                ],
+            }
+        );
+    }
+
+    /// Indentation between elements is kept if text is not trimmed,
+    /// but whitespaces outside of the root element are skipped
+    #[test]
+    fn indentation() {
+        assert_eq!(
+            untrimmed("\n<p>\n  <b>one</b>\n  <b>two</b>\n</p>\n"),
+            Paragraph {
+                content: vec![
+                    Inline::Text("\n  ".into()),
+                    Inline::B("one".into()),
+                    Inline::Text("\n  ".into()),
+                    Inline::B("two".into()),
+                    Inline::Text("\n".into()),
+                ],
+            }
+        );
+    }
+
+    /// Indentation between elements that are not captured by `$value`
+    /// or `$text` fields is skipped if text is not trimmed
+    #[test]
+    fn indented_document() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Root {
+            a: u32,
+            b: String,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Outer {
+            root: Vec<Root>,
+        }
+
+        let mut de = Deserializer::from_str(
+            r#"
+            <outer>
+                <root>
+                    <a>1</a>
+                    <b>x</b>
+                </root>
+                <root>
+                    <a>2</a>
+                    <b> y </b>
+                </root>
+            </outer>
+            "#,
+        );
+        de.trim_text(false);
+
+        assert_eq!(
+            Outer::deserialize(&mut de).unwrap(),
+            Outer {
+                root: vec![
+                    Root {
+                        a: 1,
+                        b: "x".into(),
+                    },
+                    Root {
+                        a: 2,
+                        b: " y ".into(),
+                    },
+                ],
+            }
+        );
+    }
+}
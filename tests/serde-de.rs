@@ -86,80 +86,57 @@ mod trivial {
     use super::*;
 
     #[rustfmt::skip] // excess spaces used for readability
-    macro_rules! eof {
-        ($name:ident: $type:ty = $value:expr) => {
+    macro_rules! invalid {
+        ($error:ident, $name:ident: $type:ty = $value:expr) => {
             #[test]
             fn $name() {
                 match from_str::<$type>($value) {
-                    Err(DeError::UnexpectedEof) => (),
+                    Err(DeError::$error) => (),
                     x => panic!(
-                        r#"Expected `Err(DeError::UnexpectedEof)`, but got `{:?}`"#,
+                        r#"Expected `Err(DeError::{})`, but got `{:?}`"#,
+                        stringify!($error),
                         x
                     ),
                 }
             }
         };
-        ($value:expr) => {
-            eof!(i8_:    i8    = $value);
-            eof!(i16_:   i16   = $value);
-            eof!(i32_:   i32   = $value);
-            eof!(i64_:   i64   = $value);
-            eof!(isize_: isize = $value);
-
-            eof!(u8_:    u8    = $value);
-            eof!(u16_:   u16   = $value);
-            eof!(u32_:   u32   = $value);
-            eof!(u64_:   u64   = $value);
-            eof!(usize_: usize = $value);
+        ($error:ident, $value:expr) => {
+            invalid!($error, i8_:    i8    = $value);
+            invalid!($error, i16_:   i16   = $value);
+            invalid!($error, i32_:   i32   = $value);
+            invalid!($error, i64_:   i64   = $value);
+            invalid!($error, isize_: isize = $value);
+
+            invalid!($error, u8_:    u8    = $value);
+            invalid!($error, u16_:   u16   = $value);
+            invalid!($error, u32_:   u32   = $value);
+            invalid!($error, u64_:   u64   = $value);
+            invalid!($error, usize_: usize = $value);
 
             serde_if_integer128! {
-                eof!(u128_: u128 = $value);
-                eof!(i128_: i128 = $value);
+                invalid!($error, u128_: u128 = $value);
+                invalid!($error, i128_: i128 = $value);
             }
 
-            eof!(f32_: f32 = $value);
-            eof!(f64_: f64 = $value);
-
-            eof!(false_: bool = $value);
-            eof!(true_: bool = $value);
-            eof!(char_: char = $value);
+            invalid!($error, f32_: f32 = $value);
+            invalid!($error, f64_: f64 = $value);
 
-            eof!(string: String = $value);
+            invalid!($error, false_: bool = $value);
+            invalid!($error, true_: bool = $value);
+            invalid!($error, char_: char = $value);
 
-            /// XML does not able to store binary data
-            #[test]
-            fn byte_buf() {
-                match from_str::<ByteBuf>($value) {
-                    Err(DeError::Unsupported(msg)) => {
-                        assert_eq!(msg, "binary data content is not supported by XML format")
-                    }
-                    x => panic!(
-                        r#"Expected `Err(DeError::Unsupported("binary data content is not supported by XML format"))`, but got `{:?}`"#,
-                        x
-                    ),
-                }
-            }
+            invalid!($error, string: String = $value);
 
-            /// XML does not able to store binary data
-            #[test]
-            fn bytes() {
-                match from_str::<Bytes>($value) {
-                    Err(DeError::Unsupported(msg)) => {
-                        assert_eq!(msg, "binary data content is not supported by XML format")
-                    }
-                    x => panic!(
-                        r#"Expected `Err(DeError::Unsupported("binary data content is not supported by XML format"))`, but got `{:?}`"#,
-                        x
-                    ),
-                }
-            }
+            invalid!($error, byte_buf: ByteBuf = $value);
+            invalid!($error, bytes: Bytes = $value);
 
             #[test]
             fn unit() {
                 match from_str::<()>($value) {
-                    Err(DeError::UnexpectedEof) => (),
+                    Err(DeError::$error) => (),
                     x => panic!(
-                        r#"Expected `Err(DeError::UnexpectedEof)`, but got `{:?}`"#,
+                        r#"Expected `Err(DeError::{})`, but got `{:?}`"#,
+                        stringify!($error),
                         x
                     ),
                 }
@@ -170,17 +147,54 @@ mod trivial {
     /// Empty document should considered invalid no matter what type we try to deserialize
     mod empty_doc {
         use super::*;
-        use pretty_assertions::assert_eq;
 
-        eof!("");
+        invalid!(UnexpectedEof, "");
+    }
+
+    /// Document that contains only whitespaces should be reported as a document
+    /// without root element
+    mod only_whitespace {
+        use super::*;
+
+        invalid!(NoRootElement, " \t\r\n ");
     }
 
-    /// Document that contains only comment should be handled as if it is empty
+    /// Document that contains only comment should be reported as a document
+    /// without root element
     mod only_comment {
         use super::*;
+
+        invalid!(NoRootElement, "<!--comment-->");
+    }
+
+    /// Document that contains only declaration, processing instructions and
+    /// whitespaces should be reported as a document without root element
+    mod only_declarations {
+        use super::*;
+
+        invalid!(NoRootElement, "<?xml version='1.0'?>\n<?pi data?>\n");
+    }
+
+    /// Optional values and sequences can be deserialized from a document without
+    /// root element
+    mod without_root {
         use pretty_assertions::assert_eq;
+        // Do not check that the whole document is consumed after deserialization
+        use quick_xml::de::from_str;
+
+        #[test]
+        fn option() {
+            assert_eq!(from_str::<Option<u32>>("").unwrap(), None);
+            assert_eq!(from_str::<Option<u32>>("  ").unwrap(), None);
+            assert_eq!(from_str::<Option<u32>>("<!--c-->").unwrap(), None);
+        }
 
-        eof!("<!--comment-->");
+        #[test]
+        fn seq() {
+            assert_eq!(from_str::<Vec<u32>>("").unwrap(), vec![]);
+            assert_eq!(from_str::<Vec<u32>>("  ").unwrap(), vec![]);
+            assert_eq!(from_str::<Vec<u32>>("<!--c-->").unwrap(), vec![]);
+        }
     }
 
     /// Tests deserialization from top-level tag content: `<root>...content...</root>`
@@ -314,33 +328,9 @@ mod trivial {
             // Escape sequences does not processed inside CDATA section
             in_struct!(string: String = "<root><![CDATA[escaped&#x20;string]]></root>", "escaped&#x20;string".into());
 
-            /// XML does not able to store binary data
-            #[test]
-            fn byte_buf() {
-                match from_str::<Trivial<ByteBuf>>("<root><![CDATA[escaped&#x20;byte_buf]]></root>") {
-                    Err(DeError::Unsupported(msg)) => {
-                        assert_eq!(msg, "binary data content is not supported by XML format")
-                    }
-                    x => panic!(
-                        r#"Expected `Err(DeError::Unsupported("binary data content is not supported by XML format"))`, but got `{:?}`"#,
-                        x
-                    ),
-                }
-            }
-
-            /// XML does not able to store binary data
-            #[test]
-            fn bytes() {
-                match from_str::<Trivial<Bytes>>("<root><![CDATA[escaped&#x20;byte_buf]]></root>") {
-                    Err(DeError::Unsupported(msg)) => {
-                        assert_eq!(msg, "binary data content is not supported by XML format")
-                    }
-                    x => panic!(
-                        r#"Expected `Err(DeError::Unsupported("binary data content is not supported by XML format"))`, but got `{:?}`"#,
-                        x
-                    ),
-                }
-            }
+            // CDATA content is returned as raw bytes
+            in_struct!(byte_buf: ByteBuf = "<root><![CDATA[escaped&#x20;byte_buf]]></root>", ByteBuf(b"escaped&#x20;byte_buf".to_vec()));
+            in_struct!(bytes: Bytes = "<root><![CDATA[escaped&#x20;bytes]]></root>", Bytes(b"escaped&#x20;bytes"));
         }
     }
 }
@@ -751,6 +741,55 @@ mod struct_ {
         );
     }
 
+    /// Attribute and element with the same local name are mapped to different fields
+    mod same_name {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "@id")]
+            attr_id: Option<u32>,
+            id: Option<u32>,
+        }
+
+        #[test]
+        fn both() {
+            let data: Root = from_str(r#"<e id="1"><id>2</id></e>"#).unwrap();
+            assert_eq!(
+                data,
+                Root {
+                    attr_id: Some(1),
+                    id: Some(2),
+                }
+            );
+        }
+
+        #[test]
+        fn only_attribute() {
+            let data: Root = from_str(r#"<e id="1"/>"#).unwrap();
+            assert_eq!(
+                data,
+                Root {
+                    attr_id: Some(1),
+                    id: None,
+                }
+            );
+        }
+
+        #[test]
+        fn only_element() {
+            let data: Root = from_str(r#"<e><id>2</id></e>"#).unwrap();
+            assert_eq!(
+                data,
+                Root {
+                    attr_id: None,
+                    id: Some(2),
+                }
+            );
+        }
+    }
+
     /// Checks that excess data before the struct correctly handled.
     /// Any data not allowed before the struct
     mod excess_data_before {
@@ -1156,6 +1195,68 @@ fn from_str_should_ignore_encoding() {
     );
 }
 
+#[test]
+fn from_slice_utf8() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct A {
+        #[serde(rename = "@a")]
+        a: String,
+    }
+
+    let a: A = quick_xml::de::from_slice(r#"<A a="€"/>"#.as_bytes()).unwrap();
+    assert_eq!(
+        a,
+        A {
+            a: "€".to_string()
+        }
+    );
+}
+
+#[cfg(feature = "encoding")]
+#[test]
+fn from_slice_should_decode_declared_encoding() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct A {
+        #[serde(rename = "@a")]
+        a: String,
+        #[serde(rename = "$text")]
+        text: String,
+    }
+
+    // 0x80 is `€` and 0xE9 is `é` in windows-1252
+    let xml = b"<?xml version=\"1.0\" encoding=\"windows-1252\" ?>\
+        <A a=\"\x80 5\">caf\xE9</A>";
+
+    let a: A = quick_xml::de::from_slice(xml).unwrap();
+    assert_eq!(
+        a,
+        A {
+            a: "€ 5".to_string(),
+            text: "café".to_string(),
+        }
+    );
+}
+
+/// A byte order mark before the XML declaration is ignored
+#[test]
+fn bom() {
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Root {
+        item: String,
+    }
+
+    let xml = "\u{feff}<?xml version=\"1.0\"?><root><item>text</item></root>";
+    let expected = Root {
+        item: "text".to_string(),
+    };
+
+    assert_eq!(from_str::<Root>(xml).unwrap(), expected);
+    assert_eq!(
+        Root::deserialize(&mut Deserializer::from_reader(xml.as_bytes())).unwrap(),
+        expected
+    );
+}
+
 /// Checks that deserializer is able to borrow data from the input
 mod borrow {
     use super::*;
@@ -1281,6 +1382,55 @@ mod borrow {
         }
     }
 
+    /// `Cow<str>` borrows when unescaping is not required and takes ownership
+    /// of the unescaped string otherwise. Note, that serde borrows into `Cow`
+    /// only with an explicit `#[serde(borrow)]` attribute
+    mod cow {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use std::borrow::Cow;
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct CowElement<'a> {
+            #[serde(borrow)]
+            x: Cow<'a, str>,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct CowAttribute<'a> {
+            #[serde(rename = "@x", borrow)]
+            x: Cow<'a, str>,
+        }
+
+        #[test]
+        fn element_non_escaped() {
+            let data: CowElement = from_str(r#"<root><x>plain</x></root>"#).unwrap();
+            assert_eq!(data.x, "plain");
+            assert!(matches!(data.x, Cow::Borrowed(_)));
+        }
+
+        #[test]
+        fn element_escaped() {
+            let data: CowElement = from_str(r#"<root><x>a &lt; b</x></root>"#).unwrap();
+            assert_eq!(data.x, "a < b");
+            assert!(matches!(data.x, Cow::Owned(_)));
+        }
+
+        #[test]
+        fn attribute_non_escaped() {
+            let data: CowAttribute = from_str(r#"<root x="plain"/>"#).unwrap();
+            assert_eq!(data.x, "plain");
+            assert!(matches!(data.x, Cow::Borrowed(_)));
+        }
+
+        #[test]
+        fn attribute_escaped() {
+            let data: CowAttribute = from_str(r#"<root x="a &lt; b"/>"#).unwrap();
+            assert_eq!(data.x, "a < b");
+            assert!(matches!(data.x, Cow::Owned(_)));
+        }
+    }
+
     #[test]
     fn element_name() {
         let data: BTreeMap<&str, &str> = from_str(
@@ -1307,7 +1457,7 @@ mod resolve {
     use super::*;
     use pretty_assertions::assert_eq;
     use quick_xml::de::EntityResolver;
-    use quick_xml::events::BytesText;
+    use quick_xml::events::BytesDocType;
     use std::collections::BTreeMap;
     use std::convert::Infallible;
     use std::iter::FromIterator;
@@ -1319,7 +1469,7 @@ mod resolve {
     impl EntityResolver for TestEntityResolver {
         type Error = Infallible;
 
-        fn capture(&mut self, doctype: BytesText) -> Result<(), Self::Error> {
+        fn capture(&mut self, doctype: BytesDocType) -> Result<(), Self::Error> {
             self.capture_called = true;
 
             assert_eq!(doctype.as_ref(), br#"dict[ <!ENTITY unc "unclassified"> ]"#);
@@ -1369,6 +1519,2659 @@ mod resolve {
             ])
         );
     }
+
+    /// Tests for `EntityResolver::try_resolve`
+    mod try_resolve {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use quick_xml::de::UnknownEntityPolicy;
+        use quick_xml::escape::EscapeError;
+        use quick_xml::Error;
+        use std::borrow::Cow;
+        use std::fmt;
+
+        #[derive(Debug)]
+        struct TooLong(usize);
+
+        impl fmt::Display for TooLong {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{} repetitions is too much", self.0)
+            }
+        }
+
+        impl std::error::Error for TooLong {}
+
+        /// Resolves `&repeat-<count>;` entities to `<count>` exclamation marks
+        struct RepeatResolver;
+
+        impl EntityResolver for RepeatResolver {
+            type Error = TooLong;
+
+            fn capture(&mut self, _doctype: BytesDocType) -> Result<(), Self::Error> {
+                Ok(())
+            }
+
+            fn resolve(&self, entity: &str) -> Option<&str> {
+                match entity {
+                    "static" => Some("borrowed"),
+                    _ => None,
+                }
+            }
+
+            fn try_resolve(&self, entity: &str) -> Result<Option<Cow<'_, str>>, Self::Error> {
+                match entity.strip_prefix("repeat-").map(str::parse) {
+                    Some(Ok(count)) if count > 5 => Err(TooLong(count)),
+                    Some(Ok(count)) => Ok(Some(Cow::Owned("!".repeat(count)))),
+                    _ => Ok(self.resolve(entity).map(Cow::Borrowed)),
+                }
+            }
+        }
+
+        #[test]
+        fn owned() {
+            let mut de = Deserializer::with_resolver(
+                "<root>&repeat-1;&lt;&repeat-3;&#x20;&static;&repeat-3;</root>".as_bytes(),
+                RepeatResolver,
+            );
+            assert_eq!(String::deserialize(&mut de).unwrap(), "!<!!! borrowed!!!");
+        }
+
+        #[test]
+        fn unknown() {
+            let mut de = Deserializer::with_resolver(
+                "<root>&repeat-2;&unknown;</root>".as_bytes(),
+                RepeatResolver,
+            );
+            match String::deserialize(&mut de) {
+                Err(DeError::InvalidXml(Error::EscapeError(EscapeError::UnrecognizedSymbol(
+                    _,
+                    entity,
+                )))) => assert_eq!(entity, "unknown"),
+                x => panic!(
+                    "Expected `Err(InvalidXml(EscapeError(UnrecognizedSymbol(_, _))))`, but got `{:?}`",
+                    x
+                ),
+            }
+        }
+
+        #[test]
+        fn unknown_keep() {
+            let mut de = Deserializer::with_resolver(
+                "<root>&repeat-2;&unknown;&repeat-1;</root>".as_bytes(),
+                RepeatResolver,
+            );
+            de.on_unknown_entity(UnknownEntityPolicy::Keep);
+            assert_eq!(String::deserialize(&mut de).unwrap(), "!!&unknown;!");
+        }
+
+        #[test]
+        fn error() {
+            let mut de = Deserializer::with_resolver(
+                "<root>&repeat-2;&repeat-10;</root>".as_bytes(),
+                RepeatResolver,
+            );
+            match String::deserialize(&mut de) {
+                Err(DeError::Custom(msg)) => assert_eq!(
+                    msg,
+                    "cannot resolve entity `repeat-10`: 10 repetitions is too much"
+                ),
+                x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+            }
+        }
+    }
+}
+
+/// Tests for `Deserializer::with_name_rewriter`
+mod name_rewriter {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@id")]
+        id: usize,
+        float: f64,
+    }
+
+    #[test]
+    fn strip_prefix() {
+        let mut de =
+            Deserializer::from_str(r#"<root vendorid="42"><vendorfloat>4.2</vendorfloat></root>"#);
+        de.with_name_rewriter(|name| match name.strip_prefix("vendor") {
+            Some(name) => name.into(),
+            None => name.into(),
+        });
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root { id: 42, float: 4.2 }
+        );
+    }
+
+    /// Rewriter receives qualified names and can strip a namespace prefix from them
+    #[test]
+    fn strip_namespace_prefix() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let names = seen.clone();
+
+        let mut de = Deserializer::from_str(
+            r#"<root xmlns:vendor="urn:vendor" vendor:id="42"><vendor:float>4.2</vendor:float></root>"#,
+        );
+        de.with_name_rewriter(move |name| {
+            names.lock().unwrap().push(name.to_owned());
+            match name.strip_prefix("vendor:") {
+                Some(name) => name.into(),
+                None => name.into(),
+            }
+        });
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root { id: 42, float: 4.2 }
+        );
+
+        let seen = seen.lock().unwrap();
+        assert!(seen.iter().any(|n| n == "vendor:id"), "{:?}", seen);
+        assert!(seen.iter().any(|n| n == "vendor:float"), "{:?}", seen);
+    }
+
+    #[test]
+    fn rename() {
+        let mut de = Deserializer::from_str(
+            r#"<root vendor-id="42"><vendor-float>4.2</vendor-float></root>"#,
+        );
+        de.with_name_rewriter(|name| name.replace("vendor-", "").into());
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root { id: 42, float: 4.2 }
+        );
+    }
+}
+
+/// Tests for handling of entities, that cannot be resolved
+mod unknown_entity {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::UnknownEntityPolicy;
+    use quick_xml::escape::EscapeError;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct X {
+        #[serde(rename = "$text")]
+        text: String,
+    }
+
+    const XML: &str = "<x>a &weird; b</x>";
+
+    #[test]
+    fn error() {
+        let mut de = Deserializer::from_str(XML);
+        de.on_unknown_entity(UnknownEntityPolicy::Error);
+
+        match X::deserialize(&mut de) {
+            Err(DeError::InvalidXml(quick_xml::Error::EscapeError(
+                EscapeError::UnrecognizedSymbol(_, entity),
+            ))) => assert_eq!(entity, "weird"),
+            x => panic!(
+                "Expected `Err(InvalidXml(EscapeError(UnrecognizedSymbol(_, _))))`, but got `{:?}`",
+                x
+            ),
+        }
+    }
+
+    #[test]
+    fn keep() {
+        let mut de = Deserializer::from_str(XML);
+        de.on_unknown_entity(UnknownEntityPolicy::Keep);
+
+        assert_eq!(
+            X::deserialize(&mut de).unwrap(),
+            X {
+                text: "a &weird; b".into()
+            }
+        );
+    }
+
+    #[test]
+    fn replace() {
+        let mut de = Deserializer::from_str(XML);
+        de.on_unknown_entity(UnknownEntityPolicy::Replace("\u{FFFD}".into()));
+
+        assert_eq!(
+            X::deserialize(&mut de).unwrap(),
+            X {
+                text: "a \u{FFFD} b".into()
+            }
+        );
+    }
+
+    /// Known entities around the unknown one should be unescaped as usual
+    #[test]
+    fn keep_with_known() {
+        let mut de = Deserializer::from_str("<x>&lt;&weird;&gt;&#x41;&weird;</x>");
+        de.on_unknown_entity(UnknownEntityPolicy::Keep);
+
+        assert_eq!(
+            X::deserialize(&mut de).unwrap(),
+            X {
+                text: "<&weird;>A&weird;".into()
+            }
+        );
+    }
+}
+
+/// Tests for capturing XML of an element with `WithRaw`
+mod with_raw {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::WithRaw;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        #[serde(rename = "@id")]
+        id: usize,
+        name: String,
+    }
+
+    #[test]
+    fn root() {
+        let item: WithRaw<Item> =
+            from_str(r#"<item id="1"><name>first</name></item>"#).unwrap();
+
+        assert_eq!(
+            item.value,
+            Item {
+                id: 1,
+                name: "first".into()
+            }
+        );
+        assert_eq!(item.raw, r#"<item id="1"><name>first</name></item>"#);
+    }
+
+    #[test]
+    fn field() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            item: WithRaw<Item>,
+            after: (),
+        }
+
+        let root: Root = from_str(
+            r#"<root><item id="1"><name>&lt;first&gt;</name><ignored/></item><after/></root>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            root.item.value,
+            Item {
+                id: 1,
+                name: "<first>".into()
+            }
+        );
+        assert_eq!(
+            root.item.raw,
+            r#"<item id="1"><name>&lt;first&gt;</name><ignored/></item>"#
+        );
+    }
+
+    /// Comments, CDATA sections, whitespaces and empty elements are kept as is
+    #[test]
+    fn verbatim() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            item: WithRaw<Item>,
+            empty: WithRaw<()>,
+        }
+
+        let root: Root = from_str(
+            "<root><item id='1'> <!--c--><name><![CDATA[<first>]]></name> <x/></item ><empty /></root>",
+        )
+        .unwrap();
+
+        assert_eq!(
+            root.item.value,
+            Item {
+                id: 1,
+                name: "<first>".into()
+            }
+        );
+        assert_eq!(
+            root.item.raw,
+            "<item id='1'> <!--c--><name><![CDATA[<first>]]></name> <x/></item >"
+        );
+        assert_eq!(root.empty.raw, "<empty />");
+    }
+
+    /// Raw XML can be captured only from a string
+    #[test]
+    fn reader() {
+        let mut de =
+            Deserializer::from_reader(r#"<item id="1"><name>first</name></item>"#.as_bytes());
+        match WithRaw::<Item>::deserialize(&mut de) {
+            Err(DeError::Unsupported(msg)) => assert_eq!(
+                msg,
+                "raw XML can be captured only when deserializing from a string"
+            ),
+            x => panic!(
+                r#"Expected `Err(DeError::Unsupported("raw XML can be captured only when deserializing from a string"))`, but got `{:?}`"#,
+                x
+            ),
+        }
+    }
+
+    #[test]
+    fn list() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            item: Vec<WithRaw<Item>>,
+        }
+
+        let root: Root = from_str(
+            r#"<root><item id="1"><name>first</name></item><item id="2"><name>second</name></item></root>"#,
+        )
+        .unwrap();
+
+        assert_eq!(root.item.len(), 2);
+        assert_eq!(root.item[0].value.id, 1);
+        assert_eq!(root.item[0].raw, r#"<item id="1"><name>first</name></item>"#);
+        assert_eq!(root.item[1].value.id, 2);
+        assert_eq!(root.item[1].raw, r#"<item id="2"><name>second</name></item>"#);
+    }
+}
+
+/// Tests for capturing markup inside an element with `RawXml`
+mod raw_xml {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::RawXml;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        p: RawXml,
+        after: (),
+    }
+
+    #[test]
+    fn root() {
+        let raw: RawXml = from_str("<p><b>x</b> y</p>").unwrap();
+        assert_eq!(raw, RawXml("<b>x</b> y".into()));
+    }
+
+    #[test]
+    fn field() {
+        let root: Root = from_str("<root><p><b>x</b> y</p><after/></root>").unwrap();
+        assert_eq!(
+            root,
+            Root {
+                p: RawXml("<b>x</b> y".into()),
+                after: (),
+            }
+        );
+    }
+
+    /// Whitespaces, comments, CDATA and escaping are preserved
+    #[test]
+    fn verbatim() {
+        let root: Root = from_str(
+            "<root><p> <!--c--><p>&lt;&amp;</p><![CDATA[<>]]>&unknown; </p><after/></root>",
+        )
+        .unwrap();
+        assert_eq!(
+            root.p,
+            RawXml(" <!--c--><p>&lt;&amp;</p><![CDATA[<>]]>&unknown; ".into())
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let root: Root = from_str("<root><p/><after/></root>").unwrap();
+        assert_eq!(root.p, RawXml("".into()));
+
+        let root: Root = from_str(r#"<root><p attr="1"></p><after/></root>"#).unwrap();
+        assert_eq!(root.p, RawXml("".into()));
+    }
+
+    #[test]
+    fn list() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            p: Vec<RawXml>,
+        }
+
+        let root: Root = from_str("<root><p><b>x</b></p><p>y</p></root>").unwrap();
+        assert_eq!(root.p, vec![RawXml("<b>x</b>".into()), RawXml("y".into())]);
+    }
+
+    #[test]
+    fn bom() {
+        let raw: RawXml = from_str("\u{FEFF}<p><b>x</b> y</p>").unwrap();
+        assert_eq!(raw, RawXml("<b>x</b> y".into()));
+    }
+
+    #[test]
+    fn from_reader() {
+        match quick_xml::de::from_reader::<_, RawXml>("<p><b>x</b> y</p>".as_bytes()) {
+            Err(DeError::Unsupported(_)) => {}
+            x => panic!("Expected `Err(Unsupported(_))`, but got `{:?}`", x),
+        }
+    }
+}
+
+/// Tests for deserializing `Cow<[u8]>` from CDATA content
+mod cdata_bytes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::borrow::Cow;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root<'a> {
+        #[serde(borrow)]
+        data: Cow<'a, [u8]>,
+    }
+
+    /// Slice-backed reader borrows CDATA content from the input
+    #[test]
+    fn borrowed() {
+        let root: Root =
+            from_str("<root><data><![CDATA[\x01 &amp; <raw>]]></data></root>").unwrap();
+
+        assert!(matches!(root.data, Cow::Borrowed(_)));
+        assert_eq!(root.data, &b"\x01 &amp; <raw>"[..]);
+    }
+
+    /// Streaming reader can not borrow from the input and owns the content
+    #[test]
+    fn owned() {
+        let xml = "<root><data><![CDATA[\x01 &amp; <raw>]]></data></root>";
+        let mut de = Deserializer::from_reader(xml.as_bytes());
+        let root = Root::deserialize(&mut de).unwrap();
+
+        assert!(matches!(root.data, Cow::Owned(_)));
+        assert_eq!(root.data, &b"\x01 &amp; <raw>"[..]);
+    }
+
+    /// Content of several consequent CDATA sections is merged, so it can not
+    /// be borrowed even from a slice-backed reader
+    #[test]
+    fn several_sections() {
+        let root: Root =
+            from_str("<root><data><![CDATA[first]]><![CDATA[second]]></data></root>").unwrap();
+
+        assert!(matches!(root.data, Cow::Owned(_)));
+        assert_eq!(root.data, &b"firstsecond"[..]);
+    }
+
+    #[test]
+    fn text_content() {
+        match from_str::<Root>("<root><data>text</data></root>") {
+            Err(DeError::Unsupported(msg)) => {
+                assert_eq!(msg, "binary data content is not supported by XML format")
+            }
+            x => panic!(
+                r#"Expected `Err(DeError::Unsupported("binary data content is not supported by XML format"))`, but got `{:?}`"#,
+                x
+            ),
+        }
+    }
+}
+
+/// Tests for `Deserializer::text_capture`
+mod text_capture {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::TextCapture;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "$text")]
+        text: String,
+        a: (),
+    }
+
+    fn capture(capture: TextCapture, xml: &str) -> Result<Root, DeError> {
+        let mut de = Deserializer::from_str(xml);
+        de.text_capture(capture);
+        Root::deserialize(&mut de)
+    }
+
+    /// By default each text node is reported separately
+    #[test]
+    fn default() {
+        match from_str::<Root>("<root>pre<a/>post</root>") {
+            Err(DeError::Custom(reason)) => assert_eq!(reason, "duplicate field `$text`"),
+            x => panic!(
+                r#"Expected `Err(Custom("duplicate field `$text`"))`, but got `{:?}`"#,
+                x
+            ),
+        }
+    }
+
+    #[test]
+    fn all() {
+        assert_eq!(
+            capture(TextCapture::All, "<root>pre<a/>post</root>").unwrap(),
+            Root {
+                text: "prepost".into(),
+                a: (),
+            }
+        );
+    }
+
+    #[test]
+    fn leading() {
+        assert_eq!(
+            capture(TextCapture::Leading, "<root>pre<a/>post</root>").unwrap(),
+            Root {
+                text: "pre".into(),
+                a: (),
+            }
+        );
+    }
+
+    #[test]
+    fn trailing() {
+        assert_eq!(
+            capture(TextCapture::Trailing, "<root>pre<a/>post</root>").unwrap(),
+            Root {
+                text: "post".into(),
+                a: (),
+            }
+        );
+    }
+
+    /// Text before the only child element is not a trailing text
+    #[test]
+    fn trailing_without_text() {
+        match capture(TextCapture::Trailing, "<root>pre<a/></root>") {
+            Err(DeError::Custom(reason)) => assert_eq!(reason, "missing field `$text`"),
+            x => panic!(
+                r#"Expected `Err(Custom("missing field `$text`"))`, but got `{:?}`"#,
+                x
+            ),
+        }
+    }
+
+    /// Text without child elements is captured in all modes
+    #[test]
+    fn text_only() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Text {
+            #[serde(rename = "$text")]
+            text: String,
+        }
+
+        for mode in [
+            TextCapture::All,
+            TextCapture::Leading,
+            TextCapture::Trailing,
+        ] {
+            let mut de = Deserializer::from_str("<root>text</root>");
+            de.text_capture(mode);
+            assert_eq!(
+                Text::deserialize(&mut de).unwrap(),
+                Text {
+                    text: "text".into()
+                }
+            );
+        }
+    }
+}
+
+mod attributes_map {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde::de::{MapAccess, Visitor};
+    use std::collections::HashMap;
+    use std::fmt;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@")]
+        attrs: HashMap<String, String>,
+    }
+
+    /// Map that remembers order of its keys
+    #[derive(Debug, PartialEq)]
+    struct Ordered(Vec<(String, String)>);
+
+    impl<'de> Deserialize<'de> for Ordered {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            struct OrderedVisitor;
+
+            impl<'de> Visitor<'de> for OrderedVisitor {
+                type Value = Ordered;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a map")
+                }
+
+                fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: MapAccess<'de>,
+                {
+                    let mut entries = Vec::new();
+                    while let Some(entry) = map.next_entry()? {
+                        entries.push(entry);
+                    }
+                    Ok(Ordered(entries))
+                }
+            }
+
+            deserializer.deserialize_map(OrderedVisitor)
+        }
+    }
+
+    fn ordered(entries: &[(&str, &str)]) -> Ordered {
+        Ordered(
+            entries
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn all() {
+        let data: Root = from_str(r#"<e a="1" b="2" c="3"/>"#).unwrap();
+        assert_eq!(
+            data.attrs,
+            HashMap::from([
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "2".to_string()),
+                ("c".to_string(), "3".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn no_attributes() {
+        let data: Root = from_str("<e/>").unwrap();
+        assert_eq!(data.attrs, HashMap::new());
+    }
+
+    /// Attributes are reported in the document order
+    #[test]
+    fn document_order() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "@")]
+            attrs: Ordered,
+        }
+
+        let data: Root = from_str(r#"<e c="3" a="1" b="2"/>"#).unwrap();
+        assert_eq!(data.attrs, ordered(&[("c", "3"), ("a", "1"), ("b", "2")]));
+    }
+
+    /// Attributes, mapped to other fields, are not included into the map
+    #[test]
+    fn with_other_fields() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "@id")]
+            id: u32,
+            #[serde(rename = "@")]
+            attrs: Ordered,
+            child: String,
+        }
+
+        let data: Root =
+            from_str(r#"<e b="2" id="42" xml:lang="en"><child>text</child></e>"#).unwrap();
+        assert_eq!(
+            data,
+            Root {
+                id: 42,
+                attrs: ordered(&[("b", "2"), ("xml:lang", "en")]),
+                child: "text".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        let data: Root = from_str(r#"<e a="&lt;&amp;&gt;" b="&#x20;"/>"#).unwrap();
+        assert_eq!(
+            data.attrs,
+            HashMap::from([
+                ("a".to_string(), "<&>".to_string()),
+                ("b".to_string(), " ".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn typed_values() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "@")]
+            attrs: HashMap<String, u32>,
+        }
+
+        let data: Root = from_str(r#"<e a="1" b="2"/>"#).unwrap();
+        assert_eq!(
+            data.attrs,
+            HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+    }
+}
+
+/// Tests for `from_str_strict` and `from_reader_strict`
+mod trailing_data {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::{from_reader_strict, from_str_strict};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@attr")]
+        attr: u32,
+    }
+
+    #[test]
+    fn clean() {
+        assert_eq!(
+            from_str_strict::<Root>(r#"<root attr="42"/>"#).unwrap(),
+            Root { attr: 42 }
+        );
+        assert_eq!(
+            from_reader_strict::<_, Root>(r#"<root attr="42"/>"#.as_bytes()).unwrap(),
+            Root { attr: 42 }
+        );
+    }
+
+    /// Whitespaces, comments and processing instructions are allowed after the value
+    #[test]
+    fn ignorable() {
+        assert_eq!(
+            from_str_strict::<Root>(r#"<root attr="42"/>  <!--c-->"#).unwrap(),
+            Root { attr: 42 }
+        );
+        assert_eq!(
+            from_str_strict::<Root>("<root attr=\"42\"/>\n<?pi?>\n").unwrap(),
+            Root { attr: 42 }
+        );
+    }
+
+    #[test]
+    fn text() {
+        match from_str_strict::<Root>(r#"<root attr="42"/>extra"#) {
+            Err(DeError::TrailingData) => {}
+            x => panic!("Expected `Err(TrailingData)`, but got `{:?}`", x),
+        }
+        match from_reader_strict::<_, Root>(r#"<root attr="42"/> <!--c--> extra"#.as_bytes()) {
+            Err(DeError::TrailingData) => {}
+            x => panic!("Expected `Err(TrailingData)`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn second_root() {
+        match from_str_strict::<Root>(r#"<root attr="42"/><root attr="43"/>"#) {
+            Err(DeError::TrailingData) => {}
+            x => panic!("Expected `Err(TrailingData)`, but got `{:?}`", x),
+        }
+    }
+
+    /// Non-strict functions ignore trailing data
+    #[test]
+    fn non_strict() {
+        assert_eq!(
+            quick_xml::de::from_str::<Root>(r#"<root attr="42"/>extra"#).unwrap(),
+            Root { attr: 42 }
+        );
+    }
+}
+
+/// Tests for `Deserializer::duplicate_attributes`
+mod duplicate_attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::DuplicateAttributePolicy;
+    use quick_xml::events::attributes::AttrError;
+    use quick_xml::Error;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@a")]
+        a: u32,
+        #[serde(rename = "@b")]
+        b: Option<u32>,
+    }
+
+    fn parse<'de, T>(policy: DuplicateAttributePolicy, xml: &'de str) -> Result<T, DeError>
+    where
+        T: Deserialize<'de>,
+    {
+        let mut de = Deserializer::from_str(xml);
+        de.duplicate_attributes(policy);
+        T::deserialize(&mut de)
+    }
+
+    #[test]
+    fn error() {
+        match from_str::<Root>(r#"<root a="1" b="2" a="3"/>"#) {
+            Err(DeError::InvalidXml(Error::InvalidAttr(AttrError::Duplicated(17, 5)))) => {}
+            x => panic!(
+                "Expected `Err(InvalidXml(InvalidAttr(Duplicated(17, 5))))`, but got `{:?}`",
+                x
+            ),
+        }
+    }
+
+    #[test]
+    fn first() {
+        assert_eq!(
+            parse::<Root>(
+                DuplicateAttributePolicy::First,
+                r#"<root a="1" b="2" a="3"/>"#
+            )
+            .unwrap(),
+            Root { a: 1, b: Some(2) }
+        );
+    }
+
+    #[test]
+    fn last() {
+        assert_eq!(
+            parse::<Root>(
+                DuplicateAttributePolicy::Last,
+                r#"<root a="1" b="2" a="3"/>"#
+            )
+            .unwrap(),
+            Root { a: 3, b: Some(2) }
+        );
+    }
+
+    /// Policy applies to the attributes collected into a `@` map too
+    #[test]
+    fn attributes_map() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "@")]
+            attrs: BTreeMap<String, u32>,
+        }
+
+        assert_eq!(
+            parse::<Root>(
+                DuplicateAttributePolicy::First,
+                r#"<root a="1" b="2" a="3" a="4"/>"#
+            )
+            .unwrap()
+            .attrs,
+            BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)])
+        );
+        assert_eq!(
+            parse::<Root>(
+                DuplicateAttributePolicy::Last,
+                r#"<root a="1" b="2" a="3" a="4"/>"#
+            )
+            .unwrap()
+            .attrs,
+            BTreeMap::from([("a".to_string(), 4), ("b".to_string(), 2)])
+        );
+    }
+}
+
+/// Tests for `Deserializer::bool_format`
+mod bool_format {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::BoolFormat;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@attr")]
+        attr: Option<bool>,
+        #[serde(rename = "$text")]
+        text: bool,
+    }
+
+    const ON_OFF: BoolFormat = BoolFormat {
+        truthy: &["on"],
+        falsy: &["off"],
+    };
+
+    fn parse<'de, T>(format: BoolFormat, xml: &'de str) -> Result<T, DeError>
+    where
+        T: Deserialize<'de>,
+    {
+        let mut de = Deserializer::from_str(xml);
+        de.bool_format(format);
+        T::deserialize(&mut de)
+    }
+
+    #[test]
+    fn default() {
+        assert_eq!(from_str::<bool>("<x>1</x>").unwrap(), true);
+        assert_eq!(from_str::<bool>("<x>no</x>").unwrap(), false);
+        assert_eq!(from_str::<bool>("<x><![CDATA[yes]]></x>").unwrap(), true);
+        assert_eq!(
+            from_str::<Root>(r#"<x attr="0">true</x>"#).unwrap(),
+            Root {
+                attr: Some(false),
+                text: true,
+            }
+        );
+    }
+
+    #[test]
+    fn xsd() {
+        assert_eq!(parse::<bool>(BoolFormat::XSD, "<x>1</x>").unwrap(), true);
+        assert_eq!(
+            parse::<bool>(BoolFormat::XSD, "<x>false</x>").unwrap(),
+            false
+        );
+        match parse::<bool>(BoolFormat::XSD, "<x>no</x>") {
+            Err(DeError::InvalidBoolean(value)) => assert_eq!(value, "no"),
+            x => panic!(r#"Expected `Err(InvalidBoolean("no"))`, but got `{:?}`"#, x),
+        }
+    }
+
+    #[test]
+    fn custom() {
+        assert_eq!(
+            parse::<Root>(ON_OFF, r#"<x attr="on"><![CDATA[off]]></x>"#).unwrap(),
+            Root {
+                attr: Some(true),
+                text: false,
+            }
+        );
+        assert_eq!(
+            parse::<Vec<bool>>(ON_OFF, "<x>on</x><x>off</x>").unwrap(),
+            vec![true, false]
+        );
+    }
+
+    #[test]
+    fn list() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct List {
+            #[serde(rename = "@list")]
+            list: Vec<bool>,
+        }
+
+        assert_eq!(
+            parse::<List>(ON_OFF, r#"<x list="on off on"/>"#).unwrap(),
+            List {
+                list: vec![true, false, true],
+            }
+        );
+    }
+
+    #[test]
+    fn unrecognized() {
+        match parse::<Root>(ON_OFF, r#"<x attr="true">off</x>"#) {
+            Err(DeError::InvalidBoolean(value)) => assert_eq!(value, "true"),
+            x => panic!(
+                r#"Expected `Err(InvalidBoolean("true"))`, but got `{:?}`"#,
+                x
+            ),
+        }
+        match parse::<Root>(ON_OFF, "<x>maybe</x>") {
+            Err(DeError::InvalidBoolean(value)) => assert_eq!(value, "maybe"),
+            x => panic!(
+                r#"Expected `Err(InvalidBoolean("maybe"))`, but got `{:?}`"#,
+                x
+            ),
+        }
+    }
+}
+
+/// Attributes with the reserved `xml` prefix are matched by their qualified names
+mod xml_prefix {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@xml:lang")]
+        xml_lang: String,
+        #[serde(rename = "@lang")]
+        lang: Option<String>,
+    }
+
+    #[test]
+    fn implicit_declaration() {
+        let data: Root = from_str(r#"<root xml:lang="en"/>"#).unwrap();
+
+        assert_eq!(
+            data,
+            Root {
+                xml_lang: "en".into(),
+                lang: None,
+            }
+        );
+    }
+
+    #[test]
+    fn explicit_declaration() {
+        let data: Root = from_str(
+            r#"<root xmlns:xml="http://www.w3.org/XML/1998/namespace" xml:lang="en" lang="de"/>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            data,
+            Root {
+                xml_lang: "en".into(),
+                lang: Some("de".into()),
+            }
+        );
+    }
+
+    /// Without `@xml:lang` field the attribute is matched by its local name
+    #[test]
+    fn local_name() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "@lang")]
+            lang: String,
+        }
+
+        let data: Root = from_str(r#"<root xml:lang="en"/>"#).unwrap();
+
+        assert_eq!(data, Root { lang: "en".into() });
+    }
+}
+
+mod limits {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::EntityResolver;
+    use quick_xml::events::BytesDocType;
+    use quick_xml::Error;
+    use std::num::NonZeroUsize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Node {
+        node: Option<Box<Node>>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct List {
+        item: Vec<String>,
+    }
+
+    /// Returns XML with `depth` nested `<node>` elements
+    fn nested(depth: usize) -> String {
+        format!("{}{}", "<node>".repeat(depth), "</node>".repeat(depth))
+    }
+
+    #[test]
+    fn max_depth() {
+        let xml = nested(3);
+
+        let mut de = Deserializer::from_str(&xml);
+        de.max_depth(NonZeroUsize::new(3));
+        assert!(Node::deserialize(&mut de).is_ok());
+
+        let mut de = Deserializer::from_str(&xml);
+        de.max_depth(NonZeroUsize::new(2));
+        match Node::deserialize(&mut de) {
+            Err(DeError::DepthLimitExceeded(max)) => assert_eq!(max.get(), 2),
+            x => panic!("Expected `Err(DepthLimitExceeded(2))`, but got `{:?}`", x),
+        }
+    }
+
+    /// Skipped elements do not change the current depth
+    #[test]
+    fn max_depth_after_skipped_elements() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            item: String,
+        }
+
+        let mut de =
+            Deserializer::from_str("<root><a><b/></a><a/><a><b/></a><item>1</item></root>");
+        de.max_depth(NonZeroUsize::new(3));
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root { item: "1".into() }
+        );
+    }
+
+    #[test]
+    fn max_events() {
+        // 2 events for each item and 1 event for the list
+        let xml = "<list><item>1</item><item>2</item></list>";
+
+        let mut de = Deserializer::from_str(xml);
+        de.max_events(NonZeroUsize::new(5));
+        assert!(List::deserialize(&mut de).is_ok());
+
+        let mut de = Deserializer::from_str(xml);
+        de.max_events(NonZeroUsize::new(4));
+        match List::deserialize(&mut de) {
+            Err(DeError::EventLimitExceeded(max)) => assert_eq!(max.get(), 4),
+            x => panic!("Expected `Err(EventLimitExceeded(4))`, but got `{:?}`", x),
+        }
+    }
+
+    /// Consequent text and CDATA nodes are counted as one text
+    #[test]
+    fn max_text_len() {
+        let xml = "<item>text &amp;<![CDATA[cdata]]></item>";
+
+        let mut de = Deserializer::from_str(xml);
+        de.max_text_len(NonZeroUsize::new(11));
+        assert_eq!(String::deserialize(&mut de).unwrap(), "text &cdata");
+
+        let mut de = Deserializer::from_str(xml);
+        de.max_text_len(NonZeroUsize::new(10));
+        match String::deserialize(&mut de) {
+            Err(DeError::TextLimitExceeded(max)) => assert_eq!(max.get(), 10),
+            x => panic!("Expected `Err(TextLimitExceeded(10))`, but got `{:?}`", x),
+        }
+    }
+
+    /// Resolves every entity to a text of 16 bytes
+    struct Expander;
+
+    impl EntityResolver for Expander {
+        type Error = std::convert::Infallible;
+
+        fn capture(&mut self, _doctype: BytesDocType) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn resolve(&self, _entity: &str) -> Option<&str> {
+            Some("0123456789abcdef")
+        }
+    }
+
+    /// Replacement texts of all entities in the document are counted together
+    #[test]
+    fn max_entity_expansion() {
+        let xml = "<list><item>&a;</item><item>&b;</item></list>";
+
+        let mut de = Deserializer::from_str_with_resolver(xml, Expander);
+        de.max_entity_expansion(NonZeroUsize::new(32));
+        assert!(List::deserialize(&mut de).is_ok());
+
+        let mut de = Deserializer::from_str_with_resolver(xml, Expander);
+        de.max_entity_expansion(NonZeroUsize::new(31));
+        match List::deserialize(&mut de) {
+            Err(DeError::ExpansionLimitExceeded(max)) => assert_eq!(max.get(), 31),
+            x => panic!(
+                "Expected `Err(ExpansionLimitExceeded(31))`, but got `{:?}`",
+                x
+            ),
+        }
+    }
+
+    mod untrusted {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn ordinary() {
+            let mut de = Deserializer::untrusted("<list><item>1</item><item>2</item></list>");
+            assert_eq!(
+                List::deserialize(&mut de).unwrap(),
+                List {
+                    item: vec!["1".into(), "2".into()],
+                }
+            );
+        }
+
+        #[test]
+        fn deeply_nested() {
+            let xml = nested(10_000);
+            match Node::deserialize(&mut Deserializer::untrusted(&xml)) {
+                Err(DeError::DepthLimitExceeded(max)) => assert_eq!(max.get(), 128),
+                x => panic!("Expected `Err(DepthLimitExceeded(128))`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn deeply_nested_reader() {
+            let xml = nested(10_000);
+            match Node::deserialize(&mut Deserializer::untrusted_reader(xml.as_bytes())) {
+                Err(DeError::DepthLimitExceeded(max)) => assert_eq!(max.get(), 128),
+                x => panic!("Expected `Err(DepthLimitExceeded(128))`, but got `{:?}`", x),
+            }
+        }
+
+        #[test]
+        fn large_text() {
+            let xml = format!("<item>{}</item>", "a".repeat(2 << 20));
+            match String::deserialize(&mut Deserializer::untrusted(&xml)) {
+                Err(DeError::TextLimitExceeded(max)) => assert_eq!(max.get(), 1 << 20),
+                x => panic!(
+                    "Expected `Err(TextLimitExceeded(1048576))`, but got `{:?}`",
+                    x
+                ),
+            }
+        }
+
+        #[test]
+        fn large_text_reader() {
+            let xml = format!("<item>{}</item>", "a".repeat(2 << 20));
+            match String::deserialize(&mut Deserializer::untrusted_reader(xml.as_bytes())) {
+                Err(DeError::TextLimitExceeded(max)) => assert_eq!(max.get(), 1 << 20),
+                x => panic!(
+                    "Expected `Err(TextLimitExceeded(1048576))`, but got `{:?}`",
+                    x
+                ),
+            }
+        }
+
+        /// Many small text nodes are merged to a large text
+        #[test]
+        fn large_merged_text() {
+            let xml = format!("<item>{}</item>", "aaaa<!---->".repeat(1 << 19));
+            match String::deserialize(&mut Deserializer::untrusted(&xml)) {
+                Err(DeError::TextLimitExceeded(max)) => assert_eq!(max.get(), 1 << 20),
+                x => panic!(
+                    "Expected `Err(TextLimitExceeded(1048576))`, but got `{:?}`",
+                    x
+                ),
+            }
+        }
+
+        #[test]
+        fn too_many_events() {
+            let xml = format!("<list>{}</list>", "<item>1</item>".repeat(500_000));
+            match List::deserialize(&mut Deserializer::untrusted(&xml)) {
+                Err(DeError::EventLimitExceeded(max)) => assert_eq!(max.get(), 1_000_000),
+                x => panic!(
+                    "Expected `Err(EventLimitExceeded(1000000))`, but got `{:?}`",
+                    x
+                ),
+            }
+        }
+
+        /// Entities, defined in the DTD, are not expanded
+        #[test]
+        fn entity_expansion() {
+            let xml = r#"<!DOCTYPE item [
+                <!ENTITY a "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa">
+                <!ENTITY b "&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;&a;">
+            ]>
+            <item>&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;&b;</item>"#;
+            match String::deserialize(&mut Deserializer::untrusted(xml)) {
+                Err(DeError::InvalidXml(Error::EscapeError(_))) => {}
+                x => panic!(
+                    "Expected `Err(InvalidXml(EscapeError(_)))`, but got `{:?}`",
+                    x
+                ),
+            }
+        }
+    }
+}
+
+/// Tests for `Deserializer::with_include_resolver`
+mod include {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Root {
+        item: Vec<String>,
+    }
+
+    /// Returns events of an `<item>` element with the specified text
+    fn item(text: &'static str) -> Vec<Event<'static>> {
+        vec![
+            Event::Start(BytesStart::new("item")),
+            Event::Text(BytesText::new(text)),
+            Event::End(BytesEnd::new("item")),
+        ]
+    }
+
+    #[test]
+    fn splice() {
+        let mut de = Deserializer::from_str(
+            r#"<root xmlns:xi="http://www.w3.org/2001/XInclude"><item>first</item><xi:include href="second.xml"/><item>third</item></root>"#,
+        );
+        de.with_include_resolver(|href| match href {
+            "second.xml" => Ok(item("second")),
+            _ => Err(DeError::Custom(format!("unknown include `{}`", href))),
+        });
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                item: vec!["first".into(), "second".into(), "third".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn nested() {
+        let mut de = Deserializer::from_str(
+            r#"<root xmlns:xi="http://www.w3.org/2001/XInclude"><xi:include href="a.xml"></xi:include></root>"#,
+        );
+        de.with_include_resolver(|href| match href {
+            "a.xml" => {
+                let mut events = item("a");
+                events.push(Event::Empty(
+                    BytesStart::new("xi:include").with_attributes([("href", "b.xml")]),
+                ));
+                Ok(events)
+            }
+            "b.xml" => Ok(item("b")),
+            _ => Err(DeError::Custom(format!("unknown include `{}`", href))),
+        });
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                item: vec!["a".into(), "b".into()],
+            }
+        );
+    }
+
+    #[test]
+    fn cycle() {
+        let mut de = Deserializer::from_str(
+            r#"<root xmlns:xi="http://www.w3.org/2001/XInclude"><xi:include href="a.xml"/></root>"#,
+        );
+        de.with_include_resolver(|href| {
+            let next = if href == "a.xml" { "b.xml" } else { "a.xml" };
+            Ok(vec![Event::Empty(
+                BytesStart::new("xi:include").with_attributes([("href", next)]),
+            )])
+        });
+
+        match Root::deserialize(&mut de) {
+            Err(DeError::Custom(e)) => assert_eq!(e, "cyclic include of `a.xml`"),
+            x => panic!(
+                "Expected `Err(Custom(\"cyclic include of `a.xml`\"))`, but got `{:?}`",
+                x
+            ),
+        }
+    }
+
+    /// Only elements in the XInclude namespace are replaced
+    #[test]
+    fn not_xinclude() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Schema {
+            include: String,
+        }
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Root {
+            include: String,
+            schema: Schema,
+        }
+
+        let mut de = Deserializer::from_str(
+            r#"<root><include href="a.xml">plain</include><schema xmlns:xs="http://www.w3.org/2001/XMLSchema"><xs:include>schema</xs:include></schema></root>"#,
+        );
+        de.with_include_resolver(|href| {
+            Err(DeError::Custom(format!("unexpected include `{}`", href)))
+        });
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                include: "plain".into(),
+                schema: Schema {
+                    include: "schema".into(),
+                },
+            }
+        );
+    }
+
+    /// The prefix bound to the XInclude namespace does not matter
+    #[test]
+    fn default_namespace() {
+        let mut de = Deserializer::from_str(
+            r#"<root><include xmlns="http://www.w3.org/2001/XInclude" href="a.xml"/></root>"#,
+        );
+        de.with_include_resolver(|href| match href {
+            "a.xml" => Ok(item("a")),
+            _ => Err(DeError::Custom(format!("unknown include `{}`", href))),
+        });
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                item: vec!["a".into()],
+            }
+        );
+    }
+}
+
+/// Tests for `Deserializer::whitespace_as_none`
+mod whitespace_as_none {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Root {
+        string: Option<String>,
+        number: Option<i32>,
+    }
+
+    fn deserialize(xml: &str, enable: bool) -> Result<Root, DeError> {
+        let mut de = Deserializer::from_str(xml);
+        de.whitespace_as_none(enable);
+        Root::deserialize(&mut de)
+    }
+
+    #[test]
+    fn whitespace() {
+        let xml = "<root>\n  <string>\n    </string>\n  <number>\n    </number>\n</root>";
+        assert_eq!(
+            deserialize(xml, true).unwrap(),
+            Root {
+                string: None,
+                number: None,
+            }
+        );
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(
+            deserialize("<root><string/><number></number></root>", true).unwrap(),
+            Root {
+                string: None,
+                number: None,
+            }
+        );
+    }
+
+    #[test]
+    fn significant_text() {
+        assert_eq!(
+            deserialize(
+                "<root><string>\n  text\n</string><number> 42 </number></root>",
+                true
+            )
+            .unwrap(),
+            Root {
+                string: Some("text".into()),
+                number: Some(42),
+            }
+        );
+    }
+
+    #[test]
+    fn cdata() {
+        assert_eq!(
+            deserialize(
+                "<root><string><![CDATA[  ]]></string><number/></root>",
+                true
+            )
+            .unwrap(),
+            Root {
+                string: Some("  ".into()),
+                number: None,
+            }
+        );
+    }
+
+    #[test]
+    fn disabled() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Root {
+            string: Option<String>,
+        }
+
+        let mut de = Deserializer::from_str("<root><string>\n  </string></root>");
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                string: Some("".into()),
+            }
+        );
+
+        match deserialize("<root><number>\n  </number></root>", false) {
+            Err(DeError::InvalidInt(_)) => {}
+            x => panic!("Expected `Err(InvalidInt(_))`, but got `{:?}`", x),
+        }
+    }
+
+    /// Elements with attributes are not empty even without content
+    #[test]
+    fn attributes() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Root {
+            opt: Option<Inner>,
+        }
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Inner {
+            #[serde(rename = "@a")]
+            a: String,
+        }
+
+        let mut de = Deserializer::from_str(r#"<root><opt a="1"/></root>"#);
+        de.whitespace_as_none(true);
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                opt: Some(Inner { a: "1".into() }),
+            }
+        );
+    }
+
+    #[test]
+    fn top_level() {
+        let mut de = Deserializer::from_str("<root>\n</root>");
+        de.whitespace_as_none(true);
+        assert_eq!(Option::<String>::deserialize(&mut de).unwrap(), None);
+    }
+
+    #[test]
+    fn sequence() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct List {
+            item: Vec<Option<u32>>,
+        }
+
+        let mut de =
+            Deserializer::from_str("<list><item>1</item><item> </item><item>3</item></list>");
+        de.whitespace_as_none(true);
+        assert_eq!(
+            List::deserialize(&mut de).unwrap(),
+            List {
+                item: vec![Some(1), None, Some(3)],
+            }
+        );
+    }
+}
+
+/// Unknown elements are captured by a `#[serde(flatten)]` map
+mod flatten_unknown {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Root {
+        known: u32,
+        #[serde(flatten)]
+        extra: HashMap<String, String>,
+    }
+
+    fn parse(xml: &str) -> Root {
+        let mut de = Deserializer::from_str(xml);
+        de.text_elements_as_strings(true);
+        Root::deserialize(&mut de).unwrap()
+    }
+
+    #[test]
+    fn one() {
+        let data = parse("<e><known>1</known><extra>2</extra></e>");
+        assert_eq!(
+            data,
+            Root {
+                known: 1,
+                extra: HashMap::from([("extra".to_string(), "2".to_string())]),
+            }
+        );
+    }
+
+    #[test]
+    fn several() {
+        let data = parse("<e><first>a</first><known>1</known><second>b</second></e>");
+        assert_eq!(
+            data,
+            Root {
+                known: 1,
+                extra: HashMap::from([
+                    ("first".to_string(), "a".to_string()),
+                    ("second".to_string(), "b".to_string()),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn none() {
+        let data = parse("<e><known>1</known></e>");
+        assert_eq!(
+            data,
+            Root {
+                known: 1,
+                extra: HashMap::new(),
+            }
+        );
+    }
+    /// By default text elements are deserialized as maps, so structs with
+    /// a `$text` field can be flattened
+    mod disabled {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Text {
+            #[serde(rename = "$text")]
+            text: String,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Inner {
+            a: Text,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Outer {
+            #[serde(flatten)]
+            inner: Inner,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        #[serde(untagged)]
+        enum Untagged {
+            Text(Text),
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Root {
+            a: Untagged,
+        }
+
+        #[test]
+        fn flattened_struct() {
+            let data: Outer = from_str("<root><a>text</a></root>").unwrap();
+            assert_eq!(
+                data,
+                Outer {
+                    inner: Inner {
+                        a: Text {
+                            text: "text".into()
+                        }
+                    }
+                }
+            );
+        }
+
+        #[test]
+        fn untagged_enum() {
+            let data: Root = from_str("<root><a>text</a></root>").unwrap();
+            assert_eq!(
+                data,
+                Root {
+                    a: Untagged::Text(Text {
+                        text: "text".into()
+                    })
+                }
+            );
+        }
+    }
+}
+
+/// Errors contain the path to the failed value when `Deserializer::track_path` is enabled
+mod track_path {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Root {
+        items: Items,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Items {
+        item: Vec<Item>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Item {
+        #[serde(rename = "@id")]
+        id: u32,
+        price: u32,
+    }
+
+    fn deserialize(xml: &str) -> DeError {
+        let mut de = Deserializer::from_str(xml);
+        de.track_path(true);
+        Root::deserialize(&mut de).unwrap_err()
+    }
+
+    #[test]
+    fn missing_field() {
+        let error = deserialize("<root><items><item id='1'/></items></root>");
+        match error {
+            DeError::WithPath(ref path, ref e) => {
+                assert_eq!(path, &["root", "items", "item[0]"]);
+                assert!(matches!(**e, DeError::Custom(ref e) if e == "missing field `price`"));
+            }
+            e => panic!("Expected `Err(WithPath(..))`, but got `{:?}`", e),
+        }
+        assert_eq!(
+            error.to_string(),
+            "at root > items > item[0]: missing field `price`"
+        );
+    }
+
+    #[test]
+    fn element() {
+        let error = deserialize(
+            "<root><items>\
+                <item id='1'><price>1</price></item>\
+                <item id='2'><price>1</price></item>\
+                <item id='3'><price>1</price></item>\
+                <item id='4'><price>x</price></item>\
+            </items></root>",
+        );
+        assert_eq!(
+            error.to_string(),
+            "at root > items > item[3] > price: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn attribute() {
+        let error = deserialize("<root><items><item id='x'><price>1</price></item></items></root>");
+        assert_eq!(
+            error.to_string(),
+            "at root > items > item[0] > @id: invalid digit found in string"
+        );
+    }
+
+    /// Attributes in the path are prefixed with `Deserializer::attribute_prefix`
+    #[test]
+    fn attribute_prefix() {
+        #[derive(Debug, Deserialize)]
+        struct Item {
+            #[serde(rename = "_id")]
+            _id: u32,
+        }
+
+        let mut de = Deserializer::from_str("<item id='x'/>");
+        de.track_path(true).attribute_prefix("_");
+        assert_eq!(
+            Item::deserialize(&mut de).unwrap_err().to_string(),
+            "at item > _id: invalid digit found in string"
+        );
+    }
+
+    /// Without the setting errors are reported as is
+    #[test]
+    fn disabled() {
+        let error = Root::deserialize(&mut Deserializer::from_str(
+            "<root><items><item id='1'/></items></root>",
+        ))
+        .unwrap_err();
+        match error {
+            DeError::Custom(e) => assert_eq!(e, "missing field `price`"),
+            e => panic!("Expected `Err(Custom(..))`, but got `{:?}`", e),
+        }
+    }
+}
+
+/// Functions registered by `Deserializer::with_scalar_hook` preprocess text
+/// content of the specified elements only
+mod scalar_hook {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Root {
+        date: String,
+        other: String,
+        #[serde(rename = "@date")]
+        attr: String,
+    }
+
+    #[test]
+    fn targeted_element_only() {
+        let mut de = Deserializer::from_str(
+            r#"<root date="2023-01-02Z"><date>2023-01-02Z</date><other>2023-01-02Z</other></root>"#,
+        );
+        de.with_scalar_hook("date", |text| text.trim_end_matches('Z').into());
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                date: "2023-01-02".into(),
+                other: "2023-01-02Z".into(),
+                attr: "2023-01-02Z".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn number() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Root {
+            number: Vec<u32>,
+        }
+
+        let mut de =
+            Deserializer::from_str("<root><number>1_000</number><number>2_000_000</number></root>");
+        de.with_scalar_hook("number", |text| text.replace('_', "").into());
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                number: vec![1_000, 2_000_000]
+            }
+        );
+    }
+
+    /// The hook is not applied to the `$text` field of a struct, deserialized
+    /// from the element with the registered name
+    #[test]
+    fn text_field() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Date {
+            #[serde(rename = "@zone")]
+            zone: String,
+            #[serde(rename = "$text")]
+            value: String,
+        }
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Root {
+            date: Date,
+        }
+
+        let mut de = Deserializer::from_str(r#"<root><date zone="Z">2023-01-02Z</date></root>"#);
+        de.with_scalar_hook("date", |text| text.trim_end_matches('Z').into());
+        de.with_scalar_hook("zone", |_| "UTC".into());
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                date: Date {
+                    zone: "Z".into(),
+                    value: "2023-01-02Z".into(),
+                },
+            }
+        );
+    }
+}
+
+/// The same field filled either from an attribute or from a text content
+/// using serde aliases
+mod text_or_attribute {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Value {
+        #[serde(alias = "@val", alias = "$text")]
+        val: u32,
+        #[serde(rename = "@unit", default)]
+        unit: Option<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        x: Vec<Value>,
+    }
+
+    #[test]
+    fn attribute() {
+        let data: Value = from_str(r#"<x val="1" unit="m"/>"#).unwrap();
+        assert_eq!(
+            data,
+            Value {
+                val: 1,
+                unit: Some("m".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn text() {
+        let data: Value = from_str(r#"<x unit="m">1</x>"#).unwrap();
+        assert_eq!(
+            data,
+            Value {
+                val: 1,
+                unit: Some("m".into()),
+            }
+        );
+    }
+
+    #[test]
+    fn element() {
+        let data: Value = from_str(r#"<x><val>1</val></x>"#).unwrap();
+        assert_eq!(data, Value { val: 1, unit: None });
+    }
+
+    #[test]
+    fn mixed() {
+        let data: Root = from_str(r#"<root><x val="1"/><x>2</x></root>"#).unwrap();
+        assert_eq!(
+            data,
+            Root {
+                x: vec![Value { val: 1, unit: None }, Value { val: 2, unit: None }],
+            }
+        );
+    }
+
+    #[test]
+    fn both() {
+        match from_str::<Value>(r#"<x val="1">2</x>"#) {
+            Err(DeError::Custom(reason)) => assert_eq!(reason, "duplicate field `val`"),
+            x => panic!(
+                "Expected `Err(Custom(duplicate field `val`))`, but got `{:?}`",
+                x
+            ),
+        }
+    }
+}
+
+/// Sequences of elements with the same name, where the enum variant of each
+/// item is selected by an attribute
+mod variant_attribute {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum ItemEnum {
+        A {
+            #[serde(rename = "@value")]
+            value: u32,
+        },
+        B {
+            #[serde(rename = "@name")]
+            name: String,
+            #[serde(default)]
+            tag: Vec<String>,
+        },
+        C,
+    }
+
+    const XML: &str = r#"
+        <items>
+            <item type="b" name="first"><tag>x</tag><tag>y</tag></item>
+            <item type="a" value="1"/>
+            <item type="c"/>
+            <item type="a" value="2"/>
+            <item type="b" name="last"/>
+        </items>
+    "#;
+
+    fn expected() -> Vec<ItemEnum> {
+        vec![
+            ItemEnum::B {
+                name: "first".into(),
+                tag: vec!["x".into(), "y".into()],
+            },
+            ItemEnum::A { value: 1 },
+            ItemEnum::C,
+            ItemEnum::A { value: 2 },
+            ItemEnum::B {
+                name: "last".into(),
+                tag: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn named_field() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Items {
+            item: Vec<ItemEnum>,
+        }
+
+        let mut de = Deserializer::from_str(XML);
+        de.variant_attribute("type");
+
+        assert_eq!(
+            Items::deserialize(&mut de).unwrap(),
+            Items { item: expected() }
+        );
+    }
+
+    #[test]
+    fn value_field() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Items {
+            #[serde(rename = "$value")]
+            items: Vec<ItemEnum>,
+        }
+
+        let mut de = Deserializer::from_str(XML);
+        de.variant_attribute("type");
+
+        assert_eq!(
+            Items::deserialize(&mut de).unwrap(),
+            Items { items: expected() }
+        );
+    }
+
+    #[test]
+    fn unknown_variant() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Items {
+            item: Vec<ItemEnum>,
+        }
+
+        let mut de =
+            Deserializer::from_str(r#"<items><item type="a" value="1"/><item type="d"/></items>"#);
+        de.variant_attribute("type");
+
+        match Items::deserialize(&mut de) {
+            Err(DeError::Custom(e)) => {
+                assert_eq!(e, "unknown variant `d`, expected one of `a`, `b`, `c`")
+            }
+            x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+        }
+    }
+}
+
+/// Tests for `Deserializer::minimized_bool_attrs`
+mod minimized_bool_attrs {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Input {
+        #[serde(rename = "@type")]
+        type_: String,
+        #[serde(rename = "@disabled")]
+        disabled: bool,
+        #[serde(rename = "@checked")]
+        checked: Option<bool>,
+    }
+
+    fn parse<'de, T>(xml: &'de str) -> Result<T, DeError>
+    where
+        T: Deserialize<'de>,
+    {
+        let mut de = Deserializer::from_str(xml);
+        de.minimized_bool_attrs(true);
+        T::deserialize(&mut de)
+    }
+
+    #[test]
+    fn valueless() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Disabled {
+            #[serde(rename = "@disabled")]
+            disabled: bool,
+        }
+
+        assert_eq!(
+            parse::<Disabled>("<input disabled/>").unwrap(),
+            Disabled { disabled: true }
+        );
+    }
+
+    #[test]
+    fn mixed() {
+        assert_eq!(
+            parse::<Input>(r#"<input type="checkbox" disabled checked/>"#).unwrap(),
+            Input {
+                type_: "checkbox".into(),
+                disabled: true,
+                checked: Some(true),
+            }
+        );
+        assert_eq!(
+            parse::<Input>(r#"<input disabled='false' type="text"/>"#).unwrap(),
+            Input {
+                type_: "text".into(),
+                disabled: false,
+                checked: None,
+            }
+        );
+    }
+
+    /// Attributes without value are `true` with any configured boolean format
+    #[test]
+    fn bool_format() {
+        let mut de = Deserializer::from_str(r#"<input type="checkbox" disabled checked="no"/>"#);
+        de.minimized_bool_attrs(true);
+        de.bool_format(quick_xml::de::BoolFormat {
+            truthy: &["yes"],
+            falsy: &["no"],
+        });
+
+        assert_eq!(
+            Input::deserialize(&mut de).unwrap(),
+            Input {
+                type_: "checkbox".into(),
+                disabled: true,
+                checked: Some(false),
+            }
+        );
+    }
+
+    #[test]
+    fn strict_by_default() {
+        match from_str::<Input>(r#"<input type="checkbox" disabled/>"#) {
+            Err(DeError::InvalidXml(_)) => {}
+            x => panic!("Expected `Err(InvalidXml(_))`, but got `{:?}`", x),
+        }
+    }
+}
+
+/// Tests for `XsDuration`
+#[cfg(feature = "xs-duration")]
+mod xs_duration {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::XsDuration;
+    use std::time::Duration;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Task {
+        #[serde(rename = "@timeout")]
+        timeout: XsDuration,
+        period: Option<XsDuration>,
+    }
+
+    #[test]
+    fn full() {
+        let task: Task =
+            from_str(r#"<task timeout="-P1Y2M3DT4H5M6.789S"><period>P3D</period></task>"#).unwrap();
+        assert_eq!(
+            task,
+            Task {
+                timeout: XsDuration {
+                    negative: true,
+                    years: 1,
+                    months: 2,
+                    days: 3,
+                    hours: 4,
+                    minutes: 5,
+                    seconds: 6,
+                    nanoseconds: 789_000_000,
+                },
+                period: Some(XsDuration {
+                    days: 3,
+                    ..XsDuration::default()
+                }),
+            }
+        );
+        assert_eq!(task.timeout.to_std(), None);
+        assert_eq!(
+            task.period.unwrap().to_std(),
+            Some(Duration::from_secs(3 * 24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn time_only() {
+        let task: Task = from_str(r#"<task timeout="PT90M0.5S"/>"#).unwrap();
+        assert_eq!(
+            task.timeout,
+            XsDuration {
+                minutes: 90,
+                nanoseconds: 500_000_000,
+                ..XsDuration::default()
+            }
+        );
+        assert_eq!(
+            task.timeout.to_std(),
+            Some(Duration::from_millis(5_400_500))
+        );
+
+        assert_eq!(
+            "PT1H".parse::<XsDuration>().unwrap(),
+            XsDuration {
+                hours: 1,
+                ..XsDuration::default()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        match from_str::<Task>(r#"<task timeout="P1H"/>"#) {
+            Err(DeError::Custom(e)) => assert_eq!(e, "Invalid xs:duration value 'P1H'"),
+            x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+        }
+        for invalid in [
+            "", "P", "PT", "1D", "-P", "P1", "P1D1Y", "PT1H1D", "P1DT", "PT.5S", "PT1.S", "P-1D",
+            "P1.5D", "P 1D",
+        ] {
+            match invalid.parse::<XsDuration>() {
+                Err(DeError::Custom(e)) => {
+                    assert_eq!(e, format!("Invalid xs:duration value '{}'", invalid))
+                }
+                x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+            }
+        }
+    }
+}
+
+/// Tests for `Deserializer::deny_unknown`
+mod deny_unknown {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@id")]
+        id: u32,
+        item: Vec<Item>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        #[serde(rename = "$text")]
+        text: String,
+    }
+
+    fn parse<'de, T>(xml: &'de str) -> Result<T, DeError>
+    where
+        T: Deserialize<'de>,
+    {
+        let mut de = Deserializer::from_str(xml);
+        de.deny_unknown(true);
+        T::deserialize(&mut de)
+    }
+
+    #[test]
+    fn known() {
+        assert_eq!(
+            parse::<Root>(r#"<root xmlns:x="urn:x" id="1"><item>a</item><item>b</item></root>"#)
+                .unwrap(),
+            Root {
+                id: 1,
+                item: vec![Item { text: "a".into() }, Item { text: "b".into() },],
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_element() {
+        let xml = r#"<root id="1"><item>a</item><other>b</other></root>"#;
+        assert_eq!(from_str::<Root>(xml).unwrap().item.len(), 1);
+
+        match parse::<Root>(xml) {
+            Err(DeError::UnknownField { name, position }) => {
+                assert_eq!(name, "other");
+                assert_eq!(position, 27);
+                assert_eq!(&xml[position..position + 7], "<other>");
+            }
+            x => panic!("Expected `Err(UnknownField {{ .. }})`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn unknown_nested_element() {
+        let xml = r#"<root id="1"><item>a<b/></item></root>"#;
+        match parse::<Root>(xml) {
+            Err(DeError::UnknownField { name, position }) => {
+                assert_eq!(name, "b");
+                assert_eq!(&xml[position..], "<b/></item></root>");
+            }
+            x => panic!("Expected `Err(UnknownField {{ .. }})`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn unknown_attribute() {
+        let xml = r#"<root id="1" x:lang="en"><item>a</item></root>"#;
+        assert_eq!(from_str::<Root>(xml).unwrap().id, 1);
+
+        match parse::<Root>(xml) {
+            Err(DeError::UnknownField { name, position }) => {
+                assert_eq!(name, "@x:lang");
+                assert_eq!(position, 13);
+                assert_eq!(&xml[position..position + 6], "x:lang");
+            }
+            x => panic!("Expected `Err(UnknownField {{ .. }})`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn maps() {
+        assert_eq!(
+            parse::<HashMap<String, String>>(r#"<root a="1"><b>2</b></root>"#).unwrap(),
+            HashMap::from([("@a".into(), "1".into()), ("b".into(), "2".into())])
+        );
+    }
+}
+
+/// Tests for `Deserializer::text_field_name` and `Deserializer::attribute_prefix`
+mod special_names {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn text_field_name() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "#text")]
+            text: String,
+        }
+
+        let mut de = Deserializer::from_str("<root>content</root>");
+        de.text_field_name("#text");
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                text: "content".into()
+            }
+        );
+    }
+
+    #[test]
+    fn text_variant() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        enum Choice {
+            #[serde(rename = "#text")]
+            Text(String),
+            A,
+        }
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "$value")]
+            items: Vec<Choice>,
+        }
+
+        let mut de = Deserializer::from_str("<root><A/>text</root>");
+        de.text_field_name("#text");
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                items: vec![Choice::A, Choice::Text("text".into())]
+            }
+        );
+    }
+
+    /// The default name is not special anymore
+    #[test]
+    fn default_text_field_name() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "$text")]
+            text: String,
+        }
+
+        let mut de = Deserializer::from_str("<root>content</root>");
+        de.text_field_name("#text");
+
+        match Root::deserialize(&mut de) {
+            Err(DeError::Custom(reason)) => assert_eq!(reason, "missing field `$text`"),
+            x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn attribute_prefix() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "attr_id")]
+            attribute: u32,
+            id: u32,
+        }
+
+        let mut de = Deserializer::from_str(r#"<root id="1"><id>2</id></root>"#);
+        de.attribute_prefix("attr_");
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                attribute: 1,
+                id: 2
+            }
+        );
+    }
+
+    /// A field named as the prefix receives all unmapped attributes
+    #[test]
+    fn attributes_field() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "attr_id")]
+            id: u32,
+            #[serde(rename = "attr_")]
+            other: HashMap<String, String>,
+        }
+
+        let mut de = Deserializer::from_str(r#"<root id="1" a="2"/>"#);
+        de.attribute_prefix("attr_");
+
+        assert_eq!(
+            Root::deserialize(&mut de).unwrap(),
+            Root {
+                id: 1,
+                other: HashMap::from([("a".to_string(), "2".to_string())]),
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "text field name must not be empty")]
+    fn empty_text_field_name() {
+        Deserializer::from_str("<root/>").text_field_name("");
+    }
+
+    #[test]
+    #[should_panic(expected = "attribute prefix must not be empty")]
+    fn empty_attribute_prefix() {
+        Deserializer::from_str("<root/>").attribute_prefix("");
+    }
+}
+
+/// Whitespaces around numbers are ignored and a leading `+` is allowed
+mod number_spaces {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn signed() {
+        assert_eq!(from_str::<i32>("<n> +42 </n>").unwrap(), 42);
+    }
+
+    #[test]
+    fn unsigned() {
+        assert_eq!(from_str::<u32>("<n> +42 </n>").unwrap(), 42);
+    }
+
+    #[test]
+    fn float() {
+        assert_eq!(from_str::<f64>("<f>  1.5 </f>").unwrap(), 1.5);
+    }
+
+    #[test]
+    fn fields() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct Root {
+            #[serde(rename = "@attr")]
+            attr: u32,
+            n: i32,
+            f: f64,
+        }
+
+        let expected = Root {
+            attr: 7,
+            n: 42,
+            f: 1.5,
+        };
+        let xml = "<root attr=' +7 '><n> +42 </n><f>\t1.5\n</f></root>";
+        assert_eq!(from_str::<Root>(xml).unwrap(), expected);
+
+        // Whitespaces are ignored even when text is not trimmed
+        let mut de = Deserializer::from_str(xml);
+        de.trim_text(false);
+        assert_eq!(Root::deserialize(&mut de).unwrap(), expected);
+    }
+}
+
+/// Tests for deserialization of `char`, which requires exactly one character
+mod char_ {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        #[serde(rename = "@attr")]
+        attr: char,
+    }
+
+    #[test]
+    fn single() {
+        assert_eq!(from_str::<char>("<root>r</root>").unwrap(), 'r');
+        assert_eq!(
+            from_str::<Root>(r#"<root attr="r"/>"#).unwrap(),
+            Root { attr: 'r' }
+        );
+    }
+
+    #[test]
+    fn escaped() {
+        assert_eq!(from_str::<char>("<root>&lt;</root>").unwrap(), '<');
+        assert_eq!(
+            from_str::<Root>(r#"<root attr="&#x20;"/>"#).unwrap(),
+            Root { attr: ' ' }
+        );
+    }
+
+    #[test]
+    fn multi_byte() {
+        assert_eq!(from_str::<char>("<root>€</root>").unwrap(), '€');
+        assert_eq!(
+            from_str::<Root>(r#"<root attr="€"/>"#).unwrap(),
+            Root { attr: '€' }
+        );
+    }
+
+    #[test]
+    fn two_chars() {
+        match from_str::<char>("<root>rr</root>") {
+            Err(DeError::Custom(msg)) => assert_eq!(msg, "expected single character, found 2"),
+            x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+        }
+        match from_str::<Root>(r#"<root attr="rr"/>"#) {
+            Err(DeError::Custom(msg)) => assert_eq!(msg, "expected single character, found 2"),
+            x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn empty() {
+        match from_str::<char>("<root></root>") {
+            Err(DeError::Custom(msg)) => assert_eq!(msg, "expected single character, found 0"),
+            x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+        }
+        match from_str::<Root>(r#"<root attr=""/>"#) {
+            Err(DeError::Custom(msg)) => assert_eq!(msg, "expected single character, found 0"),
+            x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+        }
+    }
+}
+
+/// Tests for enums which variant is selected by a text content or an attribute value
+mod enum_by_text {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Status {
+        Active,
+        Inactive,
+        Other(String),
+        Pair(u32, u32),
+        Named { code: u32 },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct StatusText {
+        #[serde(rename = "$text")]
+        value: Status,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Root {
+        status: StatusText,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Attribute {
+        #[serde(rename = "@status")]
+        status: Status,
+    }
+
+    fn assert_unsupported<T: std::fmt::Debug>(result: Result<T, DeError>, kind: &str) {
+        match result {
+            Err(DeError::Unsupported(msg)) => assert_eq!(
+                msg,
+                format!(
+                    "cannot deserialize enum {} variant of `Status` from an attribute or text content value, only unit variants can be selected by text",
+                    kind
+                )
+            ),
+            x => panic!("Expected `Err(Unsupported(_))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn unit() {
+        assert_eq!(
+            from_str::<Root>("<root><status>active</status></root>").unwrap(),
+            Root {
+                status: StatusText {
+                    value: Status::Active
+                }
+            }
+        );
+        assert_eq!(
+            from_str::<Root>("<root><status>inactive</status></root>").unwrap(),
+            Root {
+                status: StatusText {
+                    value: Status::Inactive
+                }
+            }
+        );
+        assert_eq!(
+            from_str::<Attribute>(r#"<root status="active"/>"#).unwrap(),
+            Attribute {
+                status: Status::Active
+            }
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        match from_str::<Root>("<root><status>unknown</status></root>") {
+            Err(DeError::Custom(msg)) => assert_eq!(
+                msg,
+                "unknown variant `unknown`, expected one of `active`, `inactive`, `other`, `pair`, `named`"
+            ),
+            x => panic!("Expected `Err(Custom(_))`, but got `{:?}`", x),
+        }
+    }
+
+    #[test]
+    fn newtype() {
+        assert_unsupported(
+            from_str::<Root>("<root><status>other</status></root>"),
+            "newtype",
+        );
+        assert_unsupported(
+            from_str::<Attribute>(r#"<root status="other"/>"#),
+            "newtype",
+        );
+    }
+
+    #[test]
+    fn tuple() {
+        assert_unsupported(
+            from_str::<Root>("<root><status>pair</status></root>"),
+            "tuple",
+        );
+        assert_unsupported(from_str::<Attribute>(r#"<root status="pair"/>"#), "tuple");
+    }
+
+    #[test]
+    fn struct_() {
+        assert_unsupported(
+            from_str::<Root>("<root><status>named</status></root>"),
+            "struct",
+        );
+        assert_unsupported(from_str::<Attribute>(r#"<root status="named"/>"#), "struct");
+    }
 }
 
 /// Tests for https://github.com/tafia/quick-xml/pull/603.
@@ -1749,6 +1749,11 @@ mod with_root {
         => "<root>&lt;&quot;&amp;&apos;&gt;</root>\
             <root>with\t\r\n spaces</root>\
             <root>3</root>");
+    // Pairs with the `tuple::simple` test in serde-de.rs
+    serialize_as!(tuple_pair:
+        (42.0f32, "answer".to_string())
+        => "<root>42</root>\
+            <root>answer</root>");
     serialize_as!(tuple_struct:
         Tuple(42.0, "answer")
         => "<root>42</root>\
@@ -2142,3 +2147,939 @@ mod with_root {
         }
     }
 }
+
+/// Tests for `Serializer::set_quote_level`
+mod quote_level {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::se::QuoteLevel;
+    use quick_xml::writer::AttributeQuote;
+
+    #[derive(Debug, PartialEq, Serialize)]
+    struct Root {
+        #[serde(rename = "@attr")]
+        attr: &'static str,
+        #[serde(rename = "$text")]
+        text: &'static str,
+    }
+
+    #[test]
+    fn minimal() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.set_quote_level(QuoteLevel::Minimal);
+
+        let data = Root {
+            attr: "<&>\"'",
+            text: "<&>\"'",
+        };
+        data.serialize(ser).unwrap();
+        assert_eq!(
+            buffer,
+            "<Root attr=\"&lt;&amp;>&quot;'\">&lt;&amp;>\"'</Root>"
+        );
+    }
+
+    #[test]
+    fn full() {
+        let mut buffer = String::new();
+        let ser = Serializer::new(&mut buffer);
+
+        let data = Root {
+            attr: "<&>\"'",
+            text: "<&>\"'",
+        };
+        data.serialize(ser).unwrap();
+        assert_eq!(
+            buffer,
+            "<Root attr=\"&lt;&amp;&gt;&quot;&apos;\">&lt;&amp;&gt;&quot;&apos;</Root>"
+        );
+    }
+
+    /// `"` is not escaped in single-quoted attributes in minimal mode
+    #[test]
+    fn minimal_single_quoted() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.set_quote_level(QuoteLevel::Minimal);
+        ser.set_attribute_quote(AttributeQuote::Single);
+
+        let data = Root {
+            attr: "<&>\"'",
+            text: "<&>\"'",
+        };
+        data.serialize(ser).unwrap();
+        assert_eq!(
+            buffer,
+            "<Root attr='&lt;&amp;>\"&apos;'>&lt;&amp;>\"'</Root>"
+        );
+    }
+}
+
+/// Tests for serialization of `WithRaw` wrapper
+mod with_raw {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::WithRaw;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Item {
+        #[serde(rename = "@id")]
+        id: usize,
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Root {
+        item: WithRaw<Item>,
+        list: Vec<WithRaw<Item>>,
+    }
+
+    const XML: &str = "<Root>\
+        <item id=\"1\"><name>first</name></item>\
+        <list id=\"2\"><name>second</name></list>\
+        <list id=\"3\"><name>third</name></list>\
+    </Root>";
+
+    fn modified() -> Root {
+        let mut data: Root = from_str(XML).unwrap();
+        data.item.value.name = "changed".into();
+        data.list[1].value.id = 42;
+        data
+    }
+
+    #[test]
+    fn parsed() {
+        let mut buffer = String::new();
+        let ser = Serializer::new(&mut buffer);
+
+        modified().serialize(ser).unwrap();
+        assert_eq!(
+            buffer,
+            "<Root>\
+                <item id=\"1\"><name>changed</name></item>\
+                <list id=\"2\"><name>second</name></list>\
+                <list id=\"42\"><name>third</name></list>\
+            </Root>"
+        );
+    }
+
+    #[test]
+    fn raw() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.write_raw_xml(true);
+
+        modified().serialize(ser).unwrap();
+        assert_eq!(buffer, XML);
+    }
+
+    #[test]
+    fn indented() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.indent(' ', 2);
+
+        modified().serialize(ser).unwrap();
+        assert_eq!(
+            buffer,
+            "<Root>\n  \
+                <item id=\"1\">\n    <name>changed</name>\n  </item>\n  \
+                <list id=\"2\">\n    <name>second</name>\n  </list>\n  \
+                <list id=\"42\">\n    <name>third</name>\n  </list>\n\
+            </Root>"
+        );
+    }
+}
+
+/// Tests for `to_writer` and `to_writer_with_root`
+mod to_writer {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::se::{to_writer, to_writer_with_root};
+
+    /// The writer is borrowed, so it is possible to continue writing to it
+    #[test]
+    fn sequential() {
+        let mut buffer = String::new();
+
+        to_writer(&mut buffer, &Newtype(true)).unwrap();
+        buffer.push('\n');
+        to_writer_with_root(&mut buffer, "root", &Newtype(false)).unwrap();
+        buffer.push('\n');
+        assert_eq!(buffer, "<Newtype>true</Newtype>\n<root>false</root>\n");
+    }
+}
+
+/// Tests for `Serializer::primitives_as_attributes`
+mod primitives_as_attributes {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Serialize)]
+    enum Kind {
+        Unit,
+        Newtype(u32),
+    }
+
+    #[derive(Serialize)]
+    struct Nested {
+        #[serde(rename = "@attr")]
+        attr: bool,
+        value: f64,
+    }
+
+    #[derive(Serialize)]
+    struct Root {
+        id: u32,
+        name: &'static str,
+        flag: Option<bool>,
+        kind: Kind,
+        newtype: Newtype,
+        list: Vec<u32>,
+        nested: Nested,
+        variant: Kind,
+        #[serde(rename = "$text")]
+        text: &'static str,
+    }
+
+    fn root() -> Root {
+        Root {
+            id: 42,
+            name: "<answer>",
+            flag: Some(true),
+            kind: Kind::Unit,
+            newtype: Newtype(false),
+            list: vec![1, 2],
+            nested: Nested {
+                attr: true,
+                value: 4.2,
+            },
+            variant: Kind::Newtype(7),
+            text: "text",
+        }
+    }
+
+    fn serialize(primitives_as_attributes: bool) -> String {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.primitives_as_attributes(primitives_as_attributes);
+
+        root().serialize(ser).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn disabled() {
+        assert_eq!(
+            serialize(false),
+            "<Root>\
+                <id>42</id>\
+                <name>&lt;answer&gt;</name>\
+                <flag>true</flag>\
+                <Unit/>\
+                <newtype>false</newtype>\
+                <list>1</list>\
+                <list>2</list>\
+                <nested attr=\"true\"><value>4.2</value></nested>\
+                <Newtype>7</Newtype>\
+                text\
+            </Root>"
+        );
+    }
+
+    #[test]
+    fn enabled() {
+        assert_eq!(
+            serialize(true),
+            "<Root id=\"42\" name=\"&lt;answer&gt;\" flag=\"true\" kind=\"Unit\" newtype=\"false\">\
+                <list>1</list>\
+                <list>2</list>\
+                <nested attr=\"true\" value=\"4.2\"/>\
+                <Newtype>7</Newtype>\
+                text\
+            </Root>"
+        );
+    }
+}
+
+/// Tests for `Serializer::variant_attribute`
+mod variant_attribute {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Serialize)]
+    enum Shape {
+        Circle {
+            #[serde(rename = "@radius")]
+            radius: f64,
+        },
+        Rect {
+            #[serde(rename = "@width")]
+            width: u32,
+            height: u32,
+        },
+        Point,
+        Newtype(u32),
+    }
+
+    #[derive(Serialize)]
+    struct Drawing {
+        shape: Vec<Shape>,
+    }
+
+    fn serialize<T: Serialize>(value: &T, root: Option<&str>) -> Result<String, DeError> {
+        let mut buffer = String::new();
+        let mut ser = Serializer::with_root(&mut buffer, root).unwrap();
+        ser.variant_attribute("type");
+
+        value.serialize(ser)?;
+        Ok(buffer)
+    }
+
+    #[test]
+    fn struct_variant() {
+        assert_eq!(
+            serialize(&Shape::Circle { radius: 1.0 }, Some("shape")).unwrap(),
+            r#"<shape type="Circle" radius="1"/>"#
+        );
+        assert_eq!(
+            serialize(
+                &Shape::Rect {
+                    width: 2,
+                    height: 3
+                },
+                Some("shape")
+            )
+            .unwrap(),
+            r#"<shape type="Rect" width="2"><height>3</height></shape>"#
+        );
+    }
+
+    #[test]
+    fn unit_variant() {
+        assert_eq!(
+            serialize(&Shape::Point, None).unwrap(),
+            r#"<Shape type="Point"/>"#
+        );
+    }
+
+    #[test]
+    fn in_field() {
+        let drawing = Drawing {
+            shape: vec![Shape::Circle { radius: 0.5 }, Shape::Point],
+        };
+        assert_eq!(
+            serialize(&drawing, None).unwrap(),
+            r#"<Drawing><shape type="Circle" radius="0.5"/><shape type="Point"/></Drawing>"#
+        );
+    }
+
+    #[test]
+    fn newtype_variant() {
+        match serialize(&Shape::Newtype(1), None) {
+            Err(DeError::Unsupported(msg)) => assert_eq!(
+                msg,
+                "cannot serialize enum newtype variant `Shape::Newtype` with the variant name in an attribute"
+            ),
+            e => panic!("Expected `Unsupported` error, but got {:?}", e),
+        }
+    }
+}
+
+mod cdata_policy {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::se::CDataPolicy;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Snippet {
+        #[serde(rename = "@lang")]
+        lang: String,
+        title: String,
+        code: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Text {
+        #[serde(rename = "$text")]
+        text: String,
+    }
+
+    fn serialize<T: Serialize>(value: &T, policy: CDataPolicy) -> String {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.cdata_policy(policy);
+
+        value.serialize(ser).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn threshold() {
+        let snippet = Snippet {
+            lang: "<html>".into(),
+            title: "Q&A".into(),
+            code: "<p>Q&amp;A</p>".into(),
+        };
+        let xml = serialize(&snippet, CDataPolicy::Threshold(1));
+
+        assert_eq!(
+            xml,
+            "<Snippet lang=\"&lt;html&gt;\">\
+                <title>Q&amp;A</title>\
+                <code><![CDATA[<p>Q&amp;A</p>]]></code>\
+            </Snippet>"
+        );
+        assert_eq!(from_str::<Snippet>(&xml).unwrap(), snippet);
+    }
+
+    #[test]
+    fn never() {
+        let text = Text { text: "<p>".into() };
+        assert_eq!(
+            serialize(&text, CDataPolicy::Never),
+            "<Text>&lt;p&gt;</Text>"
+        );
+    }
+
+    #[test]
+    fn always() {
+        let text = Text {
+            text: "plain".into(),
+        };
+        assert_eq!(
+            serialize(&text, CDataPolicy::Always),
+            "<Text><![CDATA[plain]]></Text>"
+        );
+
+        let text = Text { text: "".into() };
+        assert_eq!(serialize(&text, CDataPolicy::Always), "<Text/>");
+    }
+
+    /// `]]>` inside the content should split CDATA section
+    #[test]
+    fn split_terminator() {
+        let text = Text {
+            text: "a]]>b]]>".into(),
+        };
+        let xml = serialize(&text, CDataPolicy::Always);
+
+        assert_eq!(
+            xml,
+            "<Text><![CDATA[a]]]]><![CDATA[>b]]]]><![CDATA[>]]></Text>"
+        );
+        assert_eq!(from_str::<Text>(&xml).unwrap(), text);
+    }
+}
+
+mod float_format {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::se::FloatFormat;
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    struct Values {
+        #[serde(rename = "@attr")]
+        attr: f64,
+        single: f32,
+        double: f64,
+        list: Vec<f64>,
+    }
+
+    fn serialize<T: Serialize>(value: &T, format: FloatFormat) -> String {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.float_format(format);
+
+        value.serialize(ser).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn shortest() {
+        let values = Values {
+            attr: 0.1,
+            single: 0.1,
+            double: 0.1 + 0.2,
+            list: vec![0.1, 1e21],
+        };
+        let xml = serialize(&values, FloatFormat::Shortest);
+
+        assert_eq!(
+            xml,
+            "<Values attr=\"0.1\">\
+                <single>0.1</single>\
+                <double>0.30000000000000004</double>\
+                <list>0.1</list>\
+                <list>1000000000000000000000</list>\
+            </Values>"
+        );
+        assert_eq!(from_str::<Values>(&xml).unwrap(), values);
+    }
+
+    #[test]
+    fn fixed() {
+        let values = Values {
+            attr: 0.1,
+            single: 0.125,
+            double: 0.1 + 0.2,
+            list: vec![2.0 / 3.0],
+        };
+
+        assert_eq!(
+            serialize(&values, FloatFormat::Fixed(2)),
+            "<Values attr=\"0.10\">\
+                <single>0.12</single>\
+                <double>0.30</double>\
+                <list>0.67</list>\
+            </Values>"
+        );
+    }
+
+    #[test]
+    fn custom() {
+        fn exponent(value: f64, output: &mut String) {
+            *output += &format!("{:e}", value);
+        }
+
+        let values = Values {
+            attr: 0.1,
+            single: 1500.0,
+            double: 0.001,
+            list: vec![],
+        };
+
+        assert_eq!(
+            serialize(&values, FloatFormat::Custom(exponent)),
+            "<Values attr=\"1e-1\">\
+                <single>1.5e3</single>\
+                <double>1e-3</double>\
+            </Values>"
+        );
+    }
+
+    /// Items of `xs:list` are formatted too
+    #[test]
+    fn xs_list() {
+        #[derive(Serialize)]
+        struct List {
+            #[serde(rename = "@values")]
+            values: Vec<f64>,
+        }
+
+        assert_eq!(
+            serialize(
+                &List {
+                    values: vec![0.1, 0.25]
+                },
+                FloatFormat::Fixed(1)
+            ),
+            r#"<List values="0.1 0.2"/>"#
+        );
+    }
+}
+
+/// Tuples in element position are serialized as repeated elements with the name
+/// of the field
+mod tuple {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn field() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Root {
+            r: (f32, String),
+        }
+
+        let root = Root {
+            r: (42.0, "a".into()),
+        };
+
+        let mut buffer = String::new();
+        root.serialize(Serializer::new(&mut buffer)).unwrap();
+
+        assert_eq!(buffer, "<Root><r>42</r><r>a</r></Root>");
+        assert_eq!(from_str::<Root>(&buffer).unwrap(), root);
+    }
+
+    #[test]
+    fn tuple_struct_field() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Pair(u32, String);
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Root {
+            pair: Pair,
+            after: bool,
+        }
+
+        let root = Root {
+            pair: Pair(1, "one".into()),
+            after: true,
+        };
+
+        let mut buffer = String::new();
+        root.serialize(Serializer::new(&mut buffer)).unwrap();
+
+        assert_eq!(
+            buffer,
+            "<Root><pair>1</pair><pair>one</pair><after>true</after></Root>"
+        );
+        assert_eq!(from_str::<Root>(&buffer).unwrap(), root);
+    }
+}
+
+/// Tests for `Serializer::text_field_name` and `Serializer::attribute_prefix`
+mod special_names {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use quick_xml::de::Deserializer;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Root {
+        #[serde(rename = "attr_id")]
+        attribute: u32,
+        id: u32,
+        #[serde(rename = "#text")]
+        text: String,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let root = Root {
+            attribute: 1,
+            id: 2,
+            text: "text".into(),
+        };
+
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.text_field_name("#text").attribute_prefix("attr_");
+        root.serialize(ser).unwrap();
+
+        assert_eq!(buffer, r#"<Root id="1"><id>2</id>text</Root>"#);
+
+        let mut de = Deserializer::from_str(&buffer);
+        de.text_field_name("#text").attribute_prefix("attr_");
+        assert_eq!(Root::deserialize(&mut de).unwrap(), root);
+    }
+
+    #[test]
+    fn text_variant() {
+        #[derive(Serialize)]
+        enum Choice {
+            #[serde(rename = "#text")]
+            Text(&'static str),
+        }
+
+        let mut buffer = String::new();
+        let mut ser = Serializer::with_root(&mut buffer, Some("root")).unwrap();
+        ser.text_field_name("#text");
+        Choice::Text("text").serialize(ser).unwrap();
+
+        assert_eq!(buffer, "text");
+    }
+
+    #[test]
+    #[should_panic(expected = "text field name must not be empty")]
+    fn empty_text_field_name() {
+        let mut buffer = String::new();
+        Serializer::new(&mut buffer).text_field_name("");
+    }
+
+    #[test]
+    #[should_panic(expected = "attribute prefix must not be empty")]
+    fn empty_attribute_prefix() {
+        let mut buffer = String::new();
+        Serializer::new(&mut buffer).attribute_prefix("");
+    }
+}
+
+/// Attributes are written in the order of declaration of fields before any
+/// content, wherever they are declared
+mod attribute_order {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Serialize)]
+    struct Root {
+        first: u32,
+        #[serde(rename = "@z")]
+        z: u32,
+        #[serde(rename = "$text")]
+        text: &'static str,
+        #[serde(rename = "@a")]
+        a: u32,
+        second: u32,
+        #[serde(rename = "@m")]
+        m: u32,
+    }
+
+    const ROOT: Root = Root {
+        first: 1,
+        z: 2,
+        text: "text",
+        a: 3,
+        second: 4,
+        m: 5,
+    };
+
+    #[test]
+    fn plain() {
+        let mut buffer = String::new();
+        ROOT.serialize(Serializer::new(&mut buffer)).unwrap();
+
+        assert_eq!(
+            buffer,
+            r#"<Root z="2" a="3" m="5"><first>1</first>text<second>4</second></Root>"#
+        );
+    }
+
+    #[test]
+    fn with_indent() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.indent(' ', 2);
+        ROOT.serialize(ser).unwrap();
+
+        assert_eq!(
+            buffer,
+            "<Root z=\"2\" a=\"3\" m=\"5\">\n  \
+                <first>1</first>\n  \
+                text\n  \
+                <second>4</second>\n\
+            </Root>"
+        );
+    }
+}
+
+/// Maps are serialized as elements named after keys
+mod map {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    #[test]
+    fn three_entries() {
+        let map = BTreeMap::from([("k1", 1), ("k2", 2), ("k3", 3)]);
+
+        let mut buffer = String::new();
+        let ser = Serializer::with_root(&mut buffer, Some("root")).unwrap();
+        map.serialize(ser).unwrap();
+
+        assert_eq!(buffer, "<root><k1>1</k1><k2>2</k2><k3>3</k3></root>");
+    }
+
+    #[test]
+    fn field() {
+        #[derive(Serialize)]
+        struct Root {
+            map: BTreeMap<String, i32>,
+        }
+
+        let root = Root {
+            map: BTreeMap::from([("k1".to_string(), 1), ("k2".to_string(), 2)]),
+        };
+
+        let mut buffer = String::new();
+        root.serialize(Serializer::new(&mut buffer)).unwrap();
+
+        assert_eq!(buffer, "<Root><map><k1>1</k1><k2>2</k2></map></Root>");
+    }
+
+    #[test]
+    fn non_string_key() {
+        let map = HashMap::from([(1, "one")]);
+
+        let mut buffer = String::new();
+        let ser = Serializer::with_root(&mut buffer, Some("root")).unwrap();
+        match map.serialize(ser) {
+            Err(DeError::Unsupported(e)) => assert_eq!(
+                e,
+                "cannot serialize map key `1` of type `i32` as an XML tag name, only string keys are supported"
+            ),
+            e => panic!("Expected `Err(Unsupported)`, but got `{:?}`", e),
+        }
+    }
+}
+
+/// XML declaration and DOCTYPE written before the root element
+mod preamble {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Serialize)]
+    struct Root {
+        #[serde(rename = "@id")]
+        id: u32,
+        value: &'static str,
+    }
+
+    const ROOT: Root = Root {
+        id: 1,
+        value: "text",
+    };
+
+    #[test]
+    fn none() {
+        let mut buffer = String::new();
+        let ser = Serializer::new(&mut buffer);
+        ROOT.serialize(ser).unwrap();
+        assert_eq!(buffer, r#"<Root id="1"><value>text</value></Root>"#);
+    }
+
+    #[test]
+    fn declaration_and_doctype() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.write_declaration("1.0", Some("UTF-8"), Some("yes"));
+        ser.write_doctype("Root");
+        ROOT.serialize(ser).unwrap();
+        assert_eq!(
+            buffer,
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><!DOCTYPE Root><Root id="1"><value>text</value></Root>"#
+        );
+    }
+
+    #[test]
+    fn indented() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.indent(' ', 2);
+        ser.write_declaration("1.0", None, None);
+        ser.write_doctype(r#"Root SYSTEM "root.dtd""#);
+        ROOT.serialize(ser).unwrap();
+        assert_eq!(
+            buffer,
+            r#"<?xml version="1.0"?>
+<!DOCTYPE Root SYSTEM "root.dtd">
+<Root id="1">
+  <value>text</value>
+</Root>"#
+        );
+    }
+
+    #[test]
+    fn with_root() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::with_root(&mut buffer, Some("root")).unwrap();
+        ser.write_declaration("1.1", None, None);
+        42.serialize(ser).unwrap();
+        assert_eq!(buffer, r#"<?xml version="1.1"?><root>42</root>"#);
+    }
+
+    /// Nothing is written when serialization fails early
+    #[test]
+    fn error() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.write_declaration("1.0", None, None);
+        assert!(42.serialize(ser).is_err());
+        assert_eq!(buffer, "");
+    }
+}
+
+mod finalizer {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde::ser::{Error, SerializeStruct, Serializer as _};
+
+    /// Deliberately buggy implementation that ignores an error in the field
+    /// and closes the struct anyway
+    struct Buggy;
+    impl Serialize for Buggy {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            let mut root = serializer.serialize_struct("root", 2)?;
+            root.serialize_field("@id", &1)?;
+            let _ = root.serialize_field("inner", &Failing);
+            root.end()
+        }
+    }
+
+    /// Starts a struct and fails in the middle of it
+    struct Failing;
+    impl Serialize for Failing {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            let mut inner = serializer.serialize_struct("Failing", 1)?;
+            inner.serialize_field("@attr", "value")?;
+            Err(S::Error::custom("failed"))
+        }
+    }
+
+    #[test]
+    fn buggy_serialize() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        let finalizer = ser.finalizer();
+
+        // Serialization itself succeeds, but the output is malformed
+        Buggy.serialize(ser).unwrap();
+        assert_eq!(buffer, r#"<root id="1"><inner attr="value"</root>"#);
+
+        match finalizer.finalize() {
+            Err(DeError::NotFinalized(open)) => assert_eq!(open, vec!["inner"]),
+            e => panic!("Expected `NotFinalized` error, found {:?}", e),
+        }
+    }
+
+    #[test]
+    fn manual() {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        let finalizer = ser.finalizer();
+
+        let mut root = ser.serialize_struct("root", 1).unwrap();
+        root.serialize_field("@id", &1).unwrap();
+        drop(root);
+
+        assert_eq!(buffer, r#"<root id="1""#);
+        match finalizer.finalize() {
+            Err(e @ DeError::NotFinalized(_)) => assert_eq!(
+                e.to_string(),
+                "Serialization was not finalized, elements were not closed: root"
+            ),
+            e => panic!("Expected `NotFinalized` error, found {:?}", e),
+        }
+    }
+
+    #[test]
+    fn complete() {
+        #[derive(Serialize)]
+        struct Root {
+            #[serde(rename = "@id")]
+            id: u32,
+            inner: Inner,
+            list: Vec<Inner>,
+        }
+        #[derive(Serialize)]
+        struct Inner {
+            value: &'static str,
+        }
+
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        let finalizer = ser.finalizer();
+
+        Root {
+            id: 1,
+            inner: Inner { value: "a" },
+            list: vec![Inner { value: "b" }, Inner { value: "c" }],
+        }
+        .serialize(ser)
+        .unwrap();
+
+        assert_eq!(
+            buffer,
+            r#"<Root id="1"><inner><value>a</value></inner><list><value>b</value></list><list><value>c</value></list></Root>"#
+        );
+        assert!(finalizer.finalize().is_ok());
+    }
+}
@@ -1,4 +1,7 @@
-use quick_xml::{de::from_str, se::to_string};
+use quick_xml::de::{from_str, BytesFormat, Deserializer};
+use quick_xml::se::{to_string, QuoteLevel, Serializer};
+use quick_xml::utils::ByteBuf;
+use quick_xml::writer::AttributeQuote;
 use serde::{Deserialize, Serialize};
 
 use pretty_assertions::assert_eq;
@@ -51,3 +54,424 @@ fn round_trip_list_of_enums() {
     let deserialized_nodes: Nodes = from_str(serialized_nodes.as_str()).unwrap();
     assert_eq!(deserialized_nodes, nodes);
 }
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+enum Shape {
+    Circle {
+        #[serde(rename = "@radius")]
+        radius: f64,
+    },
+    Rect {
+        #[serde(rename = "@width")]
+        width: u32,
+        #[serde(rename = "@height")]
+        height: u32,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "drawing")]
+struct Drawing {
+    shape: Vec<Shape>,
+}
+
+/// Enum variants selected by the `type` attribute instead of element names
+#[test]
+fn round_trip_enum_with_variant_attribute() {
+    let drawing = Drawing {
+        shape: vec![
+            Shape::Circle { radius: 1.0 },
+            Shape::Rect {
+                width: 2,
+                height: 3,
+            },
+        ],
+    };
+
+    let mut buffer = String::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.variant_attribute("type");
+    drawing.serialize(ser).unwrap();
+
+    assert_eq!(
+        buffer,
+        r#"<drawing><shape type="Circle" radius="1"/><shape type="Rect" width="2" height="3"/></drawing>"#
+    );
+
+    let mut de = Deserializer::from_str(&buffer);
+    de.variant_attribute("type");
+    assert_eq!(Drawing::deserialize(&mut de).unwrap(), drawing);
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "input")]
+struct Input {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "@disabled", default)]
+    disabled: bool,
+    #[serde(rename = "@checked", default)]
+    checked: bool,
+}
+
+/// Boolean attributes written as a bare attribute name or omitted
+#[test]
+fn round_trip_minimized_bool_attrs() {
+    let round_trip = |input: Input, expected: &str| {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.minimized_bool_attrs(true);
+        input.serialize(ser).unwrap();
+
+        assert_eq!(buffer, expected);
+
+        let mut de = Deserializer::from_str(&buffer);
+        de.minimized_bool_attrs(true);
+        assert_eq!(Input::deserialize(&mut de).unwrap(), input);
+    };
+
+    round_trip(
+        Input {
+            name: "true".into(),
+            disabled: true,
+            checked: true,
+        },
+        r#"<input name="true" disabled checked/>"#,
+    );
+    round_trip(
+        Input {
+            name: "false".into(),
+            disabled: false,
+            checked: false,
+        },
+        r#"<input name="false"/>"#,
+    );
+    round_trip(
+        Input {
+            name: "mixed".into(),
+            disabled: false,
+            checked: true,
+        },
+        r#"<input name="mixed" checked/>"#,
+    );
+}
+
+/// Without the option valueless attributes are an error
+#[test]
+fn minimized_bool_attrs_disabled() {
+    assert!(from_str::<Input>("<input name='x' disabled/>").is_err());
+}
+
+/// Attribute values enclosed in single quotes
+#[test]
+fn round_trip_single_quoted_attributes() {
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    #[serde(rename = "root")]
+    struct Root {
+        #[serde(rename = "@attr")]
+        attr: String,
+        #[serde(rename = "$text")]
+        text: String,
+    }
+
+    let input = Root {
+        attr: r#"'single' and "double" <&>"#.into(),
+        text: "text".into(),
+    };
+
+    let mut buffer = String::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.set_quote_level(QuoteLevel::Minimal);
+    ser.set_attribute_quote(AttributeQuote::Single);
+    input.serialize(ser).unwrap();
+
+    assert_eq!(
+        buffer,
+        r#"<root attr='&apos;single&apos; and "double" &lt;&amp;>'>text</root>"#
+    );
+    assert_eq!(from_str::<Root>(&buffer).unwrap(), input);
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "binary")]
+struct Binary {
+    #[serde(rename = "@hash")]
+    hash: ByteBuf,
+    data: ByteBuf,
+}
+
+fn round_trip_bytes(format: BytesFormat, input: Binary, expected: &str) {
+    let mut buffer = String::new();
+    let mut ser = Serializer::new(&mut buffer);
+    ser.bytes_format(format);
+    input.serialize(ser).unwrap();
+
+    assert_eq!(buffer, expected);
+
+    let mut de = Deserializer::from_str(&buffer);
+    de.bytes_format(format);
+    assert_eq!(Binary::deserialize(&mut de).unwrap(), input);
+}
+
+/// Binary data written as `xs:base64Binary`
+#[test]
+fn round_trip_bytes_base64() {
+    round_trip_bytes(
+        BytesFormat::Base64,
+        Binary {
+            hash: ByteBuf(vec![0xFB, 0xFF]),
+            data: ByteBuf(b"any carnal pleas".to_vec()),
+        },
+        r#"<binary hash="+/8="><data>YW55IGNhcm5hbCBwbGVhcw==</data></binary>"#,
+    );
+    round_trip_bytes(
+        BytesFormat::Base64,
+        Binary {
+            hash: ByteBuf(vec![]),
+            data: ByteBuf(b"any carnal pleasu".to_vec()),
+        },
+        r#"<binary hash=""><data>YW55IGNhcm5hbCBwbGVhc3U=</data></binary>"#,
+    );
+    round_trip_bytes(
+        BytesFormat::Base64,
+        Binary {
+            hash: ByteBuf(vec![0]),
+            data: ByteBuf(b"any carnal pleasur".to_vec()),
+        },
+        r#"<binary hash="AA=="><data>YW55IGNhcm5hbCBwbGVhc3Vy</data></binary>"#,
+    );
+
+    // Whitespace is ignored
+    let mut de =
+        Deserializer::from_str("<binary hash='AA=='><data>\n  YW55\n  IA==\n</data></binary>");
+    de.bytes_format(BytesFormat::Base64);
+    assert_eq!(
+        Binary::deserialize(&mut de).unwrap(),
+        Binary {
+            hash: ByteBuf(vec![0]),
+            data: ByteBuf(b"any ".to_vec()),
+        }
+    );
+}
+
+/// Binary data written as `xs:hexBinary`
+#[test]
+fn round_trip_bytes_hex() {
+    round_trip_bytes(
+        BytesFormat::Hex,
+        Binary {
+            hash: ByteBuf(vec![0xCA, 0xFE, 0x00]),
+            data: ByteBuf(vec![1, 2, 0xAB]),
+        },
+        r#"<binary hash="cafe00"><data>0102ab</data></binary>"#,
+    );
+
+    // Uppercase digits and surrounding whitespace are accepted
+    let mut de = Deserializer::from_str("<binary hash=' CAFE '><data>\n  0A\n</data></binary>");
+    de.bytes_format(BytesFormat::Hex);
+    assert_eq!(
+        Binary::deserialize(&mut de).unwrap(),
+        Binary {
+            hash: ByteBuf(vec![0xCA, 0xFE]),
+            data: ByteBuf(vec![0x0A]),
+        }
+    );
+}
+
+#[test]
+fn bytes_invalid() {
+    let mut de = Deserializer::from_str("<binary hash='ABC'><data/></binary>");
+    de.bytes_format(BytesFormat::Hex);
+    assert!(Binary::deserialize(&mut de).is_err());
+
+    let mut de = Deserializer::from_str("<binary hash='A'><data/></binary>");
+    de.bytes_format(BytesFormat::Base64);
+    assert!(Binary::deserialize(&mut de).is_err());
+
+    let mut de = Deserializer::from_str("<binary hash='A!=='><data/></binary>");
+    de.bytes_format(BytesFormat::Base64);
+    assert!(Binary::deserialize(&mut de).is_err());
+}
+
+/// Without the option binary data is an error
+#[test]
+fn bytes_format_disabled() {
+    let input = Binary {
+        hash: ByteBuf(vec![1]),
+        data: ByteBuf(vec![2]),
+    };
+    assert!(to_string(&input).is_err());
+    assert!(from_str::<Binary>("<binary hash='01'><data>02</data></binary>").is_err());
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "record")]
+struct Record {
+    #[serde(rename = "@xmlns:xsi")]
+    xsi: String,
+    name: String,
+    email: Option<String>,
+    age: Option<u32>,
+    address: Option<Address>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Address {
+    city: String,
+}
+
+const XSI: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// `None` elements written with the `xsi:nil` attribute
+#[test]
+fn round_trip_nil() {
+    let round_trip = |input: Record, expected: &str| {
+        let mut buffer = String::new();
+        let mut ser = Serializer::new(&mut buffer);
+        ser.nil_prefix(Some("xsi"));
+        input.serialize(ser).unwrap();
+
+        assert_eq!(buffer, expected);
+
+        let mut de = Deserializer::from_str(&buffer);
+        de.xsi_nil(true);
+        assert_eq!(Record::deserialize(&mut de).unwrap(), input);
+    };
+
+    round_trip(
+        Record {
+            xsi: XSI.into(),
+            name: "Alice".into(),
+            email: None,
+            age: None,
+            address: None,
+        },
+        r#"<record xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><name>Alice</name><email xsi:nil="true"/><age xsi:nil="true"/><address xsi:nil="true"/></record>"#,
+    );
+    round_trip(
+        Record {
+            xsi: XSI.into(),
+            name: "Bob".into(),
+            email: Some("bob@example.com".into()),
+            age: Some(42),
+            address: Some(Address {
+                city: "Paris".into(),
+            }),
+        },
+        r#"<record xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><name>Bob</name><email>bob@example.com</email><age>42</age><address><city>Paris</city></address></record>"#,
+    );
+    round_trip(
+        Record {
+            xsi: XSI.into(),
+            name: "Carol".into(),
+            email: Some("".into()),
+            age: None,
+            address: None,
+        },
+        r#"<record xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><name>Carol</name><email/><age xsi:nil="true"/><address xsi:nil="true"/></record>"#,
+    );
+}
+
+/// `xsi:nil` is recognized regardless of the element content
+#[test]
+fn nil_deserialization() {
+    let alice = || Record {
+        xsi: XSI.into(),
+        name: "Alice".into(),
+        email: None,
+        age: None,
+        address: None,
+    };
+    let from_str = |xml: &str| {
+        let mut de = Deserializer::from_str(xml);
+        de.xsi_nil(true);
+        Record::deserialize(&mut de).unwrap()
+    };
+
+    let xml = r#"
+        <record xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+            <name>Alice</name>
+            <email xsi:nil="1"></email>
+            <age xsi:nil="true">42</age>
+            <address xsi:nil="true"><city>Paris</city></address>
+        </record>
+    "#;
+    assert_eq!(from_str(xml), alice());
+
+    // Attribute with other values or without namespace is not recognized
+    let xml = r#"
+        <record xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+            <name>Alice</name>
+            <email xsi:nil="false">alice@example.com</email>
+            <age nil="true">42</age>
+        </record>
+    "#;
+    assert_eq!(
+        from_str(xml),
+        Record {
+            email: Some("alice@example.com".into()),
+            age: Some(42),
+            ..alice()
+        }
+    );
+}
+
+/// `xsi:nil` is recognized by the namespace, not by the prefix
+#[test]
+fn nil_namespace() {
+    let alice = || Record {
+        xsi: XSI.into(),
+        name: "Alice".into(),
+        email: None,
+        age: None,
+        address: None,
+    };
+    let from_str = |xml: &str| {
+        let mut de = Deserializer::from_str(xml);
+        de.xsi_nil(true);
+        Record::deserialize(&mut de).unwrap()
+    };
+
+    // XSI namespace bound to another prefix, also on the element itself
+    let xml = r#"
+        <record xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:i="http://www.w3.org/2001/XMLSchema-instance">
+            <name>Alice</name>
+            <email i:nil="true"/>
+            <age x:nil="true" xmlns:x="http://www.w3.org/2001/XMLSchema-instance">42</age>
+            <address i:nil="true"><city>Paris</city></address>
+        </record>
+    "#;
+    assert_eq!(from_str(xml), alice());
+
+    // `xsi` prefix bound to another namespace
+    let xml = r#"
+        <record xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+            <name>Alice</name>
+            <email xmlns:xsi="urn:other" xsi:nil="true">alice@example.com</email>
+            <age xsi:nil="true">42</age>
+        </record>
+    "#;
+    assert_eq!(
+        from_str(xml),
+        Record {
+            email: Some("alice@example.com".into()),
+            ..alice()
+        }
+    );
+}
+
+/// `xsi:nil` is not recognized by default
+#[test]
+fn nil_disabled() {
+    let xml = r#"<record xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><name>Alice</name><age xsi:nil="true">42</age></record>"#;
+    assert_eq!(
+        from_str::<Record>(xml).unwrap(),
+        Record {
+            xsi: XSI.into(),
+            name: "Alice".into(),
+            email: None,
+            age: Some(42),
+            address: None,
+        }
+    );
+}
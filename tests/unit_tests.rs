@@ -4,11 +4,12 @@ use std::str::from_utf8;
 
 use quick_xml::events::attributes::{AttrError, Attribute};
 use quick_xml::events::Event::*;
-use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText};
+use quick_xml::events::{BytesCData, BytesDecl, BytesEnd, BytesPI, BytesStart, BytesText};
 use quick_xml::name::QName;
-use quick_xml::reader::Reader;
+use quick_xml::name::{Namespace, ResolveResult};
+use quick_xml::reader::{NsReader, Reader};
 use quick_xml::writer::Writer;
-use quick_xml::Result;
+use quick_xml::{Error, IllFormedError, Result};
 
 use pretty_assertions::assert_eq;
 
@@ -251,6 +252,128 @@ fn test_writer_indent() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_write_events_transformed() -> Result<()> {
+    let input = r#"<root><foo a="1"><foo/>text</foo><baz/></root>"#;
+    let mut reader = Reader::from_str(input);
+    let mut events = Vec::new();
+    loop {
+        match reader.read_event()? {
+            Eof => break,
+            Start(mut e) if e.name().as_ref() == b"foo" => {
+                e.set_name(b"bar");
+                events.push(Start(e));
+            }
+            Empty(mut e) if e.name().as_ref() == b"foo" => {
+                e.set_name(b"bar");
+                events.push(Empty(e));
+            }
+            End(e) if e.name().as_ref() == b"foo" => events.push(End(BytesEnd::new("bar"))),
+            e => events.push(e),
+        }
+    }
+
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    writer.write_events(&events)?;
+    let result = writer.into_inner().into_inner();
+    assert_eq!(
+        from_utf8(&result).unwrap(),
+        "<root>\n  <bar a=\"1\">\n    <bar/>text</bar>\n  <baz/>\n</root>"
+    );
+
+    // The same output as writing events one by one
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    for event in &events {
+        writer.write_event(event)?;
+    }
+    assert_eq!(writer.into_inner().into_inner(), result);
+    Ok(())
+}
+
+#[test]
+fn test_write_events_stops_on_error() {
+    let events = [
+        Start(BytesStart::new("root")),
+        Empty(BytesStart::new("child")),
+        End(BytesEnd::new("root")),
+    ];
+    // Only enough space for the first event
+    let mut buffer = [0u8; 6];
+    let mut writer = Writer::new(&mut buffer[..]);
+    let mut consumed = 0;
+    let result = writer.write_events(events.iter().inspect(|_| consumed += 1));
+    assert!(result.is_err());
+    assert_eq!(consumed, 2);
+    assert_eq!(&buffer, b"<root>");
+}
+
+#[test]
+fn test_write_cdata_or_text() {
+    let write = |content: &str| {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_cdata_or_text(content).unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    };
+
+    // Simple strings are written as text
+    assert_eq!(write(""), "");
+    assert_eq!(write("simple text"), "simple text");
+    assert_eq!(write("a < b"), "a &lt; b");
+    // Strings with a lot of markup are written as CDATA
+    assert_eq!(
+        write("<a href=\"#\">x & y</a>"),
+        "<![CDATA[<a href=\"#\">x & y</a>]]>"
+    );
+    // `]]>` cannot appear inside CDATA, so the section is split
+    assert_eq!(
+        write("<p><b><i>]]></i></b></p>"),
+        "<![CDATA[<p><b><i>]]]]><![CDATA[></i></b></p>]]>"
+    );
+    // ... which makes CDATA longer than escaped text
+    assert_eq!(write("a]]>b"), "a]]&gt;b");
+}
+
+#[test]
+fn test_pi_target_and_content() {
+    let mut reader = Reader::from_str(r#"<?xml-stylesheet type="text/xsl" href="x"?>"#);
+    match reader.read_event().unwrap() {
+        PI(e) => {
+            assert_eq!(e.target(), b"xml-stylesheet");
+            assert_eq!(e.content(), br#"type="text/xsl" href="x""#);
+        }
+        e => panic!("Expected PI, found {:?}", e),
+    }
+}
+
+#[test]
+fn test_pi_without_content() {
+    let mut reader = Reader::from_str("<?target?><?target \t\n?>");
+    for _ in 0..2 {
+        match reader.read_event().unwrap() {
+            PI(e) => {
+                assert_eq!(e.target(), b"target");
+                assert_eq!(e.content(), b"");
+            }
+            e => panic!("Expected PI, found {:?}", e),
+        }
+    }
+}
+
+#[test]
+fn test_writer_pi_from_parts() -> Result<()> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(PI(BytesPI::from_parts(
+        "xml-stylesheet",
+        r#"type="text/xsl" href="x""#,
+    )))?;
+    writer.write_event(PI(BytesPI::from_parts("target", "")))?;
+    assert_eq!(
+        from_utf8(&writer.into_inner()).unwrap(),
+        r#"<?xml-stylesheet type="text/xsl" href="x"?><?target?>"#
+    );
+    Ok(())
+}
+
 #[test]
 fn test_writer_indent_cdata() -> Result<()> {
     let txt = include_str!("../tests/documents/test_writer_indent_cdata.xml");
@@ -270,6 +393,38 @@ fn test_writer_indent_cdata() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_writer_cdata_with_end() -> Result<()> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(CData(BytesCData::new("a]]>b")))?;
+
+    let result = writer.into_inner();
+    assert_eq!(
+        String::from_utf8(result).expect("utf-8 output"),
+        "<![CDATA[a]]]]><![CDATA[>b]]>",
+        "writer output (LHS)"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_writer_accessors() -> Result<()> {
+    let mut writer = Writer::new(Vec::new());
+    writer.write_event(Start(BytesStart::new("tag")))?;
+    assert_eq!(writer.get_ref().as_slice(), b"<tag>");
+
+    writer.get_mut().extend_from_slice(b"text");
+    writer.write_event(End(BytesEnd::new("tag")))?;
+
+    let result = writer.into_inner();
+    assert_eq!(
+        String::from_utf8(result).expect("utf-8 output"),
+        "<tag>text</tag>",
+        "writer output (LHS)"
+    );
+    Ok(())
+}
+
 #[test]
 fn test_write_empty_element_attrs() -> Result<()> {
     let str_from = r#"<source attr="val"/>"#;
@@ -320,6 +475,50 @@ fn test_write_attrs() -> Result<()> {
     Ok(())
 }
 
+/// Attributes copied from another tag keep their quotes
+#[test]
+fn test_write_attrs_mixed_quotes() -> Result<()> {
+    let str_from = r#"<source a="1" b='2' c='"3"' d="'4'"/>"#;
+    let expected = r#"<copy a="1" b='2' c='"3"' d="'4'" e="5"/>"#;
+    let mut reader = Reader::from_str(str_from);
+    let mut writer = Writer::new(Vec::new());
+    loop {
+        let event = match reader.read_event()? {
+            Eof => break,
+            Empty(elem) => {
+                let mut copy = BytesStart::new("copy");
+                for attr in elem.attributes().quoted() {
+                    copy.push_attr(attr?);
+                }
+                copy.push_attribute(("e", "5"));
+                Empty(copy)
+            }
+            e => e,
+        };
+        writer.write_event(event)?;
+    }
+
+    assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), expected);
+    Ok(())
+}
+
+/// Unchanged events are written as they were read
+#[test]
+fn test_round_trip_mixed_quotes() -> Result<()> {
+    let xml = r#"<root a="1" b='2'><item c='"3"' d="'4'"/></root>"#;
+    let mut reader = Reader::from_str(xml);
+    let mut writer = Writer::new(Vec::new());
+    loop {
+        match reader.read_event()? {
+            Eof => break,
+            e => writer.write_event(e)?,
+        }
+    }
+
+    assert_eq!(String::from_utf8(writer.into_inner()).unwrap(), xml);
+    Ok(())
+}
+
 #[test]
 fn test_new_xml_decl_full() {
     let mut writer = Writer::new(Vec::new());
@@ -477,6 +676,615 @@ fn test_offset_err_comment_trim_text() {
     }
 }
 
+#[test]
+fn test_entity_in_element_name() {
+    let mut r = Reader::from_str("<a&amp;b/>");
+    r.check_entities_in_names(true);
+
+    match r.read_event() {
+        Err(Error::IllFormed(IllFormedError::EntityInName(tag))) => assert_eq!(tag, "a&amp;b/"),
+        e => panic!(
+            "expecting `IllFormed(EntityInName(_))` error, found {:?}",
+            e
+        ),
+    }
+}
+
+#[test]
+fn test_entity_in_attribute_name() {
+    let mut r = Reader::from_str(r#"<a x&amp;y="1"/>"#);
+    r.check_entities_in_names(true);
+
+    match r.read_event() {
+        Err(Error::IllFormed(IllFormedError::EntityInName(tag))) => {
+            assert_eq!(tag, r#"a x&amp;y="1"/"#)
+        }
+        e => panic!(
+            "expecting `IllFormed(EntityInName(_))` error, found {:?}",
+            e
+        ),
+    }
+}
+
+#[test]
+fn test_entity_in_end_name() {
+    let mut r = Reader::from_str("<a></a&amp;b>");
+    r.check_end_names(false);
+    r.check_entities_in_names(true);
+
+    next_eq!(r, Start, b"a");
+    match r.read_event() {
+        Err(Error::IllFormed(IllFormedError::EntityInName(tag))) => assert_eq!(tag, "/a&amp;b"),
+        e => panic!(
+            "expecting `IllFormed(EntityInName(_))` error, found {:?}",
+            e
+        ),
+    }
+}
+
+/// Without the check references in names are returned as a part of the names
+#[test]
+fn test_entity_in_name_unchecked() {
+    let mut r = Reader::from_str(r#"<a&amp;b x&amp;y="1"></a&amp;b>"#);
+
+    match r.read_event() {
+        Ok(Start(e)) => {
+            assert_eq!(e.name(), QName(b"a&amp;b"));
+            let attr = e.attributes().next().unwrap().unwrap();
+            assert_eq!(attr.key, QName(b"x&amp;y"));
+        }
+        e => panic!("expecting `Start` event, found {:?}", e),
+    }
+    next_eq!(r, End, b"a&amp;b");
+}
+
+/// References are allowed in attribute values, even if the value contains quotes
+#[test]
+fn test_entity_in_attribute_value() {
+    let mut r = Reader::from_str(r#"<a x='"&amp;' y="'&lt;"/>"#);
+    r.check_entities_in_names(true);
+
+    match r.read_event() {
+        Ok(Empty(e)) => assert_eq!(e.name(), QName(b"a")),
+        e => panic!("expecting `Empty` event, found {:?}", e),
+    }
+}
+
+/// A single stray end tag is returned as a text and does not close any element
+#[test]
+fn test_error_recovery_mismatched_end() {
+    let mut r = Reader::from_str("<a><b></c>text</b></a>");
+    r.error_recovery(true);
+
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("a")));
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("b")));
+    assert_eq!(
+        r.read_event().unwrap(),
+        Text(BytesText::from_escaped("&lt;/c>"))
+    );
+    assert_eq!(r.read_event().unwrap(), Text(BytesText::new("text")));
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("b")));
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("a")));
+    assert_eq!(r.read_event().unwrap(), Eof);
+
+    match r.recovered_errors() {
+        [(6, Error::EndEventMismatch { expected, found })] => {
+            assert_eq!(expected, "b");
+            assert_eq!(found, "c");
+        }
+        e => panic!("expecting one `EndEventMismatch` error, found {:?}", e),
+    }
+}
+
+/// End tag of the outer element closes all unclosed inner elements
+#[test]
+fn test_error_recovery_unclosed_elements() {
+    let mut r = Reader::from_str("<a><b><c><d/></a><e/>");
+    r.error_recovery(true);
+
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("a")));
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("b")));
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("c")));
+    assert_eq!(r.read_event().unwrap(), Empty(BytesStart::new("d")));
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("c")));
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("b")));
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("a")));
+    assert_eq!(r.read_event().unwrap(), Empty(BytesStart::new("e")));
+    assert_eq!(r.read_event().unwrap(), Eof);
+
+    // Only one error is reported: `</a>` was found instead of `</c>`
+    assert_eq!(r.recovered_errors().len(), 1);
+}
+
+/// End tag without opened elements is returned as a text
+#[test]
+fn test_error_recovery_unmatched_end() {
+    let mut r = Reader::from_str("<a/></b>");
+    r.error_recovery(true);
+
+    assert_eq!(r.read_event().unwrap(), Empty(BytesStart::new("a")));
+    assert_eq!(
+        r.read_event().unwrap(),
+        Text(BytesText::from_escaped("&lt;/b>"))
+    );
+    assert_eq!(r.read_event().unwrap(), Eof);
+
+    match r.recovered_errors() {
+        [(4, Error::EndEventMismatch { expected, found })] => {
+            assert_eq!(expected, "");
+            assert_eq!(found, "b");
+        }
+        e => panic!("expecting one `EndEventMismatch` error, found {:?}", e),
+    }
+}
+
+#[test]
+fn test_error_recovery_unescaped_lt() {
+    let mut r = Reader::from_reader("<a>1 < 2 <<b/></a>".as_bytes());
+    r.error_recovery(true);
+    let mut buf = Vec::new();
+
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        Start(BytesStart::new("a"))
+    );
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        Text(BytesText::new("1 "))
+    );
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        Text(BytesText::from_escaped("&lt;"))
+    );
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        Text(BytesText::new(" 2 "))
+    );
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        Text(BytesText::from_escaped("&lt;"))
+    );
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        Empty(BytesStart::new("b"))
+    );
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        End(BytesEnd::new("a"))
+    );
+    assert_eq!(r.read_event_into(&mut buf).unwrap(), Eof);
+
+    match r.recovered_errors() {
+        [(5, Error::IllFormed(IllFormedError::UnescapedLt)), (9, Error::IllFormed(IllFormedError::UnescapedLt))] =>
+            {}
+        e => panic!("expecting two `UnescapedLt` errors, found {:?}", e),
+    }
+}
+
+/// Without error recovery mismatched end tag is an error
+#[test]
+fn test_error_recovery_disabled() {
+    let mut r = Reader::from_str("<a></b>");
+
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("a")));
+    match r.read_event() {
+        Err(Error::EndEventMismatch { .. }) => {}
+        e => panic!("expecting `EndEventMismatch` error, found {:?}", e),
+    }
+    assert_eq!(r.recovered_errors().len(), 0);
+}
+
+#[test]
+fn test_error_recovery_take_errors() {
+    let mut r = Reader::from_str("<a></x></a><b></y></b>");
+    r.error_recovery(true);
+
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("a")));
+    assert_eq!(
+        r.read_event().unwrap(),
+        Text(BytesText::from_escaped("&lt;/x>"))
+    );
+    match r.take_recovered_errors().as_slice() {
+        [(3, Error::EndEventMismatch { expected, found })] => {
+            assert_eq!(expected, "a");
+            assert_eq!(found, "x");
+        }
+        e => panic!("expecting one `EndEventMismatch` error, found {:?}", e),
+    }
+    assert_eq!(r.recovered_errors().len(), 0);
+
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("a")));
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("b")));
+    assert_eq!(
+        r.read_event().unwrap(),
+        Text(BytesText::from_escaped("&lt;/y>"))
+    );
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("b")));
+    assert_eq!(r.read_event().unwrap(), Eof);
+
+    match r.take_recovered_errors().as_slice() {
+        [(14, Error::EndEventMismatch { expected, found })] => {
+            assert_eq!(expected, "b");
+            assert_eq!(found, "y");
+        }
+        e => panic!("expecting one `EndEventMismatch` error, found {:?}", e),
+    }
+}
+
+/// Collects names of the currently opened elements
+fn open_elements<R>(r: &Reader<R>) -> Vec<&str> {
+    r.open_elements()
+        .map(|name| from_utf8(name.into_inner()).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_open_elements() {
+    let mut r =
+        Reader::from_reader("<table><row><cell>1</cell><cell/></row><row/></table>".as_bytes());
+    let mut buf = Vec::new();
+    assert_eq!(open_elements(&r), Vec::<&str>::new());
+
+    let mut next = |r: &mut Reader<_>| {
+        buf.clear();
+        r.read_event_into(&mut buf).unwrap().into_owned()
+    };
+
+    assert_eq!(next(&mut r), Start(BytesStart::new("table")));
+    assert_eq!(open_elements(&r), ["table"]);
+    assert_eq!(next(&mut r), Start(BytesStart::new("row")));
+    assert_eq!(open_elements(&r), ["table", "row"]);
+    assert_eq!(next(&mut r), Start(BytesStart::new("cell")));
+    assert_eq!(open_elements(&r), ["table", "row", "cell"]);
+    assert_eq!(r.open_elements().len(), 3);
+    assert_eq!(r.open_elements().next_back(), Some(QName(b"cell")));
+    assert_eq!(next(&mut r), Text(BytesText::new("1")));
+    assert_eq!(open_elements(&r), ["table", "row", "cell"]);
+    assert_eq!(next(&mut r), End(BytesEnd::new("cell")));
+    assert_eq!(open_elements(&r), ["table", "row"]);
+    assert_eq!(next(&mut r), Empty(BytesStart::new("cell")));
+    assert_eq!(open_elements(&r), ["table", "row"]);
+    assert_eq!(next(&mut r), End(BytesEnd::new("row")));
+    assert_eq!(open_elements(&r), ["table"]);
+    assert_eq!(next(&mut r), Empty(BytesStart::new("row")));
+    assert_eq!(open_elements(&r), ["table"]);
+    assert_eq!(next(&mut r), End(BytesEnd::new("table")));
+    assert_eq!(open_elements(&r), Vec::<&str>::new());
+    assert_eq!(next(&mut r), Eof);
+}
+
+/// Expanded empty elements are opened until their synthetic `End` event
+#[test]
+fn test_open_elements_expand_empty() {
+    let mut r = Reader::from_str("<a><b/></a>");
+    r.expand_empty_elements(true);
+
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("a")));
+    assert_eq!(open_elements(&r), ["a"]);
+    assert_eq!(r.read_event().unwrap(), Start(BytesStart::new("b")));
+    assert_eq!(open_elements(&r), ["a", "b"]);
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("b")));
+    assert_eq!(open_elements(&r), ["a"]);
+    assert_eq!(r.read_event().unwrap(), End(BytesEnd::new("a")));
+    assert_eq!(open_elements(&r), Vec::<&str>::new());
+}
+
+#[test]
+fn test_relax_end_names() {
+    let mut reader =
+        Reader::from_str("<root><strict></strict><messy><a></b></messy><c></d></root>");
+
+    assert_eq!(reader.read_event().unwrap(), Start(BytesStart::new("root")));
+    assert_eq!(
+        reader.read_event().unwrap(),
+        Start(BytesStart::new("strict"))
+    );
+    assert_eq!(reader.read_event().unwrap(), End(BytesEnd::new("strict")));
+    {
+        let mut reader = reader.relax_end_names();
+        assert_eq!(
+            reader.read_event().unwrap(),
+            Start(BytesStart::new("messy"))
+        );
+        assert_eq!(reader.read_event().unwrap(), Start(BytesStart::new("a")));
+        assert_eq!(reader.read_event().unwrap(), End(BytesEnd::new("b")));
+        assert_eq!(reader.read_event().unwrap(), End(BytesEnd::new("messy")));
+    }
+    assert_eq!(reader.read_event().unwrap(), Start(BytesStart::new("c")));
+    match reader.read_event() {
+        Err(Error::EndEventMismatch { expected, found }) => {
+            assert_eq!(expected, "c");
+            assert_eq!(found, "d");
+        }
+        e => panic!("expecting `EndEventMismatch` error, found {:?}", e),
+    }
+}
+
+#[test]
+fn test_restricted_chars_disabled() {
+    let mut r = Reader::from_str("<a b=\"\x07\">bell\x07</a>");
+    match r.read_event() {
+        Ok(Start(e)) => assert_eq!(
+            e.try_get_attribute("b").unwrap().unwrap().value,
+            Cow::Borrowed(b"\x07")
+        ),
+        e => panic!("expecting start element, found {:?}", e),
+    }
+    match r.read_event() {
+        Ok(Text(e)) => assert_eq!(&*e, b"bell\x07"),
+        e => panic!("expecting text, found {:?}", e),
+    }
+}
+
+#[test]
+fn test_restricted_chars_text() {
+    let mut r = Reader::from_str("<a>\t\r\nbell\x07</a>");
+    r.check_restricted_chars(true);
+    next_eq!(r, Start, b"a");
+    match r.read_event() {
+        Err(Error::IllegalCharacter { byte, position }) => {
+            assert_eq!(byte, 0x07);
+            assert_eq!(position, 10);
+        }
+        e => panic!("expecting `IllegalCharacter` error, found {:?}", e),
+    }
+}
+
+#[test]
+fn test_restricted_chars_attribute() {
+    let mut r = Reader::from_str("<a b=\"\x07\"/>");
+    r.check_restricted_chars(true);
+    match r.read_event() {
+        Err(Error::IllegalCharacter { byte, position }) => {
+            assert_eq!(byte, 0x07);
+            assert_eq!(position, 6);
+        }
+        e => panic!("expecting `IllegalCharacter` error, found {:?}", e),
+    }
+}
+
+#[test]
+fn test_event_spans() {
+    let xml = r#"<?xml version="1.0"?><root attr='1'>
+  text &amp; more
+  <empty/><![CDATA[data]]></root>"#;
+    let mut r = Reader::from_str(xml);
+    r.trim_text(true);
+
+    let mut next = || {
+        let (event, span) = r.read_event_with_span().unwrap();
+        (event.into_owned(), &xml[span])
+    };
+
+    assert_eq!(
+        next(),
+        (
+            Decl(BytesDecl::new("1.0", None, None)),
+            r#"<?xml version="1.0"?>"#
+        )
+    );
+    assert_eq!(
+        next(),
+        (
+            Start(BytesStart::from_content("root attr='1'", 4)),
+            "<root attr='1'>"
+        )
+    );
+    assert_eq!(
+        next(),
+        (
+            Text(BytesText::from_escaped("text &amp; more")),
+            "text &amp; more"
+        )
+    );
+    assert_eq!(next(), (Empty(BytesStart::new("empty")), "<empty/>"));
+    assert_eq!(next(), (CData(BytesCData::new("data")), "<![CDATA[data]]>"));
+    assert_eq!(next(), (End(BytesEnd::new("root")), "</root>"));
+    assert_eq!(next(), (Eof, ""));
+}
+
+#[test]
+fn test_event_spans_buffered() {
+    let xml = "<a>text<b/></a>";
+    let mut r = Reader::from_reader(xml.as_bytes());
+    r.expand_empty_elements(true);
+    let mut buf = Vec::new();
+
+    let mut next = |r: &mut Reader<_>| {
+        buf.clear();
+        let (event, span) = r.read_event_into_with_span(&mut buf).unwrap();
+        (event.into_owned(), span)
+    };
+
+    assert_eq!(next(&mut r), (Start(BytesStart::new("a")), 0..3));
+    assert_eq!(next(&mut r), (Text(BytesText::new("text")), 3..7));
+    assert_eq!(next(&mut r), (Start(BytesStart::new("b")), 7..11));
+    // Generated event does not correspond to any input
+    assert_eq!(next(&mut r), (End(BytesEnd::new("b")), 11..11));
+    assert_eq!(next(&mut r), (End(BytesEnd::new("a")), 11..15));
+    assert_eq!(next(&mut r), (Eof, 15..15));
+}
+
+#[test]
+fn test_coalesce_text() {
+    let mut r = Reader::from_str("<a>a<![CDATA[b]]>c</a><![CDATA[d]]>e<b/>");
+    r.coalesce_text(true);
+
+    next_eq!(r, Start, b"a");
+    next_eq!(r, Text, b"abc");
+    next_eq!(r, End, b"a");
+    // A lone CDATA and a text at the end of the run
+    next_eq!(r, Text, b"de");
+    next_eq!(r, Empty, b"b");
+    assert_eq!(r.read_event().unwrap(), Eof);
+}
+
+#[test]
+fn test_coalesce_text_disabled() {
+    let mut r = Reader::from_str("<a>a<![CDATA[b]]>c</a>");
+
+    next_eq!(r, Start, b"a");
+    next_eq!(r, Text, b"a");
+    next_eq!(r, CData, b"b");
+    next_eq!(r, Text, b"c");
+    next_eq!(r, End, b"a");
+    assert_eq!(r.read_event().unwrap(), Eof);
+}
+
+/// Content of CDATA sections is escaped, so unescaped text is the same as seen
+/// by an XML processor
+#[test]
+fn test_coalesce_text_escape() {
+    let mut r = Reader::from_str("<a>x &amp; <![CDATA[<y> & ]]>z</a>");
+    r.coalesce_text(true);
+
+    next_eq!(r, Start, b"a");
+    match r.read_event() {
+        Ok(Text(e)) => {
+            assert_eq!(e.as_ref(), b"x &amp; &lt;y&gt; &amp; z");
+            assert_eq!(e.unescape().unwrap(), "x & <y> & z");
+        }
+        e => panic!("expecting text, found {:?}", e),
+    }
+    next_eq!(r, End, b"a");
+}
+
+/// Only spaces at the edges of the whole run are trimmed
+#[test]
+fn test_coalesce_text_trim() {
+    let mut r = Reader::from_str("<a> x <![CDATA[ y ]]> z </a><b> <![CDATA[ ]]> </b>");
+    r.trim_text(true).coalesce_text(true);
+
+    next_eq!(r, Start, b"a");
+    next_eq!(r, Text, b"x  y  z");
+    next_eq!(r, End, b"a");
+    // Text which is empty after trimming is skipped
+    next_eq!(r, Start, b"b", End, b"b");
+    assert_eq!(r.read_event().unwrap(), Eof);
+}
+
+/// Spans of coalesced text do not include trimmed spaces
+#[test]
+fn test_coalesce_text_trim_span() {
+    let xml = "a<![CDATA[b]]>c  <x/>";
+    let mut r = Reader::from_str(xml);
+    r.trim_text(true).coalesce_text(true);
+
+    let (event, span) = r.read_event_with_span().unwrap();
+    assert_eq!(event, Text(BytesText::from_escaped("abc")));
+    assert_eq!(span, 0..15);
+    assert_eq!(&xml[span], "a<![CDATA[b]]>c");
+
+    let (event, span) = r.read_event_with_span().unwrap();
+    assert_eq!(event, Empty(BytesStart::new("x")));
+    assert_eq!(&xml[span], "<x/>");
+
+    // Spaces inside of CDATA cannot be excluded from the span
+    let xml = "<a><![CDATA[b ]]> </a>";
+    let mut r = Reader::from_str(xml);
+    r.trim_text(true).coalesce_text(true);
+
+    r.read_event().unwrap();
+    let (event, span) = r.read_event_with_span().unwrap();
+    assert_eq!(event, Text(BytesText::from_escaped("b")));
+    assert_eq!(&xml[span], "<![CDATA[b ]]>");
+}
+
+#[test]
+fn test_stream_position() {
+    use std::fs::{self, File};
+    use std::io::{BufReader, Seek, SeekFrom};
+
+    let path = std::env::temp_dir().join(format!(
+        "quick-xml-stream-position-{}.xml",
+        std::process::id()
+    ));
+    fs::write(
+        &path,
+        "prefix\n<root>\n  text\n  <item id='1'>value</item>\n</root>",
+    )
+    .unwrap();
+
+    let mut file = File::open(&path).unwrap();
+    // Start parsing after the prefix, so positions in the file differ from
+    // positions, reported by `buffer_position()`
+    file.seek(SeekFrom::Start(7)).unwrap();
+    let mut r = Reader::from_reader(BufReader::with_capacity(4, file));
+    let mut buf = Vec::new();
+
+    let mut position = None;
+    loop {
+        let start = r.stream_position().unwrap();
+        match r.read_event_into(&mut buf).unwrap() {
+            Start(e) if e.name().as_ref() == b"item" => {
+                position = Some(start);
+                break;
+            }
+            Eof => break,
+            _ => buf.clear(),
+        }
+    }
+    let position = position.expect("`<item>` not found");
+    assert_eq!(position, 23);
+    assert_eq!(r.buffer_position(), 29);
+
+    let mut file = r.into_inner().into_inner();
+    file.seek(SeekFrom::Start(position)).unwrap();
+    let mut r = Reader::from_reader(BufReader::new(file));
+    buf.clear();
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        Start(BytesStart::from_content("item id='1'", 4))
+    );
+    buf.clear();
+    assert_eq!(
+        r.read_event_into(&mut buf).unwrap(),
+        Text(BytesText::new("value"))
+    );
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_coalesce_text_buffered() {
+    let xml = "<a>a<![CDATA[b]]>c<b/></a>";
+    let mut r = Reader::from_reader(xml.as_bytes());
+    r.coalesce_text(true);
+    let mut buf = Vec::new();
+
+    let mut next = |r: &mut Reader<_>| {
+        buf.clear();
+        let (event, span) = r.read_event_into_with_span(&mut buf).unwrap();
+        (event.into_owned(), span)
+    };
+
+    assert_eq!(next(&mut r), (Start(BytesStart::new("a")), 0..3));
+    assert_eq!(next(&mut r), (Text(BytesText::new("abc")), 3..18));
+    assert_eq!(next(&mut r), (Empty(BytesStart::new("b")), 18..22));
+    assert_eq!(next(&mut r), (End(BytesEnd::new("a")), 22..26));
+    assert_eq!(next(&mut r), (Eof, 26..26));
+}
+
+#[test]
+fn test_coalesce_text_ns_reader() {
+    let mut r = NsReader::from_str("<p:a xmlns:p='urn'>x<![CDATA[y]]></p:a>");
+    r.coalesce_text(true);
+
+    let ns = ResolveResult::Bound(Namespace(b"urn"));
+    match r.read_resolved_event() {
+        Ok((n, Start(e))) if n == ns && e.name() == QName(b"p:a") => {}
+        e => panic!("expecting <p:a>, found {:?}", e),
+    }
+    match r.read_resolved_event() {
+        Ok((ResolveResult::Unbound, Text(e))) => assert_eq!(e.as_ref(), b"xy"),
+        e => panic!("expecting text, found {:?}", e),
+    }
+    match r.read_resolved_event() {
+        Ok((n, End(e))) if n == ns && e.name() == QName(b"p:a") => {}
+        e => panic!("expecting </p:a>, found {:?}", e),
+    }
+}
+
 #[test]
 fn test_escaped_content() {
     let mut r = Reader::from_str("<a>&lt;test&gt;</a>");
@@ -699,3 +1507,76 @@ fn test_closing_bracket_in_single_quote_mixed() {
     }
     next_eq!(r, End, b"a");
 }
+
+#[test]
+fn test_assume_utf8() {
+    fn events(xml: &[u8], assume_utf8: bool) -> Vec<quick_xml::events::Event<'static>> {
+        let mut reader = Reader::from_reader(xml);
+        reader.assume_utf8(assume_utf8);
+        let mut buf = Vec::new();
+        let mut events = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf).unwrap() {
+                Eof => break,
+                event => events.push(event.into_owned()),
+            }
+            buf.clear();
+        }
+        events
+    }
+
+    for xml in [
+        &include_bytes!("documents/libreoffice_document.fodt")[..],
+        &include_bytes!("documents/rpm_primary.xml")[..],
+        &include_bytes!("documents/sample_rss.xml")[..],
+    ] {
+        assert_eq!(events(xml, true), events(xml, false));
+    }
+}
+
+#[test]
+fn test_assume_utf8_violated() {
+    let mut r = Reader::from_reader(&b"\xFF\xFE<\0a\0/\0>\0"[..]);
+    r.assume_utf8(true);
+    match r.read_event() {
+        Err(Error::UnexpectedEncoding(e)) => assert_eq!(e, "UTF-16LE"),
+        e => panic!("Expected `UnexpectedEncoding` error, got {:?}", e),
+    }
+    assert_eq!(r.read_event().unwrap(), Eof);
+}
+
+/// Malformed events are reported only in debug builds
+#[test]
+#[cfg(debug_assertions)]
+fn test_assume_utf8_malformed() {
+    let mut r = Reader::from_reader(&b"<a>\xFF</a>"[..]);
+    r.assume_utf8(true);
+    assert!(matches!(r.read_event().unwrap(), Start(_)));
+    match r.read_event() {
+        Err(Error::NonDecodable(Some(_))) => {}
+        e => panic!("Expected `NonDecodable(Some(_))` error, got {:?}", e),
+    }
+}
+
+#[test]
+fn test_config() {
+    let mut config = quick_xml::reader::Config::default();
+    config.trim_text(true);
+    config.expand_empty_elements = true;
+    config.check_end_names = false;
+
+    let mut r1 = Reader::from_reader_with_config(&b" <a/> <b></c>"[..], config.clone());
+    let mut r2 = Reader::from_reader_with_config(&b"<x/>"[..], config.clone());
+    assert_eq!(r1.config(), &config);
+    assert_eq!(r2.config(), &config);
+
+    next_eq!(r1, Start, b"a", End, b"a", Start, b"b", End, b"c");
+    next_eq!(r2, Start, b"x", End, b"x");
+
+    // Changes of one reader do not affect another
+    r1.config_mut().expand_empty_elements = false;
+    r1.trim_text(false);
+    assert_eq!(r2.config(), &config);
+    assert!(!r1.config().expand_empty_elements);
+    assert!(!r1.config().trim_text_start);
+}